@@ -1,13 +1,15 @@
+use crate::measurements::MeasurementField;
 use alloc::{string::String, vec::Vec};
 use borsh::{BorshDeserialize, BorshSerialize};
 use derive_more::{Deref, From};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Helper struct to deserialize the `app_compose` JSON from TCB info. This is a workaround due to
 /// current limitations in the Dstack SDK.
 ///
 /// See: https://github.com/Dstack-TEE/dstack/issues/267
-#[derive(Debug, Deserialize, Serialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, BorshSerialize, BorshDeserialize)]
 pub struct AppCompose {
     pub manifest_version: u32,
     pub name: String,
@@ -34,5 +36,406 @@ pub struct AppCompose {
 /// a string. For example the docker compose file below can be read as a string and initialize this type.
 ///
 /// This type does currently not do any validation of the string
-#[derive(Debug, Deserialize, Serialize, BorshSerialize, BorshDeserialize, From, Deref)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    BorshSerialize,
+    BorshDeserialize,
+    From,
+    Deref,
+)]
 pub struct DockerComposeString(String);
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AppComposeError {
+    #[error("invalid docker compose file: {0}")]
+    InvalidDockerCompose(String),
+    #[error("service `{service}` has no image")]
+    MissingImage { service: String },
+    #[error("service `{service}` has an invalid image reference `{image}`")]
+    InvalidImageReference { service: String, image: String },
+    #[error(
+        "service `{service}` pulls `{image}` from the registry `{registry}`, which is not allowed"
+    )]
+    DisallowedRegistry {
+        service: String,
+        image: String,
+        registry: String,
+    },
+    #[error("app compose field `{0}` is empty")]
+    EmptyField(&'static str),
+    #[error("docker compose file has no services")]
+    NoServices,
+    #[error("service `{service}` is defined more than once")]
+    DuplicateService { service: String },
+    #[error("app compose file I/O failed: {0}")]
+    Io(String),
+    #[error("invalid app compose: {0}")]
+    InvalidAppCompose(String),
+    #[error("app compose hash {computed} does not match the expected {expected}")]
+    HashMismatch { expected: String, computed: String },
+}
+
+/// The registry Docker Hub images are pulled from when the reference names no registry.
+pub const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// Returns the registry host of an OCI image reference, following the Docker normalization
+/// rules: the first path component is the registry if it contains a `.` or a `:` (a port), or
+/// is `localhost`, and the image is on [`DEFAULT_REGISTRY`] otherwise. `index.docker.io` is
+/// normalized to [`DEFAULT_REGISTRY`].
+///
+/// Returns `None` if `image` is not a valid reference, including unexpanded variables such as
+/// `${IMAGE}`, as the image pulled cannot be known from the compose file.
+///
+/// ```
+/// use attestation::app_compose::registry_host;
+///
+/// assert_eq!(registry_host("nearone/mpc-launcher@sha256:4b7a"), Some("docker.io"));
+/// assert_eq!(registry_host("ghcr.io/near/mpc:latest"), Some("ghcr.io"));
+/// assert_eq!(registry_host("localhost:5000/mpc"), Some("localhost:5000"));
+/// assert_eq!(registry_host("${IMAGE}"), None);
+/// ```
+pub fn registry_host(image: &str) -> Option<&str> {
+    // The digest may contain `:`, and the tag must not be confused with a registry port.
+    let name = image.split_once('@').map_or(image, |(name, _digest)| name);
+    let is_valid = |component: &str| {
+        !component.is_empty()
+            && component.bytes().all(|byte| {
+                byte.is_ascii_lowercase()
+                    || byte.is_ascii_digit()
+                    || matches!(byte, b'.' | b'_' | b'-' | b':')
+            })
+    };
+
+    let (first, rest) = match name.split_once('/') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (name, None),
+    };
+    let is_registry = rest.is_some()
+        && (first.contains('.') || first.contains(':') || first == "localhost")
+        && first
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'-' | b':'));
+    let path = if is_registry { rest? } else { name };
+
+    if !path.split('/').all(is_valid) {
+        return None;
+    }
+
+    match first {
+        "index.docker.io" if is_registry => Some(DEFAULT_REGISTRY),
+        registry if is_registry => Some(registry),
+        _ => Some(DEFAULT_REGISTRY),
+    }
+}
+
+/// Checks that every service of the docker compose file of `compose` pulls its image from one
+/// of the `allowed` registry hosts, e.g. `["docker.io", "ghcr.io"]`, see [`registry_host`].
+/// Registries are compared case-insensitively, and `allowed` must include the port if the
+/// registry has one.
+///
+/// This complements pinning the image digests: it restricts where images come from, not
+/// which images they are.
+///
+/// # Errors
+///
+/// Returns an [`AppComposeError`] naming the first offending service: services with an image
+/// from another registry, with an invalid image reference, or without an image, e.g. built
+/// from a local context, are all rejected.
+#[cfg(feature = "docker-compose")]
+pub fn validate_registries(compose: &AppCompose, allowed: &[&str]) -> Result<(), AppComposeError> {
+    use alloc::string::ToString;
+    use serde_yaml::Value as YamlValue;
+
+    let docker_compose: YamlValue = serde_yaml::from_str(&compose.docker_compose_file)
+        .map_err(|e| AppComposeError::InvalidDockerCompose(e.to_string()))?;
+    let services = docker_compose
+        .get("services")
+        .and_then(YamlValue::as_mapping)
+        .ok_or_else(|| AppComposeError::InvalidDockerCompose("missing services".to_string()))?;
+
+    for (service, definition) in services {
+        let service = service.as_str().unwrap_or_default().to_string();
+        let image = definition
+            .get("image")
+            .and_then(YamlValue::as_str)
+            .ok_or_else(|| AppComposeError::MissingImage {
+                service: service.clone(),
+            })?;
+        let registry =
+            registry_host(image).ok_or_else(|| AppComposeError::InvalidImageReference {
+                service: service.clone(),
+                image: image.to_string(),
+            })?;
+
+        if !allowed
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(registry))
+        {
+            return Err(AppComposeError::DisallowedRegistry {
+                service,
+                image: image.to_string(),
+                registry: registry.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that `compose` is structurally sound before it is hashed or deployed, so that a
+/// malformed app compose fails with a clear error instead of producing an unexpected hash. The
+/// checks are, in order:
+///
+/// 1. `name`, `runner` and `docker_compose_file` are not empty.
+/// 2. The docker compose file is YAML with a non-empty `services` mapping.
+/// 3. No service name appears twice. YAML parsers disagree on which definition wins, so the
+///    services that run may not be the ones reviewed.
+/// 4. Every service has a non-empty `image`.
+///
+/// Unlike [`validate_registries`], the image references themselves are not checked.
+///
+/// # Errors
+///
+/// Returns the [`AppComposeError`] of the first check that fails:
+/// [`AppComposeError::EmptyField`], [`AppComposeError::InvalidDockerCompose`] or
+/// [`AppComposeError::NoServices`], [`AppComposeError::DuplicateService`], and
+/// [`AppComposeError::MissingImage`].
+#[cfg(feature = "docker-compose")]
+pub fn validate(compose: &AppCompose) -> Result<(), AppComposeError> {
+    use alloc::string::ToString;
+    use serde_yaml::Value as YamlValue;
+
+    for (field, value) in [
+        ("name", compose.name.as_str()),
+        ("runner", compose.runner.as_str()),
+        ("docker_compose_file", compose.docker_compose_file.as_str()),
+    ] {
+        if value.trim().is_empty() {
+            return Err(AppComposeError::EmptyField(field));
+        }
+    }
+
+    let docker_compose: docker_compose::Services =
+        serde_yaml::from_str(&compose.docker_compose_file)
+            .map_err(|e| AppComposeError::InvalidDockerCompose(e.to_string()))?;
+    let services = docker_compose
+        .services
+        .ok_or_else(|| AppComposeError::InvalidDockerCompose("missing services".to_string()))?;
+    if services.0.is_empty() {
+        return Err(AppComposeError::NoServices);
+    }
+
+    let mut names = alloc::collections::BTreeSet::new();
+    for (service, definition) in &services.0 {
+        let service = service
+            .as_str()
+            .ok_or_else(|| {
+                AppComposeError::InvalidDockerCompose("service name is not a string".to_string())
+            })?
+            .to_string();
+        if !names.insert(service.clone()) {
+            return Err(AppComposeError::DuplicateService { service });
+        }
+        if definition
+            .get("image")
+            .and_then(YamlValue::as_str)
+            .is_none_or(|image| image.trim().is_empty())
+        {
+            return Err(AppComposeError::MissingImage { service });
+        }
+    }
+
+    Ok(())
+}
+
+/// Deserializes the services of a docker compose file as a list of entries, so that duplicate
+/// service names are seen rather than resolved by the YAML mapping.
+#[cfg(feature = "docker-compose")]
+mod docker_compose {
+    use alloc::vec::Vec;
+    use core::fmt;
+    use serde::{
+        Deserialize, Deserializer,
+        de::{MapAccess, Visitor},
+    };
+    use serde_yaml::Value;
+
+    #[derive(Deserialize)]
+    pub(super) struct Services {
+        pub(super) services: Option<Entries>,
+    }
+
+    pub(super) struct Entries(pub(super) Vec<(Value, Value)>);
+
+    impl<'de> Deserialize<'de> for Entries {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_map(EntriesVisitor)
+        }
+    }
+
+    struct EntriesVisitor;
+
+    impl<'de> Visitor<'de> for EntriesVisitor {
+        type Value = Entries;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a mapping of services")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = Vec::new();
+            while let Some(entry) = map.next_entry::<Value, Value>()? {
+                entries.push(entry);
+            }
+            Ok(Entries(entries))
+        }
+    }
+}
+
+/// The measurements that change when an [`AppCompose`] is changed.
+///
+/// The app compose is only measured at runtime, through events extended into RTMR3. MRTD and
+/// RTMR0-2 measure the firmware, kernel and OS image, and are therefore never affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AffectedSet {
+    /// The app_compose hash payload of the `compose-hash` event changes.
+    pub app_compose_hash_payload: bool,
+    /// The digest of the `key-provider` event changes.
+    pub key_provider_event_digest: bool,
+    /// RTMR3 changes, as it is the replay of the events above.
+    pub rtmr3: bool,
+}
+
+impl AffectedSet {
+    /// Returns true if no measurement changes.
+    pub fn is_empty(&self) -> bool {
+        !self.app_compose_hash_payload && !self.key_provider_event_digest && !self.rtmr3
+    }
+
+    /// Returns the affected fields of [`crate::measurements::FullMeasurements`].
+    pub fn fields(&self) -> Vec<MeasurementField> {
+        let mut fields = Vec::new();
+        if self.key_provider_event_digest {
+            fields.push(MeasurementField::KeyProviderEventDigest);
+        }
+        if self.app_compose_hash_payload {
+            fields.push(MeasurementField::AppComposeHashPayload);
+        }
+        fields
+    }
+}
+
+/// Predicts which measurements will shift when the app compose is changed from `old` to `new`.
+/// This lets operators prepare the correct new baseline before deploying.
+pub fn affected_measurements(old: &AppCompose, new: &AppCompose) -> AffectedSet {
+    // Any change to the app compose changes its hash.
+    let app_compose_hash_payload = old != new;
+
+    // The key-provider event records which key provider is used and its ID.
+    let key_provider_event_digest = old.kms_enabled != new.kms_enabled
+        || old.local_key_provider_enabled != new.local_key_provider_enabled
+        || old.key_provider_id != new.key_provider_id;
+
+    AffectedSet {
+        app_compose_hash_payload,
+        key_provider_event_digest,
+        rtmr3: app_compose_hash_payload || key_provider_event_digest,
+    }
+}
+
+/// Returns the canonical JSON form of `compose`, so that the same app compose can be
+/// serialized identically by other tools. The rules are, in the spirit of RFC 8785 (JCS):
+///
+/// - All fields of [`AppCompose`] are included, and unset optional fields are `null`. Fields
+///   omitted from [`AppCompose`], such as `docker_config`, are not part of the output.
+/// - Object keys are sorted by their UTF-8 bytes. All keys are ASCII, so this is the same
+///   order as JCS.
+/// - There is no whitespace outside of strings.
+/// - Strings are escaped as by `serde_json`: `"`, `\` and control characters are escaped,
+///   using the short forms `\n`, `\t` etc. where they exist, anything else is emitted as is.
+/// - Numbers are unsigned integers in decimal, without leading zeros.
+///
+/// Note that the compose hash of an attestation is over the `app_compose` string of the TCB
+/// info exactly as deployed. It only matches the hash of the canonical form if the deployed
+/// app compose was itself produced by this function.
+pub fn canonicalize(compose: &AppCompose) -> Result<String, serde_json::Error> {
+    let mut canonical = String::new();
+    write_canonical(&serde_json::to_value(compose)?, &mut canonical)?;
+    Ok(canonical)
+}
+
+fn write_canonical(value: &Value, out: &mut String) -> Result<(), serde_json::Error> {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+            out.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(key)?);
+                out.push(':');
+                write_canonical(value, out)?;
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&serde_json::to_string(scalar)?),
+    }
+    Ok(())
+}
+
+/// Reads the app compose JSON at `compose_file_path`, and checks that the SHA-256 hash of its
+/// [`canonicalize`]d form is `expected_payload`, e.g. the
+/// [`crate::measurements::FullMeasurements::app_compose_hash_payload`] of a published baseline.
+/// On a mismatch, the hash computed locally is logged and returned in the error, so that an
+/// operator can see which app compose the baseline was not built from.
+///
+/// This only reproduces baselines whose app compose was deployed in canonical form, see
+/// [`canonicalize`]. Duplicate keys in the file are rejected.
+#[cfg(feature = "std")]
+pub fn reproduce_and_compare(
+    compose_file_path: &std::path::Path,
+    expected_payload: &[u8; 32],
+) -> Result<(), AppComposeError> {
+    use alloc::string::ToString;
+    use sha2::{Digest as _, Sha256};
+
+    let json = std::fs::read_to_string(compose_file_path)
+        .map_err(|e| AppComposeError::Io(alloc::format!("{}: {e}", compose_file_path.display())))?;
+    let compose: AppCompose = crate::strict_json::from_str(&json)
+        .map_err(|e| AppComposeError::InvalidAppCompose(e.to_string()))?;
+    let canonical =
+        canonicalize(&compose).map_err(|e| AppComposeError::InvalidAppCompose(e.to_string()))?;
+
+    let computed: [u8; 32] = Sha256::digest(canonical.as_bytes()).into();
+    if computed != *expected_payload {
+        let computed = hex::encode(computed);
+        tracing::warn!(
+            path = %compose_file_path.display(),
+            %computed,
+            "app compose hash does not match the baseline"
+        );
+        return Err(AppComposeError::HashMismatch {
+            expected: hex::encode(expected_payload),
+            computed,
+        });
+    }
+    Ok(())
+}
@@ -1,36 +1,498 @@
 use crate::{
     // app_compose::AppCompose,
-    collateral::Collateral,
-    measurements::{FullMeasurements, MeasurementsError},
-    quote::QuoteBytes,
-    report_data::ReportData,
+    collateral::{
+        Certificates, Collateral, CollateralError, CollateralLimits, CollateralValidity, Fmspc,
+        INTEL_SGX_ROOT_CA_DER, INTEL_SGX_ROOT_CA_SHA256, MAX_PCK_CHAIN_LEN,
+        assert_fmspc_consistency,
+    },
+    measurements::{
+        ExtendedMeasurements, FullMeasurements, Measurements, MeasurementsDiff, MeasurementsError,
+    },
+    quote::{ParsedQuote, QuoteBytes, QuoteContext, QuoteError, QuoteType, qe_isv_svn, quote_type},
+    report_data::{
+        ReportData, mr_config_id, mr_owner, mr_service_td, seam_svn, tee_tcb_svn,
+        verify_with_domain,
+    },
     tcb_info::{EventLog, HexBytes, TcbInfo},
+    tcb_status::{TcbPolicy, TcbStatus, evaluate_qe_tcb, evaluate_tdx_module_tcb, pck_svn},
 };
 
 use alloc::{
+    boxed::Box,
     format,
     string::{String, ToString},
     vec::Vec,
 };
-use serde_json::json;
 use borsh::{BorshDeserialize, BorshSerialize};
 use core::fmt;
-use dcap_qvl::verify::VerifiedReport;
+use dcap_qvl::{quote::Report, verify::VerifiedReport};
 use derive_more::Constructor;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::{hex::Hex, serde_as};
 use sha2::{Digest as _, Sha256, Sha384};
-
-/// Expected TCB status for a successfully verified TEE quote.
-const EXPECTED_QUOTE_STATUS: &str = "UpToDate";
+use x509_cert::der::Encode;
 
 // DSTACK_EVENT_TYPE is defined in https://github.com/Dstack-TEE/dstack/blob/cfa4cc4e8a4f525d537883b1a0ba5d9fbfd87f1e/tdx-attest/src/lib.rs#L28
 // It is the same for all events
 const DSTACK_EVENT_TYPE: u32 = 134217729;
 
-const COMPOSE_HASH_EVENT: &str = "compose-hash";
+pub(crate) const COMPOSE_HASH_EVENT: &str = "compose-hash";
 pub(crate) const KEY_PROVIDER_EVENT: &str = "key-provider";
 
-const RTMR3_INDEX: u32 = 3;
+/// The `DEBUG` bit of the first byte of `TD_ATTRIBUTES`.
+const TD_ATTRIBUTES_DEBUG: u8 = 0x01;
+
+pub(crate) const RTMR3_INDEX: u32 = 3;
+
+mod app_identity;
+mod batch;
+mod bundle;
+#[cfg(feature = "std")]
+mod clock;
+mod collateral_fn;
+mod collateral_set;
+mod db_row;
+mod diagnose;
+#[cfg(feature = "std")]
+mod downgrade;
+mod explain;
+#[cfg(feature = "std")]
+mod fleet;
+#[cfg(feature = "std")]
+mod guidance;
+#[cfg(feature = "std")]
+mod hex_input;
+#[cfg(feature = "in-toto")]
+mod in_toto;
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "near")]
+mod near;
+#[cfg(any(feature = "std", feature = "near"))]
+mod quote_summary;
+#[cfg(feature = "std")]
+mod replay;
+mod resharing;
+#[cfg(feature = "signed-summary")]
+mod signed_summary;
+mod stability;
+#[cfg(feature = "std")]
+mod transcript;
+
+pub use app_identity::verify_app_identity;
+#[cfg(feature = "parallel")]
+pub use batch::verify_batch_parallel;
+pub use batch::{verify_aggregate, verify_batch, verify_stream};
+pub use bundle::Bundle;
+#[cfg(feature = "std")]
+pub use clock::{SystemTimeSource, TimeSource, verify_now, verify_quote_with_time_source};
+pub use collateral_fn::verify_with_collateral_fn;
+pub use collateral_set::{CollateralId, verify_with_collateral_set};
+pub use db_row::DbRow;
+pub use diagnose::{SuggestedRelaxation, diagnose_failure};
+#[cfg(feature = "std")]
+pub use downgrade::{DowngradeGuard, InMemoryTcbEvaluation, TcbEvaluationStore};
+#[cfg(feature = "std")]
+pub use fleet::{FleetImage, FleetReport, summarize_fleet};
+#[cfg(feature = "std")]
+pub use guidance::{GuidedError, verify_with_guidance};
+#[cfg(feature = "std")]
+pub use hex_input::verify_hex;
+#[cfg(feature = "in-toto")]
+pub use in_toto::{
+    APP_COMPOSE_SUBJECT, IN_TOTO_STATEMENT_TYPE, InTotoStatement, InTotoSubject,
+    MEASUREMENTS_SUBJECT, TDX_VERIFICATION_PREDICATE_TYPE, TdxVerificationOutcome,
+    TdxVerificationPredicate,
+};
+#[cfg(feature = "std")]
+pub use metrics::{
+    ADVISORIES_METRIC, Metric, TCB_INFO_NEXT_UPDATE_METRIC, VERIFIED_AT_METRIC, VERIFIED_METRIC,
+};
+#[cfg(feature = "near")]
+pub use near::{NearPublicKey, verify_against_contract_commitment, verify_to_near_public_key};
+#[cfg(feature = "std")]
+pub use replay::{InMemorySeenQuotes, ReplayGuard, SeenQuoteStore};
+pub use resharing::{
+    verify_participant_keys, verify_participants, verify_resharing_set, verify_same_app,
+};
+#[cfg(feature = "signed-summary")]
+pub use signed_summary::SignedSummary;
+pub use stability::stability_window;
+#[cfg(feature = "std")]
+pub use transcript::{RecordedResult, Transcript};
+
+/// Recommended value for [`QuoteVerifyOptions::allowed_skew_secs`]. Five minutes comfortably
+/// covers NTP drift between the caller and Intel PCS, while being negligible compared to the
+/// 30 day validity window of the collateral.
+pub const RECOMMENDED_ALLOWED_SKEW_SECS: u64 = 5 * 60;
+
+/// Options controlling how a TEE quote is verified against its collateral.
+///
+/// Serializes registers and DER certificates as hex, e.g. to record the options a verification
+/// ran with.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuoteVerifyOptions {
+    /// Clock skew, in seconds, tolerated on both ends of the collateral validity window.
+    /// The collateral is accepted within `[notBefore - skew, nextUpdate + skew]`.
+    ///
+    /// Defaults to zero, i.e. the caller supplied timestamp must be within the validity
+    /// window. See [`RECOMMENDED_ALLOWED_SKEW_SECS`] for a recommended value.
+    pub allowed_skew_secs: u64,
+    /// TCB statuses and advisories accepted for the platform, the QE and the TDX module.
+    /// Defaults to only [`TcbStatus::UpToDate`].
+    ///
+    /// Statuses other than `UpToDate` come with Intel security advisories, which are accepted
+    /// if the policy allows them, and reported in [`AttestationSummary::advisory_ids`].
+    pub tcb_policy: TcbPolicy,
+    /// If set, the report must be a TD 1.5 report with this `MRSERVICETD`, see
+    /// [`crate::report_data::mr_service_td`]. This constrains the TDX module's service TDs, in
+    /// addition to the guest measurements. Defaults to `None`, accepting any.
+    #[serde_as(as = "Option<Hex>")]
+    pub expected_mr_service_td: Option<[u8; 48]>,
+    /// If set, the report must have this `MRCONFIGID`, see
+    /// [`crate::report_data::mr_config_id`]. This pins the TD to a tenant in deployments that
+    /// set it to a tenant identifier. Defaults to `None`, accepting any.
+    #[serde_as(as = "Option<Hex>")]
+    pub expected_mr_config_id: Option<[u8; 48]>,
+    /// If set, the report must have this `MROWNER`, see [`crate::report_data::mr_owner`].
+    /// This confirms the TD was launched under the expected ownership, in deployments that
+    /// set it to the hash of a governance key. Defaults to `None`, accepting any.
+    #[serde_as(as = "Option<Hex>")]
+    pub expected_mr_owner: Option<[u8; 48]>,
+    /// If set, the report data must be [`crate::report_data::with_domain`] of this domain tag
+    /// and the expected report data given to [`DstackAttestation::verify_with_options`], rather
+    /// than the expected report data itself. This keeps a quote produced for another protocol
+    /// from being accepted. Defaults to `None`.
+    #[serde_as(as = "Option<Hex>")]
+    pub report_data_domain: Option<Vec<u8>>,
+    /// DER encoded root CA that every collateral issuer chain must end with, checked with
+    /// [`QuoteVerifyOptions::extended_collateral_checks`] and by
+    /// [`crate::collateral::PreparedCollateral::new`]. Defaults to the pinned
+    /// [`INTEL_SGX_ROOT_CA_DER`]; only override this for testing.
+    ///
+    /// Note that DCAP verification of the quote itself always uses the Intel root pinned in
+    /// `dcap-qvl`.
+    #[serde_as(as = "Hex")]
+    pub trusted_root_ca_der: Vec<u8>,
+    /// If set, the collateral must have a [`Collateral::tcb_evaluation_number`] of at least
+    /// this value. This rejects collateral that predates a TCB recovery, even if it has not
+    /// expired yet. Defaults to `None`.
+    pub min_tcb_eval_number: Option<u32>,
+    /// **Weakens verification.** If set, the TCB level of the Quoting Enclave is not evaluated
+    /// against the QE identity, see [`verify_qe_tcb`], and [`AttestationSummary::qe_tcb_status`]
+    /// is `None`. The PCK chain, the platform and TDX module TCB and the measurements are still
+    /// verified. Defaults to `false`, and no preset sets it.
+    ///
+    /// Only set this in controlled environments where the QE is trusted out of band: an
+    /// out-of-date or revoked QE, which Intel will no longer vouch for, is accepted. Note that
+    /// DCAP verification of the quote itself still requires the QE identity in the collateral.
+    pub skip_qe_identity: bool,
+    /// If set, reports from a debug TD or a debug TDX module are rejected, see
+    /// [`verify_not_debug`]. The host can read and modify the memory of such TDs. This does not
+    /// detect pre-production platforms, whose PCK certificates root outside the production
+    /// Intel SGX Root CA, see [`assert_production_root`]. Defaults to `false`.
+    pub reject_debug_td: bool,
+    /// If set, the TCB info of the collateral must have been issued at most this many seconds
+    /// before the verification time, even if its `nextUpdate` is later. This keeps nodes on
+    /// recent TCB levels, beyond the expiry check. Defaults to `None`.
+    pub max_collateral_age_secs: Option<u64>,
+    /// If set, the TD must run on a TDX module with a [`seam_svn`] of at least this value,
+    /// which rejects modules with known vulnerabilities independently of the TCB levels of
+    /// the collateral. Defaults to `None`.
+    pub min_seam_svn: Option<u16>,
+    /// If set, the report must have this RTMR3, see [`ExtendedMeasurements::rtmr3`]. This pins
+    /// platforms which measure into RTMR3 outside of the dstack event log; a report that never
+    /// extended its RTMR3 is rejected. Defaults to `None`, accepting any.
+    #[serde_as(as = "Option<Hex>")]
+    pub expected_rtmr3: Option<[u8; 48]>,
+    /// If set, the accepted measurements the attestation matched must have a non-zero
+    /// [`FullMeasurements::app_compose_hash_payload`], see [`verify_app_compose_present`].
+    /// A zero payload is what a baseline with an unfilled app compose field carries, and
+    /// accepting it leaves the app identity unchecked. Defaults to `false` for compatibility;
+    /// deployments pinning an app compose should set it.
+    pub require_app_compose: bool,
+    /// If set, the collateral and the PCK certificate chain of the quote are checked beyond
+    /// what DCAP verification checks, see [`verify_quote`]: the collateral limits, trust anchor,
+    /// issuer chains, PCK CRL signature, root CA revocations and CRL validity, the X.509
+    /// constraints of the PCK chain, the TEE type and FMSPC the TCB info is for, and the overlap
+    /// of the PCK certificate and collateral validity.
+    ///
+    /// These cost about five ECDSA verifications per quote on top of DCAP verification, which
+    /// matters in a contract. Defaults to `false`, which keeps the cost of DCAP verification
+    /// alone; verifiers off-chain, or which verify the collateral once, should set it.
+    pub extended_collateral_checks: bool,
+}
+
+impl Default for QuoteVerifyOptions {
+    fn default() -> Self {
+        Self {
+            allowed_skew_secs: 0,
+            tcb_policy: TcbPolicy::default(),
+            expected_mr_service_td: None,
+            expected_mr_config_id: None,
+            expected_mr_owner: None,
+            report_data_domain: None,
+            trusted_root_ca_der: INTEL_SGX_ROOT_CA_DER.to_vec(),
+            min_tcb_eval_number: None,
+            skip_qe_identity: false,
+            reject_debug_td: false,
+            max_collateral_age_secs: None,
+            min_seam_svn: None,
+            expected_rtmr3: None,
+            require_app_compose: false,
+            extended_collateral_checks: false,
+        }
+    }
+}
+
+impl QuoteVerifyOptions {
+    /// Starts building options from [`QuoteVerifyOptions::strict`].
+    ///
+    /// ```
+    /// use attestation::{attestation::QuoteVerifyOptions, tcb_status::TcbStatus};
+    ///
+    /// let options = QuoteVerifyOptions::builder()
+    ///     .allowed_skew_secs(60)
+    ///     .allow_status(TcbStatus::SWHardeningNeeded)
+    ///     .min_tcb_eval_number(17)
+    ///     .build();
+    ///
+    /// assert_eq!(options.allowed_skew_secs, 60);
+    /// assert_eq!(
+    ///     options.tcb_policy.allowed_statuses,
+    ///     [TcbStatus::UpToDate, TcbStatus::SWHardeningNeeded]
+    /// );
+    /// ```
+    pub fn builder() -> QuoteVerifyOptionsBuilder {
+        QuoteVerifyOptionsBuilder {
+            options: Self::strict(),
+        }
+    }
+
+    /// The production preset, identical to [`QuoteVerifyOptions::default`]: only
+    /// [`TcbStatus::UpToDate`] platforms are accepted, and the timestamp must be within the
+    /// collateral validity window.
+    ///
+    /// ```
+    /// use attestation::{attestation::QuoteVerifyOptions, tcb_status::TcbStatus};
+    ///
+    /// let options = QuoteVerifyOptions::strict();
+    ///
+    /// assert_eq!(options.tcb_policy.allowed_statuses, [TcbStatus::UpToDate]);
+    /// assert_eq!(options.allowed_skew_secs, 0);
+    /// ```
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// Accepts every TCB status but [`TcbStatus::Revoked`], along with their advisories, and
+    /// tolerates [`RECOMMENDED_ALLOWED_SKEW_SECS`] of clock skew. Intended for environments
+    /// where platform patching lags behind Intel's TCB recoveries.
+    pub fn permissive() -> Self {
+        Self {
+            allowed_skew_secs: RECOMMENDED_ALLOWED_SKEW_SECS,
+            tcb_policy: TcbPolicy::allowing(
+                TcbStatus::ALL
+                    .into_iter()
+                    .filter(|status| *status != TcbStatus::Revoked),
+            ),
+            ..Self::default()
+        }
+    }
+
+    /// Accepts every TCB status, including [`TcbStatus::Revoked`], and tolerates
+    /// [`RECOMMENDED_ALLOWED_SKEW_SECS`] of clock skew. Intended for observing the TCB status
+    /// of a fleet through [`AttestationSummary`]; it must never be used to grant access.
+    pub fn monitoring() -> Self {
+        Self {
+            allowed_skew_secs: RECOMMENDED_ALLOWED_SKEW_SECS,
+            tcb_policy: TcbPolicy::allowing(TcbStatus::ALL),
+            ..Self::default()
+        }
+    }
+}
+
+/// Chainable builder for [`QuoteVerifyOptions`], see [`QuoteVerifyOptions::builder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteVerifyOptionsBuilder {
+    options: QuoteVerifyOptions,
+}
+
+impl QuoteVerifyOptionsBuilder {
+    /// Sets [`QuoteVerifyOptions::allowed_skew_secs`].
+    pub fn allowed_skew_secs(mut self, allowed_skew_secs: u64) -> Self {
+        self.options.allowed_skew_secs = allowed_skew_secs;
+        self
+    }
+
+    /// Replaces [`QuoteVerifyOptions::tcb_policy`].
+    pub fn tcb_policy(mut self, tcb_policy: TcbPolicy) -> Self {
+        self.options.tcb_policy = tcb_policy;
+        self
+    }
+
+    /// Replaces the [`TcbPolicy::allowed_statuses`] of the TCB policy.
+    pub fn allowed_statuses(mut self, statuses: impl IntoIterator<Item = TcbStatus>) -> Self {
+        self.options.tcb_policy.allowed_statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Adds `status` to the allowed statuses of the TCB policy, see [`TcbPolicy::allow_status`].
+    pub fn allow_status(mut self, status: TcbStatus) -> Self {
+        self.options.tcb_policy = self.options.tcb_policy.allow_status(status);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::expected_mr_service_td`].
+    pub fn expected_mr_service_td(mut self, mr_service_td: [u8; 48]) -> Self {
+        self.options.expected_mr_service_td = Some(mr_service_td);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::expected_mr_config_id`].
+    pub fn expected_mr_config_id(mut self, mr_config_id: [u8; 48]) -> Self {
+        self.options.expected_mr_config_id = Some(mr_config_id);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::expected_mr_owner`].
+    pub fn expected_mr_owner(mut self, mr_owner: [u8; 48]) -> Self {
+        self.options.expected_mr_owner = Some(mr_owner);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::report_data_domain`].
+    pub fn report_data_domain(mut self, domain: Vec<u8>) -> Self {
+        self.options.report_data_domain = Some(domain);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::trusted_root_ca_der`].
+    pub fn trusted_root_ca_der(mut self, trusted_root_ca_der: Vec<u8>) -> Self {
+        self.options.trusted_root_ca_der = trusted_root_ca_der;
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::min_tcb_eval_number`].
+    pub fn min_tcb_eval_number(mut self, min_tcb_eval_number: u32) -> Self {
+        self.options.min_tcb_eval_number = Some(min_tcb_eval_number);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::skip_qe_identity`], see its warning.
+    pub fn skip_qe_identity(mut self, skip_qe_identity: bool) -> Self {
+        self.options.skip_qe_identity = skip_qe_identity;
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::reject_debug_td`].
+    pub fn reject_debug_td(mut self, reject_debug_td: bool) -> Self {
+        self.options.reject_debug_td = reject_debug_td;
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::max_collateral_age_secs`].
+    pub fn max_collateral_age_secs(mut self, max_collateral_age_secs: u64) -> Self {
+        self.options.max_collateral_age_secs = Some(max_collateral_age_secs);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::min_seam_svn`].
+    pub fn min_seam_svn(mut self, min_seam_svn: u16) -> Self {
+        self.options.min_seam_svn = Some(min_seam_svn);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::expected_rtmr3`].
+    pub fn expected_rtmr3(mut self, rtmr3: [u8; 48]) -> Self {
+        self.options.expected_rtmr3 = Some(rtmr3);
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::require_app_compose`].
+    pub fn require_app_compose(mut self, require_app_compose: bool) -> Self {
+        self.options.require_app_compose = require_app_compose;
+        self
+    }
+
+    /// Sets [`QuoteVerifyOptions::extended_collateral_checks`].
+    pub fn extended_collateral_checks(mut self, extended_collateral_checks: bool) -> Self {
+        self.options.extended_collateral_checks = extended_collateral_checks;
+        self
+    }
+
+    pub fn build(self) -> QuoteVerifyOptions {
+        self.options
+    }
+}
+
+/// Outcome of a successful [`DstackAttestation::verify_with_summary`].
+#[derive(Debug, Clone)]
+pub struct AttestationSummary {
+    /// The accepted measurements that matched the attestation.
+    pub measurements: FullMeasurements,
+    /// The verified PPID of the platform.
+    pub ppid: HexBytes<16>,
+    /// The FMSPC of the platform, that of the TCB info it was verified against.
+    pub fmspc: Fmspc,
+    /// The TCB status of the platform, accepted by [`QuoteVerifyOptions::tcb_policy`].
+    pub platform_tcb_status: TcbStatus,
+    /// The TCB status of the Quoting Enclave, see [`verify_qe_tcb`]. Also accepted by
+    /// [`QuoteVerifyOptions::tcb_policy`]. `None` if it was not evaluated, see
+    /// [`QuoteVerifyOptions::skip_qe_identity`].
+    pub qe_tcb_status: Option<TcbStatus>,
+    /// The `MRCONFIGID` of the report, checked against
+    /// [`QuoteVerifyOptions::expected_mr_config_id`] if set.
+    pub mr_config_id: [u8; 48],
+    /// The `MROWNER` of the report, checked against [`QuoteVerifyOptions::expected_mr_owner`]
+    /// if set.
+    pub mr_owner: [u8; 48],
+    /// The report data of the report. [`DstackAttestation::verify_with_summary`] checks it
+    /// against the expected report data.
+    pub report_data: ReportData,
+    /// Intel security advisories outstanding for the platform. Always empty for
+    /// [`TcbStatus::UpToDate`].
+    pub advisory_ids: Vec<String>,
+    /// Unix time in seconds the attestation was verified at, the `timestamp_seconds` passed to
+    /// [`DstackAttestation::verify_with_summary`].
+    pub verified_at: u64,
+    /// Unix time in seconds of the `issueDate` of the TCB info of the collateral, e.g. to show
+    /// how old the collateral the attestation was verified with is.
+    pub tcb_info_issued_at: u64,
+    /// Unix time in seconds of the `nextUpdate` of the TCB info of the collateral.
+    pub tcb_info_next_update: u64,
+}
+
+impl AttestationSummary {
+    /// A stable identifier of the node, combining the code it runs and the key it attested to:
+    /// `sha256(measurements fingerprint || public key hash of the report data)`, see
+    /// [`FullMeasurements::fingerprint`] and [`ReportData::public_key_hash`].
+    ///
+    /// The identity changes whenever either changes: an upgraded node, or one that rotated its
+    /// key, has a new identity.
+    pub fn node_identity(&self) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(self.measurements.fingerprint())
+            .chain_update(self.report_data.public_key_hash())
+            .finalize()
+            .into()
+    }
+}
+
+/// The measurements observed in the verified attestation, e.g. to capture them as a new
+/// baseline candidate.
+///
+/// Verification only succeeds if every register and digest of the attestation equals one of
+/// the accepted measurements, so this is exactly what the report and event log carry: the
+/// RTMRs of the report, the digest of the `key-provider` event and the payload of the
+/// `compose-hash` event.
+impl From<&AttestationSummary> for FullMeasurements {
+    fn from(summary: &AttestationSummary) -> Self {
+        summary.measurements
+    }
+}
 
 #[derive(Clone, Constructor, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 pub struct DstackAttestation {
@@ -43,12 +505,36 @@ pub struct DstackAttestation {
 pub enum VerificationError {
     #[error("could not parse embedded measurements: {0}")]
     EmbeddedMeasurementsParsing(MeasurementsError),
-    #[error("dcap verification failed: {0}")]
-    DcapVerification(String),
+    #[error("dcap verification failed{}: {reason}", quote_context_suffix(.context))]
+    DcapVerification {
+        reason: String,
+        /// The quote header, if it was long enough to be read.
+        context: Option<QuoteContext>,
+    },
     #[error("verification report is not TD10")]
     ReportNotTd10,
-    #[error("TCB status `{0}` is not up to date")]
-    TcbStatusNotUpToDate(String),
+    #[error("TDX module TCB evaluation failed: {0}")]
+    TdxModuleTcb(String),
+    #[error("QE TCB evaluation failed: {0}")]
+    QeTcb(String),
+    #[error("MRSERVICETD is pinned, but the report is not a TD 1.5 report")]
+    MissingMrServiceTd,
+    #[error("RTMR3 is pinned, but the report never extended it")]
+    MissingRtmr3,
+    #[error("an app compose is required, but the matched measurements have a zero payload")]
+    MissingAppCompose,
+    #[error("MRCONFIGID {found} does not match the expected {expected}")]
+    ConfigIdMismatch { expected: String, found: String },
+    #[error("MROWNER {found} does not match the expected {expected}")]
+    OwnerMismatch { expected: String, found: String },
+    #[error("report data is not bound to the domain `{0}`")]
+    DomainMismatch(String),
+    #[error("SEAM SVN {found:#06x} is below the required {minimum:#06x}")]
+    SeamSvnTooLow { found: u16, minimum: u16 },
+    #[error("debug TD is not allowed: {0}")]
+    DebugTdNotAllowed(String),
+    #[error("TCB status `{0}` is not allowed")]
+    TcbStatusNotAllowed(String),
     #[error("ouststanding advisories reported: {0}")]
     NonEmptyAdvisoryIds(String),
     #[error("wrong {name} hash (found {found} expected {expected})")]
@@ -84,10 +570,77 @@ pub enum VerificationError {
     PpidWrongSize(usize),
     #[error("the mock attestation is invalid per definition")]
     InvalidMockAttestation,
+    #[error("invalid attestation bundle: {0}")]
+    InvalidBundle(String),
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("quote was already accepted at {last_seen}")]
+    Replayed { last_seen: u64 },
+    #[error("resharing participant {index} was rejected: {error}")]
+    ResharingParticipant {
+        index: usize,
+        error: Box<VerificationError>,
+    },
+    #[error("resharing participant {index} binds report data {report_data}, not an expected key")]
+    UnexpectedParticipant { index: usize, report_data: String },
+    #[error("resharing participants {first} and {index} bind the same key {key}")]
+    DuplicateParticipant {
+        first: usize,
+        index: usize,
+        key: String,
+    },
+    #[error("no resharing participant binds the expected key {key}")]
+    MissingParticipant { key: String },
+    #[error("{quote_tee:?} quote cannot be verified with {collateral_tee:?} collateral")]
+    TeeTypeMismatch {
+        quote_tee: QuoteType,
+        collateral_tee: QuoteType,
+    },
+    #[error("collateral tcbEvaluationDataNumber {found} is below the required {minimum}")]
+    TcbEvaluationTooOld { found: u32, minimum: u32 },
+    #[error(
+        "TCB info issued at {issued_at} is {age_secs}s old, more than the allowed {max_age_secs}s"
+    )]
+    CollateralTooOld {
+        issued_at: u64,
+        age_secs: u64,
+        max_age_secs: u64,
+    },
+    #[error("collateral tcbEvaluationDataNumber {found} is below the highest seen, {highest}")]
+    TcbDowngrade { found: u32, highest: u32 },
+    #[error(
+        "collateral valid from {collateral_not_before} until {collateral_next_update} does not \
+         overlap the PCK certificate valid from {pck_not_before} until {pck_not_after}"
+    )]
+    CollateralQuoteTimeMismatch {
+        pck_not_before: u64,
+        pck_not_after: u64,
+        collateral_not_before: u64,
+        collateral_next_update: u64,
+    },
+    #[error("PCK certificate chain roots at {fingerprint}, not the Intel SGX Root CA")]
+    NonProductionRoot {
+        /// Hex SHA-256 fingerprint of the root certificate of the chain.
+        fingerprint: String,
+    },
+    #[error("report data {found} does not match the committed {expected}")]
+    ReportDataMismatch { expected: String, found: String },
+    #[error("collateral hash {found} does not match the committed {expected}")]
+    CollateralHashMismatch { expected: String, found: String },
+    #[error("verification was {recorded} when recorded, but is {replayed} when replayed")]
+    TranscriptMismatch { recorded: String, replayed: String },
+    #[error("collateral check failed: {0}")]
+    Collateral(String),
     #[error("custom error: `{0}`")]
     Custom(String),
 }
 
+fn quote_context_suffix(context: &Option<QuoteContext>) -> String {
+    context
+        .map(|context| format!(" ({context})"))
+        .unwrap_or_default()
+}
+
 impl fmt::Debug for DstackAttestation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         const MAX_BYTES: usize = 2048;
@@ -141,11 +694,7 @@ impl Default for DstackAttestation {
             app_compose: String::new(),
             event_log: Vec::new(),
         };
-        DstackAttestation::new(
-            QuoteBytes::from(Vec::new()),
-            collateral,
-            tcb_info,
-        )
+        DstackAttestation::new(QuoteBytes::from(Vec::new()), collateral, tcb_info)
     }
 }
 
@@ -167,9 +716,52 @@ impl DstackAttestation {
         accepted_measurements: &[FullMeasurements],
         accepted_ppids: &[HexBytes<16>],
     ) -> Result<(FullMeasurements, HexBytes<16>), VerificationError> {
-        let verification_result =
-            dcap_qvl::verify::verify(&self.quote, &self.collateral, timestamp_seconds)
-                .map_err(|e| VerificationError::DcapVerification(e.to_string()))?;
+        self.verify_with_options(
+            expected_report_data,
+            timestamp_seconds,
+            accepted_measurements,
+            accepted_ppids,
+            &QuoteVerifyOptions::default(),
+        )
+    }
+
+    /// Same as [`DstackAttestation::verify`], but with explicit [`QuoteVerifyOptions`].
+    pub fn verify_with_options(
+        &self,
+        expected_report_data: ReportData,
+        timestamp_seconds: u64,
+        accepted_measurements: &[FullMeasurements],
+        accepted_ppids: &[HexBytes<16>],
+        options: &QuoteVerifyOptions,
+    ) -> Result<(FullMeasurements, HexBytes<16>), VerificationError> {
+        self.verify_with_summary(
+            expected_report_data,
+            timestamp_seconds,
+            accepted_measurements,
+            accepted_ppids,
+            options,
+        )
+        .map(|summary| (summary.measurements, summary.ppid))
+    }
+
+    /// Same as [`DstackAttestation::verify_with_options`], but returns an
+    /// [`AttestationSummary`] including the TCB status and any accepted advisories.
+    pub fn verify_with_summary(
+        &self,
+        expected_report_data: ReportData,
+        timestamp_seconds: u64,
+        accepted_measurements: &[FullMeasurements],
+        accepted_ppids: &[HexBytes<16>],
+        options: &QuoteVerifyOptions,
+    ) -> Result<AttestationSummary, VerificationError> {
+        let parsed = ParsedQuote::parse(&self.quote);
+        let verification_result = verify_parsed_quote(
+            &self.quote,
+            parsed.as_ref(),
+            &self.collateral,
+            timestamp_seconds,
+            options,
+        )?;
 
         let report_data = verification_result
             .report
@@ -177,8 +769,17 @@ impl DstackAttestation {
             .ok_or(VerificationError::ReportNotTd10)?;
 
         // Verify all attestation components
-        self.verify_tcb_status(&verification_result)?;
-        self.verify_report_data(&expected_report_data, report_data)?;
+        let (platform_tcb_status, qe_tcb_status) = verify_parsed_report_policy(
+            parsed.as_ref(),
+            &self.collateral,
+            &verification_result,
+            options,
+        )?;
+        self.verify_report_data(
+            &expected_report_data,
+            options.report_data_domain.as_deref(),
+            report_data,
+        )?;
         let ppid = self.verify_ppid(verification_result.ppid, accepted_ppids)?;
 
         self.verify_rtmr3(report_data, &self.tcb_info)?;
@@ -186,7 +787,31 @@ impl DstackAttestation {
 
         let measurements =
             self.verify_any_measurements(report_data, &self.tcb_info, accepted_measurements)?;
-        Ok((measurements, ppid))
+        if options.require_app_compose {
+            verify_app_compose_present(&measurements)?;
+        }
+        let tcb_info_validity = self
+            .collateral
+            .tcb_info_validity()
+            .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+        let fmspc = self
+            .collateral
+            .fmspc()
+            .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+        Ok(AttestationSummary {
+            measurements,
+            ppid,
+            fmspc,
+            platform_tcb_status,
+            qe_tcb_status,
+            mr_config_id: report_data.mr_config_id,
+            mr_owner: report_data.mr_owner,
+            report_data: ReportData::new(report_data.report_data),
+            advisory_ids: verification_result.advisory_ids,
+            verified_at: timestamp_seconds,
+            tcb_info_issued_at: tcb_info_validity.not_before,
+            tcb_info_next_update: tcb_info_validity.next_update,
+        })
     }
 
     /// Replays RTMR3 from the event log by hashing all relevant events together and verifies all
@@ -254,39 +879,25 @@ impl DstackAttestation {
         compare_hashes("app_compose_payload", &app_compose_hash, &expected_payload)
     }
 
-    /// Verifies TCB status and security advisories.
-    fn verify_tcb_status(
-        &self,
-        verification_result: &VerifiedReport,
-    ) -> Result<(), VerificationError> {
-        // The "UpToDate" TCB status indicates that the measured platform components (CPU
-        // microcode, firmware, etc.) match the latest known good values published by Intel
-        // and do not require any updates or mitigations.
-        let status_is_up_to_date = verification_result.status == EXPECTED_QUOTE_STATUS;
-
-        // Advisory IDs indicate known security vulnerabilities or issues with the TEE.
-        // For a quote to be considered secure, there should be no outstanding advisories.
-        let no_security_advisories = verification_result.advisory_ids.is_empty();
-
-        status_is_up_to_date.or_err(|| {
-            VerificationError::TcbStatusNotUpToDate(verification_result.status.clone())
-        })?;
-
-        no_security_advisories.or_err(|| {
-            VerificationError::NonEmptyAdvisoryIds(verification_result.advisory_ids.join(", "))
-        })?;
-
-        Ok(())
-    }
-
     /// Verifies report data matches expected values.
     fn verify_report_data(
         &self,
         expected: &ReportData,
+        domain: Option<&[u8]>,
         actual: &dcap_qvl::quote::TDReport10,
     ) -> Result<(), VerificationError> {
-        // Check the report data from the report matches the expected report data.
-        compare_hashes("report_data", &actual.report_data, &expected.to_bytes())
+        match domain {
+            Some(domain) => verify_with_domain(
+                &ReportData::new(actual.report_data),
+                domain,
+                expected.as_bytes(),
+            )
+            .map_err(|_| {
+                VerificationError::DomainMismatch(String::from_utf8_lossy(domain).into_owned())
+            }),
+            // Check the report data from the report matches the expected report data.
+            None => compare_hashes("report_data", &actual.report_data, expected.as_bytes()),
+        }
     }
 
     /// Verifies PPID is in the allowed PPIDs list. Returns the matched PPID on success.
@@ -472,7 +1083,7 @@ impl DstackAttestation {
     }
 
     // Implementation taken to match Dstack's https://github.com/Dstack-TEE/dstack/blob/cfa4cc4e8a4f525d537883b1a0ba5d9fbfd87f1e/cc-eventlog/src/lib.rs#L54
-    fn event_digest(event_type: u32, event: &str, payload: &[u8]) -> [u8; 48] {
+    pub(crate) fn event_digest(event_type: u32, event: &str, payload: &[u8]) -> [u8; 48] {
         let mut hasher = Sha384::new();
         hasher.update(event_type.to_ne_bytes());
         hasher.update(b":");
@@ -483,6 +1094,648 @@ impl DstackAttestation {
     }
 }
 
+/// Outcome of [`quick_check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickCheckResult {
+    /// The MRTD and RTMR0-2 of the quote equal the expected ones.
+    MeasurementsMatch,
+    /// The quote carries other registers, as listed in the diff from the expected ones.
+    MeasurementsMismatch(MeasurementsDiff),
+    /// The quote could not be parsed, or does not carry a TD report.
+    Unparseable(String),
+}
+
+/// Compares the MRTD and RTMR0-2 of `quote` with `expected`, **without verifying anything**.
+///
+/// **This is not a security check.** Neither the quote signature, nor its certificate chain,
+/// nor the TCB status is verified, so anyone can produce a quote that matches. It is only a
+/// cheap signal of whether a quote comes from the expected image, e.g. to validate input in a
+/// UI before running [`DstackAttestation::verify`]. The key-provider and app compose digests
+/// of `expected` are not compared either, as they are part of the event log, not the quote.
+pub fn quick_check(quote: &[u8], expected: &FullMeasurements) -> QuickCheckResult {
+    let parsed = match ParsedQuote::parse(quote) {
+        Ok(parsed) => parsed,
+        Err(e) => return QuickCheckResult::Unparseable(e.to_string()),
+    };
+    let found = match Measurements::try_from(&parsed.quote().report) {
+        Ok(found) => found,
+        Err(e) => return QuickCheckResult::Unparseable(e.to_string()),
+    };
+
+    let diff = expected.rtmrs.diff(&found);
+    if diff.is_empty() {
+        QuickCheckResult::MeasurementsMatch
+    } else {
+        QuickCheckResult::MeasurementsMismatch(diff)
+    }
+}
+
+/// Why [`detect_measurement_change`] flagged a report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeasurementChange {
+    /// The MRTD or RTMR0-2 of the report differ from the previous ones, as listed in the diff
+    /// from the previous to the current measurements.
+    Changed(MeasurementsDiff),
+    /// The measurements could not be read from the report, e.g. because it is not a TD report.
+    Unreadable(MeasurementsError),
+}
+
+/// Checks that the MRTD and RTMR0-2 of `current_report` still equal those of `previous`, for
+/// a watcher that periodically re-attests a node. Any change mid-operation means the node
+/// rebooted into another image, or is compromised, and should be alerted on.
+///
+/// This does not allocate if the measurements did not change. Only the registers of the report
+/// are compared; the key-provider and app compose digests are measured into RTMR3 through the
+/// event log, see [`FullMeasurements::verify_report`].
+pub fn detect_measurement_change(
+    previous: &FullMeasurements,
+    current_report: &VerifiedReport,
+) -> Result<(), MeasurementChange> {
+    let current = Measurements::try_from(current_report).map_err(MeasurementChange::Unreadable)?;
+    if previous.rtmrs == current {
+        Ok(())
+    } else {
+        Err(MeasurementChange::Changed(previous.rtmrs.diff(&current)))
+    }
+}
+
+/// Verifies the quote signature, certificate chain and collateral at `timestamp_seconds`.
+///
+/// With [`QuoteVerifyOptions::extended_collateral_checks`], the collateral is first checked
+/// against the default [`CollateralLimits`], as it may come from an untrusted party. The issuer
+/// chains of the collateral are then checked to end with
+/// [`QuoteVerifyOptions::trusted_root_ca_der`] and against the root CA CRL, the TCB info and
+/// QE identity issuer chains to start with the TCB signing certificate, and the PCK CRL to be
+/// signed by its issuer chain, before running DCAP verification, see
+/// [`Collateral::check_trust_anchor`], [`Collateral::check_root_ca_revocations`],
+/// [`Collateral::check_issuer_chains`] and [`Collateral::check_pck_crl`].
+///
+/// The collateral validity window is checked with the clock skew allowed by `options`. If the
+/// timestamp is within the tolerated skew, the quote is evaluated at the nearest point in time
+/// inside the validity window.
+pub fn verify_quote(
+    quote: &[u8],
+    collateral: &Collateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<VerifiedReport, VerificationError> {
+    verify_parsed_quote(
+        quote,
+        ParsedQuote::parse(quote).as_ref(),
+        collateral,
+        timestamp_seconds,
+        options,
+    )
+}
+
+/// Same as [`verify_quote`], for a quote already parsed into `parsed`, so that callers which
+/// also check its policy parse it only once.
+fn verify_parsed_quote(
+    quote: &[u8],
+    parsed: Result<&ParsedQuote, &QuoteError>,
+    collateral: &Collateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<VerifiedReport, VerificationError> {
+    if options.extended_collateral_checks {
+        collateral
+            .check_limits(&CollateralLimits::default())
+            .and_then(|()| collateral.check_trust_anchor(&options.trusted_root_ca_der))
+            .and_then(|()| collateral.check_issuer_chains(&options.trusted_root_ca_der))
+            .and_then(|()| collateral.check_pck_crl(&options.trusted_root_ca_der))
+            .and_then(|()| collateral.check_root_ca_revocations())
+            .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+    }
+    let validity = collateral
+        .validity_window()
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+
+    verify_quote_within(
+        quote,
+        parsed,
+        collateral,
+        validity,
+        timestamp_seconds,
+        options,
+    )
+}
+
+/// Verifies `quote` as [`verify_quote`] does with the default [`QuoteVerifyOptions`], after
+/// checking that `collateral` is the one committed to by `committed_hash`, its
+/// [`Collateral::canonical_hash`]. This lets e.g. a contract store only the 32 byte commitment,
+/// while the node supplies the full collateral.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::CollateralHashMismatch`] if the collateral does not match the
+/// commitment, and the errors of [`verify_quote`] otherwise.
+pub fn verify_with_committed_collateral(
+    quote: &[u8],
+    collateral: &Collateral,
+    committed_hash: [u8; 32],
+    timestamp_seconds: u64,
+) -> Result<VerifiedReport, VerificationError> {
+    let found = collateral.canonical_hash();
+    (found == committed_hash).or_err(|| VerificationError::CollateralHashMismatch {
+        expected: hex::encode(committed_hash),
+        found: hex::encode(found),
+    })?;
+
+    verify_quote(
+        quote,
+        collateral,
+        timestamp_seconds,
+        &QuoteVerifyOptions::default(),
+    )
+}
+
+/// Verifies only that `quote` is well-formed and signed by a genuine Intel platform: the quote
+/// signature, the PCK certificate chain and the collateral at `timestamp_seconds`, as
+/// [`verify_quote`] with the default [`QuoteVerifyOptions`].
+///
+/// **This is not a complete security decision on its own.** The TCB status and advisories of
+/// the returned report are not checked against any policy, and neither are the report data,
+/// the measurements or the event log. Use it for debugging, or as the first stage of a flow
+/// which checks them later, e.g. with [`verify_tcb_status`], [`verify_qe_tcb`] and
+/// [`FullMeasurements::verify_report`].
+pub fn verify_signature_only(
+    quote: &[u8],
+    collateral: &Collateral,
+    timestamp_seconds: u64,
+) -> Result<VerifiedReport, VerificationError> {
+    verify_quote(
+        quote,
+        collateral,
+        timestamp_seconds,
+        &QuoteVerifyOptions::default(),
+    )
+}
+
+/// Same as [`verify_quote`], for a quote parsed into `parsed` and a collateral whose validity
+/// window is already known and whose issuer chains have already been checked against the trust
+/// anchor and the root CA CRL, if [`QuoteVerifyOptions::extended_collateral_checks`] is set.
+pub(crate) fn verify_quote_within(
+    quote: &[u8],
+    parsed: Result<&ParsedQuote, &QuoteError>,
+    collateral: &Collateral,
+    validity: CollateralValidity,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<VerifiedReport, VerificationError> {
+    if options.extended_collateral_checks {
+        check_tee_type(quote, collateral)?;
+        check_fmspc(parsed.ok(), collateral)?;
+        check_pck_chain(parsed.ok(), collateral)?;
+    }
+    validity
+        .check(timestamp_seconds, options.allowed_skew_secs)
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+    if options.extended_collateral_checks {
+        collateral
+            .check_crls(timestamp_seconds, options.allowed_skew_secs)
+            .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+        collateral_quote_time(parsed.ok(), collateral, validity)?;
+    }
+
+    if let Some(minimum) = options.min_tcb_eval_number {
+        let found = collateral
+            .tcb_evaluation_number()
+            .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+        (found >= minimum).or_err(|| VerificationError::TcbEvaluationTooOld { found, minimum })?;
+    }
+    if let Some(max_age_secs) = options.max_collateral_age_secs {
+        let issued_at = collateral
+            .tcb_info_validity()
+            .map_err(|e| VerificationError::Collateral(e.to_string()))?
+            .not_before;
+        let age_secs = timestamp_seconds.saturating_sub(issued_at);
+        (age_secs <= max_age_secs).or_err(|| VerificationError::CollateralTooOld {
+            issued_at,
+            age_secs,
+            max_age_secs,
+        })?;
+    }
+
+    let effective_timestamp_seconds = validity.clamp(timestamp_seconds);
+
+    dcap_qvl::verify::verify(quote, collateral, effective_timestamp_seconds).map_err(|e| {
+        VerificationError::DcapVerification {
+            reason: e.to_string(),
+            context: QuoteContext::from_header(quote).ok(),
+        }
+    })
+}
+
+/// Checks the report of `quote`, verified against `collateral` e.g. by [`verify_quote`],
+/// against the TCB policy and the report constraints of `options`, and returns the platform
+/// and QE TCB statuses. The QE TCB status is `None` if
+/// [`QuoteVerifyOptions::skip_qe_identity`] is set.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::TcbStatusNotAllowed`] if the platform, QE or TDX module TCB
+/// status is not allowed by the policy, and the errors of the report constraint checks, such
+/// as [`verify_mr_config_id`], otherwise.
+pub fn verify_report_policy(
+    quote: &[u8],
+    collateral: &Collateral,
+    verified: &VerifiedReport,
+    options: &QuoteVerifyOptions,
+) -> Result<(TcbStatus, Option<TcbStatus>), VerificationError> {
+    verify_parsed_report_policy(
+        ParsedQuote::parse(quote).as_ref(),
+        collateral,
+        verified,
+        options,
+    )
+}
+
+/// Same as [`verify_report_policy`], for a quote already parsed into `parsed`.
+fn verify_parsed_report_policy(
+    parsed: Result<&ParsedQuote, &QuoteError>,
+    collateral: &Collateral,
+    verified: &VerifiedReport,
+    options: &QuoteVerifyOptions,
+) -> Result<(TcbStatus, Option<TcbStatus>), VerificationError> {
+    let platform_tcb_status = verify_tcb_status(verified, &options.tcb_policy)?;
+    let qe_tcb_status = if options.skip_qe_identity {
+        tracing::warn!("skipping QE identity verification");
+        None
+    } else {
+        Some(verify_parsed_qe_tcb(
+            parsed,
+            &collateral.qe_identity,
+            &options.tcb_policy,
+        )?)
+    };
+    verify_mr_service_td(&verified.report, options.expected_mr_service_td)?;
+    verify_mr_config_id(&verified.report, options.expected_mr_config_id)?;
+    verify_mr_owner(&verified.report, options.expected_mr_owner)?;
+    if options.reject_debug_td {
+        verify_not_debug(&verified.report)?;
+    }
+    verify_seam_svn(&verified.report, options.min_seam_svn)?;
+    verify_expected_rtmr3(&verified.report, options.expected_rtmr3)?;
+    verify_parsed_tdx_module_tcb(
+        parsed.ok(),
+        &verified.report,
+        collateral,
+        &options.tcb_policy,
+    )?;
+    Ok((platform_tcb_status, qe_tcb_status))
+}
+
+/// Checks that the validity period of the PCK certificate of `quote`, from the PCK certificate
+/// chain of the quote or else of `collateral`, overlaps the `validity` window of the
+/// collateral, so that a quote is not verified against collateral from another era, e.g. a new
+/// platform's quote with collateral issued before its PCK certificate. This complements
+/// [`CollateralValidity::check`], which only checks the verification time.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::CollateralQuoteTimeMismatch`] if the windows do not overlap,
+/// [`VerificationError::InvalidInput`] if neither the quote nor the collateral has a readable
+/// PCK certificate chain, and [`VerificationError::Collateral`] if the chain is malformed.
+pub fn check_collateral_quote_time(
+    quote: &[u8],
+    collateral: &Collateral,
+    validity: CollateralValidity,
+) -> Result<(), VerificationError> {
+    collateral_quote_time(
+        ParsedQuote::parse(quote).ok().as_ref(),
+        collateral,
+        validity,
+    )
+}
+
+/// Same as [`check_collateral_quote_time`], for a quote parsed into `parsed`, if it could be.
+fn collateral_quote_time(
+    parsed: Option<&ParsedQuote>,
+    collateral: &Collateral,
+    validity: CollateralValidity,
+) -> Result<(), VerificationError> {
+    let chain = pck_chain(parsed, collateral)
+        .ok_or_else(|| VerificationError::InvalidInput("no PCK certificate chain".to_string()))?;
+    let pck_validity = Certificates::parse_any(chain)
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?
+        .first()
+        .map(|pck| pck.tbs_certificate.validity)
+        .ok_or_else(|| VerificationError::Collateral("empty PCK certificate chain".to_string()))?;
+    let pck_not_before = pck_validity.not_before.to_unix_duration().as_secs();
+    let pck_not_after = pck_validity.not_after.to_unix_duration().as_secs();
+
+    (validity.next_update >= pck_not_before && validity.not_before <= pck_not_after).or_err(|| {
+        VerificationError::CollateralQuoteTimeMismatch {
+            pck_not_before,
+            pck_not_after,
+            collateral_not_before: validity.not_before,
+            collateral_next_update: validity.next_update,
+        }
+    })
+}
+
+/// Checks that the PCK certificate chain of `quote`, or of `collateral` if the quote does not
+/// embed one, roots at the production Intel SGX Root CA, i.e. that its last certificate has
+/// the fingerprint [`INTEL_SGX_ROOT_CA_SHA256`]. Quotes from pre-production platforms and
+/// from TDX emulators chain to other roots, such as Intel's test CAs.
+///
+/// Unlike [`verify_quote`], this ignores [`QuoteVerifyOptions::trusted_root_ca_der`], so that
+/// a deployment which overrides the trust anchor for testing can still refuse such quotes in
+/// production.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::NonProductionRoot`] if the chain roots elsewhere,
+/// [`VerificationError::InvalidInput`] if the quote cannot be parsed or neither the quote nor
+/// the collateral has a PCK certificate chain, and [`VerificationError::Collateral`] if the
+/// chain is malformed.
+pub fn assert_production_root(
+    quote: &[u8],
+    collateral: &Collateral,
+) -> Result<(), VerificationError> {
+    let parsed = ParsedQuote::parse(quote)
+        .map_err(|e| VerificationError::InvalidInput(format!("invalid quote: {e}")))?;
+    let chain = pck_chain(Some(&parsed), collateral)
+        .ok_or_else(|| VerificationError::InvalidInput("no PCK certificate chain".to_string()))?;
+
+    let root = Certificates::parse_any(chain)
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?
+        .last()
+        .map(Encode::to_der)
+        .transpose()
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?
+        .ok_or_else(|| VerificationError::Collateral("empty PCK certificate chain".to_string()))?;
+    let fingerprint = hex::encode(Sha256::digest(root));
+
+    (fingerprint == INTEL_SGX_ROOT_CA_SHA256)
+        .or_err(|| VerificationError::NonProductionRoot { fingerprint })
+}
+
+/// The PCK certificate chain embedded in `parsed`, or else the one of `collateral`, as parsed
+/// by [`Certificates::parse_any`]. A quote which cannot be parsed, e.g. because its
+/// certification data embeds no chain, has its chain read from the collateral.
+fn pck_chain<'a>(parsed: Option<&'a ParsedQuote>, collateral: &'a Collateral) -> Option<&'a [u8]> {
+    parsed
+        .and_then(|parsed| parsed.pck_cert_chain().ok())
+        .or_else(|| {
+            collateral
+                .pck_certificate_chain
+                .as_ref()
+                .map(String::as_bytes)
+        })
+}
+
+/// Checks that the TCB info of `collateral` is for the FMSPC of `quote`, see
+/// [`assert_fmspc_consistency`]. Quotes and TCB info whose FMSPC cannot be read are left to DCAP
+/// verification to reject.
+fn check_fmspc(
+    parsed: Option<&ParsedQuote>,
+    collateral: &Collateral,
+) -> Result<(), VerificationError> {
+    let Some(parsed) = parsed else {
+        return Ok(());
+    };
+    match assert_fmspc_consistency(parsed, collateral) {
+        Err(e @ CollateralError::FmspcMismatch { .. }) => {
+            Err(VerificationError::Collateral(e.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the PCK certificate chain of `quote`, or else that of `collateral`, follows the
+/// X.509 constraints of its certificates and is at most [`MAX_PCK_CHAIN_LEN`] long, see
+/// [`Certificates::check_constraints`]. DCAP verification only checks the signatures of the
+/// chain. Quotes without a readable chain are left to DCAP verification to reject.
+fn check_pck_chain(
+    parsed: Option<&ParsedQuote>,
+    collateral: &Collateral,
+) -> Result<(), VerificationError> {
+    let Some(chain) = pck_chain(parsed, collateral) else {
+        return Ok(());
+    };
+    Certificates::parse_any(chain)
+        .and_then(|chain| chain.check_constraints(MAX_PCK_CHAIN_LEN))
+        .map_err(|e| VerificationError::Collateral(e.to_string()))
+}
+
+/// Checks that `quote` and `collateral` are for the same kind of TEE, see
+/// [`crate::quote::quote_type`] and [`Collateral::tee_type`], so that pairing e.g. a TDX quote
+/// with SGX collateral fails early and clearly. Quotes and collateral whose TEE type cannot be
+/// read are left to DCAP verification to reject.
+fn check_tee_type(quote: &[u8], collateral: &Collateral) -> Result<(), VerificationError> {
+    match (quote_type(quote), collateral.tee_type()) {
+        (Ok(quote_tee), Some(collateral_tee)) if quote_tee != collateral_tee => {
+            Err(VerificationError::TeeTypeMismatch {
+                quote_tee,
+                collateral_tee,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Verifies the TCB status and advisories are acceptable by `policy`, see
+/// [`TcbPolicy::is_acceptable`], and returns the status.
+///
+/// The "UpToDate" TCB status indicates that the measured platform components (CPU microcode,
+/// firmware, etc.) match the latest known good values published by Intel and do not require
+/// any updates or mitigations. Advisory IDs indicate known security vulnerabilities or issues
+/// with the TEE. They are only accepted along with an explicitly allowed status other than
+/// "UpToDate", and are logged so they can be reviewed.
+pub fn verify_tcb_status(
+    verification_result: &VerifiedReport,
+    policy: &TcbPolicy,
+) -> Result<TcbStatus, VerificationError> {
+    let status: TcbStatus = verification_result
+        .status
+        .parse()
+        .map_err(|_| VerificationError::TcbStatusNotAllowed(verification_result.status.clone()))?;
+
+    policy
+        .allows_status(status)
+        .or_err(|| VerificationError::TcbStatusNotAllowed(verification_result.status.clone()))?;
+
+    if !verification_result.advisory_ids.is_empty() {
+        policy
+            .is_acceptable(status, &verification_result.advisory_ids)
+            .or_err(|| {
+                VerificationError::NonEmptyAdvisoryIds(verification_result.advisory_ids.join(", "))
+            })?;
+        tracing::warn!(
+            %status,
+            advisory_ids = %verification_result.advisory_ids.join(", "),
+            "accepting TCB status with outstanding advisories"
+        );
+    }
+
+    Ok(status)
+}
+
+/// Evaluates the TCB level of the Quoting Enclave of `quote` against the QE identity, see
+/// [`evaluate_qe_tcb`], and verifies that the QE TCB status is allowed by `policy`.
+pub fn verify_qe_tcb(
+    quote: &[u8],
+    qe_identity: &str,
+    policy: &TcbPolicy,
+) -> Result<TcbStatus, VerificationError> {
+    verify_parsed_qe_tcb(ParsedQuote::parse(quote).as_ref(), qe_identity, policy)
+}
+
+/// Same as [`verify_qe_tcb`], for a quote already parsed into `parsed`.
+fn verify_parsed_qe_tcb(
+    parsed: Result<&ParsedQuote, &QuoteError>,
+    qe_identity: &str,
+    policy: &TcbPolicy,
+) -> Result<TcbStatus, VerificationError> {
+    let parsed = parsed.map_err(|e| VerificationError::QeTcb(e.to_string()))?;
+    let status = evaluate_qe_tcb(qe_isv_svn(parsed), qe_identity)
+        .map_err(|e| VerificationError::QeTcb(e.to_string()))?;
+
+    policy
+        .allows_status(status)
+        .or_err(|| VerificationError::TcbStatusNotAllowed(format!("QE {status}")))?;
+
+    Ok(status)
+}
+
+/// Verifies the TDX TCB level of the TD report of `quote` against the TCB info of `collateral`,
+/// see [`evaluate_tdx_module_tcb`], and that its status is allowed by `policy`. The SGX SVNs of
+/// the platform are read from the PCK certificate chain of the quote, or else of `collateral`.
+pub fn verify_tdx_module_tcb(
+    quote: &[u8],
+    report: &Report,
+    collateral: &Collateral,
+    policy: &TcbPolicy,
+) -> Result<TcbStatus, VerificationError> {
+    verify_parsed_tdx_module_tcb(
+        ParsedQuote::parse(quote).ok().as_ref(),
+        report,
+        collateral,
+        policy,
+    )
+}
+
+/// Same as [`verify_tdx_module_tcb`], for a quote parsed into `parsed`, if it could be.
+fn verify_parsed_tdx_module_tcb(
+    parsed: Option<&ParsedQuote>,
+    report: &Report,
+    collateral: &Collateral,
+    policy: &TcbPolicy,
+) -> Result<TcbStatus, VerificationError> {
+    tee_tcb_svn(report).ok_or(VerificationError::ReportNotTd10)?;
+    let chain = pck_chain(parsed, collateral)
+        .ok_or_else(|| VerificationError::TdxModuleTcb("no PCK certificate chain".to_string()))?;
+    let status = pck_svn(chain)
+        .and_then(|pck_svn| evaluate_tdx_module_tcb(report, &pck_svn, &collateral.tcb_info))
+        .map_err(|e| VerificationError::TdxModuleTcb(e.to_string()))?;
+
+    policy
+        .allows_status(status)
+        .or_err(|| VerificationError::TcbStatusNotAllowed(format!("TDX module {status}")))?;
+
+    Ok(status)
+}
+
+/// Verifies the report's `MRSERVICETD` matches `expected`, if set.
+pub fn verify_mr_service_td(
+    report: &Report,
+    expected: Option<[u8; 48]>,
+) -> Result<(), VerificationError> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let found = mr_service_td(report).ok_or(VerificationError::MissingMrServiceTd)?;
+    compare_hashes("mr_service_td", &found, &expected)
+}
+
+/// Verifies the report's `MRCONFIGID` matches `expected`, if set.
+pub fn verify_mr_config_id(
+    report: &Report,
+    expected: Option<[u8; 48]>,
+) -> Result<(), VerificationError> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let found = mr_config_id(report).ok_or(VerificationError::ReportNotTd10)?;
+    (found == expected).or_err(|| VerificationError::ConfigIdMismatch {
+        expected: hex::encode(expected),
+        found: hex::encode(found),
+    })
+}
+
+/// Verifies the report's `MROWNER` matches `expected`, if set.
+pub fn verify_mr_owner(
+    report: &Report,
+    expected: Option<[u8; 48]>,
+) -> Result<(), VerificationError> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let found = mr_owner(report).ok_or(VerificationError::ReportNotTd10)?;
+    (found == expected).or_err(|| VerificationError::OwnerMismatch {
+        expected: hex::encode(expected),
+        found: hex::encode(found),
+    })
+}
+
+/// Checks that `report` comes from a TD running on a TDX module with a [`seam_svn`] of at least
+/// `minimum`, if set. SGX reports are rejected with [`VerificationError::ReportNotTd10`].
+pub fn verify_seam_svn(report: &Report, minimum: Option<u16>) -> Result<(), VerificationError> {
+    let Some(minimum) = minimum else {
+        return Ok(());
+    };
+    let found = seam_svn(report).ok_or(VerificationError::ReportNotTd10)?;
+    (found >= minimum).or_err(|| VerificationError::SeamSvnTooLow { found, minimum })
+}
+
+/// Verifies the report's RTMR3 matches `expected`, if set, see
+/// [`QuoteVerifyOptions::expected_rtmr3`].
+pub fn verify_expected_rtmr3(
+    report: &Report,
+    expected: Option<[u8; 48]>,
+) -> Result<(), VerificationError> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let found = ExtendedMeasurements::try_from(report)
+        .map_err(VerificationError::EmbeddedMeasurementsParsing)?
+        .rtmr3
+        .ok_or(VerificationError::MissingRtmr3)?;
+    compare_hashes("rtmr3", &found, &expected)
+}
+
+/// Checks that `measurements` pin an app compose, i.e. their
+/// [`FullMeasurements::app_compose_hash_payload`] is not all zeros, the value
+/// [`FullMeasurements`] defaults it to.
+pub fn verify_app_compose_present(
+    measurements: &FullMeasurements,
+) -> Result<(), VerificationError> {
+    (measurements.app_compose_hash_payload != [0; 32])
+        .or_err(|| VerificationError::MissingAppCompose)
+}
+
+/// Checks that `report` does not come from a debug TD or a debug TDX module: the `DEBUG` bit of
+/// its `TD_ATTRIBUTES` is clear, and its `SEAM_ATTRIBUTES` are zero, which they are for every
+/// production TDX module. SGX reports are rejected with [`VerificationError::ReportNotTd10`].
+///
+/// This is a property of the TD and its module, not of the platform: use
+/// [`assert_production_root`] to reject pre-production platforms.
+pub fn verify_not_debug(report: &Report) -> Result<(), VerificationError> {
+    let report = report.as_td10().ok_or(VerificationError::ReportNotTd10)?;
+    let debug = report
+        .td_attributes
+        .first()
+        .is_some_and(|byte| byte & TD_ATTRIBUTES_DEBUG != 0);
+    (!debug).or_err(|| VerificationError::DebugTdNotAllowed("the TD is a debug TD".to_string()))?;
+    (report.seam_attributes == [0; 8]).or_err(|| {
+        VerificationError::DebugTdNotAllowed(format!(
+            "the TDX module has SEAM attributes {}",
+            hex::encode(report.seam_attributes)
+        ))
+    })
+}
+
 fn compare_hashes(
     name: &'static str,
     found: &[u8],
@@ -522,9 +1775,14 @@ pub trait GetSingleEvent {
 }
 
 impl GetSingleEvent for TcbInfo {
+    fn get_single_event(&self, event_name: &'static str) -> Result<&EventLog, VerificationError> {
+        self.event_log.get_single_event(event_name)
+    }
+}
+
+impl GetSingleEvent for [EventLog] {
     fn get_single_event(&self, event_name: &'static str) -> Result<&EventLog, VerificationError> {
         let mut events = self
-            .event_log
             .iter()
             .filter(|event| event.event == event_name && event.imr == RTMR3_INDEX);
 
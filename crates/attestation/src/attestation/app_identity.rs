@@ -0,0 +1,45 @@
+use super::{COMPOSE_HASH_EVENT, DstackAttestation, GetSingleEvent as _, VerificationError};
+use crate::tcb_info::EventLog;
+
+use dcap_qvl::verify::VerifiedReport;
+
+/// Checks that the app running in the TD of `report` was deployed with exactly `app_compose`:
+///
+/// 1. The compose hash is `sha256(app_compose)`.
+/// 2. The event log has a single `compose-hash` event in RTMR3, whose payload is the compose
+///    hash and whose digest commits to that payload.
+/// 3. Replaying the RTMR3 events of the log, with every digest checked against its event,
+///    produces RTMR3 of `report`.
+///
+/// `app_compose` must be the app compose string exactly as deployed, e.g. the `app_compose` of
+/// the [`crate::tcb_info::TcbInfo`]: the hash is over its bytes, which an
+/// [`crate::app_compose::AppCompose`] does not preserve. Current dstack images measure the
+/// compose hash into RTMR3; see [`crate::measurements::verify_app_compose_in_rtmr2`] for
+/// images that extend it into RTMR2.
+///
+/// `report` must already be verified, e.g. by [`super::verify_quote`]; this does not check the
+/// other registers or the report data.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::ReportNotTd10`] if `report` is not a TD report,
+/// [`VerificationError::MissingEvent`] or [`VerificationError::DuplicateEvent`] if there is not
+/// exactly one `compose-hash` event, [`VerificationError::WrongHash`] if any hash in the chain
+/// does not match, and the errors of [`super::DstackAttestation::verify`] for malformed events.
+pub fn verify_app_identity(
+    app_compose: &str,
+    event_log: &[EventLog],
+    report: &VerifiedReport,
+) -> Result<(), VerificationError> {
+    let td10 = report
+        .report
+        .as_td10()
+        .ok_or(VerificationError::ReportNotTd10)?;
+
+    let compose_event = event_log.get_single_event(COMPOSE_HASH_EVENT)?;
+    DstackAttestation::validate_app_compose_payload(&compose_event.event_payload, app_compose)?;
+
+    // Also checks that the digest of every RTMR3 event, including `compose-hash`, commits to
+    // its payload.
+    DstackAttestation::verify_event_log_rtmr3(event_log, td10.rt_mr3)
+}
@@ -0,0 +1,117 @@
+use super::{OrErr as _, QuoteVerifyOptions, VerificationError, verify_quote_within};
+use crate::{
+    collateral::PreparedCollateral,
+    quote::{ParsedQuote, split_aggregate},
+};
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use dcap_qvl::verify::VerifiedReport;
+
+/// Verifies each quote against the same prepared collateral. The results are returned in the
+/// same order as `quotes`.
+///
+/// Each quote fails with [`VerificationError::Collateral`] if the trust anchor of `options` is
+/// not the one `collateral` was prepared with, see [`PreparedCollateral::new`].
+pub fn verify_batch(
+    quotes: &[&[u8]],
+    collateral: &PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Vec<Result<VerifiedReport, VerificationError>> {
+    quotes
+        .iter()
+        .map(|quote| verify_single(quote, collateral, timestamp_seconds, options))
+        .collect()
+}
+
+/// Splits an aggregate quote, the crate's envelope of [`crate::quote::split_aggregate`], and
+/// verifies each embedded quote as [`verify_batch`] does. The results are in the order of the
+/// embedded quotes.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::InvalidInput`] if `raw` is not an aggregate quote, including
+/// if it is a single quote.
+pub fn verify_aggregate(
+    raw: &[u8],
+    collateral: &PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<Vec<Result<VerifiedReport, VerificationError>>, VerificationError> {
+    let quotes =
+        split_aggregate(raw).map_err(|e| VerificationError::InvalidInput(e.to_string()))?;
+    Ok(verify_batch(
+        &quotes,
+        collateral,
+        timestamp_seconds,
+        options,
+    ))
+}
+
+/// Lazily verifies each quote yielded by `quotes` against the same prepared collateral, in
+/// order, without buffering the input.
+///
+/// If `abort_on_first_failure` is set, the iterator ends right after yielding the first
+/// error.
+pub fn verify_stream<'a, I>(
+    quotes: I,
+    collateral: &'a PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &'a QuoteVerifyOptions,
+    abort_on_first_failure: bool,
+) -> impl Iterator<Item = Result<VerifiedReport, VerificationError>> + 'a
+where
+    I: IntoIterator<Item = &'a [u8]>,
+    I::IntoIter: 'a,
+{
+    let mut aborted = false;
+    quotes.into_iter().map_while(move |quote| {
+        if aborted {
+            return None;
+        }
+        let result = verify_single(quote, collateral, timestamp_seconds, options);
+        aborted = abort_on_first_failure && result.is_err();
+        Some(result)
+    })
+}
+
+/// Parallel version of [`verify_batch`], backed by rayon. The results are identical to, and
+/// in the same order as, those of [`verify_batch`].
+#[cfg(feature = "parallel")]
+pub fn verify_batch_parallel(
+    quotes: &[&[u8]],
+    collateral: &PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Vec<Result<VerifiedReport, VerificationError>> {
+    use rayon::prelude::*;
+
+    quotes
+        .par_iter()
+        .map(|quote| verify_single(quote, collateral, timestamp_seconds, options))
+        .collect()
+}
+
+fn verify_single(
+    quote: &[u8],
+    collateral: &PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<VerifiedReport, VerificationError> {
+    (collateral.trusted_root_ca_der() == options.trusted_root_ca_der.as_slice()).or_err(|| {
+        VerificationError::Collateral(String::from(
+            "collateral was prepared with another trust anchor",
+        ))
+    })?;
+    verify_quote_within(
+        quote,
+        ParsedQuote::parse(quote).as_ref(),
+        collateral.collateral(),
+        collateral.validity(),
+        timestamp_seconds,
+        options,
+    )
+}
@@ -0,0 +1,38 @@
+use alloc::{string::ToString, vec::Vec};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::VerificationError;
+use crate::{collateral::Collateral, quote::QuoteBytes};
+
+/// Path of the dstack attestation in the `/public_data` document served by an MPC node.
+const PUBLIC_DATA_ATTESTATION_POINTER: &str = "/tee_participant_info/Dstack";
+
+/// A quote along with its collateral, as produced by an MPC node running in dstack.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Bundle {
+    pub quote: QuoteBytes,
+    pub collateral: Collateral,
+}
+
+impl Bundle {
+    /// Splits a JSON bundle into the quote bytes and its collateral, ready for verification.
+    ///
+    /// `raw` is either the JSON of a [`super::DstackAttestation`], i.e. an object with the
+    /// `quote` as an array of bytes and the `collateral` as read by
+    /// [`Collateral::try_from_json`], or the whole `/public_data` document of an MPC node, which
+    /// holds the attestation under `tee_participant_info.Dstack`. Other fields, such as the
+    /// `tcb_info`, are ignored.
+    pub fn parse(raw: &[u8]) -> Result<(Vec<u8>, Collateral), VerificationError> {
+        let invalid = |e: serde_json::Error| VerificationError::InvalidBundle(e.to_string());
+
+        let mut document: Value = serde_json::from_slice(raw).map_err(invalid)?;
+        let attestation = match document.pointer_mut(PUBLIC_DATA_ATTESTATION_POINTER) {
+            Some(attestation) => attestation.take(),
+            None => document,
+        };
+        let bundle = Bundle::deserialize(attestation).map_err(invalid)?;
+
+        Ok((bundle.quote.into(), bundle.collateral))
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dcap_qvl::verify::VerifiedReport;
+
+use super::{QuoteVerifyOptions, VerificationError, verify_quote};
+use crate::collateral::Collateral;
+
+/// A source of the current time, in seconds since the Unix epoch. Implement this to control
+/// the time [`verify_quote_with_time_source`] verifies at, e.g. in tests.
+pub trait TimeSource {
+    fn now_seconds(&self) -> u64;
+}
+
+/// A [`TimeSource`] reading [`SystemTime::now`]. A clock set before the Unix epoch reads as
+/// `0`, at which no collateral is valid.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now_seconds(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+}
+
+/// Runs [`verify_quote`] at the current system time, see [`SystemTimeSource`].
+pub fn verify_now(
+    quote: &[u8],
+    collateral: &Collateral,
+    options: &QuoteVerifyOptions,
+) -> Result<VerifiedReport, VerificationError> {
+    verify_quote_with_time_source(quote, collateral, options, &SystemTimeSource)
+}
+
+/// Runs [`verify_quote`] at the time read from `time_source`.
+pub fn verify_quote_with_time_source(
+    quote: &[u8],
+    collateral: &Collateral,
+    options: &QuoteVerifyOptions,
+    time_source: &impl TimeSource,
+) -> Result<VerifiedReport, VerificationError> {
+    verify_quote(quote, collateral, time_source.now_seconds(), options)
+}
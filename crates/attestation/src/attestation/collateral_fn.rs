@@ -0,0 +1,46 @@
+use super::{QuoteVerifyOptions, VerificationError, verify_quote};
+use crate::{
+    collateral::{
+        CaType, Collateral, CollateralError, Fmspc, assert_fmspc_consistency,
+        missing_collateral_hint,
+    },
+    quote::ParsedQuote,
+};
+
+use alloc::{format, string::ToString};
+use dcap_qvl::verify::VerifiedReport;
+
+/// Verifies `quote` as [`verify_quote`] does, against the collateral returned by `fetch` for
+/// the quote's FMSPC and PCK CA.
+///
+/// The collateral is only fetched once the quote is known to need it, so callers choose where
+/// it comes from, e.g. Intel PCS, a local cache or a different source per platform, without
+/// collecting it before verification. `fetch` is called at most once.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::InvalidInput`] if the quote cannot be parsed or carries no
+/// PCK certificate to read the FMSPC and CA from, [`VerificationError::Collateral`] if `fetch`
+/// fails or returns collateral for another FMSPC, and the errors of [`verify_quote`]
+/// otherwise.
+pub fn verify_with_collateral_fn(
+    quote: &[u8],
+    mut fetch: impl FnMut(Fmspc, CaType) -> Result<Collateral, CollateralError>,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<VerifiedReport, VerificationError> {
+    let parsed = ParsedQuote::parse(quote)
+        .map_err(|e| VerificationError::InvalidInput(format!("invalid quote: {e}")))?;
+    let (fmspc, ca) = missing_collateral_hint(&parsed)
+        .and_then(|hint| Ok((hint.fmspc, hint.ca_type()?)))
+        .map_err(|e| VerificationError::InvalidInput(e.to_string()))?;
+
+    let collateral = fetch(fmspc, ca)
+        .and_then(|collateral| {
+            assert_fmspc_consistency(&parsed, &collateral)?;
+            Ok(collateral)
+        })
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+
+    verify_quote(quote, &collateral, timestamp_seconds, options)
+}
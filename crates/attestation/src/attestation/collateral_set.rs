@@ -0,0 +1,83 @@
+use super::{QuoteVerifyOptions, VerificationError, verify_quote};
+use crate::{
+    collateral::{Collateral, Fmspc},
+    quote::ParsedQuote,
+};
+
+use alloc::{format, vec::Vec};
+use dcap_qvl::verify::VerifiedReport;
+
+/// Identifies the collateral of a set that a quote was verified against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollateralId {
+    /// Index of the collateral in the set.
+    pub index: usize,
+    pub fmspc: Fmspc,
+    /// See [`Collateral::tcb_evaluation_number`].
+    pub tcb_evaluation_number: u32,
+}
+
+/// Verifies `quote` as [`verify_quote`] does, against whichever of `collaterals` fits it, and
+/// returns the id of the collateral used along with the report.
+///
+/// Around a TCB recovery, the collateral before and after the recovery are both in use, and a
+/// quote may only verify against one of them. This considers the collaterals for the quote's
+/// FMSPC which are valid at `timestamp_seconds`, with the clock skew allowed by `options`,
+/// and tries them from the highest `tcbEvaluationDataNumber` down, the first in `collaterals`
+/// first among equals. Collaterals whose FMSPC, validity or evaluation number cannot be read
+/// are skipped.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::InvalidInput`] if the quote cannot be parsed or carries no
+/// FMSPC, [`VerificationError::Collateral`] if no collateral fits the quote, and otherwise the
+/// error of the newest collateral if the quote verifies against none of them.
+pub fn verify_with_collateral_set(
+    quote: &[u8],
+    collaterals: &[Collateral],
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<(VerifiedReport, CollateralId), VerificationError> {
+    let fmspc = ParsedQuote::parse(quote)
+        .and_then(|parsed| parsed.fmspc())
+        .map_err(|e| VerificationError::InvalidInput(format!("invalid quote: {e}")))?;
+
+    let mut candidates: Vec<CollateralId> = collaterals
+        .iter()
+        .enumerate()
+        .filter(|(_, collateral)| {
+            collateral
+                .check_expiration(timestamp_seconds, options.allowed_skew_secs)
+                .is_ok()
+        })
+        .filter_map(|(index, collateral)| {
+            let id = CollateralId {
+                index,
+                fmspc: collateral.fmspc().ok()?,
+                tcb_evaluation_number: collateral.tcb_evaluation_number().ok()?,
+            };
+            (id.fmspc == fmspc).then_some(id)
+        })
+        .collect();
+    // Stable, so collaterals with the same evaluation number stay in input order.
+    candidates.sort_by(|a, b| b.tcb_evaluation_number.cmp(&a.tcb_evaluation_number));
+
+    let mut newest_error = None;
+    for id in candidates {
+        let Some(collateral) = collaterals.get(id.index) else {
+            continue;
+        };
+        match verify_quote(quote, collateral, timestamp_seconds, options) {
+            Ok(report) => return Ok((report, id)),
+            Err(e) => {
+                newest_error.get_or_insert(e);
+            }
+        }
+    }
+
+    Err(newest_error.unwrap_or_else(|| {
+        VerificationError::Collateral(format!(
+            "no collateral for FMSPC {fmspc} is valid at {timestamp_seconds}"
+        ))
+    }))
+}
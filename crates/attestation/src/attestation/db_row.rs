@@ -0,0 +1,56 @@
+use super::AttestationSummary;
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde::{Deserialize, Serialize};
+
+/// A verification result as stored by the coordinator, one row per verified attestation.
+///
+/// Every register, digest and identifier is lowercase hex, and the TCB statuses are their
+/// Intel names, see [`crate::tcb_status::TcbStatus::as_str`]. The field names are the column
+/// names of the coordinator schema and must not change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbRow {
+    pub mrtd: String,
+    pub rtmr0: String,
+    pub rtmr1: String,
+    pub rtmr2: String,
+    pub key_provider_event_digest: String,
+    pub app_compose_hash_payload: String,
+    pub ppid: String,
+    pub mr_config_id: String,
+    pub mr_owner: String,
+    pub platform_tcb_status: String,
+    pub qe_tcb_status: Option<String>,
+    pub advisory_ids: Vec<String>,
+    /// Unix time in seconds the attestation was verified at.
+    pub verified_at: u64,
+    /// Whether the attestation was accepted.
+    pub accepted: bool,
+}
+
+impl AttestationSummary {
+    /// The row the coordinator stores for this summary. A summary is only produced for an
+    /// accepted attestation, so [`DbRow::accepted`] is always set.
+    pub fn to_db_row(&self) -> DbRow {
+        let rtmrs = &self.measurements.rtmrs;
+        DbRow {
+            mrtd: hex::encode(rtmrs.mrtd),
+            rtmr0: hex::encode(rtmrs.rtmr0),
+            rtmr1: hex::encode(rtmrs.rtmr1),
+            rtmr2: hex::encode(rtmrs.rtmr2),
+            key_provider_event_digest: hex::encode(self.measurements.key_provider_event_digest),
+            app_compose_hash_payload: hex::encode(self.measurements.app_compose_hash_payload),
+            ppid: hex::encode(*self.ppid),
+            mr_config_id: hex::encode(self.mr_config_id),
+            mr_owner: hex::encode(self.mr_owner),
+            platform_tcb_status: self.platform_tcb_status.as_str().to_string(),
+            qe_tcb_status: self.qe_tcb_status.map(|status| status.as_str().to_string()),
+            advisory_ids: self.advisory_ids.clone(),
+            verified_at: self.verified_at,
+            accepted: true,
+        }
+    }
+}
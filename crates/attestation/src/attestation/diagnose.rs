@@ -0,0 +1,254 @@
+use super::{QuoteVerifyOptions, verify_quote, verify_report_policy};
+use crate::{
+    collateral::{Collateral, CrlValidity},
+    tcb_status::TcbStatus,
+};
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A change to [`QuoteVerifyOptions`] which makes verification accept more quotes, as
+/// suggested by [`diagnose_failure`]. Each relaxation has a security cost, see
+/// [`SuggestedRelaxation::security_cost`]; it is a diagnostic, not a recommendation to apply it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuggestedRelaxation {
+    /// Raise [`QuoteVerifyOptions::allowed_skew_secs`] to `secs`.
+    RaiseAllowedSkew { secs: u64 },
+    /// Clear [`QuoteVerifyOptions::max_collateral_age_secs`].
+    RemoveMaxCollateralAge,
+    /// Clear [`QuoteVerifyOptions::min_tcb_eval_number`].
+    RemoveMinTcbEvalNumber,
+    /// Add the status to the [`crate::tcb_status::TcbPolicy::allowed_statuses`] of
+    /// [`QuoteVerifyOptions::tcb_policy`].
+    AllowStatus(TcbStatus),
+    /// Accept any advisory for the allowed statuses, clearing the advisory allowlist and
+    /// denylist of [`QuoteVerifyOptions::tcb_policy`].
+    AllowAnyAdvisory,
+    /// Clear [`QuoteVerifyOptions::min_seam_svn`].
+    RemoveMinSeamSvn,
+    /// Clear [`QuoteVerifyOptions::reject_debug_td`].
+    AllowDebugTd,
+    /// Set [`QuoteVerifyOptions::skip_qe_identity`].
+    SkipQeIdentity,
+    /// Clear [`QuoteVerifyOptions::expected_mr_service_td`].
+    RemoveExpectedMrServiceTd,
+    /// Clear [`QuoteVerifyOptions::expected_mr_config_id`].
+    RemoveExpectedMrConfigId,
+    /// Clear [`QuoteVerifyOptions::expected_mr_owner`].
+    RemoveExpectedMrOwner,
+    /// Clear [`QuoteVerifyOptions::expected_rtmr3`].
+    RemoveExpectedRtmr3,
+}
+
+impl SuggestedRelaxation {
+    /// `options` with the relaxation applied.
+    pub fn apply(&self, options: &QuoteVerifyOptions) -> QuoteVerifyOptions {
+        let mut relaxed = options.clone();
+        match self {
+            Self::RaiseAllowedSkew { secs } => relaxed.allowed_skew_secs = *secs,
+            Self::RemoveMaxCollateralAge => relaxed.max_collateral_age_secs = None,
+            Self::RemoveMinTcbEvalNumber => relaxed.min_tcb_eval_number = None,
+            Self::AllowStatus(status) => {
+                relaxed.tcb_policy = relaxed.tcb_policy.allow_status(*status);
+            }
+            Self::AllowAnyAdvisory => {
+                relaxed.tcb_policy.allowed_advisories = None;
+                relaxed.tcb_policy.denied_advisories.clear();
+            }
+            Self::RemoveMinSeamSvn => relaxed.min_seam_svn = None,
+            Self::AllowDebugTd => relaxed.reject_debug_td = false,
+            Self::SkipQeIdentity => relaxed.skip_qe_identity = true,
+            Self::RemoveExpectedMrServiceTd => relaxed.expected_mr_service_td = None,
+            Self::RemoveExpectedMrConfigId => relaxed.expected_mr_config_id = None,
+            Self::RemoveExpectedMrOwner => relaxed.expected_mr_owner = None,
+            Self::RemoveExpectedRtmr3 => relaxed.expected_rtmr3 = None,
+        }
+        relaxed
+    }
+
+    /// What accepting the relaxation gives up, for an operator deciding whether to apply it.
+    pub fn security_cost(&self) -> &'static str {
+        match self {
+            Self::RaiseAllowedSkew { .. } => {
+                "collateral is accepted further outside its validity window, so revocations and \
+                 TCB recoveries published since take longer to apply"
+            }
+            Self::RemoveMaxCollateralAge => {
+                "collateral older than the configured age is accepted, so platforms may stay on \
+                 TCB levels Intel has since superseded"
+            }
+            Self::RemoveMinTcbEvalNumber => {
+                "collateral predating a TCB recovery is accepted, along with platforms that \
+                 recovery marks as out of date"
+            }
+            Self::AllowStatus(TcbStatus::UpToDate) => "none, the platform is fully patched",
+            Self::AllowStatus(_) => {
+                "platforms affected by the status' Intel security advisories are accepted"
+            }
+            Self::AllowAnyAdvisory => {
+                "platforms affected by advisories the policy excluded, possibly exploitable ones, \
+                 are accepted"
+            }
+            Self::RemoveMinSeamSvn => {
+                "TDX modules with known vulnerabilities fixed in later SVNs are accepted"
+            }
+            Self::AllowDebugTd => {
+                "debug TDs and debug TDX modules, whose memory the host can read, are accepted"
+            }
+            Self::SkipQeIdentity => {
+                "the Quoting Enclave is not evaluated, so an out of date or revoked QE is trusted"
+            }
+            Self::RemoveExpectedMrServiceTd => "TDs with any set of service TDs are accepted",
+            Self::RemoveExpectedMrConfigId => {
+                "TDs configured for any tenant are accepted, as MRCONFIGID is not checked"
+            }
+            Self::RemoveExpectedMrOwner => {
+                "TDs launched under any ownership are accepted, as MROWNER is not checked"
+            }
+            Self::RemoveExpectedRtmr3 => {
+                "anything measured into RTMR3 outside of the event log is accepted"
+            }
+        }
+    }
+}
+
+impl fmt::Display for SuggestedRelaxation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RaiseAllowedSkew { secs } => write!(f, "raise the allowed skew to {secs}s"),
+            Self::RemoveMaxCollateralAge => f.write_str("remove the maximum collateral age"),
+            Self::RemoveMinTcbEvalNumber => {
+                f.write_str("remove the minimum tcbEvaluationDataNumber")
+            }
+            Self::AllowStatus(status) => write!(f, "allow the {} TCB status", status.as_str()),
+            Self::AllowAnyAdvisory => f.write_str("allow any advisory"),
+            Self::RemoveMinSeamSvn => f.write_str("remove the minimum TDX module SVN"),
+            Self::AllowDebugTd => f.write_str("allow debug TDs and TDX modules"),
+            Self::SkipQeIdentity => f.write_str("skip the QE identity check"),
+            Self::RemoveExpectedMrServiceTd => f.write_str("remove the expected MRSERVICETD"),
+            Self::RemoveExpectedMrConfigId => f.write_str("remove the expected MRCONFIGID"),
+            Self::RemoveExpectedMrOwner => f.write_str("remove the expected MROWNER"),
+            Self::RemoveExpectedRtmr3 => f.write_str("remove the expected RTMR3"),
+        }
+    }
+}
+
+/// Finds which single relaxation of `strict_options` would have made `quote` verify against
+/// `collateral` at `now`, to show an operator what blocks its acceptance.
+///
+/// Verification covers [`verify_quote`] and the TCB policy and report constraints of the
+/// options, as [`super::DstackAttestation::verify_with_options`] checks them; the report data
+/// and the measurements are not part of the options, so failures there are not diagnosed. Only
+/// relaxations of options that are set are tried, and [`TcbStatus::Revoked`] is never
+/// suggested. For clock skew, the suggestion is the least skew covering the collateral at
+/// `now`, and its CRLs with [`QuoteVerifyOptions::extended_collateral_checks`].
+///
+/// Returns the relaxations that each make verification pass alone, from the least to the most
+/// costly. The result is empty if verification passes under `strict_options`, and if it fails
+/// but only a combination of relaxations, or none, would make it pass.
+pub fn diagnose_failure(
+    quote: &[u8],
+    collateral: &Collateral,
+    now: u64,
+    strict_options: &QuoteVerifyOptions,
+) -> Vec<SuggestedRelaxation> {
+    let passes = |options: &QuoteVerifyOptions| {
+        verify_quote(quote, collateral, now, options)
+            .and_then(|verified| verify_report_policy(quote, collateral, &verified, options))
+            .is_ok()
+    };
+    if passes(strict_options) {
+        return Vec::new();
+    }
+
+    candidates(collateral, now, strict_options)
+        .into_iter()
+        .filter(|relaxation| passes(&relaxation.apply(strict_options)))
+        .collect()
+}
+
+/// The relaxations of the options that are set, in increasing order of security cost.
+fn candidates(
+    collateral: &Collateral,
+    now: u64,
+    options: &QuoteVerifyOptions,
+) -> Vec<SuggestedRelaxation> {
+    let mut candidates = Vec::new();
+    if let Some(secs) =
+        required_skew(collateral, now, options).filter(|secs| *secs > options.allowed_skew_secs)
+    {
+        candidates.push(SuggestedRelaxation::RaiseAllowedSkew { secs });
+    }
+    if options.max_collateral_age_secs.is_some() {
+        candidates.push(SuggestedRelaxation::RemoveMaxCollateralAge);
+    }
+    if options.min_tcb_eval_number.is_some() {
+        candidates.push(SuggestedRelaxation::RemoveMinTcbEvalNumber);
+    }
+    candidates.extend(
+        TcbStatus::ALL
+            .into_iter()
+            .filter(|status| *status != TcbStatus::Revoked)
+            .filter(|status| !options.tcb_policy.allowed_statuses.contains(status))
+            .map(SuggestedRelaxation::AllowStatus),
+    );
+    if options.tcb_policy.allowed_advisories.is_some()
+        || !options.tcb_policy.denied_advisories.is_empty()
+    {
+        candidates.push(SuggestedRelaxation::AllowAnyAdvisory);
+    }
+    if options.min_seam_svn.is_some() {
+        candidates.push(SuggestedRelaxation::RemoveMinSeamSvn);
+    }
+    if options.reject_debug_td {
+        candidates.push(SuggestedRelaxation::AllowDebugTd);
+    }
+    if !options.skip_qe_identity {
+        candidates.push(SuggestedRelaxation::SkipQeIdentity);
+    }
+    for (set, relaxation) in [
+        (
+            options.expected_mr_service_td.is_some(),
+            SuggestedRelaxation::RemoveExpectedMrServiceTd,
+        ),
+        (
+            options.expected_mr_config_id.is_some(),
+            SuggestedRelaxation::RemoveExpectedMrConfigId,
+        ),
+        (
+            options.expected_mr_owner.is_some(),
+            SuggestedRelaxation::RemoveExpectedMrOwner,
+        ),
+        (
+            options.expected_rtmr3.is_some(),
+            SuggestedRelaxation::RemoveExpectedRtmr3,
+        ),
+    ] {
+        if set {
+            candidates.push(relaxation);
+        }
+    }
+    candidates
+}
+
+/// The least clock skew for which the collateral documents, and the CRLs if `options` check
+/// them, are all valid at `now`. `None` if their validity cannot be read.
+fn required_skew(collateral: &Collateral, now: u64, options: &QuoteVerifyOptions) -> Option<u64> {
+    let outside = |start: u64, end: Option<u64>| {
+        if now < start {
+            start.saturating_sub(now)
+        } else {
+            end.map_or(0, |end| now.saturating_sub(end))
+        }
+    };
+
+    let validity = collateral.validity_window().ok()?;
+    let mut skew = outside(validity.not_before, Some(validity.next_update));
+    if options.extended_collateral_checks {
+        for (crl, invalid) in collateral.crls() {
+            let crl = CrlValidity::parse(crl, invalid).ok()?;
+            skew = skew.max(outside(crl.this_update, crl.next_update));
+        }
+    }
+    Some(skew)
+}
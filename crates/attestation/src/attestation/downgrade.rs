@@ -0,0 +1,116 @@
+use dcap_qvl::verify::VerifiedReport;
+
+use super::{QuoteVerifyOptions, VerificationError, verify_quote};
+use crate::collateral::Collateral;
+
+use alloc::string::ToString;
+
+/// Storage of the highest [`Collateral::tcb_evaluation_number`] seen by a [`DowngradeGuard`].
+/// Implement this to share or persist it, e.g. across nodes or restarts.
+pub trait TcbEvaluationStore {
+    /// Returns the highest number recorded, if any.
+    fn highest(&self) -> Option<u32>;
+
+    /// Records that collateral with `number` was accepted. Only called with numbers at least
+    /// as high as [`TcbEvaluationStore::highest`].
+    fn record(&mut self, number: u32);
+}
+
+/// A [`TcbEvaluationStore`] kept in memory.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InMemoryTcbEvaluation {
+    highest: Option<u32>,
+}
+
+impl TcbEvaluationStore for InMemoryTcbEvaluation {
+    fn highest(&self) -> Option<u32> {
+        self.highest
+    }
+
+    fn record(&mut self, number: u32) {
+        self.highest = Some(number);
+    }
+}
+
+/// Rejects collateral with a lower `tcbEvaluationDataNumber` than the highest seen so far.
+///
+/// Collateral from before a TCB recovery stays valid until it expires, so an attacker could
+/// feed it to a verifier to have a platform that is only up to date under the old TCB levels
+/// accepted. Unlike [`QuoteVerifyOptions::min_tcb_eval_number`], which is a fixed floor, the
+/// guard raises its floor with every newer collateral it accepts.
+#[derive(Debug, Clone)]
+pub struct DowngradeGuard<S = InMemoryTcbEvaluation> {
+    store: S,
+}
+
+impl DowngradeGuard {
+    pub fn new() -> Self {
+        Self::with_store(InMemoryTcbEvaluation::default())
+    }
+}
+
+impl Default for DowngradeGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: TcbEvaluationStore> DowngradeGuard<S> {
+    pub fn with_store(store: S) -> Self {
+        Self { store }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Checks that `collateral` is not older than the highest collateral seen, and records its
+    /// number if it is newer. Returns the number of `collateral`.
+    ///
+    /// The number is recorded before any signature is checked, so only observe collateral from
+    /// a trusted source, or use [`DowngradeGuard::verify_quote`], which records it only once the
+    /// quote verified against it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VerificationError::TcbDowngrade`] if the collateral is older, and
+    /// [`VerificationError::Collateral`] if its number cannot be read.
+    pub fn observe(&mut self, collateral: &Collateral) -> Result<u32, VerificationError> {
+        let number = self.check(collateral)?;
+        self.record(number);
+        Ok(number)
+    }
+
+    /// Same as [`super::verify_quote`], but first rejects `collateral` if it is older than the
+    /// highest collateral seen, and records its number once the quote verified against it.
+    pub fn verify_quote(
+        &mut self,
+        quote: &[u8],
+        collateral: &Collateral,
+        timestamp_seconds: u64,
+        options: &QuoteVerifyOptions,
+    ) -> Result<VerifiedReport, VerificationError> {
+        let number = self.check(collateral)?;
+        let report = verify_quote(quote, collateral, timestamp_seconds, options)?;
+        self.record(number);
+        Ok(report)
+    }
+
+    fn check(&self, collateral: &Collateral) -> Result<u32, VerificationError> {
+        let found = collateral
+            .tcb_evaluation_number()
+            .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+        match self.store.highest() {
+            Some(highest) if found < highest => {
+                Err(VerificationError::TcbDowngrade { found, highest })
+            }
+            _ => Ok(found),
+        }
+    }
+
+    fn record(&mut self, number: u32) {
+        if self.store.highest().is_none_or(|highest| number > highest) {
+            self.store.record(number);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use alloc::{format, string::String};
+
+use super::VerificationError;
+
+impl VerificationError {
+    /// Returns a plain-English explanation of the failure, with its likely causes and how to
+    /// remediate it, for user-facing support tooling. [`core::fmt::Display`] stays concise for
+    /// logs.
+    pub fn explain(&self) -> String {
+        match self {
+            VerificationError::EmbeddedMeasurementsParsing(e) => format!(
+                "The measurements embedded in this build could not be parsed ({e}). This is a \
+                 build problem, not a problem with the node: rebuild from a release with valid \
+                 measurement files."
+            ),
+            VerificationError::DcapVerification { reason, context } => {
+                let quote = match context {
+                    Some(context) => format!("The quote ({context})"),
+                    None => String::from("The quote, whose header could not be read,"),
+                };
+                format!(
+                    "{quote} failed Intel DCAP verification: {reason}. The quote may be \
+                     truncated or corrupted in transit, signed by a platform unknown to \
+                     Intel, or verified against collateral for another platform. Regenerate \
+                     the quote on the node and fetch collateral for its FMSPC."
+                )
+            }
+            VerificationError::ReportNotTd10 => String::from(
+                "The quote does not contain a TDX TD report, so it was not produced by a TDX \
+                 guest. Make sure the node runs in a TDX confidential VM and regenerate the \
+                 quote there.",
+            ),
+            VerificationError::TdxModuleTcb(reason) => format!(
+                "The TCB level of the TDX module could not be evaluated: {reason}. The TDX \
+                 module of the host is likely older than every TCB level Intel still lists. \
+                 Ask the host operator to update the TDX module, then regenerate the quote."
+            ),
+            VerificationError::QeTcb(reason) => format!(
+                "The TCB level of the Quoting Enclave could not be evaluated: {reason}. The \
+                 Quoting Enclave of the host is likely older than every TCB level Intel still \
+                 lists. Ask the host operator to update the DCAP quoting libraries, then \
+                 regenerate the quote."
+            ),
+            VerificationError::MissingMrServiceTd => String::from(
+                "The verifier requires a specific service TD, but the quote comes from a TDX \
+                 1.0 module that does not report one. Either run the node on a host with a \
+                 TDX 1.5 module, or stop pinning MRSERVICETD.",
+            ),
+            VerificationError::MissingRtmr3 => String::from(
+                "The verifier requires a specific RTMR3, but the report's RTMR3 is all zeros, \
+                 so nothing was measured into it. Run the node on a platform that measures \
+                 into RTMR3, or stop pinning RTMR3.",
+            ),
+            VerificationError::MissingAppCompose => String::from(
+                "The verifier requires an app compose, but the accepted measurements the node \
+                 matched have an all-zero app compose hash payload, so the app identity was \
+                 never pinned. Fill in the app compose hash of the baseline.",
+            ),
+            VerificationError::ConfigIdMismatch { expected, found } => format!(
+                "The TD was created with MRCONFIGID {found}, but the verifier only accepts \
+                 {expected}. The node belongs to another tenant, or was launched with the \
+                 wrong configuration ID by its host."
+            ),
+            VerificationError::OwnerMismatch { expected, found } => format!(
+                "The TD was launched with MROWNER {found}, but the verifier only accepts \
+                 {expected}. The node was not launched under the expected governance key; \
+                 relaunch it with the owner set by governance."
+            ),
+            VerificationError::SeamSvnTooLow { found, minimum } => format!(
+                "The TD runs on a TDX module with SEAM SVN {found:#06x}, below the required \
+                 {minimum:#06x}. The module has known vulnerabilities; ask the cloud provider \
+                 or host operator to update the TDX module."
+            ),
+            VerificationError::DebugTdNotAllowed(reason) => format!(
+                "The quote comes from a debug TD or TDX module: {reason}. Debug TDs and \
+                 modules do not protect their memory from the host; launch the node as a \
+                 production TD."
+            ),
+            VerificationError::DomainMismatch(domain) => format!(
+                "The report data does not bind the expected payload to the domain `{domain}`. \
+                 The quote was produced for another protocol, or for another payload, and cannot \
+                 be replayed here."
+            ),
+            VerificationError::TcbStatusNotAllowed(status) => format!(
+                "The TCB status `{status}` is not accepted by the verifier. The platform's TCB \
+                 is out of date or needs additional configuration; apply the latest microcode \
+                 and firmware updates, or update the component named in the status, and \
+                 regenerate the quote."
+            ),
+            VerificationError::NonEmptyAdvisoryIds(advisories) => format!(
+                "Intel reports outstanding security advisories for this platform: \
+                 {advisories}. Review the advisories and apply the mitigations they describe, \
+                 or explicitly accept them in the TCB policy."
+            ),
+            VerificationError::WrongHash {
+                name,
+                found,
+                expected,
+            } => format!(
+                "The {name} measurement of the node is {found}, but {expected} was expected. \
+                 The node runs a different image, configuration or app compose than the \
+                 accepted one. Deploy the expected release, or add its measurements to the \
+                 accepted set if the change is intended."
+            ),
+            VerificationError::InvalidEventType(event_type) => format!(
+                "The event log contains an RTMR3 event of type {event_type}, while dstack only \
+                 produces events of one type. The event log was not produced by dstack, or was \
+                 tampered with; regenerate the TCB info on the node."
+            ),
+            VerificationError::EventDecoding(digest) => format!(
+                "The payload of the event with digest {digest} is not valid hex. The event log \
+                 is corrupted; regenerate the TCB info on the node."
+            ),
+            VerificationError::AppComposeParsing(reason) => format!(
+                "The app compose of the node is not valid JSON: {reason}. Check the app compose \
+                 deployed with the node."
+            ),
+            VerificationError::MissingEvent(event) => format!(
+                "The event log has no `{event}` event. The node runs a dstack version that does \
+                 not record it, or the event log is incomplete; upgrade dstack and regenerate \
+                 the TCB info."
+            ),
+            VerificationError::DuplicateEvent(event) => format!(
+                "The event log has more than one `{event}` event, so it is ambiguous which one \
+                 applies. The event log was tampered with; regenerate the TCB info on the node."
+            ),
+            VerificationError::InvalidAppComposeConfig(_) => String::from(
+                "The app compose of the node enables settings that are not allowed, such as \
+                 key management or pre-launch scripts. Deploy the node with the reference app \
+                 compose.",
+            ),
+            VerificationError::AppComposeEventPayloadWrongSize(size) => format!(
+                "The compose-hash event carries {size} bytes instead of a 32 byte hash. The \
+                 event log was not produced by a supported dstack version; upgrade dstack."
+            ),
+            VerificationError::AppComposeEventPayloadNotHex(_) => String::from(
+                "The compose-hash event payload is not valid hex. The event log is corrupted; \
+                 regenerate the TCB info on the node.",
+            ),
+            VerificationError::ExpiredCertificate {
+                attestation_time,
+                expiry_time,
+            } => format!(
+                "The attestation was checked at {attestation_time}, after its certificate \
+                 expired at {expiry_time}. Have the node submit a fresh attestation."
+            ),
+            VerificationError::PpidWrongSize(size) => format!(
+                "The platform ID (PPID) of the quote is {size} bytes long instead of 16. The \
+                 quote does not embed a PCK certificate with a valid PPID; regenerate it with \
+                 an up-to-date quote provider."
+            ),
+            VerificationError::InvalidMockAttestation => String::from(
+                "A mock attestation was submitted where a real one is required. Mock \
+                 attestations are only valid in test environments; run the node in a TEE.",
+            ),
+            VerificationError::InvalidBundle(reason) => format!(
+                "The attestation bundle could not be read: {reason}. Pass the node's \
+                 `/public_data` document, or its attestation, unmodified."
+            ),
+            VerificationError::InvalidInput(reason) => format!(
+                "The input could not be read: {reason}. Pass the quote as a hex string and \
+                 the collateral as the JSON document fetched for the quote."
+            ),
+            VerificationError::Replayed { last_seen } => format!(
+                "This exact quote was already accepted at {last_seen}. A quote must not be \
+                 reused; have the node generate a fresh quote, with a fresh nonce."
+            ),
+            VerificationError::ResharingParticipant { index, error } => format!(
+                "Participant {index} of the resharing set was rejected, so the whole set is. {}",
+                error.explain()
+            ),
+            VerificationError::UnexpectedParticipant { index, report_data } => format!(
+                "Participant {index} of the resharing set binds report data {report_data}, \
+                 which is not the binding of any expected participant key. Check that the \
+                 node is an expected participant and binds its Ed25519 key with \
+                 report_data::for_public_key."
+            ),
+            VerificationError::DuplicateParticipant { first, index, key } => format!(
+                "Participants {first} and {index} of the resharing set both bind key {key}. \
+                 Each participant must attest exactly once, with its own key."
+            ),
+            VerificationError::MissingParticipant { key } => format!(
+                "No participant of the resharing set binds the expected key {key}. Collect \
+                 the quote of that participant, or remove it from the expected participants."
+            ),
+            VerificationError::TeeTypeMismatch {
+                quote_tee,
+                collateral_tee,
+            } => format!(
+                "The quote is from a {quote_tee:?} TEE, but the collateral is for \
+                 {collateral_tee:?}. Fetch the collateral for the quote's TEE type, e.g. with \
+                 collateral::missing_collateral_hint."
+            ),
+            VerificationError::TcbEvaluationTooOld { found, minimum } => format!(
+                "The collateral is from TCB evaluation {found}, while at least {minimum} is \
+                 required. It predates a TCB recovery, even if it has not expired. Fetch \
+                 fresh collateral from Intel PCS."
+            ),
+            VerificationError::CollateralTooOld {
+                issued_at,
+                age_secs,
+                max_age_secs,
+            } => format!(
+                "The TCB info of the collateral was issued at {issued_at}, {age_secs} seconds \
+                 ago, but collateral may be at most {max_age_secs} seconds old. It has not \
+                 expired, but fresher collateral is required; fetch it from Intel PCS."
+            ),
+            VerificationError::TcbDowngrade { found, highest } => format!(
+                "The collateral is from TCB evaluation {found}, older than the evaluation \
+                 {highest} already seen by this verifier. Older collateral can hide a TCB \
+                 recovery, so it is rejected; fetch fresh collateral from Intel PCS."
+            ),
+            VerificationError::CollateralQuoteTimeMismatch {
+                pck_not_before,
+                pck_not_after,
+                collateral_not_before,
+                collateral_next_update,
+            } => format!(
+                "The collateral, valid from {collateral_not_before} until \
+                 {collateral_next_update}, is from another period than the quote, whose PCK \
+                 certificate is valid from {pck_not_before} until {pck_not_after}. The \
+                 collateral was fetched long before or after the quote was generated; fetch \
+                 current collateral for the quote."
+            ),
+            VerificationError::NonProductionRoot { fingerprint } => format!(
+                "The PCK certificate chain of the quote roots at the certificate with \
+                 fingerprint {fingerprint}, not at the Intel SGX Root CA. The quote comes from \
+                 a pre-production platform or a TDX emulator; run the node on production \
+                 hardware."
+            ),
+            VerificationError::ReportDataMismatch { expected, found } => format!(
+                "The quote carries report data {found}, but the contract committed to \
+                 {expected}. The quote was generated for another key or registration, or the \
+                 commitment was read for another node; have the node generate a quote for its \
+                 current registration."
+            ),
+            VerificationError::CollateralHashMismatch { expected, found } => format!(
+                "The collateral hashes to {found}, but {expected} was committed to. The node \
+                 supplied other collateral than the committed one; have it fetch the committed \
+                 collateral, or commit to the hash of the new collateral."
+            ),
+            VerificationError::TranscriptMismatch { recorded, replayed } => format!(
+                "The verification was {recorded} when it was recorded, but is {replayed} when \
+                 replayed from the same inputs. The transcript was altered, or was recorded by \
+                 a verifier that decides differently; review the transcript and the version of \
+                 the verifier that recorded it."
+            ),
+            VerificationError::Collateral(reason) => format!(
+                "The collateral could not be used: {reason}. It may have expired, be issued \
+                 by another root or contain a revoked certificate, or the verifier's clock may \
+                 be off. Fetch fresh collateral from Intel PCS and check the system clock."
+            ),
+            VerificationError::Custom(reason) => format!("Verification failed: {reason}."),
+        }
+    }
+}
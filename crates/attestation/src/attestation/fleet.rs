@@ -0,0 +1,79 @@
+use alloc::vec::Vec;
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::AttestationSummary;
+use crate::{
+    measurements::{FullMeasurements, FullMeasurementsHex},
+    tcb_info::HexBytes,
+};
+
+/// Fleet-wide view of the verified attestations of a set of nodes, see [`summarize_fleet`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FleetReport {
+    /// Number of summarized nodes.
+    pub node_count: usize,
+    /// One entry per distinct set of measurements, by decreasing node count, then by
+    /// fingerprint.
+    pub images: Vec<FleetImage>,
+}
+
+/// A distinct set of measurements running in the fleet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FleetImage {
+    /// [`FullMeasurements::fingerprint`] of the measurements.
+    pub fingerprint: HexBytes<32>,
+    pub measurements: FullMeasurementsHex,
+    /// Number of nodes running these measurements.
+    pub node_count: usize,
+    /// Whether these are the measurements of a test image, see [`FleetReport::flag_test_images`].
+    pub test_image: bool,
+}
+
+impl FleetReport {
+    /// Flags the images whose measurements are among `test_measurements`, e.g. the dev
+    /// measurements accepted alongside the production ones.
+    ///
+    /// Measurements carry no classification of their own, so which images are test images is
+    /// up to the caller.
+    pub fn flag_test_images(mut self, test_measurements: &[FullMeasurements]) -> Self {
+        for image in &mut self.images {
+            let measurements = FullMeasurements::from(image.measurements.clone());
+            image.test_image = image.test_image || test_measurements.contains(&measurements);
+        }
+        self
+    }
+
+    /// The images flagged with [`FleetReport::flag_test_images`].
+    pub fn test_images(&self) -> impl Iterator<Item = &FleetImage> {
+        self.images.iter().filter(|image| image.test_image)
+    }
+}
+
+/// Groups the nodes of `summaries` by the fingerprint of their accepted measurements, for an
+/// audit of every distinct image accepted across a fleet.
+pub fn summarize_fleet(summaries: &[AttestationSummary]) -> FleetReport {
+    let mut images: BTreeMap<[u8; 32], FleetImage> = BTreeMap::new();
+    for summary in summaries {
+        let fingerprint = summary.measurements.fingerprint();
+        images
+            .entry(fingerprint)
+            .and_modify(|image| image.node_count = image.node_count.saturating_add(1))
+            .or_insert_with(|| FleetImage {
+                fingerprint: HexBytes::from(fingerprint),
+                measurements: FullMeasurementsHex::from(summary.measurements),
+                node_count: 1,
+                test_image: false,
+            });
+    }
+
+    let mut images: Vec<FleetImage> = images.into_values().collect();
+    // Stable, so images with equal counts stay ordered by fingerprint.
+    images.sort_by(|a, b| b.node_count.cmp(&a.node_count));
+
+    FleetReport {
+        node_count: summaries.len(),
+        images,
+    }
+}
@@ -0,0 +1,152 @@
+use super::{AttestationSummary, VerificationError, hex_input::verify_against_expected};
+use crate::{
+    collateral::Collateral,
+    measurements::{FullMeasurements, FullMeasurementsHex, Measurements, RtmrRegister},
+    quote::ParsedQuote,
+};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// A verification failure, with the steps an operator should take to resolve it.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{error}")]
+pub struct GuidedError {
+    pub error: VerificationError,
+    /// Actionable steps, in the order they should be taken.
+    pub remediation: Vec<String>,
+}
+
+impl GuidedError {
+    /// The remediation steps as a numbered checklist, one step per line.
+    pub fn checklist(&self) -> String {
+        self.remediation
+            .iter()
+            .zip(1..)
+            .map(|(step, number)| format!("{number}. {step}\n"))
+            .collect()
+    }
+}
+
+/// Verifies `quote` and `collateral` at `now` against the `expected` measurements as
+/// [`super::verify_hex`] does, and on failure returns the steps to resolve it along with the
+/// error.
+///
+/// The steps are tailored to the failure: if the registers do not match, they name every
+/// register that differs, with the expected and found values and what the register measures,
+/// see [`Measurements::diff`]. Collateral, quote, TCB and input failures get a checklist for
+/// their kind of failure, and any other failure its [`VerificationError::explain`] as the
+/// single step.
+pub fn verify_with_guidance(
+    quote: &[u8],
+    collateral: &Collateral,
+    now: u64,
+    expected: &FullMeasurementsHex,
+) -> Result<AttestationSummary, GuidedError> {
+    verify_against_expected(quote, collateral, now, expected).map_err(|error| GuidedError {
+        remediation: remediation(&error, quote, expected),
+        error,
+    })
+}
+
+fn remediation(
+    error: &VerificationError,
+    quote: &[u8],
+    expected: &FullMeasurementsHex,
+) -> Vec<String> {
+    match error {
+        VerificationError::WrongHash { .. } => {
+            let expected = FullMeasurements::from(expected.clone());
+            let live = ParsedQuote::parse(quote)
+                .ok()
+                .and_then(|parsed| Measurements::try_from(&parsed.quote().report).ok());
+            register_mismatch_steps(&expected.rtmrs, live.as_ref())
+        }
+        VerificationError::Collateral(_) | VerificationError::TeeTypeMismatch { .. } => vec![
+            "Check that the clock of the verifying machine is correct.".to_string(),
+            "Fetch fresh collateral for the quote's FMSPC and TEE type from Intel PCS, e.g. with \
+             collateral::missing_collateral_hint."
+                .to_string(),
+            "Verify again with the fresh collateral.".to_string(),
+        ],
+        VerificationError::DcapVerification { .. } => vec![
+            "Check that the quote was copied completely, e.g. by comparing its length with the \
+             node's output."
+                .to_string(),
+            "Fetch the collateral for this quote again; collateral of another platform does \
+             not verify it."
+                .to_string(),
+            "Have the node generate a fresh quote and verify again.".to_string(),
+        ],
+        VerificationError::TcbStatusNotAllowed(_)
+        | VerificationError::NonEmptyAdvisoryIds(_)
+        | VerificationError::TdxModuleTcb(_)
+        | VerificationError::QeTcb(_) => vec![
+            "Look up the advisories of the platform's TCB level in the collateral.".to_string(),
+            "Ask the cloud provider or host operator to update the CPU microcode, firmware \
+             and TDX module."
+                .to_string(),
+            "Once updated, have the node generate a fresh quote and verify again.".to_string(),
+        ],
+        VerificationError::InvalidInput(_) => vec![
+            "Pass the quote as the raw bytes produced by the node.".to_string(),
+            "Pass the collateral as the JSON document fetched for the quote.".to_string(),
+        ],
+        _ => vec![error.explain()],
+    }
+}
+
+/// Steps for measurements that don't match `expected`, naming each register that differs
+/// from `live` if the quote could be read.
+fn register_mismatch_steps(expected: &Measurements, live: Option<&Measurements>) -> Vec<String> {
+    let mut steps = vec![
+        "Confirm which image the node runs, and that the expected measurements were built from \
+         it."
+        .to_string(),
+    ];
+    if let Some(live) = live {
+        steps.extend(
+            expected
+                .diff(live)
+                .changes
+                .iter()
+                .map(|(register, change)| {
+                    format!(
+                        "Check {}: expected {}, found {}. {}",
+                        register.name(),
+                        hex::encode(change.old),
+                        hex::encode(change.new),
+                        register_hint(*register)
+                    )
+                }),
+        );
+    }
+    steps.push(
+        "If the new image is intended, propose its measurements for the allowlist; otherwise \
+         redeploy the node from the expected release."
+            .to_string(),
+    );
+    steps
+}
+
+/// What a change of `register` usually means.
+fn register_hint(register: RtmrRegister) -> &'static str {
+    match register {
+        RtmrRegister::Mrtd | RtmrRegister::Rtmr0 => {
+            "It measures the TD firmware, which changes when the cloud provider updates it; \
+             compare it against the firmware allowlist."
+        }
+        RtmrRegister::Rtmr1 => {
+            "It measures the kernel and its command line; check the node runs the kernel of \
+             the release."
+        }
+        RtmrRegister::Rtmr2 => {
+            "It measures the root filesystem; rebuild the image from the release tag and \
+             compare."
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::{
+    AttestationSummary, QuoteVerifyOptions, VerificationError, compare_hashes,
+    quote_summary::summarize_quote,
+};
+use crate::{
+    collateral::Collateral,
+    measurements::{FullMeasurements, FullMeasurementsHex},
+};
+
+use alloc::{format, string::ToString};
+
+/// Verifies a hex encoded quote with its JSON collateral at `now`, in seconds since the UNIX
+/// epoch, for command line tools taking their inputs as strings.
+///
+/// The quote and collateral are verified with the default [`QuoteVerifyOptions`], and the TCB
+/// status of the platform, its Quoting Enclave and its TDX module checked against the default
+/// policy. The MRTD and RTMR0-2 of the report must match `expected`.
+///
+/// No event log is given, so the key-provider and app compose digests of `expected` are not
+/// checked and are returned as is in [`AttestationSummary::measurements`], and neither are the
+/// report data or the PPID. Use [`super::DstackAttestation::verify_with_summary`] for the
+/// complete check of an MPC node.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::InvalidInput`] if the quote is not hex or the collateral cannot
+/// be parsed, [`VerificationError::WrongHash`] if a register does not match `expected`, and the
+/// errors of [`super::verify_quote`] and the TCB checks.
+pub fn verify_hex(
+    quote_hex: &str,
+    collateral_json: &str,
+    now: u64,
+    expected: &FullMeasurementsHex,
+) -> Result<AttestationSummary, VerificationError> {
+    let quote = hex::decode(quote_hex.trim())
+        .map_err(|e| VerificationError::InvalidInput(format!("quote is not hex: {e}")))?;
+    let collateral = serde_json::from_str(collateral_json)
+        .map_err(|e| e.to_string())
+        .and_then(|json| Collateral::try_from_json(json).map_err(|e| e.to_string()))
+        .map_err(|e| VerificationError::InvalidInput(format!("invalid collateral: {e}")))?;
+
+    verify_against_expected(&quote, &collateral, now, expected)
+}
+
+/// [`verify_hex`] on the decoded quote and collateral.
+pub(super) fn verify_against_expected(
+    quote: &[u8],
+    collateral: &Collateral,
+    now: u64,
+    expected: &FullMeasurementsHex,
+) -> Result<AttestationSummary, VerificationError> {
+    summarize_quote(
+        quote,
+        collateral,
+        now,
+        &QuoteVerifyOptions::default(),
+        |report| {
+            let measurements = FullMeasurements::from(expected.clone());
+            let expected_rtmrs = &measurements.rtmrs;
+            compare_hashes("mrtd", &report.mr_td, &expected_rtmrs.mrtd)?;
+            compare_hashes("rtmr0", &report.rt_mr0, &expected_rtmrs.rtmr0)?;
+            compare_hashes("rtmr1", &report.rt_mr1, &expected_rtmrs.rtmr1)?;
+            compare_hashes("rtmr2", &report.rt_mr2, &expected_rtmrs.rtmr2)?;
+            Ok(measurements)
+        },
+    )
+}
@@ -0,0 +1,115 @@
+use super::AttestationSummary;
+use crate::{
+    collateral::Fmspc, measurements::FullMeasurementsHex, tcb_info::HexBytes, tcb_status::TcbStatus,
+};
+
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+use serde::{Deserialize, Serialize};
+
+/// `_type` of an in-toto v1 statement.
+pub const IN_TOTO_STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+
+/// Predicate type of [`TdxVerificationPredicate`]. It identifies the predicate schema and is
+/// not meant to be fetched; a new schema gets a new version.
+pub const TDX_VERIFICATION_PREDICATE_TYPE: &str =
+    "https://github.com/near/mpc/attestation/tdx-verification/v1";
+
+/// Name of the [`InTotoSubject`] for the app compose file of the attested app.
+pub const APP_COMPOSE_SUBJECT: &str = "app-compose.json";
+/// Name of the [`InTotoSubject`] for the measurements of the attested TD, see
+/// [`crate::measurements::FullMeasurements::fingerprint`].
+pub const MEASUREMENTS_SUBJECT: &str = "tdx-measurements";
+
+/// An [in-toto v1 statement](https://github.com/in-toto/attestation/blob/main/spec/v1/statement.md)
+/// that an attestation was verified, as returned by [`AttestationSummary::to_in_toto`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InTotoStatement {
+    /// Always [`IN_TOTO_STATEMENT_TYPE`].
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    pub subject: Vec<InTotoSubject>,
+    /// Always [`TDX_VERIFICATION_PREDICATE_TYPE`].
+    #[serde(rename = "predicateType")]
+    pub predicate_type: String,
+    pub predicate: TdxVerificationPredicate,
+}
+
+/// An artifact the statement is about, identified by its digests, by algorithm name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InTotoSubject {
+    pub name: String,
+    /// Lowercase hex digests, e.g. under `sha256`.
+    pub digest: BTreeMap<String, String>,
+}
+
+/// The predicate of [`TDX_VERIFICATION_PREDICATE_TYPE`]: the measurements of a TDX TD, its
+/// platform and the outcome of verifying its attestation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TdxVerificationPredicate {
+    pub measurements: FullMeasurementsHex,
+    pub fmspc: Fmspc,
+    pub ppid: HexBytes<16>,
+    pub mr_config_id: HexBytes<48>,
+    pub mr_owner: HexBytes<48>,
+    pub report_data: HexBytes<64>,
+    pub verification: TdxVerificationOutcome,
+}
+
+/// The outcome of verifying the attestation. Timestamps are Unix time in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TdxVerificationOutcome {
+    /// Always `true`, as a summary is only produced for an accepted attestation.
+    pub accepted: bool,
+    pub platform_tcb_status: TcbStatus,
+    pub qe_tcb_status: Option<TcbStatus>,
+    pub advisory_ids: Vec<String>,
+    pub verified_at: u64,
+    pub tcb_info_issued_at: u64,
+    pub tcb_info_next_update: u64,
+}
+
+impl AttestationSummary {
+    /// The summary as an in-toto statement, for supply chain tooling such as SLSA verifiers.
+    ///
+    /// The subjects are the app compose file, by its SHA-256 hash as measured into RTMR3, and
+    /// the measurements, by [`crate::measurements::FullMeasurements::fingerprint`]. The
+    /// predicate holds every field of the summary.
+    ///
+    /// The statement is not signed: wrap it in a DSSE envelope signed by the verifier for
+    /// consumers to trust it.
+    pub fn to_in_toto(&self) -> InTotoStatement {
+        let sha256_subject = |name: &str, digest: &[u8]| InTotoSubject {
+            name: String::from(name),
+            digest: BTreeMap::from([(String::from("sha256"), hex::encode(digest))]),
+        };
+
+        InTotoStatement {
+            statement_type: String::from(IN_TOTO_STATEMENT_TYPE),
+            subject: vec![
+                sha256_subject(
+                    APP_COMPOSE_SUBJECT,
+                    &self.measurements.app_compose_hash_payload,
+                ),
+                sha256_subject(MEASUREMENTS_SUBJECT, &self.measurements.fingerprint()),
+            ],
+            predicate_type: String::from(TDX_VERIFICATION_PREDICATE_TYPE),
+            predicate: TdxVerificationPredicate {
+                measurements: FullMeasurementsHex::from(self.measurements),
+                fmspc: self.fmspc,
+                ppid: self.ppid.clone(),
+                mr_config_id: HexBytes::from(self.mr_config_id),
+                mr_owner: HexBytes::from(self.mr_owner),
+                report_data: HexBytes::from(self.report_data.to_bytes()),
+                verification: TdxVerificationOutcome {
+                    accepted: true,
+                    platform_tcb_status: self.platform_tcb_status,
+                    qe_tcb_status: self.qe_tcb_status,
+                    advisory_ids: self.advisory_ids.clone(),
+                    verified_at: self.verified_at,
+                    tcb_info_issued_at: self.tcb_info_issued_at,
+                    tcb_info_next_update: self.tcb_info_next_update,
+                },
+            },
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use super::AttestationSummary;
+
+/// A metric as returned by [`AttestationSummary::to_metrics`]: name, value and labels.
+pub type Metric = (String, f64, Vec<(String, String)>);
+
+/// Gauge set to `1` for every verified attestation.
+pub const VERIFIED_METRIC: &str = "mpc_attestation_verified";
+/// Gauge of the number of outstanding advisories of the platform.
+pub const ADVISORIES_METRIC: &str = "mpc_attestation_advisories";
+/// Gauge of the Unix time, in seconds, the attestation was verified at.
+pub const VERIFIED_AT_METRIC: &str = "mpc_attestation_verified_at_seconds";
+/// Gauge of the Unix time, in seconds, of the `nextUpdate` of the TCB info.
+pub const TCB_INFO_NEXT_UPDATE_METRIC: &str = "mpc_attestation_tcb_info_next_update_seconds";
+
+impl AttestationSummary {
+    /// The summary as Prometheus gauges, for the caller to record in its metrics registry:
+    /// [`VERIFIED_METRIC`], [`ADVISORIES_METRIC`], [`VERIFIED_AT_METRIC`] and
+    /// [`TCB_INFO_NEXT_UPDATE_METRIC`].
+    ///
+    /// Every metric has the same labels: `fmspc` in lowercase hex, `tcb_status`, the Intel name
+    /// of the platform TCB status, and `accepted`, always `true` as a summary is only produced
+    /// for an accepted attestation.
+    pub fn to_metrics(&self) -> Vec<Metric> {
+        let labels = vec![
+            (String::from("fmspc"), self.fmspc.to_hex()),
+            (
+                String::from("tcb_status"),
+                String::from(self.platform_tcb_status.as_str()),
+            ),
+            (String::from("accepted"), String::from("true")),
+        ];
+        let advisory_count = u64::try_from(self.advisory_ids.len()).unwrap_or(u64::MAX);
+
+        [
+            (VERIFIED_METRIC, 1),
+            (ADVISORIES_METRIC, advisory_count),
+            (VERIFIED_AT_METRIC, self.verified_at),
+            (TCB_INFO_NEXT_UPDATE_METRIC, self.tcb_info_next_update),
+        ]
+        .into_iter()
+        .map(|(name, value)| (String::from(name), gauge_value(value), labels.clone()))
+        .collect()
+    }
+}
+
+/// `value` as a gauge value, exact up to 2^53, assembled from its 32 bit halves as there is no
+/// lossless conversion from `u64`.
+fn gauge_value(value: u64) -> f64 {
+    let high = u32::try_from(value >> 32).unwrap_or(u32::MAX);
+    let low = u32::try_from(value & u64::from(u32::MAX)).unwrap_or(u32::MAX);
+    f64::from(high) * (f64::from(u32::MAX) + 1.0) + f64::from(low)
+}
@@ -0,0 +1,117 @@
+use super::{
+    AttestationSummary, OrErr as _, QuoteVerifyOptions, VerificationError, compare_hashes,
+    quote_summary::summarize_quote, verify_quote, verify_tcb_status,
+};
+use crate::{
+    collateral::Collateral,
+    measurements::{FullMeasurements, Measurements},
+    report_data::{KeyScheme, for_public_key},
+};
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// Prefix of an ed25519 public key in NEAR's string form.
+const ED25519_PREFIX: &str = "ed25519:";
+
+/// A public key in NEAR's string form, e.g. `ed25519:<base58>`, as used for the implicit
+/// account and access keys of a node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NearPublicKey(String);
+
+impl NearPublicKey {
+    /// Formats an ed25519 public key as `ed25519:` followed by its base58 encoding.
+    pub fn from_ed25519(public_key: &[u8; 32]) -> Self {
+        Self([ED25519_PREFIX, &bs58::encode(public_key).into_string()].concat())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for NearPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Verifies `quote` and checks that its report data binds the ed25519 `public_key`, see
+/// [`crate::report_data::for_public_key`], returning the key in NEAR's string form.
+///
+/// The report data only holds a hash of the key, so the key itself must be provided, e.g.
+/// from the node's registration; this checks that it is the key the TEE attested to. The quote
+/// must verify against `collateral` and have a TCB status accepted by
+/// [`QuoteVerifyOptions::tcb_policy`]. The measurements are not checked, use
+/// [`super::DstackAttestation::verify`] for the full attestation.
+///
+/// # Errors
+///
+/// Returns the errors of [`verify_quote`] and [`verify_tcb_status`],
+/// [`VerificationError::ReportNotTd10`] if the quote does not carry a TD report, and
+/// [`VerificationError::WrongHash`] if the report data binds another key.
+pub fn verify_to_near_public_key(
+    quote: &[u8],
+    collateral: &Collateral,
+    timestamp_seconds: u64,
+    public_key: &[u8; 32],
+    options: &QuoteVerifyOptions,
+) -> Result<NearPublicKey, VerificationError> {
+    let verified = verify_quote(quote, collateral, timestamp_seconds, options)?;
+    verify_tcb_status(&verified, &options.tcb_policy)?;
+
+    let td10 = verified
+        .report
+        .as_td10()
+        .ok_or(VerificationError::ReportNotTd10)?;
+    let expected = for_public_key(KeyScheme::Ed25519, public_key)
+        .map_err(|e| VerificationError::Custom(e.to_string()))?;
+    compare_hashes("report_data", &td10.report_data, &expected)?;
+
+    Ok(NearPublicKey::from_ed25519(public_key))
+}
+
+/// Verifies `quote` against `collateral` at `now` with the default [`QuoteVerifyOptions`],
+/// including the TCB of the platform, its Quoting Enclave and its TDX module, and checks
+/// that its report data is `contract_commitment`, the report data the contract expects for
+/// the node, e.g. as returned by a view call.
+///
+/// Only the quote is given, without the event log, so the measurements are those of the
+/// report: [`AttestationSummary::measurements`] has the MRTD and RTMR0-2 of the report, and
+/// zeros for the event digests. They are not checked against any accepted measurements.
+///
+/// # Errors
+///
+/// Returns the errors of [`verify_quote`] and the TCB checks, and
+/// [`VerificationError::ReportDataMismatch`] if the report data is not the commitment.
+pub fn verify_against_contract_commitment(
+    quote: &[u8],
+    collateral: &Collateral,
+    now: u64,
+    contract_commitment: &[u8; 64],
+) -> Result<AttestationSummary, VerificationError> {
+    summarize_quote(
+        quote,
+        collateral,
+        now,
+        &QuoteVerifyOptions::default(),
+        |report| {
+            (report.report_data == *contract_commitment).or_err(|| {
+                VerificationError::ReportDataMismatch {
+                    expected: hex::encode(contract_commitment),
+                    found: hex::encode(report.report_data),
+                }
+            })?;
+            Ok(FullMeasurements {
+                rtmrs: Measurements {
+                    mrtd: report.mr_td,
+                    rtmr0: report.rt_mr0,
+                    rtmr1: report.rt_mr1,
+                    rtmr2: report.rt_mr2,
+                },
+                key_provider_event_digest: [0; 48],
+                app_compose_hash_payload: [0; 32],
+            })
+        },
+    )
+}
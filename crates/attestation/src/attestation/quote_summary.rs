@@ -0,0 +1,74 @@
+use super::{
+    AttestationSummary, QuoteVerifyOptions, VerificationError, verify_qe_tcb, verify_quote,
+    verify_tcb_status, verify_tdx_module_tcb,
+};
+use crate::{
+    collateral::Collateral, measurements::FullMeasurements, report_data::ReportData,
+    tcb_info::HexBytes,
+};
+
+use alloc::string::ToString;
+use dcap_qvl::quote::TDReport10;
+
+/// Verifies `quote` against `collateral` at `now` with `options`, and the TCB status of the
+/// platform, its Quoting Enclave and its TDX module against
+/// [`QuoteVerifyOptions::tcb_policy`], for verifiers which have a quote but no event log.
+///
+/// `check` then checks the TD report, and returns the measurements reported in
+/// [`AttestationSummary::measurements`].
+pub(super) fn summarize_quote(
+    quote: &[u8],
+    collateral: &Collateral,
+    now: u64,
+    options: &QuoteVerifyOptions,
+    check: impl FnOnce(&TDReport10) -> Result<FullMeasurements, VerificationError>,
+) -> Result<AttestationSummary, VerificationError> {
+    let verification_result = verify_quote(quote, collateral, now, options)?;
+    let report = verification_result
+        .report
+        .as_td10()
+        .ok_or(VerificationError::ReportNotTd10)?;
+
+    let platform_tcb_status = verify_tcb_status(&verification_result, &options.tcb_policy)?;
+    let qe_tcb_status = if options.skip_qe_identity {
+        tracing::warn!("skipping QE identity verification");
+        None
+    } else {
+        Some(verify_qe_tcb(
+            quote,
+            &collateral.qe_identity,
+            &options.tcb_policy,
+        )?)
+    };
+    verify_tdx_module_tcb(
+        quote,
+        &verification_result.report,
+        collateral,
+        &options.tcb_policy,
+    )?;
+
+    let measurements = check(report)?;
+
+    let tcb_info_validity = collateral
+        .tcb_info_validity()
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+    let fmspc = collateral
+        .fmspc()
+        .map_err(|e| VerificationError::Collateral(e.to_string()))?;
+    let ppid = <[u8; 16]>::try_from(verification_result.ppid.as_slice())
+        .map_err(|_| VerificationError::PpidWrongSize(verification_result.ppid.len()))?;
+    Ok(AttestationSummary {
+        measurements,
+        ppid: HexBytes::from(ppid),
+        fmspc,
+        platform_tcb_status,
+        qe_tcb_status,
+        mr_config_id: report.mr_config_id,
+        mr_owner: report.mr_owner,
+        report_data: ReportData::new(report.report_data),
+        advisory_ids: verification_result.advisory_ids,
+        verified_at: now,
+        tcb_info_issued_at: tcb_info_validity.not_before,
+        tcb_info_next_update: tcb_info_validity.next_update,
+    })
+}
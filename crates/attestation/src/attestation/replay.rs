@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use sha2::{Digest as _, Sha256};
+
+use super::VerificationError;
+
+/// Storage of the quotes seen by a [`ReplayGuard`], keyed by the SHA-256 hash of the quote.
+/// Implement this to share or persist the seen quotes, e.g. across nodes or restarts.
+pub trait SeenQuoteStore {
+    /// Returns when the quote with `quote_hash` was last recorded, if it was.
+    fn last_seen(&self, quote_hash: &[u8; 32]) -> Option<u64>;
+
+    /// Records that the quote with `quote_hash` was accepted at `seen_at`.
+    fn record(&mut self, quote_hash: [u8; 32], seen_at: u64);
+
+    /// Forgets every quote last recorded before `cutoff`.
+    fn prune(&mut self, cutoff: u64);
+}
+
+/// A [`SeenQuoteStore`] kept in memory.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySeenQuotes {
+    seen: HashMap<[u8; 32], u64>,
+}
+
+impl InMemorySeenQuotes {
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl SeenQuoteStore for InMemorySeenQuotes {
+    fn last_seen(&self, quote_hash: &[u8; 32]) -> Option<u64> {
+        self.seen.get(quote_hash).copied()
+    }
+
+    fn record(&mut self, quote_hash: [u8; 32], seen_at: u64) {
+        self.seen.insert(quote_hash, seen_at);
+    }
+
+    fn prune(&mut self, cutoff: u64) {
+        self.seen.retain(|_, seen_at| *seen_at >= cutoff);
+    }
+}
+
+/// Rejects a quote that was already accepted within the last `window_secs` seconds.
+///
+/// This complements the nonce in the report data: a replayed quote is rejected even if the
+/// verifier reuses an expected nonce.
+#[derive(Debug, Clone)]
+pub struct ReplayGuard<S = InMemorySeenQuotes> {
+    store: S,
+    window_secs: u64,
+}
+
+impl ReplayGuard {
+    pub fn new(window_secs: u64) -> Self {
+        Self::with_store(InMemorySeenQuotes::default(), window_secs)
+    }
+}
+
+impl<S: SeenQuoteStore> ReplayGuard<S> {
+    pub fn with_store(store: S, window_secs: u64) -> Self {
+        Self { store, window_secs }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Checks that `quote` was not accepted within the window before `now`, and records it as
+    /// seen at `now` if so. A rejected quote is not recorded again, so the window is not
+    /// extended by replay attempts.
+    pub fn check_and_record(&mut self, quote: &[u8], now: u64) -> Result<(), VerificationError> {
+        let quote_hash: [u8; 32] = Sha256::digest(quote).into();
+
+        if let Some(last_seen) = self.store.last_seen(&quote_hash) {
+            if now.saturating_sub(last_seen) < self.window_secs {
+                return Err(VerificationError::Replayed { last_seen });
+            }
+        }
+
+        self.store.record(quote_hash, now);
+        Ok(())
+    }
+
+    /// Forgets the quotes that fell out of the window before `now`.
+    pub fn prune(&mut self, now: u64) {
+        self.store.prune(now.saturating_sub(self.window_secs));
+    }
+}
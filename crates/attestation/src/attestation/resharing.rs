@@ -0,0 +1,219 @@
+use super::{QuoteVerifyOptions, VerificationError, verify_quote_within, verify_tcb_status};
+use crate::{
+    collateral::PreparedCollateral,
+    measurements::{FullMeasurements, Measurements, RtmrRegister},
+    quote::ParsedQuote,
+    report_data::{KeyScheme, ReportData, for_public_key},
+};
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use dcap_qvl::verify::VerifiedReport;
+
+/// Verifies the quotes of every participant of a resharing, and returns their report data, in
+/// the same order as `quotes`, only if all of them pass: each quote must verify against the
+/// collateral, have a TCB status accepted by [`QuoteVerifyOptions::tcb_policy`], and match
+/// the MRTD and RTMR0-2 of `expected`.
+///
+/// The key-provider and app compose digests of `expected` are measured into RTMR3 through the
+/// event log, which is not part of a quote, so they are not checked here. Use
+/// [`super::DstackAttestation::verify`] to check the full attestation of each participant.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::ResharingParticipant`] with the first rejected participant,
+/// and [`VerificationError::Custom`] if `quotes` is empty.
+pub fn verify_resharing_set(
+    quotes: &[&[u8]],
+    expected: &FullMeasurements,
+    collateral: &PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<Vec<ReportData>, VerificationError> {
+    if quotes.is_empty() {
+        return Err(VerificationError::Custom(String::from(
+            "the resharing set has no participants",
+        )));
+    }
+
+    quotes
+        .iter()
+        .enumerate()
+        .map(|(index, quote)| {
+            verify_participant(quote, expected, collateral, timestamp_seconds, options).map_err(
+                |error| VerificationError::ResharingParticipant {
+                    index,
+                    error: Box::new(error),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Verifies the quotes of the participants of a resharing as [`verify_resharing_set`] does,
+/// and checks that they are exactly the participants with `expected_keys`: every quote must
+/// bind one of the Ed25519 keys, see [`for_public_key`], and every key must be bound by one
+/// quote.
+///
+/// # Errors
+///
+/// Returns the errors of [`verify_resharing_set`], and of [`verify_participant_keys`] for the
+/// report data of the quotes.
+pub fn verify_participants(
+    quotes: &[&[u8]],
+    expected_keys: &[[u8; 32]],
+    expected: &FullMeasurements,
+    collateral: &PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<(), VerificationError> {
+    let report_data =
+        verify_resharing_set(quotes, expected, collateral, timestamp_seconds, options)?;
+    verify_participant_keys(&report_data, expected_keys)
+}
+
+/// Checks that the report data of the participants of a resharing, in the order of their
+/// quotes, binds exactly the Ed25519 `expected_keys`, one participant per key. The order of
+/// the keys does not matter, and a key listed twice is expected once.
+///
+/// # Errors
+///
+/// Returns, for the first participant at fault, [`VerificationError::UnexpectedParticipant`]
+/// if its report data binds none of the keys, and [`VerificationError::DuplicateParticipant`]
+/// if it binds the key of an earlier participant. Otherwise returns
+/// [`VerificationError::MissingParticipant`] with the first key no participant binds.
+pub fn verify_participant_keys(
+    report_data: &[ReportData],
+    expected_keys: &[[u8; 32]],
+) -> Result<(), VerificationError> {
+    let mut keys: Vec<&[u8; 32]> = Vec::with_capacity(expected_keys.len());
+    for key in expected_keys {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    let mut expected: Vec<(&[u8; 32], ReportData, Option<usize>)> = keys
+        .into_iter()
+        .map(|key| {
+            for_public_key(KeyScheme::Ed25519, key)
+                .map(|binding| (key, ReportData::new(binding), None))
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|e| VerificationError::InvalidInput(e.to_string()))?;
+
+    for (index, participant) in report_data.iter().enumerate() {
+        let Some((key, _, bound_by)) = expected
+            .iter_mut()
+            .find(|(_, binding, _)| binding == participant)
+        else {
+            return Err(VerificationError::UnexpectedParticipant {
+                index,
+                report_data: hex::encode(participant.as_bytes()),
+            });
+        };
+        if let Some(first) = *bound_by {
+            return Err(VerificationError::DuplicateParticipant {
+                first,
+                index,
+                key: hex::encode(key),
+            });
+        }
+        *bound_by = Some(index);
+    }
+
+    match expected.iter().find(|(_, _, bound_by)| bound_by.is_none()) {
+        Some((key, _, _)) => Err(VerificationError::MissingParticipant {
+            key: hex::encode(key),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the participants of a resharing, given by their verified reports, run the same
+/// app: the MRTD, RTMR1 (kernel and boot parameters) and RTMR2 (OS application) of every
+/// report must equal those of the first. RTMR0 measures the virtual firmware and its
+/// configuration, which legitimately differ between cloud hosts, so it is not compared.
+///
+/// The app compose digest is measured into RTMR3 through the event log, along with
+/// per-instance events, so it cannot be compared from the reports alone. Use
+/// [`super::verify_app_identity`] for each participant to check it.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::ResharingParticipant`] with the first participant that
+/// diverges from the first one, holding a [`VerificationError::WrongHash`] for the first
+/// differing register or [`VerificationError::ReportNotTd10`], and [`VerificationError::Custom`]
+/// if `reports` is empty.
+pub fn verify_same_app(reports: &[&VerifiedReport]) -> Result<(), VerificationError> {
+    let participant = |index, error| VerificationError::ResharingParticipant {
+        index,
+        error: Box::new(error),
+    };
+    let measurements = |index, report: &VerifiedReport| {
+        Measurements::try_from(report)
+            .map_err(|_| participant(index, VerificationError::ReportNotTd10))
+    };
+
+    let Some((first, rest)) = reports.split_first() else {
+        return Err(VerificationError::Custom(String::from(
+            "the resharing set has no participants",
+        )));
+    };
+    let reference = measurements(0, first)?;
+
+    for (index, report) in (1..).zip(rest) {
+        let diff = reference.diff(&measurements(index, report)?);
+        if let Some((register, change)) = diff
+            .changes
+            .iter()
+            .find(|(register, _)| *register != RtmrRegister::Rtmr0)
+        {
+            return Err(participant(
+                index,
+                VerificationError::WrongHash {
+                    name: register.name(),
+                    found: hex::encode(change.new),
+                    expected: hex::encode(change.old),
+                },
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn verify_participant(
+    quote: &[u8],
+    expected: &FullMeasurements,
+    collateral: &PreparedCollateral,
+    timestamp_seconds: u64,
+    options: &QuoteVerifyOptions,
+) -> Result<ReportData, VerificationError> {
+    let verified = verify_quote_within(
+        quote,
+        ParsedQuote::parse(quote).as_ref(),
+        collateral.collateral(),
+        collateral.validity(),
+        timestamp_seconds,
+        options,
+    )?;
+    verify_tcb_status(&verified, &options.tcb_policy)?;
+
+    let measurements =
+        Measurements::try_from(&verified).map_err(|_| VerificationError::ReportNotTd10)?;
+    if let Some((register, change)) = expected.rtmrs.diff(&measurements).changes.first() {
+        return Err(VerificationError::WrongHash {
+            name: register.name(),
+            found: hex::encode(change.new),
+            expected: hex::encode(change.old),
+        });
+    }
+
+    let td10 = verified
+        .report
+        .as_td10()
+        .ok_or(VerificationError::ReportNotTd10)?;
+    Ok(ReportData::new(td10.report_data))
+}
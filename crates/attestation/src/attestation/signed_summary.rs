@@ -0,0 +1,104 @@
+use super::AttestationSummary;
+use crate::tcb_status::TcbStatus;
+
+use alloc::vec::Vec;
+use ed25519_dalek::{Signature, SignatureError, Signer as _, SigningKey, VerifyingKey};
+
+/// Domain separator of the payload signed for a [`SignedSummary`].
+const SIGNING_DOMAIN: &[u8] = b"mpc-attestation:signed-summary:v1";
+
+/// An [`AttestationSummary`] signed with ed25519 by the verifier that produced it, e.g. a
+/// coordinator verifying attestations on behalf of services which trust it, so that they accept
+/// the summary without running DCAP verification themselves.
+#[derive(Debug, Clone)]
+pub struct SignedSummary {
+    pub summary: AttestationSummary,
+    pub signature: [u8; 64],
+}
+
+impl AttestationSummary {
+    /// Signs [`AttestationSummary::signing_payload`] with `signing_key`.
+    pub fn sign(&self, signing_key: &SigningKey) -> SignedSummary {
+        SignedSummary {
+            summary: self.clone(),
+            signature: signing_key.sign(&self.signing_payload()).to_bytes(),
+        }
+    }
+
+    /// The canonical encoding covered by the signature of a [`SignedSummary`]: a domain tag
+    /// followed by every field, in declaration order.
+    ///
+    /// - Fixed size fields are their bytes, [`AttestationSummary::measurements`] in the
+    ///   encoding of [`crate::measurements::FullMeasurements::to_packed`], and timestamps are
+    ///   8 bytes little endian.
+    /// - TCB statuses are Intel's string, see [`TcbStatus::as_str`], prefixed by their length.
+    ///   [`AttestationSummary::qe_tcb_status`] is prefixed by a `1` byte if present and
+    ///   encoded as just a `0` byte otherwise.
+    /// - [`AttestationSummary::advisory_ids`] is prefixed by its count, and each ID by its
+    ///   length. Counts and lengths are 8 bytes little endian.
+    ///
+    /// The encoding does not depend on how the summary is transported, so the signature
+    /// survives any serialization that preserves the fields.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        fn push_len(payload: &mut Vec<u8>, len: usize) {
+            let len = u64::try_from(len).unwrap_or(u64::MAX);
+            payload.extend_from_slice(&len.to_le_bytes());
+        }
+        fn push_status(payload: &mut Vec<u8>, status: TcbStatus) {
+            push_len(payload, status.as_str().len());
+            payload.extend_from_slice(status.as_str().as_bytes());
+        }
+
+        let mut payload = SIGNING_DOMAIN.to_vec();
+        payload.extend_from_slice(&self.measurements.to_packed());
+        payload.extend_from_slice(self.ppid.as_ref());
+        payload.extend_from_slice(&<[u8; 6]>::from(self.fmspc));
+        push_status(&mut payload, self.platform_tcb_status);
+        match self.qe_tcb_status {
+            Some(status) => {
+                payload.push(1);
+                push_status(&mut payload, status);
+            }
+            None => payload.push(0),
+        }
+        payload.extend_from_slice(&self.mr_config_id);
+        payload.extend_from_slice(&self.mr_owner);
+        payload.extend_from_slice(self.report_data.as_bytes());
+        push_len(&mut payload, self.advisory_ids.len());
+        for advisory_id in &self.advisory_ids {
+            push_len(&mut payload, advisory_id.len());
+            payload.extend_from_slice(advisory_id.as_bytes());
+        }
+        for timestamp in [
+            self.verified_at,
+            self.tcb_info_issued_at,
+            self.tcb_info_next_update,
+        ] {
+            payload.extend_from_slice(&timestamp.to_le_bytes());
+        }
+        payload
+    }
+}
+
+impl SignedSummary {
+    /// Checks that the summary was signed by `trusted_key`, and returns it.
+    ///
+    /// The summary is only as trustworthy as the signer: this does not verify the attestation
+    /// again. Note that the summary records the time it was verified at,
+    /// [`AttestationSummary::verified_at`], which callers should check for freshness.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SignatureError`] if the signature is not a valid signature by `trusted_key`
+    /// over [`AttestationSummary::signing_payload`].
+    pub fn verify(
+        &self,
+        trusted_key: &VerifyingKey,
+    ) -> Result<&AttestationSummary, SignatureError> {
+        trusted_key.verify_strict(
+            &self.summary.signing_payload(),
+            &Signature::from_bytes(&self.signature),
+        )?;
+        Ok(&self.summary)
+    }
+}
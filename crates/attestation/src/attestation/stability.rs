@@ -0,0 +1,109 @@
+use super::QuoteVerifyOptions;
+use crate::{
+    collateral::{Certificates, Collateral, CrlValidity},
+    quote::ParsedQuote,
+};
+
+use alloc::{vec, vec::Vec};
+
+/// The window `[earliest, latest]` of verification times around `now`, in seconds since the
+/// UNIX epoch, for which [`super::verify_quote`] of `quote` and `collateral` with `options`
+/// gives the same result as at `now`.
+///
+/// Verification depends on the time through the validity window of the collateral, and of its
+/// CRLs with [`QuoteVerifyOptions::extended_collateral_checks`], widened by
+/// [`QuoteVerifyOptions::allowed_skew_secs`], the
+/// [`QuoteVerifyOptions::max_collateral_age_secs`], and the validity of the certificates of
+/// the quote and collateral, which DCAP checks at the time clamped into the collateral
+/// validity window. A node whose `now` is close to either end of the window, e.g. around a
+/// TCB rollover, may intermittently fail or pass verification depending on the verifier's
+/// clock.
+///
+/// The window is conservative: the result is identical within it, and may stay identical
+/// past its ends. If the dates of the inputs cannot be read, verification fails at any time,
+/// and the window is `(0, u64::MAX)`.
+pub fn stability_window(
+    quote: &[u8],
+    collateral: &Collateral,
+    now: u64,
+    options: &QuoteVerifyOptions,
+) -> (u64, u64) {
+    let Some(boundaries) = boundaries(quote, collateral, options) else {
+        return (0, u64::MAX);
+    };
+
+    let earliest = boundaries
+        .iter()
+        .copied()
+        .filter(|boundary| *boundary <= now)
+        .max()
+        .unwrap_or(0);
+    let latest = boundaries
+        .iter()
+        .copied()
+        .filter(|boundary| *boundary > now)
+        .min()
+        .map_or(u64::MAX, |boundary| boundary.saturating_sub(1));
+    (earliest, latest)
+}
+
+/// The times at which the verification result may differ from that of the second before.
+/// `None` if a date cannot be read.
+fn boundaries(
+    quote: &[u8],
+    collateral: &Collateral,
+    options: &QuoteVerifyOptions,
+) -> Option<Vec<u64>> {
+    let skew = options.allowed_skew_secs;
+    let validity = collateral.validity_window().ok()?;
+    let mut boundaries = vec![
+        validity.not_before.saturating_sub(skew),
+        validity.next_update.saturating_add(skew).saturating_add(1),
+    ];
+
+    if options.extended_collateral_checks {
+        for (crl, invalid) in collateral.crls() {
+            let crl = CrlValidity::parse(crl, invalid).ok()?;
+            boundaries.push(crl.this_update.saturating_sub(skew));
+            if let Some(next_update) = crl.next_update {
+                boundaries.push(next_update.saturating_add(skew).saturating_add(1));
+            }
+        }
+    }
+
+    if let Some(max_age_secs) = options.max_collateral_age_secs {
+        let issued_at = collateral.tcb_info_validity().ok()?.not_before;
+        boundaries.push(issued_at.saturating_add(max_age_secs).saturating_add(1));
+    }
+
+    // DCAP verification runs at the time clamped into the validity window, so the validity of
+    // a certificate only matters where the clamped time moves.
+    let clamped =
+        |boundary: &u64| validity.not_before < *boundary && *boundary <= validity.next_update;
+    let parsed = ParsedQuote::parse(quote).ok()?;
+    let pck_chain = parsed.pck_cert_chain().ok().or_else(|| {
+        collateral
+            .pck_certificate_chain
+            .as_ref()
+            .map(|chain| chain.as_bytes())
+    })?;
+    for chain in [
+        pck_chain,
+        collateral.tcb_info_issuer_chain.as_bytes(),
+        collateral.qe_identity_issuer_chain.as_bytes(),
+        collateral.pck_crl_issuer_chain.as_bytes(),
+    ] {
+        for certificate in Certificates::parse_any(chain).ok()?.iter() {
+            let certificate_validity = &certificate.tbs_certificate.validity;
+            let not_before = certificate_validity.not_before.to_unix_duration().as_secs();
+            let not_after = certificate_validity.not_after.to_unix_duration().as_secs();
+            boundaries.extend(
+                [not_before, not_after.saturating_add(1)]
+                    .into_iter()
+                    .filter(clamped),
+            );
+        }
+    }
+
+    Some(boundaries)
+}
@@ -0,0 +1,115 @@
+use super::{AttestationSummary, DbRow, DstackAttestation, QuoteVerifyOptions, VerificationError};
+use crate::{measurements::FullMeasurements, report_data::ReportData, tcb_info::HexBytes};
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a recorded verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedResult {
+    /// The attestation was accepted, with the row of its [`AttestationSummary`].
+    Accepted(Box<DbRow>),
+    /// The attestation was rejected, with the message of the error.
+    Rejected(String),
+}
+
+impl RecordedResult {
+    fn of(result: &Result<AttestationSummary, VerificationError>) -> Self {
+        match result {
+            Ok(summary) => Self::Accepted(Box::new(summary.to_db_row())),
+            Err(e) => Self::Rejected(e.to_string()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Accepted(row) => format!(
+                "accepted as {}",
+                serde_json::to_string(row).unwrap_or_default()
+            ),
+            Self::Rejected(message) => format!("rejected with `{message}`"),
+        }
+    }
+}
+
+/// Every input of a [`DstackAttestation::verify_with_summary`] and its result, so that a third
+/// party can reproduce the decision, see [`Transcript::replay`].
+///
+/// Serializes to JSON with serde, the quote and collateral included as they were verified.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub attestation: DstackAttestation,
+    pub expected_report_data: ReportData,
+    /// The `timestamp_seconds` the attestation was verified at.
+    pub now: u64,
+    pub accepted_measurements: Vec<FullMeasurements>,
+    pub accepted_ppids: Vec<HexBytes<16>>,
+    pub options: QuoteVerifyOptions,
+    pub result: RecordedResult,
+}
+
+impl Transcript {
+    /// Verifies `attestation` as [`DstackAttestation::verify_with_summary`] does, and records
+    /// the inputs and result of the verification along with the result.
+    pub fn record(
+        attestation: DstackAttestation,
+        expected_report_data: ReportData,
+        now: u64,
+        accepted_measurements: &[FullMeasurements],
+        accepted_ppids: &[HexBytes<16>],
+        options: &QuoteVerifyOptions,
+    ) -> (Result<AttestationSummary, VerificationError>, Self) {
+        let result = attestation.verify_with_summary(
+            expected_report_data,
+            now,
+            accepted_measurements,
+            accepted_ppids,
+            options,
+        );
+        let transcript = Self {
+            attestation,
+            expected_report_data,
+            now,
+            accepted_measurements: accepted_measurements.to_vec(),
+            accepted_ppids: accepted_ppids.to_vec(),
+            options: options.clone(),
+            result: RecordedResult::of(&result),
+        };
+        (result, transcript)
+    }
+
+    /// Verifies the recorded attestation again from the recorded inputs, and returns the result
+    /// if it is the recorded one.
+    ///
+    /// An accepted attestation must be accepted with the same [`DbRow`], and a rejected one
+    /// rejected with the same error message.
+    ///
+    /// # Errors
+    ///
+    /// Returns the replayed error if the attestation was rejected as recorded, and
+    /// [`VerificationError::TranscriptMismatch`] if the replayed result is not the recorded
+    /// one.
+    pub fn replay(&self) -> Result<AttestationSummary, VerificationError> {
+        let replayed = self.attestation.verify_with_summary(
+            self.expected_report_data,
+            self.now,
+            &self.accepted_measurements,
+            &self.accepted_ppids,
+            &self.options,
+        );
+        let replayed_result = RecordedResult::of(&replayed);
+        if replayed_result != self.result {
+            return Err(VerificationError::TranscriptMismatch {
+                recorded: self.result.describe(),
+                replayed: replayed_result.describe(),
+            });
+        }
+        replayed
+    }
+}
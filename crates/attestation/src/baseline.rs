@@ -0,0 +1,166 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+use borsh::{BorshDeserialize, BorshSerialize};
+use dcap_qvl::verify::VerifiedReport;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_with::{Bytes, serde_as};
+
+use crate::measurements::{FullMeasurements, Measurements, MeasurementsError};
+
+/// Domain separator of the payload signed for a [`SignedBaseline`].
+const SIGNING_DOMAIN: &[u8] = b"mpc-attestation:signed-baseline:v1";
+
+/// Length of [`SignedBaseline::signing_payload`].
+pub const SIGNING_PAYLOAD_SIZE: usize = SIGNING_DOMAIN.len() + 4 + 32;
+
+/// Domain separator of the payload signed for a [`QuorumSignedBaseline`].
+const QUORUM_SIGNING_DOMAIN: &[u8] = b"mpc-attestation:quorum-signed-baseline:v1";
+
+/// Length of [`QuorumSignedBaseline::signing_payload`].
+pub const QUORUM_SIGNING_PAYLOAD_SIZE: usize = QUORUM_SIGNING_DOMAIN.len() + 4 + 8 + 32;
+
+/// Accepted measurements, versioned and signed by governance with ed25519.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SignedBaseline {
+    pub version: u32,
+    pub measurements: FullMeasurements,
+    #[serde_as(as = "Bytes")]
+    pub signature: [u8; 64],
+    /// Ed25519 public key of the signer.
+    pub signer: [u8; 32],
+}
+
+impl SignedBaseline {
+    /// The payload covered by the signature:
+    /// `domain || version (4 bytes little endian) || measurements fingerprint`, where the
+    /// fingerprint is [`FullMeasurements::fingerprint`].
+    pub fn signing_payload(
+        version: u32,
+        measurements: &FullMeasurements,
+    ) -> [u8; SIGNING_PAYLOAD_SIZE] {
+        let mut payload = [0u8; SIGNING_PAYLOAD_SIZE];
+        let (domain, rest) = payload.split_at_mut(SIGNING_DOMAIN.len());
+        let (version_bytes, fingerprint) = rest.split_at_mut(4);
+        domain.copy_from_slice(SIGNING_DOMAIN);
+        version_bytes.copy_from_slice(&version.to_le_bytes());
+        fingerprint.copy_from_slice(&measurements.fingerprint());
+        payload
+    }
+
+    /// Checks that `signature` is a valid ed25519 signature by `signer` over
+    /// [`SignedBaseline::signing_payload`]. Whether `signer` is trusted is up to the caller.
+    pub fn verify_signature(&self) -> bool {
+        let Ok(signer) = VerifyingKey::from_bytes(&self.signer) else {
+            return false;
+        };
+        let signature = Signature::from_bytes(&self.signature);
+        let payload = Self::signing_payload(self.version, &self.measurements);
+
+        signer.verify_strict(&payload, &signature).is_ok()
+    }
+
+    /// Checks the signature of the baseline and that the MRTD and RTMR0-2 of `report` match it.
+    ///
+    /// The key-provider and app compose digests are not part of the report, they are checked
+    /// against the event log by [`crate::attestation::DstackAttestation::verify`].
+    pub fn check_report(&self, report: &VerifiedReport) -> Result<(), MeasurementsError> {
+        if !self.verify_signature() {
+            return Err(MeasurementsError::InvalidBaselineSignature);
+        }
+
+        let live = Measurements::try_from(report)?;
+        let diff = self.measurements.rtmrs.diff(&live);
+        if diff.is_empty() {
+            Ok(())
+        } else {
+            Err(MeasurementsError::BaselineMismatch {
+                registers: diff.changed_registers().collect(),
+            })
+        }
+    }
+}
+
+/// Accepted measurements, versioned and signed by a quorum of governance keys with ed25519.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct QuorumSignedBaseline {
+    pub version: u32,
+    pub measurements: FullMeasurements,
+    /// Ed25519 public keys of the signers, each with its signature over
+    /// [`QuorumSignedBaseline::signing_payload`].
+    #[serde_as(as = "Vec<(_, Bytes)>")]
+    pub signatures: Vec<([u8; 32], [u8; 64])>,
+    /// The number of distinct authorized signers required. It is part of the signed payload,
+    /// so that it cannot be lowered without invalidating the signatures.
+    pub threshold: u64,
+}
+
+impl QuorumSignedBaseline {
+    /// The payload covered by each signature:
+    /// `domain || version (4 bytes little endian) || threshold (8 bytes little endian) ||
+    /// measurements fingerprint`, where the fingerprint is [`FullMeasurements::fingerprint`].
+    /// The domain differs from that of [`SignedBaseline`], so that a signature of one is never
+    /// valid for the other.
+    pub fn signing_payload(
+        version: u32,
+        threshold: u64,
+        measurements: &FullMeasurements,
+    ) -> [u8; QUORUM_SIGNING_PAYLOAD_SIZE] {
+        let mut payload = [0u8; QUORUM_SIGNING_PAYLOAD_SIZE];
+        let (domain, rest) = payload.split_at_mut(QUORUM_SIGNING_DOMAIN.len());
+        let (version_bytes, rest) = rest.split_at_mut(4);
+        let (threshold_bytes, fingerprint) = rest.split_at_mut(8);
+        domain.copy_from_slice(QUORUM_SIGNING_DOMAIN);
+        version_bytes.copy_from_slice(&version.to_le_bytes());
+        threshold_bytes.copy_from_slice(&threshold.to_le_bytes());
+        fingerprint.copy_from_slice(&measurements.fingerprint());
+        payload
+    }
+
+    /// Checks that at least `threshold` distinct keys of `authorized_signers` validly signed
+    /// [`QuorumSignedBaseline::signing_payload`], and that `threshold` is at least the
+    /// verifier's `min_threshold`, as the signers choose `threshold` and a single one could
+    /// otherwise sign a baseline with a threshold of one. Signatures by other keys, invalid
+    /// signatures and repeated signers are not counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::ThresholdBelowMinimum`] if `threshold` is below
+    /// `min_threshold`, and [`MeasurementsError::QuorumNotReached`] with the number of counted
+    /// signers if there are fewer than `threshold`, or if `threshold` is zero.
+    pub fn verify(
+        &self,
+        authorized_signers: &[[u8; 32]],
+        min_threshold: u64,
+    ) -> Result<(), MeasurementsError> {
+        if self.threshold < min_threshold {
+            return Err(MeasurementsError::ThresholdBelowMinimum {
+                threshold: self.threshold,
+                min_threshold,
+            });
+        }
+        let payload = Self::signing_payload(self.version, self.threshold, &self.measurements);
+        let signers: BTreeSet<&[u8; 32]> = self
+            .signatures
+            .iter()
+            .filter(|(signer, _)| authorized_signers.contains(signer))
+            .filter(|(signer, signature)| {
+                VerifyingKey::from_bytes(signer).is_ok_and(|key| {
+                    key.verify_strict(&payload, &Signature::from_bytes(signature))
+                        .is_ok()
+                })
+            })
+            .map(|(signer, _)| signer)
+            .collect();
+
+        let valid = u64::try_from(signers.len()).unwrap_or(u64::MAX);
+        if self.threshold == 0 || valid < self.threshold {
+            return Err(MeasurementsError::QuorumNotReached {
+                valid,
+                threshold: self.threshold,
+            });
+        }
+        Ok(())
+    }
+}
@@ -1,21 +1,72 @@
-use alloc::{string::String, vec::Vec};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use base64::{Engine as _, prelude::BASE64_STANDARD};
 use borsh::{BorshDeserialize, BorshSerialize};
-use core::str::FromStr;
+use core::{fmt, str::FromStr};
 use derive_more::{Deref, From, Into};
 use hex::FromHexError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use serde_with::{hex::Hex, serde_as};
+use sha2::{Digest as _, Sha256};
 use thiserror::Error;
+use x509_cert::der::Encode;
 
 pub use dcap_qvl::QuoteCollateralV3;
 
+use crate::{
+    attestation::QuoteVerifyOptions,
+    quote::{ParsedQuote, QuoteType},
+    tcb_status::{TcbStatus, advisories_by_level},
+};
+
+mod certificates;
+mod crl;
+
+/// The Intel SGX Root CA certificate, DER encoded. This is the trust anchor of all Intel
+/// collateral, pinned here so that verification does not depend on a root fetched at runtime.
+///
+/// Subject: `CN=Intel SGX Root CA, O=Intel Corporation, L=Santa Clara, ST=CA, C=US`
+/// SHA-256 fingerprint: [`INTEL_SGX_ROOT_CA_SHA256`]
+pub const INTEL_SGX_ROOT_CA_DER: &[u8] = include_bytes!("collateral/intel_sgx_root_ca.der");
+
+/// Hex SHA-256 fingerprint of [`INTEL_SGX_ROOT_CA_DER`], the root of production Intel
+/// platforms. Pre-production platforms and emulators root their PCK certificates elsewhere.
+pub const INTEL_SGX_ROOT_CA_SHA256: &str =
+    "44a0196b2b99f889b8e149e95b807a350e7424964399e885a7cbb8ccfab674d3";
+
+/// Domain separator of [`Collateral::canonical_hash`].
+const CANONICAL_HASH_DOMAIN: &[u8] = b"mpc-attestation:collateral:v1";
+
+/// Default for [`CollateralLimits::max_certs`]. Intel issuer chains have two or three
+/// certificates.
+pub const DEFAULT_MAX_CERTS: usize = 8;
+
+/// Default for [`CollateralLimits::max_tcb_levels`]. Intel TCB info documents currently have
+/// a few dozen TCB levels at most.
+pub const DEFAULT_MAX_TCB_LEVELS: usize = 64;
+
+pub use certificates::{Certificates, MAX_DER_NESTING_DEPTH, MAX_PCK_CHAIN_LEN};
+pub use crl::{
+    CrlValidity, INTEL_ORGANIZATION, INTEL_PCK_PLATFORM_CA_COMMON_NAME,
+    INTEL_PCK_PROCESSOR_CA_COMMON_NAME, TCB_SIGNING_COMMON_NAME, check_pck_crl, check_root_ca_crl,
+    check_tcb_signing_chain, is_intel_pck_ca,
+};
+
+/// Constructor of the error reporting a CRL of the collateral as invalid.
+pub(crate) type InvalidCrl = fn(String) -> CollateralError;
+
 /// Supplemental data for the TEE quote, including Intel certificates to verify it came from genuine
 /// Intel hardware, along with details about the Trusted Computing Base (TCB) versioning, status,
 /// and other relevant info.
 #[derive(
     Clone, From, Deref, Into, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
 )]
-#[serde(try_from = "Value")]
+#[serde(into = "Value", try_from = "Value")]
 pub struct Collateral(QuoteCollateralV3);
 
 impl Collateral {
@@ -26,7 +77,22 @@ impl Collateral {
     /// Returns a [`CollateralError`] if:
     /// - Any required field is missing or has an invalid type
     /// - Hex fields cannot be decoded
+    /// - The collateral exceeds the [`CollateralLimits::default`], see
+    ///   [`Collateral::try_from_json_with_limits`]
     pub fn try_from_json(v: Value) -> Result<Self, CollateralError> {
+        Self::try_from_json_with_limits(v, &CollateralLimits::default())
+    }
+
+    /// Same as [`Collateral::try_from_json`], with explicit limits on the size of the
+    /// collateral, see [`Collateral::check_limits`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`Collateral::try_from_json`] or [`Collateral::check_limits`].
+    pub fn try_from_json_with_limits(
+        v: Value,
+        limits: &CollateralLimits,
+    ) -> Result<Self, CollateralError> {
         fn get_str(v: &Value, key: &str) -> Result<String, CollateralError> {
             v.get(key)
                 .and_then(Value::as_str)
@@ -49,14 +115,991 @@ impl Collateral {
             qe_identity_issuer_chain: get_str(&v, "qe_identity_issuer_chain")?,
             qe_identity: get_str(&v, "qe_identity")?,
             qe_identity_signature: get_hex(&v, "qe_identity_signature")?,
-            pck_certificate_chain: get_str(&v, "pck_certificate_chain").ok(),            pck_crl_issuer_chain: get_str(&v, "pck_crl_issuer_chain")?,
+            pck_certificate_chain: get_str(&v, "pck_certificate_chain").ok(),
+            pck_crl_issuer_chain: get_str(&v, "pck_crl_issuer_chain")?,
             root_ca_crl: get_hex(&v, "root_ca_crl")?,
             pck_crl: get_hex(&v, "pck_crl")?,
         };
-        Ok(Self(quote_collateral))
+        let collateral = Self(quote_collateral);
+        collateral.check_limits(limits)?;
+        Ok(collateral)
+    }
+
+    /// Returns the JSON representation read by [`Collateral::try_from_json`], with the binary
+    /// artifacts hex encoded. This is also the serde representation of [`Collateral`], so it
+    /// can be cached, e.g. on disk, and loaded back.
+    pub fn to_json(&self) -> Value {
+        let mut json = serde_json::json!({
+            "tcb_info_issuer_chain": self.tcb_info_issuer_chain,
+            "tcb_info": self.tcb_info,
+            "tcb_info_signature": hex::encode(&self.tcb_info_signature),
+            "qe_identity_issuer_chain": self.qe_identity_issuer_chain,
+            "qe_identity": self.qe_identity,
+            "qe_identity_signature": hex::encode(&self.qe_identity_signature),
+            "pck_crl_issuer_chain": self.pck_crl_issuer_chain,
+            "root_ca_crl": hex::encode(&self.root_ca_crl),
+            "pck_crl": hex::encode(&self.pck_crl),
+        });
+        if let (Some(chain), Some(object)) = (&self.pck_certificate_chain, json.as_object_mut()) {
+            object.insert(
+                String::from("pck_certificate_chain"),
+                Value::String(chain.clone()),
+            );
+        }
+        json
+    }
+
+    /// A SHA-256 commitment to every artifact of the collateral, e.g. for a contract to store
+    /// in place of the collateral itself:
+    ///
+    /// `sha256(domain || field || field || ...)`, with the fields in the order of
+    /// [`Collateral::to_json`] and each encoded as its length (8 bytes little endian) followed
+    /// by its bytes. The PCK certificate chain comes last, prefixed by a `1` byte if present
+    /// and encoded as just a `0` byte otherwise.
+    ///
+    /// The encoding does not depend on how the collateral was serialized, so the commitment
+    /// survives a round trip through JSON or Borsh.
+    pub fn canonical_hash(&self) -> [u8; 32] {
+        fn update(hasher: &mut Sha256, field: &[u8]) {
+            let len = u64::try_from(field.len()).unwrap_or(u64::MAX);
+            hasher.update(len.to_le_bytes());
+            hasher.update(field);
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(CANONICAL_HASH_DOMAIN);
+        for field in [
+            self.tcb_info_issuer_chain.as_bytes(),
+            self.tcb_info.as_bytes(),
+            self.tcb_info_signature.as_slice(),
+            self.qe_identity_issuer_chain.as_bytes(),
+            self.qe_identity.as_bytes(),
+            self.qe_identity_signature.as_slice(),
+            self.pck_crl_issuer_chain.as_bytes(),
+            self.root_ca_crl.as_slice(),
+            self.pck_crl.as_slice(),
+        ] {
+            update(&mut hasher, field);
+        }
+        match &self.pck_certificate_chain {
+            Some(chain) => {
+                hasher.update([1]);
+                update(&mut hasher, chain.as_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        hasher.finalize().into()
+    }
+
+    /// Builds a [`Collateral`] from base64 encoded artifacts, as returned by many PCS relay APIs.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CollateralError`] if:
+    /// - Any field is not valid base64, or a textual field is not valid UTF-8
+    /// - The TCB info or QE identity is malformed, see [`Collateral::validity_window`]
+    /// - Any certificate chain cannot be parsed, see [`Certificates::parse_any`]
+    pub fn from_base64_parts(parts: &CollateralParts) -> Result<Self, CollateralError> {
+        fn decode(field: &str, value: &str) -> Result<Vec<u8>, CollateralError> {
+            BASE64_STANDARD
+                .decode(value)
+                .map_err(|source| CollateralError::Base64Decode {
+                    field: String::from(field),
+                    source,
+                })
+        }
+
+        fn decode_text(field: &str, value: &str) -> Result<String, CollateralError> {
+            String::from_utf8(decode(field, value)?)
+                .map_err(|_| CollateralError::InvalidUtf8(String::from(field)))
+        }
+
+        let collateral = Self(QuoteCollateralV3 {
+            tcb_info_issuer_chain: decode_text(
+                "tcb_info_issuer_chain",
+                &parts.tcb_info_issuer_chain,
+            )?,
+            tcb_info: decode_text("tcb_info", &parts.tcb_info)?,
+            tcb_info_signature: decode("tcb_info_signature", &parts.tcb_info_signature)?,
+            qe_identity_issuer_chain: decode_text(
+                "qe_identity_issuer_chain",
+                &parts.qe_identity_issuer_chain,
+            )?,
+            qe_identity: decode_text("qe_identity", &parts.qe_identity)?,
+            qe_identity_signature: decode("qe_identity_signature", &parts.qe_identity_signature)?,
+            pck_certificate_chain: parts
+                .pck_certificate_chain
+                .as_deref()
+                .map(|chain| decode_text("pck_certificate_chain", chain))
+                .transpose()?,
+            pck_crl_issuer_chain: decode_text("pck_crl_issuer_chain", &parts.pck_crl_issuer_chain)?,
+            root_ca_crl: decode("root_ca_crl", &parts.root_ca_crl)?,
+            pck_crl: decode("pck_crl", &parts.pck_crl)?,
+        });
+
+        collateral.check_limits(&CollateralLimits::default())?;
+        collateral.validity_window()?;
+        let chains = [
+            Some(&collateral.tcb_info_issuer_chain),
+            Some(&collateral.qe_identity_issuer_chain),
+            Some(&collateral.pck_crl_issuer_chain),
+            collateral.pck_certificate_chain.as_ref(),
+        ];
+        for chain in chains.into_iter().flatten() {
+            Certificates::parse_any(chain.as_bytes())?;
+        }
+
+        Ok(collateral)
+    }
+
+    /// Checks the number of certificates of every issuer chain and the number of TCB levels of
+    /// the TCB info and QE identity against `limits`. This is cheap compared to parsing the
+    /// certificates and the TCB levels, so untrusted collateral should be checked first to
+    /// bound the work of verifying it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::TooManyCerts`] or [`CollateralError::TooManyTcbLevels`] if a
+    /// limit is exceeded, and [`CollateralError::InvalidDocument`] if the TCB info or QE
+    /// identity is not valid JSON. Empty documents, as in the dummy collateral of
+    /// [`crate::attestation::DstackAttestation::default`], have no TCB levels to count and are
+    /// left to verification to reject.
+    pub fn check_limits(&self, limits: &CollateralLimits) -> Result<(), CollateralError> {
+        let chains = [
+            Some(&self.tcb_info_issuer_chain),
+            Some(&self.qe_identity_issuer_chain),
+            Some(&self.pck_crl_issuer_chain),
+            self.pck_certificate_chain.as_ref(),
+        ];
+        for chain in chains.into_iter().flatten() {
+            if certificates::pem_certificate_count(chain.as_bytes()) > limits.max_certs {
+                return Err(CollateralError::TooManyCerts {
+                    max: limits.max_certs,
+                });
+            }
+        }
+
+        for (field, json) in [
+            ("tcb_info", &self.tcb_info),
+            ("qe_identity", &self.qe_identity),
+        ] {
+            if json.is_empty() {
+                continue;
+            }
+            let value: Value = serde_json::from_str(json)
+                .map_err(|_| CollateralError::InvalidDocument(String::from(field)))?;
+            let tcb_levels = value
+                .get("tcbLevels")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            if tcb_levels > limits.max_tcb_levels {
+                return Err(CollateralError::TooManyTcbLevels {
+                    field: String::from(field),
+                    max: limits.max_tcb_levels,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the time window during which this collateral is valid, i.e. the latest
+    /// `issueDate` and the earliest `nextUpdate` across the TCB info and QE identity.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CollateralError`] if the TCB info or QE identity JSON is malformed or
+    /// carries an invalid date.
+    pub fn validity_window(&self) -> Result<CollateralValidity, CollateralError> {
+        let tcb_info = IssuedDocument::parse("tcb_info", &self.tcb_info)?;
+        let qe_identity = IssuedDocument::parse("qe_identity", &self.qe_identity)?;
+
+        Ok(CollateralValidity {
+            not_before: tcb_info.issue_date.max(qe_identity.issue_date),
+            next_update: tcb_info.next_update.min(qe_identity.next_update),
+        })
+    }
+
+    /// Returns the FMSPC the TCB info applies to. DCAP verification checks that it is that of
+    /// the quote's platform.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CollateralError`] if the TCB info JSON is malformed or has no valid `fmspc`.
+    pub fn fmspc(&self) -> Result<Fmspc, CollateralError> {
+        tcb_info_fmspc(&self.tcb_info)
+    }
+
+    /// Returns the `issueDate` and `nextUpdate` of the TCB info, as the [`CollateralValidity`]
+    /// of the TCB info alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CollateralError`] if the TCB info JSON is malformed or carries an invalid
+    /// date.
+    pub fn tcb_info_validity(&self) -> Result<CollateralValidity, CollateralError> {
+        let tcb_info = IssuedDocument::parse("tcb_info", &self.tcb_info)?;
+        Ok(CollateralValidity {
+            not_before: tcb_info.issue_date,
+            next_update: tcb_info.next_update,
+        })
+    }
+
+    /// Returns when the certificates and documents of the collateral expire, so that it can be
+    /// renewed ahead of time.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CollateralError`] if a certificate chain cannot be parsed, or the TCB info or
+    /// QE identity is malformed.
+    pub fn expiry_dates(&self) -> Result<CollateralExpiry, CollateralError> {
+        let not_after = |certificate: &x509_cert::Certificate| {
+            certificate
+                .tbs_certificate
+                .validity
+                .not_after
+                .to_unix_duration()
+                .as_secs()
+        };
+
+        let (pck_not_after, pck_issuers) = match &self.pck_certificate_chain {
+            Some(chain) => Certificates::parse_any(chain.as_bytes())?
+                .split_first()
+                .map(|(pck, issuers)| (Some(not_after(pck)), issuers.to_vec()))
+                .unwrap_or_default(),
+            None => (None, Vec::new()),
+        };
+
+        let mut intermediate_not_after = u64::MAX;
+        for chain in [
+            &self.tcb_info_issuer_chain,
+            &self.qe_identity_issuer_chain,
+            &self.pck_crl_issuer_chain,
+        ] {
+            for certificate in Certificates::parse_any(chain.as_bytes())?.iter() {
+                intermediate_not_after = intermediate_not_after.min(not_after(certificate));
+            }
+        }
+        for certificate in &pck_issuers {
+            intermediate_not_after = intermediate_not_after.min(not_after(certificate));
+        }
+
+        Ok(CollateralExpiry {
+            pck_not_after,
+            intermediate_not_after,
+            tcb_info_next_update: IssuedDocument::parse("tcb_info", &self.tcb_info)?.next_update,
+            qe_identity_next_update: IssuedDocument::parse("qe_identity", &self.qe_identity)?
+                .next_update,
+        })
+    }
+
+    /// Returns the last UNIX timestamp, in seconds, at which the collateral is still valid: the
+    /// earliest of the [`Collateral::expiry_dates`] and the `nextUpdate` of the PCK and root CA
+    /// CRLs. This is the forward-looking complement of [`Collateral::check_expiration`], e.g. to
+    /// schedule a refresh before verification starts failing.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`Collateral::expiry_dates`], or
+    /// [`CollateralError::PckCrlInvalid`] or [`CollateralError::RootCrlInvalid`] if a CRL cannot
+    /// be parsed.
+    pub fn valid_until(&self) -> Result<u64, CollateralError> {
+        let mut valid_until = self.expiry_dates()?.earliest();
+        for (crl, invalid) in self.crls() {
+            if let Some(next_update) = CrlValidity::parse(crl, invalid)?.next_update {
+                valid_until = valid_until.min(next_update);
+            }
+        }
+        Ok(valid_until)
+    }
+
+    /// Checks that the PCK and root CA CRLs are well-formed and valid at `timestamp_seconds`,
+    /// within `[thisUpdate - skew, nextUpdate + skew]`, see [`CrlValidity::check`]. Their
+    /// signatures are checked by [`Collateral::check_pck_crl`] and
+    /// [`Collateral::check_root_ca_revocations`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::PckCrlInvalid`] or [`CollateralError::RootCrlInvalid`] if the
+    /// respective CRL is malformed or not valid at `timestamp_seconds`.
+    pub fn check_crls(
+        &self,
+        timestamp_seconds: u64,
+        allowed_skew_secs: u64,
+    ) -> Result<(), CollateralError> {
+        self.crls().into_iter().try_for_each(|(crl, invalid)| {
+            CrlValidity::parse(crl, invalid)?.check(timestamp_seconds, allowed_skew_secs, invalid)
+        })
+    }
+
+    /// The PCK and root CA CRLs, each with the error reporting it as invalid.
+    pub(crate) fn crls(&self) -> [(&[u8], InvalidCrl); 2] {
+        [
+            (self.pck_crl.as_slice(), CollateralError::PckCrlInvalid),
+            (self.root_ca_crl.as_slice(), CollateralError::RootCrlInvalid),
+        ]
+    }
+
+    /// Returns the kind of TEE the collateral is for, read from the `id` of the TCB info (`TDX`
+    /// or `SGX`), or of the QE identity (`TD_QE` or `QE`) if the TCB info has none, as in
+    /// version 2 SGX TCB info. `None` if neither can be read.
+    pub fn tee_type(&self) -> Option<QuoteType> {
+        let id = |json: &str| {
+            serde_json::from_str::<Value>(json)
+                .ok()
+                .and_then(|value| value.get("id")?.as_str().map(String::from))
+        };
+
+        match id(&self.tcb_info).as_deref() {
+            Some("TDX") => Some(QuoteType::Tdx),
+            Some("SGX") => Some(QuoteType::Sgx),
+            _ => match id(&self.qe_identity).as_deref() {
+                Some("TD_QE") => Some(QuoteType::Tdx),
+                Some("QE") => Some(QuoteType::Sgx),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns the TCB status and advisory IDs of every TCB level of the TCB info, from the
+    /// highest level down, see [`advisories_by_level`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::InvalidDocument`] if the TCB info is malformed.
+    pub fn advisories_by_level(&self) -> Result<Vec<(TcbStatus, Vec<String>)>, CollateralError> {
+        advisories_by_level(&self.tcb_info)
+            .map_err(|_| CollateralError::InvalidDocument(String::from("tcb_info")))
+    }
+
+    /// Returns the `tcbEvaluationDataNumber` of the collateral, i.e. the lower of those of the
+    /// TCB info and QE identity. Intel increments this number with every TCB recovery, so it
+    /// identifies how recent the TCB levels of the collateral are, independently of its
+    /// validity window.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CollateralError`] if the TCB info or QE identity JSON is malformed or lacks
+    /// a valid `tcbEvaluationDataNumber`.
+    pub fn tcb_evaluation_number(&self) -> Result<u32, CollateralError> {
+        let tcb_info = tcb_evaluation_data_number("tcb_info", &self.tcb_info)?;
+        let qe_identity = tcb_evaluation_data_number("qe_identity", &self.qe_identity)?;
+        Ok(tcb_info.min(qe_identity))
+    }
+
+    /// Checks that the collateral is valid at `timestamp_seconds`, tolerating a clock skew of
+    /// `allowed_skew_secs` on both ends of the validity window. That is, the collateral is
+    /// accepted within `[not_before - skew, next_update + skew]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::Expired`] if `timestamp_seconds` falls outside the window,
+    /// or any error from [`Collateral::validity_window`].
+    pub fn check_expiration(
+        &self,
+        timestamp_seconds: u64,
+        allowed_skew_secs: u64,
+    ) -> Result<CollateralValidity, CollateralError> {
+        let validity = self.validity_window()?;
+        validity.check(timestamp_seconds, allowed_skew_secs)?;
+        Ok(validity)
+    }
+
+    /// Checks that every issuer chain of the collateral ends with `trusted_root_ca_der`,
+    /// normally [`INTEL_SGX_ROOT_CA_DER`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::UntrustedRoot`] if a chain ends with another certificate, or
+    /// any error from [`Certificates::parse_any`].
+    pub fn check_trust_anchor(&self, trusted_root_ca_der: &[u8]) -> Result<(), CollateralError> {
+        let chains = [
+            ("tcb_info_issuer_chain", Some(&self.tcb_info_issuer_chain)),
+            (
+                "qe_identity_issuer_chain",
+                Some(&self.qe_identity_issuer_chain),
+            ),
+            ("pck_crl_issuer_chain", Some(&self.pck_crl_issuer_chain)),
+            ("pck_certificate_chain", self.pck_certificate_chain.as_ref()),
+        ];
+
+        for (field, chain) in chains {
+            let Some(chain) = chain else {
+                continue;
+            };
+            let root = Certificates::parse_any(chain.as_bytes())?
+                .last()
+                .map(Encode::to_der)
+                .transpose()
+                .map_err(|e| CollateralError::InvalidCertificateChain(e.to_string()))?;
+            if root.as_deref() != Some(trusted_root_ca_der) {
+                return Err(CollateralError::UntrustedRoot(String::from(field)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks every issuer chain of the collateral against the root CA CRL, see
+    /// [`check_root_ca_crl`]. This detects a revoked intermediate CA, such as the PCK platform
+    /// CA or the TCB signing certificate.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`check_root_ca_crl`].
+    pub fn check_root_ca_revocations(&self) -> Result<(), CollateralError> {
+        let chains = [
+            Some(&self.tcb_info_issuer_chain),
+            Some(&self.qe_identity_issuer_chain),
+            Some(&self.pck_crl_issuer_chain),
+            self.pck_certificate_chain.as_ref(),
+        ];
+
+        chains
+            .into_iter()
+            .flatten()
+            .try_for_each(|chain| check_root_ca_crl(chain.as_bytes(), &self.root_ca_crl))
+    }
+
+    /// Checks that the PCK CRL is signed by the PCK CRL issuer chain, rooted at
+    /// `trusted_root_ca_der`, see [`check_pck_crl`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`check_pck_crl`].
+    pub fn check_pck_crl(&self, trusted_root_ca_der: &[u8]) -> Result<(), CollateralError> {
+        check_pck_crl(
+            self.pck_crl_issuer_chain.as_bytes(),
+            self.pck_crl.as_slice(),
+            trusted_root_ca_der,
+        )
+    }
+
+    /// Checks the TCB info and QE identity issuer chains separately, each rooted at
+    /// `trusted_root_ca_der` and starting with the TCB signing certificate, see
+    /// [`check_tcb_signing_chain`]. The errors name the chain that failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`check_tcb_signing_chain`].
+    pub fn check_issuer_chains(&self, trusted_root_ca_der: &[u8]) -> Result<(), CollateralError> {
+        check_tcb_signing_chain(
+            "tcb_info_issuer_chain",
+            self.tcb_info_issuer_chain.as_bytes(),
+            trusted_root_ca_der,
+        )?;
+        check_tcb_signing_chain(
+            "qe_identity_issuer_chain",
+            self.qe_identity_issuer_chain.as_bytes(),
+            trusted_root_ca_der,
+        )
+    }
+}
+
+/// Expiry dates of a [`Collateral`], as UNIX timestamps in seconds, see
+/// [`Collateral::expiry_dates`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollateralExpiry {
+    /// `notAfter` of the PCK certificate, if the collateral carries the PCK certificate chain.
+    /// The quote usually embeds the PCK certificate instead.
+    pub pck_not_after: Option<u64>,
+    /// The earliest `notAfter` of the CA and signing certificates of the issuer chains, e.g.
+    /// the PCK platform CA and the TCB signing certificate.
+    pub intermediate_not_after: u64,
+    /// `nextUpdate` of the TCB info.
+    pub tcb_info_next_update: u64,
+    /// `nextUpdate` of the QE identity.
+    pub qe_identity_next_update: u64,
+}
+
+impl CollateralExpiry {
+    /// The earliest of the expiry dates, after which the collateral must have been renewed.
+    pub fn earliest(&self) -> u64 {
+        [
+            self.pck_not_after.unwrap_or(u64::MAX),
+            self.intermediate_not_after,
+            self.tcb_info_next_update,
+            self.qe_identity_next_update,
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or(u64::MAX)
+    }
+}
+
+/// Limits on the size of a [`Collateral`], which may come from an untrusted party, see
+/// [`Collateral::check_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollateralLimits {
+    /// Maximum number of certificates in each issuer chain.
+    pub max_certs: usize,
+    /// Maximum number of TCB levels in each of the TCB info and QE identity.
+    pub max_tcb_levels: usize,
+}
+
+impl Default for CollateralLimits {
+    fn default() -> Self {
+        Self {
+            max_certs: DEFAULT_MAX_CERTS,
+            max_tcb_levels: DEFAULT_MAX_TCB_LEVELS,
+        }
+    }
+}
+
+/// The artifacts of a [`Collateral`], each encoded as standard base64. Textual artifacts (the
+/// JSON documents and PEM chains) are the base64 encoding of their UTF-8 text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollateralParts {
+    pub tcb_info_issuer_chain: String,
+    pub tcb_info: String,
+    pub tcb_info_signature: String,
+    pub qe_identity_issuer_chain: String,
+    pub qe_identity: String,
+    pub qe_identity_signature: String,
+    #[serde(default)]
+    pub pck_certificate_chain: Option<String>,
+    pub pck_crl_issuer_chain: String,
+    pub root_ca_crl: String,
+    pub pck_crl: String,
+}
+
+/// Checks that the FMSPC of the PCK certificate of `quote` is the one the TCB info of
+/// `collateral` is for. TCB info fetched for another FMSPC otherwise only fails deep in DCAP
+/// verification, without saying why.
+///
+/// # Errors
+///
+/// Returns [`CollateralError::FmspcMismatch`], with the FMSPC of the TCB info and a
+/// [`CollateralHint`] for the quote's own, if they differ, [`CollateralError::InvalidQuote`] if
+/// the quote does not carry a PCK certificate, and a [`CollateralError`] if the TCB info has no
+/// valid `fmspc`.
+pub fn assert_fmspc_consistency(
+    quote: &ParsedQuote,
+    collateral: &Collateral,
+) -> Result<(), CollateralError> {
+    let hint = missing_collateral_hint(quote)?;
+    let collateral_fmspc = tcb_info_fmspc(&collateral.tcb_info)?;
+    if hint.fmspc != collateral_fmspc {
+        return Err(CollateralError::FmspcMismatch {
+            collateral: collateral_fmspc,
+            hint: Box::new(hint),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `full` is the collateral for `quote` and returns it without the PCK certificate
+/// chain if the quote embeds its own, the embedded chain being the one used for verification.
+///
+/// This is the only part of the collateral that can be dropped: the TCB info, QE identity and
+/// CRLs are signed by Intel and cannot be trimmed without invalidating their signatures, so
+/// they are kept as a whole once they are checked to be the ones for the quote's FMSPC and PCK
+/// CA. The result is therefore barely smaller than `full` when it carries no PCK chain.
+///
+/// # Errors
+///
+/// Returns a [`CollateralError`] if the collateral cannot be used to verify the quote, i.e. its
+/// TCB info is for another FMSPC or its PCK CRL is not issued by the Intel PCK CA of the quote,
+/// see [`is_intel_pck_ca`].
+pub fn minimize_for_quote(
+    full: &Collateral,
+    quote: &ParsedQuote,
+) -> Result<Collateral, CollateralError> {
+    assert_fmspc_consistency(quote, full)?;
+    let hint = missing_collateral_hint(quote)?;
+
+    let issuer_chain = Certificates::parse_any(full.pck_crl_issuer_chain.as_bytes())?;
+    let crl_issuer = issuer_chain
+        .first()
+        .ok_or_else(|| CollateralError::InvalidCertificateChain(String::from("empty chain")))?;
+    if !crl::is_intel_pck_ca_of_type(crl_issuer, hint.ca.parse()?) {
+        return Err(CollateralError::CaMismatch {
+            collateral: crl_issuer.tbs_certificate.subject.to_string(),
+            hint: Box::new(hint),
+        });
+    }
+
+    let quote_has_cert_chain = quote.quote().raw_cert_chain().is_ok();
+    let mut minimized = full.0.clone();
+    if quote_has_cert_chain {
+        minimized.pck_certificate_chain = None;
+    }
+
+    Ok(Collateral::from(minimized))
+}
+
+/// FMSPC (Family-Model-Stepping-Platform-CustomSKU) of a platform, which selects the TCB info
+/// that applies to it. Intel tools, PCS URLs and [`Display`](fmt::Display) present it as 12 hex
+/// characters, e.g. `b0c06f000000`.
+#[serde_as]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, From, Into, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Fmspc(#[serde_as(as = "Hex")] [u8; 6]);
+
+impl Fmspc {
+    /// Parses the 12 hex character representation, in either case.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::HexDecode`] if `hex` is not valid hex or not 6 bytes long.
+    pub fn from_hex(hex: &str) -> Result<Self, CollateralError> {
+        let mut fmspc = [0u8; 6];
+        hex::decode_to_slice(hex, &mut fmspc).map_err(|source| CollateralError::HexDecode {
+            field: String::from("fmspc"),
+            source,
+        })?;
+        Ok(Self(fmspc))
+    }
+
+    /// The lowercase hex representation, as used by Intel PCS.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 6] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Fmspc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Base URL of the Intel Provisioning Certification Service (PCS).
+pub const PCS_BASE_URL: &str = "https://api.trustedservices.intel.com";
+
+/// What to fetch from Intel PCS to obtain the collateral for a given quote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollateralHint {
+    pub quote_type: QuoteType,
+    /// FMSPC of the platform.
+    pub fmspc: Fmspc,
+    /// PCK CA that issued the platform's PCK certificate, `processor` or `platform`.
+    pub ca: String,
+    /// PCS path of the TCB info for the FMSPC, relative to [`PCS_BASE_URL`].
+    pub tcb_info_path: String,
+    /// PCS path of the QE identity, relative to [`PCS_BASE_URL`].
+    pub qe_identity_path: String,
+    /// PCS path of the PCK CRL for the CA, relative to [`PCS_BASE_URL`].
+    pub pck_crl_path: String,
+}
+
+impl fmt::Display for CollateralHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "fetch TCB info from {PCS_BASE_URL}{}, QE identity from {PCS_BASE_URL}{} and PCK CRL from {PCS_BASE_URL}{}",
+            self.tcb_info_path, self.qe_identity_path, self.pck_crl_path
+        )
+    }
+}
+
+/// PCK CA that issued the PCK certificate of a platform, which selects the PCK CRL that
+/// applies to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CaType {
+    Processor,
+    Platform,
+}
+
+impl CaType {
+    /// The name of the CA as used by Intel PCS, `processor` or `platform`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Processor => "processor",
+            Self::Platform => "platform",
+        }
+    }
+}
+
+impl fmt::Display for CaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CaType {
+    type Err = CollateralError;
+
+    fn from_str(ca: &str) -> Result<Self, Self::Err> {
+        match ca {
+            "processor" => Ok(Self::Processor),
+            "platform" => Ok(Self::Platform),
+            other => Err(CollateralError::InvalidQuote(format!(
+                "unknown PCK CA `{other}`"
+            ))),
+        }
+    }
+}
+
+impl CollateralHint {
+    /// [`CollateralHint::ca`] as a [`CaType`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::InvalidQuote`] if the CA is neither `processor` nor
+    /// `platform`.
+    pub fn ca_type(&self) -> Result<CaType, CollateralError> {
+        self.ca.parse()
+    }
+}
+
+/// Returns the FMSPC, PCK CA and Intel PCS paths needed to fetch the collateral for `quote`.
+///
+/// # Errors
+///
+/// Returns [`CollateralError::InvalidQuote`] if the quote does not carry a PCK certificate to
+/// read the FMSPC and CA from.
+pub fn missing_collateral_hint(quote: &ParsedQuote) -> Result<CollateralHint, CollateralError> {
+    let fmspc = quote
+        .fmspc()
+        .map_err(|e| CollateralError::InvalidQuote(e.to_string()))?;
+    let ca = quote
+        .quote()
+        .ca()
+        .map(String::from)
+        .map_err(|e| CollateralError::InvalidQuote(e.to_string()))?;
+
+    let tee = match quote.quote_type() {
+        QuoteType::Sgx => "sgx",
+        QuoteType::Tdx => "tdx",
+    };
+
+    Ok(CollateralHint {
+        quote_type: quote.quote_type(),
+        tcb_info_path: format!("/{tee}/certification/v4/tcb?fmspc={fmspc}"),
+        qe_identity_path: format!("/{tee}/certification/v4/qe/identity"),
+        // PCK CRLs are served from the SGX endpoint for both SGX and TDX.
+        pck_crl_path: format!("/sgx/certification/v4/pckcrl?ca={ca}&encoding=der"),
+        fmspc,
+        ca,
+    })
+}
+
+fn tcb_info_fmspc(tcb_info: &str) -> Result<Fmspc, CollateralError> {
+    let value: Value = serde_json::from_str(tcb_info)
+        .map_err(|_| CollateralError::InvalidDocument(String::from("tcb_info")))?;
+    value
+        .get("fmspc")
+        .and_then(Value::as_str)
+        .ok_or_else(|| CollateralError::MissingField(String::from("tcb_info.fmspc")))
+        .and_then(Fmspc::from_hex)
+}
+
+fn tcb_evaluation_data_number(field: &str, json: &str) -> Result<u32, CollateralError> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|_| CollateralError::InvalidDocument(String::from(field)))?;
+    value
+        .get("tcbEvaluationDataNumber")
+        .and_then(Value::as_u64)
+        .and_then(|number| u32::try_from(number).ok())
+        .ok_or_else(|| CollateralError::MissingField(format!("{field}.tcbEvaluationDataNumber")))
+}
+
+/// A [`Collateral`] together with its parsed validity window. Preparing the collateral once
+/// avoids re-parsing the Intel documents when the same collateral is used to verify many quotes.
+#[derive(Clone, Debug)]
+pub struct PreparedCollateral {
+    collateral: Collateral,
+    validity: CollateralValidity,
+    trusted_root_ca_der: Vec<u8>,
+}
+
+impl PreparedCollateral {
+    /// Parses the validity window of `collateral` and checks its issuer chains against the
+    /// [`QuoteVerifyOptions::trusted_root_ca_der`] trust anchor of `options` and the root CA
+    /// CRL, the signatures of its TCB info and QE identity issuer chains, and the signature of
+    /// its PCK CRL. Quotes verified against the prepared collateral must use options with the
+    /// same trust anchor, see [`PreparedCollateral::trusted_root_ca_der`].
+    ///
+    /// These checks run whether or not [`QuoteVerifyOptions::extended_collateral_checks`] is
+    /// set, as they are paid once for all the quotes verified against the prepared collateral.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`Collateral::check_limits`] with the default limits,
+    /// [`Collateral::check_trust_anchor`], [`Collateral::check_issuer_chains`],
+    /// [`Collateral::check_pck_crl`], [`Collateral::check_root_ca_revocations`] or
+    /// [`Collateral::validity_window`].
+    pub fn new(
+        collateral: Collateral,
+        options: &QuoteVerifyOptions,
+    ) -> Result<Self, CollateralError> {
+        let trusted_root_ca_der = options.trusted_root_ca_der.as_slice();
+        collateral.check_limits(&CollateralLimits::default())?;
+        collateral.check_trust_anchor(trusted_root_ca_der)?;
+        collateral.check_issuer_chains(trusted_root_ca_der)?;
+        collateral.check_pck_crl(trusted_root_ca_der)?;
+        collateral.check_root_ca_revocations()?;
+        let validity = collateral.validity_window()?;
+        Ok(Self {
+            collateral,
+            validity,
+            trusted_root_ca_der: trusted_root_ca_der.to_vec(),
+        })
+    }
+
+    pub fn collateral(&self) -> &Collateral {
+        &self.collateral
+    }
+
+    pub fn validity(&self) -> CollateralValidity {
+        self.validity
+    }
+
+    /// The DER encoded root CA the issuer chains were checked against.
+    pub fn trusted_root_ca_der(&self) -> &[u8] {
+        &self.trusted_root_ca_der
+    }
+}
+
+/// Validity window of a [`Collateral`], as UNIX timestamps in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollateralValidity {
+    /// The latest `issueDate` of the collateral documents.
+    pub not_before: u64,
+    /// The earliest `nextUpdate` of the collateral documents.
+    pub next_update: u64,
+}
+
+impl CollateralValidity {
+    /// Checks that `timestamp_seconds` is within `[not_before - skew, next_update + skew]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::Expired`] if `timestamp_seconds` falls outside the window.
+    pub fn check(
+        &self,
+        timestamp_seconds: u64,
+        allowed_skew_secs: u64,
+    ) -> Result<(), CollateralError> {
+        let earliest = self.not_before.saturating_sub(allowed_skew_secs);
+        let latest = self.next_update.saturating_add(allowed_skew_secs);
+
+        if timestamp_seconds < earliest || latest < timestamp_seconds {
+            return Err(CollateralError::Expired {
+                timestamp_seconds,
+                not_before: self.not_before,
+                next_update: self.next_update,
+                allowed_skew_secs,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Clamps `timestamp_seconds` into this window. Used to evaluate collateral at a point in
+    /// time which is within the window after the caller's clock skew has been tolerated.
+    pub fn clamp(&self, timestamp_seconds: u64) -> u64 {
+        timestamp_seconds.clamp(self.not_before, self.next_update.max(self.not_before))
+    }
+}
+
+/// The fields shared by all Intel-signed collateral documents (TCB info, QE identity).
+struct IssuedDocument {
+    issue_date: u64,
+    next_update: u64,
+}
+
+impl IssuedDocument {
+    fn parse(field: &str, json: &str) -> Result<Self, CollateralError> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|_| CollateralError::InvalidDocument(String::from(field)))?;
+
+        let get_date = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_str)
+                .and_then(parse_rfc3339_utc)
+                .ok_or_else(|| CollateralError::InvalidDate {
+                    field: String::from(field),
+                    key: String::from(key),
+                })
+        };
+
+        Ok(Self {
+            issue_date: get_date("issueDate")?,
+            next_update: get_date("nextUpdate")?,
+        })
+    }
+}
+
+/// Parses a UTC timestamp of the form `YYYY-MM-DDTHH:MM:SSZ` (as used by Intel PCS), with
+/// optional fractional seconds, into a UNIX timestamp in seconds.
+pub(crate) fn parse_rfc3339_utc(date: &str) -> Option<u64> {
+    let date = date.strip_suffix('Z')?;
+    let (date, time) = date.split_once('T')?;
+    // Fractional seconds do not affect validity checks at second granularity.
+    let time = time.split_once('.').map_or(time, |(time, _fraction)| time);
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: u64 = date_parts.next()?.parse().ok()?;
+    let month: u64 = date_parts.next()?.parse().ok()?;
+    let day: u64 = date_parts.next()?.parse().ok()?;
+
+    let mut time_parts = time.splitn(3, ':');
+    let hour: u64 = time_parts.next()?.parse().ok()?;
+    let minute: u64 = time_parts.next()?.parse().ok()?;
+    let second: u64 = time_parts.next()?.parse().ok()?;
+
+    let in_range = (1970..=9999).contains(&year)
+        && (1..=12).contains(&month)
+        && (1..=days_in_month(year, month)).contains(&day)
+        && hour < 24
+        && minute < 60
+        && second < 61;
+    if !in_range {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day)?;
+    let seconds_of_day = hour
+        .checked_mul(3_600)?
+        .checked_add(minute.checked_mul(60)?)?
+        .checked_add(second)?;
+    days.checked_mul(86_400)?.checked_add(seconds_of_day)
+}
+
+/// Number of days of `month` in `year` of the proleptic Gregorian calendar, 0 for an invalid
+/// month.
+fn days_in_month(year: u64, month: u64) -> u64 {
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => 0,
     }
 }
 
+/// Number of days since 1970-01-01 for the given proleptic Gregorian date, `None` if it
+/// predates it.
+///
+/// See: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: u64, month: u64, day: u64) -> Option<u64> {
+    let (year, month) = if month <= 2 {
+        (year.checked_sub(1)?, month.checked_add(9)?)
+    } else {
+        (year, month.checked_sub(3)?)
+    };
+    let era = year / 400;
+    let year_of_era = year.checked_sub(era.checked_mul(400)?)?;
+    let day_of_year = (month.checked_mul(153)?.checked_add(2)? / 5)
+        .checked_add(day)?
+        .checked_sub(1)?;
+    let day_of_era = year_of_era
+        .checked_mul(365)?
+        .checked_add(year_of_era / 4)?
+        .checked_sub(year_of_era / 100)?
+        .checked_add(day_of_year)?;
+    era.checked_mul(146_097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719_468)
+}
+
 impl FromStr for Collateral {
     type Err = CollateralError;
 
@@ -78,6 +1121,12 @@ impl FromStr for Collateral {
     }
 }
 
+impl From<Collateral> for Value {
+    fn from(collateral: Collateral) -> Self {
+        collateral.to_json()
+    }
+}
+
 impl TryFrom<Value> for Collateral {
     type Error = CollateralError;
 
@@ -96,6 +1145,71 @@ pub enum CollateralError {
         #[source]
         source: FromHexError,
     },
+    #[error("Failed to decode base64 field '{field}': {source}")]
+    Base64Decode {
+        field: String,
+        #[source]
+        source: base64::DecodeError,
+    },
+    #[error("Field '{0}' is not valid UTF-8")]
+    InvalidUtf8(String),
     #[error("Invalid JSON format")]
     InvalidJson,
+    #[error("Invalid JSON document in field '{0}'")]
+    InvalidDocument(String),
+    #[error("Missing or invalid date '{key}' in field '{field}'")]
+    InvalidDate { field: String, key: String },
+    #[error(
+        "Collateral is not valid at {timestamp_seconds} (valid from {not_before} until {next_update}, allowed skew {allowed_skew_secs}s)"
+    )]
+    Expired {
+        timestamp_seconds: u64,
+        not_before: u64,
+        next_update: u64,
+        allowed_skew_secs: u64,
+    },
+    #[error("Invalid certificate chain: {0}")]
+    InvalidCertificateChain(String),
+    #[error("Invalid root CA CRL: {0}")]
+    RootCrlInvalid(String),
+    #[error("Invalid PCK CRL: {0}")]
+    PckCrlInvalid(String),
+    #[error("Certificate chain has more than {max} certificates")]
+    TooManyCerts { max: usize },
+    #[error("DER encoding nests more than {max} levels deep")]
+    NestingTooDeep { max: usize },
+    #[error("Field '{field}' has more than {max} TCB levels")]
+    TooManyTcbLevels { field: String, max: usize },
+    #[error("Certificate chain '{0}' does not end with the trusted root CA")]
+    UntrustedRoot(String),
+    #[error("CRL is not signed by the root CA of the certificate chain")]
+    CrlSignature,
+    #[error("PCK CRL is not signed by its issuer chain")]
+    CrlSignatureInvalid,
+    #[error("Certificate chain '{0}' has a certificate not signed by its issuer")]
+    IssuerChainSignatureInvalid(String),
+    #[error("Certificate chain '{field}' starts with `{subject}`, which may not sign it")]
+    UnauthorizedSigner { field: String, subject: String },
+    #[error("The leaf certificate of the chain is a CA")]
+    LeafIsCa,
+    #[error("Certificate {index} of the chain issues another certificate, but is not a CA")]
+    IssuerNotCa { index: usize },
+    #[error("Certificate {index} of the chain has more CAs below it than its path length allows")]
+    PathLenExceeded { index: usize },
+    #[error("Certificate {index} of the chain does not allow the key usage of its position")]
+    InvalidKeyUsage { index: usize },
+    #[error("Certificate with serial number {serial} has been revoked")]
+    RevokedCertificate { serial: String },
+    #[error("Invalid quote: {0}")]
+    InvalidQuote(String),
+    #[error("Collateral is for FMSPC {collateral}, but the quote has FMSPC {}: {hint}", .hint.fmspc)]
+    FmspcMismatch {
+        collateral: Fmspc,
+        hint: Box<CollateralHint>,
+    },
+    #[error("Collateral PCK CRL is issued by `{collateral}`, but the quote is from the {} CA: {hint}", .hint.ca)]
+    CaMismatch {
+        collateral: String,
+        hint: Box<CollateralHint>,
+    },
 }
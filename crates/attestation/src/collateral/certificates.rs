@@ -0,0 +1,284 @@
+use alloc::{string::ToString, vec::Vec};
+use derive_more::{Deref, Into};
+use x509_cert::{
+    Certificate,
+    der::{Decode, Reader, SliceReader, pem},
+    ext::pkix::{BasicConstraints, KeyUsage},
+};
+
+use super::{CollateralError, DEFAULT_MAX_CERTS};
+
+const PEM_BOUNDARY: &[u8] = b"-----BEGIN";
+const PEM_END_BOUNDARY: &[u8] = b"-----END";
+const PEM_BOUNDARY_DELIMITER: &[u8] = b"-----";
+
+/// Maximum nesting depth of constructed DER values accepted in a certificate. X.509
+/// certificates nest about six levels deep; extensions are opaque octet strings and do not
+/// count towards the depth.
+pub const MAX_DER_NESTING_DEPTH: usize = 16;
+
+/// Length of a PCK certificate chain: the PCK certificate, the PCK platform or processor CA
+/// and the root CA.
+pub const MAX_PCK_CHAIN_LEN: usize = 3;
+
+/// A certificate chain, in the order it was provided.
+#[derive(Debug, Clone, PartialEq, Eq, Deref, Into)]
+pub struct Certificates(Vec<Certificate>);
+
+impl Certificates {
+    /// Parses one or more concatenated certificates, either all PEM or all DER encoded.
+    ///
+    /// The input is treated as PEM if it starts with a `-----BEGIN` boundary, ignoring leading
+    /// whitespace, and as DER otherwise. At most [`DEFAULT_MAX_CERTS`] certificates are parsed.
+    ///
+    /// Before a certificate is decoded, its DER encoding is checked iteratively to nest at most
+    /// [`MAX_DER_NESTING_DEPTH`] levels deep, which bounds the stack used by the decoder
+    /// regardless of the input.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::InvalidCertificateChain`] if the input is empty, mixes PEM
+    /// and DER certificates, or any certificate is malformed,
+    /// [`CollateralError::TooManyCerts`] if it has too many certificates, and
+    /// [`CollateralError::NestingTooDeep`] if it nests too deeply.
+    pub fn parse_any(input: &[u8]) -> Result<Self, CollateralError> {
+        let certificates = if input.trim_ascii_start().starts_with(PEM_BOUNDARY) {
+            parse_pem_chain(input)?
+        } else {
+            parse_der_chain(input)?
+        };
+
+        if certificates.is_empty() {
+            return Err(CollateralError::InvalidCertificateChain(
+                "empty chain".to_string(),
+            ));
+        }
+
+        Ok(Self(certificates))
+    }
+
+    /// Checks that the chain, leaf first, follows the X.509 constraints of its certificates,
+    /// beyond their signatures:
+    ///
+    /// - it has at most `max_len` certificates;
+    /// - the leaf is not a CA, and may sign with its key if it declares a key usage;
+    /// - every other certificate is a CA, and may sign certificates if it declares a key
+    ///   usage;
+    /// - no CA has more intermediate CAs below it than its path length constraint.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollateralError::TooManyCerts`], [`CollateralError::LeafIsCa`],
+    /// [`CollateralError::IssuerNotCa`], [`CollateralError::InvalidKeyUsage`] or
+    /// [`CollateralError::PathLenExceeded`] for the first violated constraint, with the index
+    /// of the certificate in the chain, and [`CollateralError::InvalidCertificateChain`] if an
+    /// extension is malformed or repeated.
+    pub fn check_constraints(&self, max_len: usize) -> Result<(), CollateralError> {
+        if self.0.len() > max_len {
+            return Err(CollateralError::TooManyCerts { max: max_len });
+        }
+
+        for (index, certificate) in self.0.iter().enumerate() {
+            let invalid =
+                |e: x509_cert::der::Error| CollateralError::InvalidCertificateChain(e.to_string());
+            let basic_constraints = certificate
+                .tbs_certificate
+                .get::<BasicConstraints>()
+                .map_err(invalid)?
+                .map(|(_, basic_constraints)| basic_constraints);
+            let key_usage = certificate
+                .tbs_certificate
+                .get::<KeyUsage>()
+                .map_err(invalid)?
+                .map(|(_, key_usage)| key_usage);
+            let is_ca = basic_constraints
+                .as_ref()
+                .is_some_and(|constraints| constraints.ca);
+
+            let Some(intermediates_below) = index.checked_sub(1) else {
+                if is_ca {
+                    return Err(CollateralError::LeafIsCa);
+                }
+                if key_usage.is_some_and(|usage| !usage.digital_signature()) {
+                    return Err(CollateralError::InvalidKeyUsage { index });
+                }
+                continue;
+            };
+            if !is_ca {
+                return Err(CollateralError::IssuerNotCa { index });
+            }
+            if key_usage.is_some_and(|usage| !usage.key_cert_sign()) {
+                return Err(CollateralError::InvalidKeyUsage { index });
+            }
+            let path_len =
+                basic_constraints.and_then(|constraints| constraints.path_len_constraint);
+            if path_len.is_some_and(|path_len| intermediates_below > usize::from(path_len)) {
+                return Err(CollateralError::PathLenExceeded { index });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_pem_chain(input: &[u8]) -> Result<Vec<Certificate>, CollateralError> {
+    let invalid = |reason: &str| CollateralError::InvalidCertificateChain(reason.to_string());
+
+    // Counted up front, so that an oversized chain is rejected before decoding any of it.
+    if pem_certificate_count(input) > DEFAULT_MAX_CERTS {
+        return Err(CollateralError::TooManyCerts {
+            max: DEFAULT_MAX_CERTS,
+        });
+    }
+
+    let mut certificates = Vec::new();
+    let mut rest = input.trim_ascii_start();
+    while !is_padding(rest) {
+        let (block, remaining) = split_pem_block(rest).ok_or_else(|| invalid("malformed PEM"))?;
+        let (label, der) = pem::decode_vec(block).map_err(|e| invalid(&e.to_string()))?;
+        if label != "CERTIFICATE" {
+            return Err(invalid("PEM block is not a certificate"));
+        }
+        check_der_nesting(&der)?;
+        certificates.push(Certificate::from_der(&der).map_err(|e| invalid(&e.to_string()))?);
+        rest = remaining.trim_ascii_start();
+    }
+    Ok(certificates)
+}
+
+/// Whether `input` is empty or only whitespace and NUL bytes, as DCAP pads the PEM PCK chain
+/// of a quote with a trailing `\0`.
+fn is_padding(input: &[u8]) -> bool {
+    input
+        .iter()
+        .all(|byte| *byte == 0 || byte.is_ascii_whitespace())
+}
+
+/// Splits the first PEM block, from its `-----BEGIN` to the end of its `-----END ...-----`
+/// boundary, off `input`.
+fn split_pem_block(input: &[u8]) -> Option<(&[u8], &[u8])> {
+    if !input.starts_with(PEM_BOUNDARY) {
+        return None;
+    }
+    let end = find(input, PEM_END_BOUNDARY)?;
+    let after_end = end.checked_add(PEM_END_BOUNDARY.len())?;
+    let closing = find(input.get(after_end..)?, PEM_BOUNDARY_DELIMITER)?;
+    let block_len = after_end
+        .checked_add(closing)?
+        .checked_add(PEM_BOUNDARY_DELIMITER.len())?;
+    input.split_at_checked(block_len)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Checks that the concatenated DER values of `der` nest at most [`MAX_DER_NESTING_DEPTH`]
+/// constructed values deep, walking the encoding with an explicit stack rather than recursion.
+fn check_der_nesting(der: &[u8]) -> Result<(), CollateralError> {
+    let malformed = || CollateralError::InvalidCertificateChain("malformed DER".to_string());
+
+    // End offsets of the constructed values enclosing the current position.
+    let mut enclosing: Vec<usize> = Vec::new();
+    let mut position = 0;
+    while position < der.len() {
+        let (constructed, value_start, value_end) =
+            read_der_header(der, position).ok_or_else(malformed)?;
+        if value_end > enclosing.last().copied().unwrap_or(der.len()) {
+            return Err(malformed());
+        }
+
+        if constructed {
+            enclosing.push(value_end);
+            if enclosing.len() > MAX_DER_NESTING_DEPTH {
+                return Err(CollateralError::NestingTooDeep {
+                    max: MAX_DER_NESTING_DEPTH,
+                });
+            }
+            position = value_start;
+        } else {
+            position = value_end;
+        }
+
+        while enclosing.last() == Some(&position) {
+            enclosing.pop();
+        }
+    }
+    Ok(())
+}
+
+/// Reads the identifier and definite length of the DER value at `position`, returning whether
+/// it is constructed and the offsets of its contents.
+fn read_der_header(der: &[u8], position: usize) -> Option<(bool, usize, usize)> {
+    let identifier = *der.get(position)?;
+    let constructed = identifier & 0x20 != 0;
+    let mut offset = position.checked_add(1)?;
+
+    // High tag numbers continue while the most significant bit is set.
+    if identifier & 0x1f == 0x1f {
+        while *der.get(offset)? & 0x80 != 0 {
+            offset = offset.checked_add(1)?;
+        }
+        offset = offset.checked_add(1)?;
+    }
+
+    let first_length_byte = *der.get(offset)?;
+    offset = offset.checked_add(1)?;
+    let length = if first_length_byte & 0x80 == 0 {
+        usize::from(first_length_byte)
+    } else {
+        // Indefinite lengths (0x80) are not valid DER.
+        let length_bytes = usize::from(first_length_byte & 0x7f);
+        if length_bytes == 0 || length_bytes > core::mem::size_of::<usize>() {
+            return None;
+        }
+        let end = offset.checked_add(length_bytes)?;
+        let length = der
+            .get(offset..end)?
+            .iter()
+            .try_fold(0usize, |length, byte| {
+                length.checked_mul(256)?.checked_add(usize::from(*byte))
+            })?;
+        offset = end;
+        length
+    };
+
+    Some((constructed, offset, offset.checked_add(length)?))
+}
+
+fn parse_der_chain(input: &[u8]) -> Result<Vec<Certificate>, CollateralError> {
+    let invalid =
+        |e: x509_cert::der::Error| CollateralError::InvalidCertificateChain(e.to_string());
+
+    if input
+        .windows(PEM_BOUNDARY.len())
+        .any(|window| window == PEM_BOUNDARY)
+    {
+        return Err(CollateralError::InvalidCertificateChain(
+            "DER input contains PEM certificates".to_string(),
+        ));
+    }
+
+    check_der_nesting(input)?;
+
+    let mut reader = SliceReader::new(input).map_err(invalid)?;
+    let mut certificates = Vec::new();
+    while !reader.is_finished() {
+        if certificates.len() == DEFAULT_MAX_CERTS {
+            return Err(CollateralError::TooManyCerts {
+                max: DEFAULT_MAX_CERTS,
+            });
+        }
+        certificates.push(Certificate::decode(&mut reader).map_err(invalid)?);
+    }
+    Ok(certificates)
+}
+
+/// Counts the PEM boundaries of `input`, without parsing the certificates.
+pub(super) fn pem_certificate_count(input: &[u8]) -> usize {
+    input
+        .windows(PEM_BOUNDARY.len())
+        .filter(|window| *window == PEM_BOUNDARY)
+        .count()
+}
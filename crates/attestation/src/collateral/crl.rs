@@ -0,0 +1,373 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use ring::signature::{ECDSA_P256_SHA256_ASN1, UnparsedPublicKey};
+use x509_cert::{
+    Certificate,
+    crl::CertificateList,
+    der::{Decode, Encode, oid::ObjectIdentifier},
+};
+
+use super::{CaType, Certificates, CollateralError, DEFAULT_MAX_CERTS};
+
+/// `ecdsa-with-SHA256`, the only signature algorithm used by the Intel SGX Root CA.
+const ECDSA_WITH_SHA_256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+
+/// `id-at-commonName`.
+const COMMON_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.4.3");
+
+/// `id-at-organizationName`.
+const ORGANIZATION_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.4.10");
+
+/// Common name of the Intel certificate that signs the TCB info and QE identity.
+pub const TCB_SIGNING_COMMON_NAME: &str = "Intel SGX TCB Signing";
+
+/// Organization of the Intel PCK CAs.
+pub const INTEL_ORGANIZATION: &str = "Intel Corporation";
+
+/// Common name of the Intel PCK CA that issues PCK certificates of multi-package platforms,
+/// and signs their CRL.
+pub const INTEL_PCK_PLATFORM_CA_COMMON_NAME: &str = "Intel SGX PCK Platform CA";
+
+/// Common name of the Intel PCK CA that issues PCK certificates of single-package platforms,
+/// and signs their CRL.
+pub const INTEL_PCK_PROCESSOR_CA_COMMON_NAME: &str = "Intel SGX PCK Processor CA";
+
+/// Whether `certificate` is one of the Intel PCK CAs: its subject has the common name
+/// [`INTEL_PCK_PLATFORM_CA_COMMON_NAME`] or [`INTEL_PCK_PROCESSOR_CA_COMMON_NAME`], and the
+/// organization [`INTEL_ORGANIZATION`]. The attributes are matched by OID, whatever their
+/// order in the subject.
+///
+/// A chain of a different CA can still verify if the trusted root is misconfigured, so this
+/// checks the chain is Intel's in addition to its signatures.
+pub fn is_intel_pck_ca(certificate: &Certificate) -> bool {
+    [CaType::Platform, CaType::Processor]
+        .into_iter()
+        .any(|ca| is_intel_pck_ca_of_type(certificate, ca))
+}
+
+/// Whether `certificate` is the Intel PCK CA of type `ca`, as [`is_intel_pck_ca`] matches it.
+pub(super) fn is_intel_pck_ca_of_type(certificate: &Certificate, ca: CaType) -> bool {
+    let common_name = match ca {
+        CaType::Platform => INTEL_PCK_PLATFORM_CA_COMMON_NAME,
+        CaType::Processor => INTEL_PCK_PROCESSOR_CA_COMMON_NAME,
+    };
+    has_subject_attribute(certificate, COMMON_NAME, "CN=", common_name)
+        && has_subject_attribute(certificate, ORGANIZATION_NAME, "O=", INTEL_ORGANIZATION)
+}
+
+/// Whether the subject of `certificate` has an `oid` attribute with `value`, the attribute
+/// rendering as `label` followed by `value`.
+fn has_subject_attribute(
+    certificate: &Certificate,
+    oid: ObjectIdentifier,
+    label: &str,
+    value: &str,
+) -> bool {
+    certificate
+        .tbs_certificate
+        .subject
+        .0
+        .iter()
+        .flat_map(|rdn| rdn.0.iter())
+        .filter(|attribute| attribute.oid == oid)
+        .any(|attribute| attribute.to_string() == [label, value].concat())
+}
+
+/// Checks that no certificate issued by the root CA of `chain` has been revoked by the root CA
+/// CRL `root_crl_der`.
+///
+/// `chain` is a PEM or DER encoded certificate chain ending with the root CA, as found in the
+/// issuer chains of the collateral, see [`Certificates::parse_any`]. The CRL must be signed by that root CA. Only the revocation status
+/// is checked here; the validity period of the CRL is checked by [`super::Collateral::check_crls`].
+///
+/// # Errors
+///
+/// Returns a [`CollateralError`] if the chain or the CRL cannot be parsed, the CRL is not
+/// signed by the root CA of the chain, or a certificate issued by the root CA is revoked.
+pub fn check_root_ca_crl(chain: &[u8], root_crl_der: &[u8]) -> Result<(), CollateralError> {
+    let chain = Certificates::parse_any(chain)?;
+    let root = chain
+        .last()
+        .ok_or_else(|| CollateralError::InvalidCertificateChain("empty chain".to_string()))?;
+
+    let crl = CertificateList::from_der(root_crl_der)
+        .map_err(|e| CollateralError::RootCrlInvalid(e.to_string()))?;
+    verify_crl_signature(
+        &crl,
+        root,
+        CollateralError::CrlSignature,
+        CollateralError::RootCrlInvalid,
+    )?;
+
+    // Entries of a CRL only refer to certificates issued by the CRL issuer, i.e. the root CA.
+    // The root CA itself cannot be revoked through its own CRL.
+    let root_subject = &root.tbs_certificate.subject;
+    let revoked_serials: Vec<_> = crl
+        .tbs_cert_list
+        .revoked_certificates
+        .iter()
+        .flatten()
+        .map(|revoked| &revoked.serial_number)
+        .collect();
+
+    let revoked = chain
+        .iter()
+        .filter(|cert| cert.tbs_certificate.subject != *root_subject)
+        .filter(|cert| cert.tbs_certificate.issuer == *root_subject)
+        .find(|cert| revoked_serials.contains(&&cert.tbs_certificate.serial_number));
+
+    match revoked {
+        Some(cert) => Err(CollateralError::RevokedCertificate {
+            serial: hex::encode(cert.tbs_certificate.serial_number.as_bytes()),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Validity window of a CRL, as UNIX timestamps in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrlValidity {
+    /// The `thisUpdate` of the CRL, when it was issued.
+    pub this_update: u64,
+    /// The `nextUpdate` of the CRL, by when a new CRL is issued, if it has one.
+    pub next_update: Option<u64>,
+}
+
+impl CrlValidity {
+    /// Parses the validity window of a DER encoded CRL, returning `invalid` with the reason if
+    /// it is malformed.
+    pub fn parse(
+        crl_der: &[u8],
+        invalid: fn(String) -> CollateralError,
+    ) -> Result<Self, CollateralError> {
+        let crl = CertificateList::from_der(crl_der).map_err(|e| invalid(e.to_string()))?;
+        Ok(Self {
+            this_update: crl.tbs_cert_list.this_update.to_unix_duration().as_secs(),
+            next_update: crl
+                .tbs_cert_list
+                .next_update
+                .map(|next_update| next_update.to_unix_duration().as_secs()),
+        })
+    }
+
+    /// Checks that `timestamp_seconds` is within `[this_update - skew, next_update + skew]`,
+    /// returning `invalid` with the window otherwise.
+    pub fn check(
+        &self,
+        timestamp_seconds: u64,
+        allowed_skew_secs: u64,
+        invalid: fn(String) -> CollateralError,
+    ) -> Result<(), CollateralError> {
+        let earliest = self.this_update.saturating_sub(allowed_skew_secs);
+        let latest = self.next_update.map_or(u64::MAX, |next_update| {
+            next_update.saturating_add(allowed_skew_secs)
+        });
+
+        if timestamp_seconds < earliest || latest < timestamp_seconds {
+            let until = self
+                .next_update
+                .map_or_else(|| String::from("no next update"), |next| next.to_string());
+            return Err(invalid(format!(
+                "not valid at {timestamp_seconds} (issued at {}, next update {until}, allowed \
+                 skew {allowed_skew_secs}s)",
+                self.this_update
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the PCK CRL `pck_crl_der` is authentic: it must be issued and signed by the
+/// first certificate of `issuer_chain`, i.e. the PCK Platform or Processor CA, and every
+/// certificate of the chain must be signed by the next one, up to the root CA
+/// `trusted_root_ca_der`, normally the Intel SGX Root CA. The first certificate must be an
+/// Intel PCK CA, see [`is_intel_pck_ca`].
+///
+/// Only the signatures are checked here, not the revocations listed by the CRL.
+///
+/// # Errors
+///
+/// Returns [`CollateralError::UntrustedRoot`] if the chain does not end with
+/// `trusted_root_ca_der`, [`CollateralError::CrlSignatureInvalid`] if a certificate or the CRL
+/// is not signed by its issuer, [`CollateralError::UnauthorizedSigner`] if the first
+/// certificate is not an Intel PCK CA, or a [`CollateralError`] if the chain or the CRL cannot
+/// be parsed.
+pub fn check_pck_crl(
+    issuer_chain: &[u8],
+    pck_crl_der: &[u8],
+    trusted_root_ca_der: &[u8],
+) -> Result<(), CollateralError> {
+    let chain = verified_chain(
+        "pck_crl_issuer_chain",
+        issuer_chain,
+        trusted_root_ca_der,
+        || CollateralError::CrlSignatureInvalid,
+    )?;
+    let issuer = chain
+        .first()
+        .ok_or_else(|| CollateralError::InvalidCertificateChain("empty chain".to_string()))?;
+    if !is_intel_pck_ca(issuer) {
+        return Err(CollateralError::UnauthorizedSigner {
+            field: String::from("pck_crl_issuer_chain"),
+            subject: issuer.tbs_certificate.subject.to_string(),
+        });
+    }
+
+    let crl = CertificateList::from_der(pck_crl_der)
+        .map_err(|e| CollateralError::PckCrlInvalid(e.to_string()))?;
+    let issuer = chain
+        .first()
+        .filter(|issuer| issuer.tbs_certificate.subject == crl.tbs_cert_list.issuer)
+        .ok_or(CollateralError::CrlSignatureInvalid)?;
+    verify_crl_signature(
+        &crl,
+        issuer,
+        CollateralError::CrlSignatureInvalid,
+        CollateralError::PckCrlInvalid,
+    )
+}
+
+/// Checks that the issuer chain `field` of the TCB info or QE identity is authentic and may
+/// sign them: every certificate of `issuer_chain` must be signed by the next one, up to the
+/// root CA `trusted_root_ca_der`, and the first certificate must be the
+/// [`TCB_SIGNING_COMMON_NAME`] certificate, the only one Intel authorizes to sign these
+/// documents.
+///
+/// The signature of the document itself by that certificate is checked by DCAP verification.
+///
+/// # Errors
+///
+/// Returns [`CollateralError::UntrustedRoot`] if the chain does not end with
+/// `trusted_root_ca_der`, [`CollateralError::IssuerChainSignatureInvalid`] if a certificate is
+/// not signed by its issuer, [`CollateralError::UnauthorizedSigner`] if the first certificate
+/// is not the TCB signing certificate, the errors of [`Certificates::check_constraints`] if
+/// the chain violates the constraints of its certificates, or a [`CollateralError`] if the
+/// chain cannot be parsed.
+pub fn check_tcb_signing_chain(
+    field: &str,
+    issuer_chain: &[u8],
+    trusted_root_ca_der: &[u8],
+) -> Result<(), CollateralError> {
+    let chain = verified_chain(field, issuer_chain, trusted_root_ca_der, || {
+        CollateralError::IssuerChainSignatureInvalid(String::from(field))
+    })?;
+
+    let signer = chain
+        .first()
+        .ok_or_else(|| CollateralError::InvalidCertificateChain("empty chain".to_string()))?;
+    if !has_subject_attribute(signer, COMMON_NAME, "CN=", TCB_SIGNING_COMMON_NAME) {
+        return Err(CollateralError::UnauthorizedSigner {
+            field: String::from(field),
+            subject: signer.tbs_certificate.subject.to_string(),
+        });
+    }
+    chain.check_constraints(DEFAULT_MAX_CERTS)
+}
+
+/// Parses `issuer_chain` and checks that it ends with `trusted_root_ca_der`, returning
+/// [`CollateralError::UntrustedRoot`] with `field` otherwise, and that every certificate is
+/// signed by the next one, returning `mismatch()` otherwise.
+fn verified_chain(
+    field: &str,
+    issuer_chain: &[u8],
+    trusted_root_ca_der: &[u8],
+    mismatch: impl Fn() -> CollateralError,
+) -> Result<Certificates, CollateralError> {
+    let chain = Certificates::parse_any(issuer_chain)?;
+    let root = chain
+        .last()
+        .map(Encode::to_der)
+        .transpose()
+        .map_err(|e| CollateralError::InvalidCertificateChain(e.to_string()))?;
+    if root.as_deref() != Some(trusted_root_ca_der) {
+        return Err(CollateralError::UntrustedRoot(String::from(field)));
+    }
+
+    for link in chain.windows(2) {
+        let [certificate, issuer] = link else {
+            continue;
+        };
+        if !is_certificate_signed_by(certificate, issuer)? {
+            return Err(mismatch());
+        }
+    }
+    Ok(chain)
+}
+
+/// Verifies the signature of `crl` by `issuer`, returning `mismatch` if it does not verify and
+/// `invalid` if the signature is malformed.
+fn verify_crl_signature(
+    crl: &CertificateList,
+    issuer: &Certificate,
+    mismatch: CollateralError,
+    invalid: fn(String) -> CollateralError,
+) -> Result<(), CollateralError> {
+    if crl.signature_algorithm.oid != ECDSA_WITH_SHA_256 {
+        return Err(invalid("unsupported signature algorithm".to_string()));
+    }
+
+    let signed_data = crl
+        .tbs_cert_list
+        .to_der()
+        .map_err(|e| invalid(e.to_string()))?;
+    let signature = crl
+        .signature
+        .as_bytes()
+        .ok_or_else(|| invalid("malformed signature".to_string()))?;
+
+    if is_signed_by(issuer, &signed_data, signature)? {
+        Ok(())
+    } else {
+        Err(mismatch)
+    }
+}
+
+/// Whether `certificate` names `issuer` as its issuer and is signed by it.
+fn is_certificate_signed_by(
+    certificate: &Certificate,
+    issuer: &Certificate,
+) -> Result<bool, CollateralError> {
+    let invalid = |reason: &str| CollateralError::InvalidCertificateChain(reason.to_string());
+
+    if certificate.signature_algorithm.oid != ECDSA_WITH_SHA_256 {
+        return Err(invalid("unsupported signature algorithm"));
+    }
+
+    let signed_data = certificate
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| invalid(&e.to_string()))?;
+    let signature = certificate
+        .signature
+        .as_bytes()
+        .ok_or_else(|| invalid("malformed signature"))?;
+
+    Ok(
+        certificate.tbs_certificate.issuer == issuer.tbs_certificate.subject
+            && is_signed_by(issuer, &signed_data, signature)?,
+    )
+}
+
+/// Whether `signature` is a valid ECDSA P-256 SHA-256 signature of `signed_data` by the public
+/// key of `issuer`.
+fn is_signed_by(
+    issuer: &Certificate,
+    signed_data: &[u8],
+    signature: &[u8],
+) -> Result<bool, CollateralError> {
+    let public_key = issuer
+        .tbs_certificate
+        .subject_public_key_info
+        .subject_public_key
+        .as_bytes()
+        .ok_or_else(|| {
+            CollateralError::InvalidCertificateChain("malformed issuer public key".to_string())
+        })?;
+
+    Ok(UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, public_key)
+        .verify(signed_data, signature)
+        .is_ok())
+}
@@ -2,6 +2,8 @@
 #![deny(clippy::mod_module_files)]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 // dcap-qvl requires getrandom but NEAR vm doesn't support it
 // error if randomness is called
@@ -18,8 +20,13 @@ mod wasm_getrandom {
 
 pub mod app_compose;
 pub mod attestation;
+#[cfg(feature = "signed-baseline")]
+pub mod baseline;
 pub mod collateral;
 pub mod measurements;
 pub mod quote;
 pub mod report_data;
+mod strict_json;
+pub mod svn;
 pub mod tcb_info;
+pub mod tcb_status;
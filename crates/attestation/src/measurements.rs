@@ -1,8 +1,75 @@
-use crate::tcb_info::HexBytes;
-use alloc::string::String;
+use crate::{
+    attestation::{COMPOSE_HASH_EVENT, DstackAttestation, KEY_PROVIDER_EVENT, RTMR3_INDEX},
+    tcb_info::{EventLog, HexBytes},
+};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
-use serde_with::{Bytes, serde_as};
+use serde_with::serde_as;
+use sha2::{Digest as _, Sha256, Sha384};
+
+#[cfg(feature = "std")]
+mod azure_maa;
+#[cfg(feature = "std")]
+mod baseline_file;
+mod codename;
+#[cfg(feature = "std")]
+mod dcap_qvl_json;
+mod extended;
+#[cfg(feature = "fetch-baseline")]
+mod fetch;
+mod field_bytes;
+mod firmware;
+mod labeled;
+mod masked;
+mod mrtd;
+#[cfg(feature = "signed-baseline")]
+mod release;
+pub mod replay;
+pub mod sev_snp;
+mod upgrade;
+#[cfg(feature = "semver")]
+mod versioned;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+#[cfg(feature = "std")]
+pub use azure_maa::{AzureMaaClaims, TDX_ATTESTATION_TYPE, from_azure_maa_claims};
+#[cfg(feature = "std")]
+pub use baseline_file::{BaselineFile, CHECKSUM_EXTENSION, load_allowlist_dir};
+#[cfg(feature = "std")]
+pub use dcap_qvl_json::from_dcap_qvl_json;
+pub use extended::ExtendedMeasurements;
+#[cfg(feature = "fetch-baseline")]
+pub use fetch::{FETCH_TIMEOUT, MAX_BASELINE_SIZE, fetch_baseline};
+pub use firmware::{FirmwareAllowlist, FirmwareVersion};
+pub use labeled::{LabeledAllowlist, LabeledMismatch};
+pub use masked::{MaskedMeasurements, Rtmr0Tolerance};
+pub use mrtd::mrtd_from_firmware;
+#[cfg(feature = "signed-baseline")]
+pub use release::ReleaseManifest;
+pub use sev_snp::{SevSnpMeasurements, from_sev_snp_report};
+pub use upgrade::{UpgradeWindow, WhichBaseline};
+#[cfg(feature = "semver")]
+pub use versioned::VersionedAllowlist;
+#[cfg(feature = "yaml")]
+pub use yaml::from_yaml_str;
+
+/// Size in bytes of a single measurement register.
+pub const REGISTER_SIZE: usize = 48;
+/// Size in bytes of the fixed-size encoding of [`Measurements`], see [`Measurements::to_bytes`].
+pub const MEASUREMENTS_SIZE: usize = 4 * REGISTER_SIZE;
+/// Size in bytes of the packed encoding of [`FullMeasurements`], see
+/// [`FullMeasurements::to_packed`].
+pub const PACKED_SIZE: usize = MEASUREMENTS_SIZE + REGISTER_SIZE + APP_COMPOSE_HASH_SIZE;
+/// Size in bytes of [`FullMeasurements::app_compose_hash_payload`].
+const APP_COMPOSE_HASH_SIZE: usize = 32;
 
 /// Required measurements for TEE attestation verification (a.k.a. RTMRs checks). These values
 /// define the trusted baseline that TEE environments must match during verification. They
@@ -11,22 +78,44 @@ use serde_with::{Bytes, serde_as};
 /// To learn more about the RTMRs, see:
 /// - https://docs.phala.network/phala-cloud/tees-attestation-and-zero-trust-security/attestation#runtime-measurement-fields
 /// - https://arxiv.org/pdf/2303.15540 (Section 9.1)
-#[serde_as]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+)]
 pub struct Measurements {
     /// MRTD (Measurement of Root of Trust for Data) - identifies the virtual firmware.
-    #[serde_as(as = "Bytes")]
+    #[serde(
+        serialize_with = "field_bytes::serialize",
+        deserialize_with = "field_bytes::mrtd"
+    )]
     pub mrtd: [u8; 48],
     /// RTMR0 (Runtime Measurement Register 0) - typically measures the bootloader, virtual
     /// firmware data, and configuration.
-    #[serde_as(as = "Bytes")]
+    #[serde(
+        serialize_with = "field_bytes::serialize",
+        deserialize_with = "field_bytes::rtmr0"
+    )]
     pub rtmr0: [u8; 48],
     /// RTMR1 (Runtime Measurement Register 1) - typically measures the OS kernel, boot parameters,
     /// and initrd (initial ramdisk).
-    #[serde_as(as = "Bytes")]
+    #[serde(
+        serialize_with = "field_bytes::serialize",
+        deserialize_with = "field_bytes::rtmr1"
+    )]
     pub rtmr1: [u8; 48],
     /// RTMR2 (Runtime Measurement Register 2) - typically measures the OS application.
-    #[serde_as(as = "Bytes")]
+    #[serde(
+        serialize_with = "field_bytes::serialize",
+        deserialize_with = "field_bytes::rtmr2"
+    )]
     pub rtmr2: [u8; 48],
 }
 
@@ -41,20 +130,796 @@ impl Default for Measurements {
     }
 }
 
-#[serde_as]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+impl From<[u8; MEASUREMENTS_SIZE]> for Measurements {
+    /// Decodes the fixed-size encoding produced by [`Measurements::to_bytes`].
+    fn from(bytes: [u8; MEASUREMENTS_SIZE]) -> Self {
+        let mut measurements = Self::default();
+        for (chunk, register) in bytes.chunks_exact(REGISTER_SIZE).zip(RtmrRegister::ALL) {
+            measurements.register_mut(register).copy_from_slice(chunk);
+        }
+        measurements
+    }
+}
+
+impl TryFrom<&[u8]> for Measurements {
+    type Error = MeasurementsError;
+
+    /// Decodes the fixed-size encoding produced by [`Measurements::to_bytes`], checking that
+    /// `bytes` is exactly [`MEASUREMENTS_SIZE`] bytes long.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; MEASUREMENTS_SIZE]>::try_from(bytes)
+            .map(Self::from)
+            .map_err(|_| MeasurementsError::WrongSize {
+                expected: MEASUREMENTS_SIZE,
+                found: bytes.len(),
+            })
+    }
+}
+
+/// Identifies one of the registers in [`Measurements`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RtmrRegister {
+    Mrtd,
+    Rtmr0,
+    Rtmr1,
+    Rtmr2,
+}
+
+impl RtmrRegister {
+    /// All registers, in their canonical order.
+    pub const ALL: [RtmrRegister; 4] = [
+        RtmrRegister::Mrtd,
+        RtmrRegister::Rtmr0,
+        RtmrRegister::Rtmr1,
+        RtmrRegister::Rtmr2,
+    ];
+
+    /// The field name of the register, as used in serialized measurements.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RtmrRegister::Mrtd => "mrtd",
+            RtmrRegister::Rtmr0 => "rtmr0",
+            RtmrRegister::Rtmr1 => "rtmr1",
+            RtmrRegister::Rtmr2 => "rtmr2",
+        }
+    }
+}
+
+impl Measurements {
+    /// Builds measurements from the raw registers. This is a `const fn`, so that baselines can
+    /// be embedded in source without any parsing at runtime:
+    ///
+    /// ```
+    /// use attestation::measurements::Measurements;
+    ///
+    /// const EXPECTED: Measurements =
+    ///     Measurements::from_arrays([1; 48], [2; 48], [3; 48], [4; 48]);
+    ///
+    /// assert_eq!(EXPECTED.rtmr1, [3; 48]);
+    /// ```
+    pub const fn from_arrays(
+        mrtd: [u8; 48],
+        rtmr0: [u8; 48],
+        rtmr1: [u8; 48],
+        rtmr2: [u8; 48],
+    ) -> Self {
+        Self {
+            mrtd,
+            rtmr0,
+            rtmr1,
+            rtmr2,
+        }
+    }
+
+    /// Fixed-size encoding of the four registers, concatenated as
+    /// `mrtd || rtmr0 || rtmr1 || rtmr2`.
+    pub fn to_bytes(&self) -> [u8; MEASUREMENTS_SIZE] {
+        let mut bytes = [0u8; MEASUREMENTS_SIZE];
+        for (chunk, register) in bytes.chunks_exact_mut(REGISTER_SIZE).zip(RtmrRegister::ALL) {
+            chunk.copy_from_slice(self.register(register));
+        }
+        bytes
+    }
+
+    /// Returns the value of the given register.
+    pub fn register(&self, register: RtmrRegister) -> &[u8; 48] {
+        match register {
+            RtmrRegister::Mrtd => &self.mrtd,
+            RtmrRegister::Rtmr0 => &self.rtmr0,
+            RtmrRegister::Rtmr1 => &self.rtmr1,
+            RtmrRegister::Rtmr2 => &self.rtmr2,
+        }
+    }
+
+    fn register_mut(&mut self, register: RtmrRegister) -> &mut [u8; 48] {
+        match register {
+            RtmrRegister::Mrtd => &mut self.mrtd,
+            RtmrRegister::Rtmr0 => &mut self.rtmr0,
+            RtmrRegister::Rtmr1 => &mut self.rtmr1,
+            RtmrRegister::Rtmr2 => &mut self.rtmr2,
+        }
+    }
+
+    /// Returns the registers that differ between `self` (old) and `other` (new).
+    pub fn diff(&self, other: &Measurements) -> MeasurementsDiff {
+        let changes = RtmrRegister::ALL
+            .into_iter()
+            .filter_map(|register| {
+                Change::between(self.register(register), other.register(register))
+                    .map(|change| (register, change))
+            })
+            .collect();
+        MeasurementsDiff { changes }
+    }
+
+    /// Returns the fraction of matching bytes across all four registers, from `0.0` (every byte
+    /// differs) to `1.0` (identical). For example, `0.75` means one register changed entirely.
+    ///
+    /// This is a monitoring aid for tracking drift, **not** a security check: any difference at
+    /// all means the measurements do not match.
+    pub fn similarity(&self, other: &Measurements) -> f32 {
+        let (matching, total) = RtmrRegister::ALL
+            .into_iter()
+            .flat_map(|register| self.register(register).iter().zip(other.register(register)))
+            .fold((0.0f32, 0.0f32), |(matching, total), (a, b)| {
+                (matching + if a == b { 1.0 } else { 0.0 }, total + 1.0)
+            });
+        matching / total
+    }
+
+    /// Whether the MRTD and RTMR0-2 are all equal, but not all zero. Real hardware never
+    /// produces this, as every register measures different data, but a stubbed measurement
+    /// source copying one value into every register does. Conversions from a report reject
+    /// such measurements with [`MeasurementsError::SuspiciousUniformRegisters`].
+    ///
+    /// All-zero measurements, e.g. [`Measurements::default`], are not flagged: they are what an
+    /// unmeasured TD reports.
+    pub fn looks_stubbed(&self) -> bool {
+        self.mrtd != [0; 48]
+            && [self.rtmr0, self.rtmr1, self.rtmr2]
+                .iter()
+                .all(|rtmr| *rtmr == self.mrtd)
+    }
+
+    /// Returns the permutation of registers under which `actual` equals these measurements, if
+    /// any: `permutation[i]` is the index, in [`RtmrRegister::ALL`] order, of the register of
+    /// `self` that the `i`-th register of `actual` equals. The identity permutation is tried
+    /// first, so equal measurements give `[0, 1, 2, 3]`, and `[0, 1, 3, 2]` means RTMR1 and
+    /// RTMR2 were swapped.
+    ///
+    /// This is a diagnostic to point out registers that were entered in the wrong order, e.g.
+    /// in a baseline. It must **not** be used to accept measurements: a TD whose registers match
+    /// only under a permutation runs different software.
+    pub fn matches_any_permutation(&self, actual: &Measurements) -> Option<[usize; 4]> {
+        let expected = RtmrRegister::ALL.map(|register| self.register(register));
+        let actual = RtmrRegister::ALL.map(|register| actual.register(register));
+
+        // The 24 permutations of 0..4 in lexicographic order, starting with the identity.
+        (0..4usize)
+            .flat_map(|a| (0..4usize).map(move |b| [a, b]))
+            .flat_map(|[a, b]| (0..4usize).map(move |c| [a, b, c]))
+            .filter_map(|[a, b, c]| {
+                let d = (0..4).find(|d| ![a, b, c].contains(d))?;
+                let permutation = [a, b, c, d];
+                (0..4)
+                    .all(|index| permutation.contains(&index))
+                    .then_some(permutation)
+            })
+            .find(|permutation| {
+                actual
+                    .iter()
+                    .zip(permutation)
+                    .all(|(register, index)| expected.get(*index) == Some(register))
+            })
+    }
+}
+
+/// Returns the smallest set of registers whose values tell all `images` apart, i.e. no two
+/// images have the same values in every returned register, so that an allowlist index can
+/// key on just those registers. Ties between sets of the same size are broken in
+/// [`RtmrRegister::ALL`] order, and the registers are returned in that order.
+///
+/// Fewer than two images are told apart by no register at all, so the result is empty. If
+/// even all four registers don't tell the images apart, e.g. because two images differ only
+/// in their key-provider or app compose digest, all four are returned.
+pub fn distinguishing_registers(images: &[FullMeasurements]) -> Vec<RtmrRegister> {
+    let mut subsets: Vec<Vec<RtmrRegister>> = vec![Vec::new()];
+    for register in RtmrRegister::ALL {
+        let with_register: Vec<_> = subsets
+            .iter()
+            .map(|subset| {
+                let mut subset = subset.clone();
+                subset.push(register);
+                subset
+            })
+            .collect();
+        subsets.extend(with_register);
+    }
+    // Stable, so subsets of the same size keep the order in which they were built.
+    subsets.sort_by_key(Vec::len);
+
+    subsets
+        .into_iter()
+        .find(|registers| distinguishes(images, registers))
+        .unwrap_or_else(|| RtmrRegister::ALL.to_vec())
+}
+
+/// Whether no two of `images` have the same values in all of `registers`.
+fn distinguishes(images: &[FullMeasurements], registers: &[RtmrRegister]) -> bool {
+    let mut seen = BTreeSet::new();
+    images.iter().all(|image| {
+        seen.insert(
+            registers
+                .iter()
+                .map(|register| image.rtmrs.register(*register))
+                .collect::<Vec<_>>(),
+        )
+    })
+}
+
+/// An old and a new value of a measurement that differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change<const N: usize> {
+    pub old: [u8; N],
+    pub new: [u8; N],
+}
+
+impl<const N: usize> Change<N> {
+    fn between(old: &[u8; N], new: &[u8; N]) -> Option<Self> {
+        (old != new).then_some(Self {
+            old: *old,
+            new: *new,
+        })
+    }
+}
+
+/// The registers that differ between two [`Measurements`], in canonical register order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MeasurementsDiff {
+    pub changes: Vec<(RtmrRegister, Change<48>)>,
+}
+
+impl MeasurementsDiff {
+    /// Returns true if the two measurements are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the registers that changed.
+    pub fn changed_registers(&self) -> impl Iterator<Item = RtmrRegister> + '_ {
+        self.changes.iter().map(|(register, _)| *register)
+    }
+
+    /// Formats the diff like a unified diff, for review in standard diff viewers: for every
+    /// changed register, in register order, a `- <name>: <old>` line followed by a
+    /// `+ <name>: <new>` line, with lowercase hex values. Each line ends with a newline, and an
+    /// empty diff is the empty string.
+    ///
+    /// ```
+    /// use attestation::measurements::Measurements;
+    ///
+    /// let old = Measurements::default();
+    /// let new = Measurements { rtmr1: [0xab; 48], ..old };
+    ///
+    /// let unified = old.diff(&new).to_unified();
+    ///
+    /// assert!(unified.starts_with("- rtmr1: 0000"));
+    /// assert!(unified.lines().nth(1).unwrap().starts_with("+ rtmr1: abab"));
+    /// ```
+    pub fn to_unified(&self) -> String {
+        let mut unified = String::new();
+        for (register, change) in &self.changes {
+            let name = register.name();
+            for (sign, value) in [('-', &change.old), ('+', &change.new)] {
+                unified.push(sign);
+                unified.push(' ');
+                unified.push_str(name);
+                unified.push_str(": ");
+                unified.push_str(&hex::encode(value));
+                unified.push('\n');
+            }
+        }
+        unified
+    }
+}
+
+/// Identifies one of the fields in [`FullMeasurements`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MeasurementField {
+    Register(RtmrRegister),
+    KeyProviderEventDigest,
+    AppComposeHashPayload,
+}
+
+/// The fields that differ between two [`FullMeasurements`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FullMeasurementsDiff {
+    pub rtmrs: MeasurementsDiff,
+    pub key_provider_event_digest: Option<Change<48>>,
+    pub app_compose_hash_payload: Option<Change<32>>,
+}
+
+impl FullMeasurementsDiff {
+    /// Returns true if the two full measurements are identical.
+    pub fn is_empty(&self) -> bool {
+        self.rtmrs.is_empty()
+            && self.key_provider_event_digest.is_none()
+            && self.app_compose_hash_payload.is_none()
+    }
+
+    /// Returns all fields that changed, registers first.
+    pub fn changed_fields(&self) -> Vec<MeasurementField> {
+        let mut fields: Vec<_> = self
+            .rtmrs
+            .changed_registers()
+            .map(MeasurementField::Register)
+            .collect();
+        if self.key_provider_event_digest.is_some() {
+            fields.push(MeasurementField::KeyProviderEventDigest);
+        }
+        if self.app_compose_hash_payload.is_some() {
+            fields.push(MeasurementField::AppComposeHashPayload);
+        }
+        fields
+    }
+
+    /// Returns true if the app_compose hash payload is the only field that changed. This is a
+    /// strong signal that the code stayed the same, but the deployment config changed.
+    pub fn only_app_compose_hash_payload_changed(&self) -> bool {
+        self.changed_fields() == [MeasurementField::AppComposeHashPayload]
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
 pub struct FullMeasurements {
     /// Expected RTMRs (Runtime Measurement Registers).
     pub rtmrs: Measurements,
     /// Expected digest for the key-provider event.
-    #[serde_as(as = "Bytes")]
+    #[serde(
+        serialize_with = "field_bytes::serialize",
+        deserialize_with = "field_bytes::key_provider_event_digest"
+    )]
     pub key_provider_event_digest: [u8; 48],
 
     /// Expected app_compose hash payload.
-    #[serde_as(as = "Bytes")]
+    #[serde(
+        serialize_with = "field_bytes::serialize",
+        deserialize_with = "field_bytes::app_compose_hash_payload"
+    )]
     pub app_compose_hash_payload: [u8; 32],
 }
 
+impl FullMeasurements {
+    /// A SHA-256 commitment to all fields, hashed in declaration order:
+    /// `sha256(mrtd || rtmr0 || rtmr1 || rtmr2 || key_provider_event_digest || app_compose_hash_payload)`.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.rtmrs.mrtd);
+        hasher.update(self.rtmrs.rtmr0);
+        hasher.update(self.rtmrs.rtmr1);
+        hasher.update(self.rtmrs.rtmr2);
+        hasher.update(self.key_provider_event_digest);
+        hasher.update(self.app_compose_hash_payload);
+        hasher.finalize().into()
+    }
+
+    /// Checks that [`Self::fingerprint`] is `expected`, e.g. a fingerprint pinned on chain in
+    /// place of the full measurements.
+    pub fn verify_against_fingerprint(&self, expected: [u8; 32]) -> Result<(), MeasurementsError> {
+        let found = self.fingerprint();
+        if found != expected {
+            return Err(MeasurementsError::FingerprintMismatch {
+                expected: hex::encode(expected),
+                found: hex::encode(found),
+            });
+        }
+        Ok(())
+    }
+
+    /// Fixed-layout encoding of all fields, without any framing, e.g. for a storage slot:
+    ///
+    /// | Offset | Size | Field                       |
+    /// |--------|------|-----------------------------|
+    /// | 0      | 48   | `rtmrs.mrtd`                |
+    /// | 48     | 48   | `rtmrs.rtmr0`               |
+    /// | 96     | 48   | `rtmrs.rtmr1`               |
+    /// | 144    | 48   | `rtmrs.rtmr2`               |
+    /// | 192    | 48   | `key_provider_event_digest` |
+    /// | 240    | 32   | `app_compose_hash_payload`  |
+    ///
+    /// The first [`MEASUREMENTS_SIZE`] bytes are [`Measurements::to_bytes`].
+    pub fn to_packed(&self) -> [u8; PACKED_SIZE] {
+        let mut packed = [0u8; PACKED_SIZE];
+        let (rtmrs, rest) = packed.split_at_mut(MEASUREMENTS_SIZE);
+        let (key_provider, app_compose) = rest.split_at_mut(REGISTER_SIZE);
+        rtmrs.copy_from_slice(&self.rtmrs.to_bytes());
+        key_provider.copy_from_slice(&self.key_provider_event_digest);
+        app_compose.copy_from_slice(&self.app_compose_hash_payload);
+        packed
+    }
+
+    /// Decodes the encoding produced by [`FullMeasurements::to_packed`].
+    pub fn from_packed(packed: &[u8; PACKED_SIZE]) -> Self {
+        let (rtmrs, rest) = packed.split_at(MEASUREMENTS_SIZE);
+        let (key_provider, app_compose) = rest.split_at(REGISTER_SIZE);
+        let mut measurements = Self {
+            rtmrs: Measurements::default(),
+            key_provider_event_digest: [0; REGISTER_SIZE],
+            app_compose_hash_payload: [0; APP_COMPOSE_HASH_SIZE],
+        };
+        for (chunk, register) in rtmrs.chunks_exact(REGISTER_SIZE).zip(RtmrRegister::ALL) {
+            measurements
+                .rtmrs
+                .register_mut(register)
+                .copy_from_slice(chunk);
+        }
+        measurements
+            .key_provider_event_digest
+            .copy_from_slice(key_provider);
+        measurements
+            .app_compose_hash_payload
+            .copy_from_slice(app_compose);
+        measurements
+    }
+
+    /// The canonical JSON encoding of `self`, for signers and verifiers of a baseline that sign
+    /// its JSON rather than its [`FullMeasurements::fingerprint`].
+    ///
+    /// Keys are sorted in byte order at every level, registers are lowercase hex without a
+    /// prefix, and there is no whitespace, so equal measurements always encode to the same bytes:
+    /// `{"app_compose_hash_payload":..,"key_provider_event_digest":..,"rtmrs":{"mrtd":..,
+    /// "rtmr0":..,"rtmr1":..,"rtmr2":..}}`.
+    pub fn canonical_signing_bytes(&self) -> Vec<u8> {
+        let rtmrs = &self.rtmrs;
+        alloc::format!(
+            concat!(
+                r#"{{"app_compose_hash_payload":"{}","key_provider_event_digest":"{}","#,
+                r#""rtmrs":{{"mrtd":"{}","rtmr0":"{}","rtmr1":"{}","rtmr2":"{}"}}}}"#,
+            ),
+            hex::encode(self.app_compose_hash_payload),
+            hex::encode(self.key_provider_event_digest),
+            hex::encode(rtmrs.mrtd),
+            hex::encode(rtmrs.rtmr0),
+            hex::encode(rtmrs.rtmr1),
+            hex::encode(rtmrs.rtmr2),
+        )
+        .into_bytes()
+    }
+
+    /// Returns the fields that differ between `self` (old) and `other` (new), covering the
+    /// four registers as well as the key-provider and app_compose digests.
+    pub fn diff(&self, other: &FullMeasurements) -> FullMeasurementsDiff {
+        FullMeasurementsDiff {
+            rtmrs: self.rtmrs.diff(&other.rtmrs),
+            key_provider_event_digest: Change::between(
+                &self.key_provider_event_digest,
+                &other.key_provider_event_digest,
+            ),
+            app_compose_hash_payload: Change::between(
+                &self.app_compose_hash_payload,
+                &other.app_compose_hash_payload,
+            ),
+        }
+    }
+    /// Whether `other` runs the same base image as `self` with another app: they differ, but
+    /// only in RTMR2, which measures the app image, and the app compose hash payload. MRTD,
+    /// RTMR0, RTMR1 and the key-provider digest, which measure the firmware, the kernel and the
+    /// key provider, are equal.
+    ///
+    /// This tells an app-only upgrade apart from a base image change, e.g. to apply other
+    /// governance rules to each.
+    pub fn differs_only_in_app(&self, other: &FullMeasurements) -> bool {
+        let changed_fields = self.diff(other).changed_fields();
+        !changed_fields.is_empty()
+            && changed_fields.iter().all(|field| {
+                matches!(
+                    field,
+                    MeasurementField::Register(RtmrRegister::Rtmr2)
+                        | MeasurementField::AppComposeHashPayload
+                )
+            })
+    }
+}
+
+/// A deduplicated set of accepted [`FullMeasurements`], ordered by fingerprint.
+#[derive(Debug, Clone, Default)]
+pub struct Allowlist {
+    entries: Vec<FullMeasurements>,
+}
+
+/// Difference between two [`Allowlist`]s, see [`Allowlist::delta`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowlistDelta {
+    pub added: Vec<FullMeasurements>,
+    pub removed: Vec<FullMeasurements>,
+}
+
+impl AllowlistDelta {
+    /// Whether the allowlists are equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+const MERKLE_LEAF_PREFIX: u8 = 0;
+const MERKLE_NODE_PREFIX: u8 = 1;
+
+impl Allowlist {
+    pub fn new(entries: impl IntoIterator<Item = FullMeasurements>) -> Self {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by_cached_key(FullMeasurements::fingerprint);
+        entries.dedup_by_key(|entry| entry.fingerprint());
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[FullMeasurements] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, entry: &FullMeasurements) -> bool {
+        self.position(entry).is_some()
+    }
+
+    /// The changes turning this allowlist into `new`: the entries only in `new`, and the entries
+    /// only in this allowlist. Both are in fingerprint order.
+    pub fn delta(&self, new: &Allowlist) -> AllowlistDelta {
+        AllowlistDelta {
+            added: new.difference(self),
+            removed: self.difference(new),
+        }
+    }
+
+    fn difference(&self, other: &Allowlist) -> Vec<FullMeasurements> {
+        self.entries
+            .iter()
+            .filter(|entry| !other.contains(entry))
+            .copied()
+            .collect()
+    }
+
+    fn position(&self, entry: &FullMeasurements) -> Option<usize> {
+        let fingerprint = entry.fingerprint();
+        self.entries
+            .binary_search_by_key(&fingerprint, FullMeasurements::fingerprint)
+            .ok()
+    }
+
+    /// The root of a SHA-256 Merkle tree over the entry fingerprints, in fingerprint order.
+    ///
+    /// Leaves are `sha256(0x00 || fingerprint)` and inner nodes are
+    /// `sha256(0x01 || left || right)`. A node without a sibling is carried up to the next level
+    /// unchanged. The root of an empty allowlist is all zeros.
+    pub fn merkle_root(&self) -> [u8; 32] {
+        let mut level = self.merkle_leaves();
+        while level.len() > 1 {
+            level = merkle_parent_level(&level);
+        }
+        level.first().copied().unwrap_or_default()
+    }
+
+    /// Returns a proof that `entry` is part of this allowlist, if it is.
+    pub fn merkle_proof(&self, entry: &FullMeasurements) -> Option<MerkleProof> {
+        let mut index = self.position(entry)?;
+        let mut level = self.merkle_leaves();
+        let mut path = Vec::new();
+
+        while level.len() > 1 {
+            let sibling_index = index ^ 1;
+            if let Some(sibling) = level.get(sibling_index) {
+                path.push(if sibling_index < index {
+                    MerkleProofNode::Left(*sibling)
+                } else {
+                    MerkleProofNode::Right(*sibling)
+                });
+            }
+            level = merkle_parent_level(&level);
+            index /= 2;
+        }
+
+        Some(MerkleProof { path })
+    }
+
+    fn merkle_leaves(&self) -> Vec<[u8; 32]> {
+        self.entries
+            .iter()
+            .map(|entry| merkle_leaf(&entry.fingerprint()))
+            .collect()
+    }
+}
+
+/// A sibling on the path from a leaf to the Merkle root of an [`Allowlist`].
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub enum MerkleProofNode {
+    /// The sibling is the left child; the current node is the right child.
+    Left([u8; 32]),
+    /// The sibling is the right child; the current node is the left child.
+    Right([u8; 32]),
+}
+
+/// Proof of membership of an entry in an [`Allowlist`], see [`Allowlist::merkle_proof`].
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct MerkleProof {
+    /// Siblings from the leaf level up to, but excluding, the root.
+    pub path: Vec<MerkleProofNode>,
+}
+
+fn merkle_leaf(fingerprint: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([MERKLE_LEAF_PREFIX]);
+    hasher.update(fingerprint);
+    hasher.finalize().into()
+}
+
+fn merkle_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([MERKLE_NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn merkle_parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => merkle_node(left, right),
+            unpaired => unpaired.first().copied().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Verifies that `entry` is part of the allowlist committed to by `root`, without requiring
+/// the whole allowlist. See [`Allowlist::merkle_root`] for the tree construction.
+pub fn verify_allowlist_membership(
+    entry: &FullMeasurements,
+    merkle_proof: &MerkleProof,
+    root: [u8; 32],
+) -> Result<(), MeasurementsError> {
+    let computed_root =
+        merkle_proof
+            .path
+            .iter()
+            .fold(
+                merkle_leaf(&entry.fingerprint()),
+                |node, sibling| match sibling {
+                    MerkleProofNode::Left(left) => merkle_node(left, &node),
+                    MerkleProofNode::Right(right) => merkle_node(&node, right),
+                },
+            );
+
+    if computed_root == root {
+        Ok(())
+    } else {
+        Err(MeasurementsError::NotInAllowlist)
+    }
+}
+
+impl FullMeasurements {
+    /// Checks in one go that a verified report and its event log match this baseline:
+    ///
+    /// 1. The MRTD and RTMR0-2 of `report` equal [`FullMeasurements::rtmrs`].
+    /// 2. The event log contains a single `key-provider` and a single `compose-hash` event in
+    ///    RTMR3, with the baseline digest and payload respectively. The `compose-hash` event
+    ///    digest must commit to its payload.
+    /// 3. Replaying the event digests of each RTMR from zero produces RTMR0-3 of `report`.
+    ///
+    /// Unlike [`DstackAttestation::verify`], this takes the event log on its own and does not
+    /// check the quote, the report data or the app compose of the TCB info.
+    pub fn verify_report(
+        &self,
+        report: &dcap_qvl::verify::VerifiedReport,
+        event_log: &[EventLog],
+    ) -> Result<(), MeasurementsError> {
+        let live = Measurements::try_from(report)?;
+        let diff = self.rtmrs.diff(&live);
+        if !diff.is_empty() {
+            return Err(MeasurementsError::BaselineMismatch {
+                registers: diff.changed_registers().collect(),
+            });
+        }
+
+        let key_provider = single_rtmr3_event(event_log, KEY_PROVIDER_EVENT)?;
+        if *key_provider.digest != self.key_provider_event_digest {
+            return Err(MeasurementsError::EventMismatch(KEY_PROVIDER_EVENT));
+        }
+
+        let compose_hash = single_rtmr3_event(event_log, COMPOSE_HASH_EVENT)?;
+        let payload = hex::decode(&compose_hash.event_payload).map_err(|e| {
+            MeasurementsError::InvalidHexValue(COMPOSE_HASH_EVENT.to_string(), e.to_string())
+        })?;
+        let payload_digest =
+            DstackAttestation::event_digest(compose_hash.event_type, &compose_hash.event, &payload);
+        if payload != self.app_compose_hash_payload || *compose_hash.digest != payload_digest {
+            return Err(MeasurementsError::EventMismatch(COMPOSE_HASH_EVENT));
+        }
+
+        let td10 = report
+            .report
+            .as_td10()
+            .ok_or(MeasurementsError::NoTd10Report)?;
+        let rtmrs = [td10.rt_mr0, td10.rt_mr1, td10.rt_mr2, td10.rt_mr3];
+        for (imr, rtmr) in (0..).zip(rtmrs) {
+            let replayed = event_log
+                .iter()
+                .filter(|event| event.imr == imr)
+                .fold([0; 48], |register, event| {
+                    extend_register(&register, &event.digest)
+                });
+            if replayed != rtmr {
+                return Err(MeasurementsError::EventLogReplayMismatch { imr });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn single_rtmr3_event<'a>(
+    event_log: &'a [EventLog],
+    name: &'static str,
+) -> Result<&'a EventLog, MeasurementsError> {
+    let mut events = event_log
+        .iter()
+        .filter(|event| event.event == name && event.imr == RTMR3_INDEX);
+    match (events.next(), events.count()) {
+        (Some(event), 0) => Ok(event),
+        (None, _) => Err(MeasurementsError::EventCount(name, 0)),
+        (Some(_), others) => Err(MeasurementsError::EventCount(
+            name,
+            others.saturating_add(1),
+        )),
+    }
+}
+
+/// Extends a TDX measurement register with `digest`, i.e. returns `sha384(register || digest)`.
+pub fn extend_register(register: &[u8; 48], digest: &[u8; 48]) -> [u8; 48] {
+    let mut hasher = Sha384::new();
+    hasher.update(register);
+    hasher.update(digest);
+    hasher.finalize().into()
+}
+
+/// Checks that `rtmr2` is `prior_rtmr2` extended with the app compose hash, zero padded from
+/// 32 to 48 bytes: `rtmr2 == sha384(prior_rtmr2 || app_compose_hash_payload || [0; 16])`.
+///
+/// Note that current dstack images measure the compose hash into RTMR3 through the
+/// `compose-hash` event, which [`crate::attestation::DstackAttestation::verify`] checks
+/// by replaying the event log. This function covers images that extend the compose hash
+/// directly into RTMR2.
+pub fn verify_app_compose_in_rtmr2(
+    app_compose_hash_payload: &[u8; 32],
+    rtmr2: &[u8; 48],
+    prior_rtmr2: &[u8; 48],
+) -> bool {
+    let mut padded = [0u8; 48];
+    padded[..32].copy_from_slice(app_compose_hash_payload);
+
+    extend_register(prior_rtmr2, &padded) == *rtmr2
+}
+
 /// Hex-compatible version of Measurements that deserializes from hex strings.
 #[serde_as]
 #[derive(
@@ -94,6 +959,79 @@ impl Default for MeasurementsHex {
     }
 }
 
+impl MeasurementsHex {
+    /// Parses a JSON document like [`serde_json::from_str`], but rejects duplicate keys, e.g.
+    /// two `rtmr0` entries, anywhere in the document. Unlike the derived deserializer, this also
+    /// covers unknown fields, and the error is the same regardless of the key.
+    ///
+    /// Configs which are first loaded as a [`serde_json::Value`] are most exposed to this, as
+    /// `Value` silently keeps the last of the duplicated values.
+    pub fn from_json_strict(json: &str) -> Result<Self, MeasurementsError> {
+        crate::strict_json::from_str(json)
+            .map_err(|e| MeasurementsError::InvalidJson(e.to_string()))
+    }
+
+    /// Parses measurements from the hex strings of each register, e.g. as given on a command
+    /// line. Errors name the register that failed to parse: [`MeasurementsError::InvalidHexValue`]
+    /// if it is not valid hex, [`MeasurementsError::DigestInRegisterField`] if it is 32 bytes,
+    /// the size of a digest, and [`MeasurementsError::InvalidLength`] with the decoded length if
+    /// it is any other length than 48 bytes.
+    pub fn from_hex_strs(
+        mrtd: &str,
+        rtmr0: &str,
+        rtmr1: &str,
+        rtmr2: &str,
+    ) -> Result<Self, MeasurementsError> {
+        Ok(Self {
+            mrtd: parse_hex("mrtd", mrtd)?,
+            rtmr0: parse_hex("rtmr0", rtmr0)?,
+            rtmr1: parse_hex("rtmr1", rtmr1)?,
+            rtmr2: parse_hex("rtmr2", rtmr2)?,
+        })
+    }
+
+    /// Decodes measurements from CBOR, as produced by [`MeasurementsHex::to_cbor`].
+    ///
+    /// The encoding is a map with the text keys `mrtd`, `rtmr0`, `rtmr1` and `rtmr2`, each
+    /// holding the raw 48 byte register as a CBOR byte string (major type 2), not as hex text.
+    /// Registers of any other length, and trailing bytes after the map, are rejected.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, MeasurementsError> {
+        let mut reader = bytes;
+        let measurements: Measurements = ciborium::from_reader(&mut reader)
+            .map_err(|e| MeasurementsError::InvalidCbor(e.to_string()))?;
+        if !reader.is_empty() {
+            return Err(MeasurementsError::InvalidCbor(alloc::format!(
+                "{} trailing bytes",
+                reader.len()
+            )));
+        }
+        Ok(measurements.into())
+    }
+
+    /// Encodes the measurements as CBOR, see [`MeasurementsHex::from_cbor`] for the layout.
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> Result<Vec<u8>, MeasurementsError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&Measurements::from(self.clone()), &mut bytes)
+            .map_err(|e| MeasurementsError::InvalidCbor(e.to_string()))?;
+        Ok(bytes)
+    }
+}
+
+fn parse_hex<const N: usize>(name: &str, value: &str) -> Result<HexBytes<N>, MeasurementsError> {
+    let bytes = hex::decode(value)
+        .map_err(|e| MeasurementsError::InvalidHexValue(name.to_string(), e.to_string()))?;
+    let array = <[u8; N]>::try_from(bytes.as_slice()).map_err(|_| {
+        if N == REGISTER_SIZE && bytes.len() == APP_COMPOSE_HASH_SIZE {
+            MeasurementsError::DigestInRegisterField(name.to_string())
+        } else {
+            MeasurementsError::InvalidLength(name.to_string(), bytes.len())
+        }
+    })?;
+    Ok(HexBytes::from(array))
+}
+
 impl From<MeasurementsHex> for Measurements {
     fn from(hex: MeasurementsHex) -> Self {
         Self {
@@ -116,6 +1054,40 @@ impl From<Measurements> for MeasurementsHex {
     }
 }
 
+/// Alternate JSON view of [`MeasurementsHex`], with the field names of the registers in the
+/// NEAR contract's schema, those of `contract_interface::types::TcbInfo` (`mrtd`, `rtmr0`,
+/// `rtmr1`, `rtmr2`). Register values are hex strings. Other fields of the contract's JSON,
+/// such as `rtmr3`, are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MeasurementsNearJson {
+    pub mrtd: HexBytes<48>,
+    pub rtmr0: HexBytes<48>,
+    pub rtmr1: HexBytes<48>,
+    pub rtmr2: HexBytes<48>,
+}
+
+impl From<MeasurementsHex> for MeasurementsNearJson {
+    fn from(hex: MeasurementsHex) -> Self {
+        Self {
+            mrtd: hex.mrtd,
+            rtmr0: hex.rtmr0,
+            rtmr1: hex.rtmr1,
+            rtmr2: hex.rtmr2,
+        }
+    }
+}
+
+impl From<MeasurementsNearJson> for MeasurementsHex {
+    fn from(near_json: MeasurementsNearJson) -> Self {
+        Self {
+            mrtd: near_json.mrtd,
+            rtmr0: near_json.rtmr0,
+            rtmr1: near_json.rtmr1,
+            rtmr2: near_json.rtmr2,
+        }
+    }
+}
+
 /// Hex-compatible version of FullMeasurements that deserializes from hex strings.
 #[serde_as]
 #[derive(
@@ -171,6 +1143,99 @@ impl From<FullMeasurements> for FullMeasurementsHex {
     }
 }
 
+/// Suffixes of the variables of [`FullMeasurementsHex::to_env_vars`], in declaration order.
+const ENV_VAR_SUFFIXES: [&str; 6] = [
+    "MRTD",
+    "RTMR0",
+    "RTMR1",
+    "RTMR2",
+    "KEY_PROVIDER_EVENT_DIGEST",
+    "APP_COMPOSE_HASH_PAYLOAD",
+];
+
+impl FullMeasurementsHex {
+    /// The measurements as environment variables, e.g. for CI/CD systems configured through
+    /// them: `{prefix}_MRTD`, `{prefix}_RTMR0` to `{prefix}_RTMR2`,
+    /// `{prefix}_KEY_PROVIDER_EVENT_DIGEST` and `{prefix}_APP_COMPOSE_HASH_PAYLOAD`, each
+    /// holding the lowercase hex value, in this order.
+    pub fn to_env_vars(&self, prefix: &str) -> Vec<(String, String)> {
+        let values = [
+            hex::encode(*self.rtmrs.mrtd),
+            hex::encode(*self.rtmrs.rtmr0),
+            hex::encode(*self.rtmrs.rtmr1),
+            hex::encode(*self.rtmrs.rtmr2),
+            hex::encode(*self.key_provider_event_digest),
+            hex::encode(*self.app_compose_hash_payload),
+        ];
+        ENV_VAR_SUFFIXES
+            .into_iter()
+            .zip(values)
+            .map(|(suffix, value)| (alloc::format!("{prefix}_{suffix}"), value))
+            .collect()
+    }
+
+    /// Parses the variables produced by [`FullMeasurementsHex::to_env_vars`] with the same
+    /// `prefix`, e.g. from `std::env::vars`. Variables without the prefix are ignored; if a
+    /// variable is repeated, the last value is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::InvalidHexValue`] naming the variable if it is missing or
+    /// not valid hex, [`MeasurementsError::DigestInRegisterField`] if a register holds 32 bytes,
+    /// and [`MeasurementsError::InvalidLength`] if it has any other wrong length.
+    pub fn from_env_vars(
+        prefix: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, MeasurementsError> {
+        let vars: BTreeMap<String, String> = vars.into_iter().collect();
+        let [mrtd, rtmr0, rtmr1, rtmr2, key_provider, app_compose] =
+            ENV_VAR_SUFFIXES.map(|suffix| alloc::format!("{prefix}_{suffix}"));
+        let var = |name: &String| {
+            vars.get(name).ok_or_else(|| {
+                MeasurementsError::InvalidHexValue(name.clone(), String::from("missing"))
+            })
+        };
+
+        Ok(Self {
+            rtmrs: MeasurementsHex {
+                mrtd: parse_hex(&mrtd, var(&mrtd)?)?,
+                rtmr0: parse_hex(&rtmr0, var(&rtmr0)?)?,
+                rtmr1: parse_hex(&rtmr1, var(&rtmr1)?)?,
+                rtmr2: parse_hex(&rtmr2, var(&rtmr2)?)?,
+            },
+            key_provider_event_digest: parse_hex(&key_provider, var(&key_provider)?)?,
+            app_compose_hash_payload: parse_hex(&app_compose, var(&app_compose)?)?,
+        })
+    }
+}
+
+/// The JSON arguments of a NEAR function call taking the measurements as its `measurements`
+/// parameter, i.e. `{"measurements": ...}` with the JSON of [`FullMeasurementsHex`]: lowercase
+/// hex registers, fields in declaration order and no whitespace.
+///
+/// ```
+/// use attestation::measurements::{FullMeasurementsHex, to_near_call_args};
+///
+/// let args = to_near_call_args(&FullMeasurementsHex::default().into());
+///
+/// assert!(args.starts_with(r#"{"measurements":{"rtmrs":{"mrtd":"0000"#));
+/// ```
+pub fn to_near_call_args(measurements: &FullMeasurements) -> String {
+    let rtmrs = &measurements.rtmrs;
+    alloc::format!(
+        concat!(
+            r#"{{"measurements":{{"rtmrs":{{"mrtd":"{}","rtmr0":"{}","rtmr1":"{}","rtmr2":"{}"}},"#,
+            r#""key_provider_event_digest":"{}","app_compose_hash_payload":"{}"}}}}"#,
+        ),
+        hex::encode(rtmrs.mrtd),
+        hex::encode(rtmrs.rtmr0),
+        hex::encode(rtmrs.rtmr1),
+        hex::encode(rtmrs.rtmr2),
+        hex::encode(measurements.key_provider_event_digest),
+        hex::encode(measurements.app_compose_hash_payload),
+    )
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum MeasurementsError {
     #[error("no TD10 report")]
@@ -181,21 +1246,123 @@ pub enum MeasurementsError {
     InvalidHexValue(String, String),
     #[error("invalid length for {0}: {1}")]
     InvalidLength(String, usize),
+    /// 64 hex characters, i.e. a 32 byte value such as an app compose hash, given for a 48 byte
+    /// register.
+    #[error(
+        "invalid length for {0}: got 64 hex characters, expected 96 for a 48-byte register; \
+         is this a 32-byte digest, e.g. an app compose hash, pasted into a register?"
+    )]
+    DigestInRegisterField(String),
+    #[error("inconsistent report: {0}")]
+    InconsistentReport(&'static str),
+    #[error("MRTD and RTMR0-2 are all equal, the measurements look stubbed")]
+    SuspiciousUniformRegisters,
+    #[error("measurements are not in the allowlist")]
+    NotInAllowlist,
+    #[error("invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("measurements are those of version {version}, which does not satisfy {requirement}")]
+    VersionNotAccepted {
+        version: String,
+        requirement: String,
+    },
+    #[error("invalid measurements JSON: {0}")]
+    InvalidJson(String),
+    #[error("invalid measurements CBOR: {0}")]
+    InvalidCbor(String),
+    #[error("invalid measurements YAML: {0}")]
+    InvalidYaml(String),
+    #[error("unsupported attestation type {0}")]
+    UnsupportedAttestationType(String),
+    #[error("failed to fetch the baseline: {0}")]
+    FetchFailed(String),
+    #[error("baseline is larger than {max} bytes")]
+    BaselineTooLarge { max: usize },
+    #[error("baseline file I/O failed: {0}")]
+    Io(String),
+    #[error("baseline file {path} is invalid: {reason}")]
+    InvalidBaselineFile {
+        path: String,
+        reason: Box<MeasurementsError>,
+    },
+    #[error("baseline checksum {found} does not match the checksum file {expected}")]
+    ChecksumMismatch { expected: String, found: String },
+    #[error("RTMR0 tolerance range {start}..{end} is not within 0..48")]
+    InvalidToleranceRange { start: usize, end: usize },
+    #[error("expected {expected} bytes of measurements, found {found}")]
+    WrongSize { expected: usize, found: usize },
+    #[error("invalid TDVF firmware: {0}")]
+    InvalidFirmware(String),
+    #[error("SEV-SNP report version {0} is not supported")]
+    UnsupportedSevSnpVersion(u32),
+    #[error("measurements fingerprint {found} does not match {expected}")]
+    FingerprintMismatch { expected: String, found: String },
+    #[error("baseline signature is invalid")]
+    InvalidBaselineSignature,
+    #[error("baseline is signed by {valid} authorized signers, {threshold} are required")]
+    QuorumNotReached { valid: u64, threshold: u64 },
+    #[error("baseline threshold {threshold} is below the required minimum of {min_threshold}")]
+    ThresholdBelowMinimum { threshold: u64, min_threshold: u64 },
+    #[error("report does not match the baseline in registers {registers:?}")]
+    BaselineMismatch { registers: Vec<RtmrRegister> },
+    #[error("report matches the previous baseline, which was accepted until {deadline}")]
+    UpgradeDeadlinePassed { deadline: u64 },
+    #[error("expected exactly one {0} event in RTMR3, found {1}")]
+    EventCount(&'static str, usize),
+    #[error("{0} event does not match the baseline")]
+    EventMismatch(&'static str),
+    #[error("replaying the event log does not produce RTMR{imr} of the report")]
+    EventLogReplayMismatch { imr: u32 },
 }
 
 impl TryFrom<dcap_qvl::verify::VerifiedReport> for Measurements {
     type Error = MeasurementsError;
 
     fn try_from(verified_report: dcap_qvl::verify::VerifiedReport) -> Result<Self, Self::Error> {
-        let td10 = verified_report
-            .report
-            .as_td10()
-            .ok_or(MeasurementsError::NoTd10Report)?;
-        Ok(Self {
+        Self::try_from(&verified_report)
+    }
+}
+
+impl TryFrom<&dcap_qvl::verify::VerifiedReport> for Measurements {
+    type Error = MeasurementsError;
+
+    fn try_from(verified_report: &dcap_qvl::verify::VerifiedReport) -> Result<Self, Self::Error> {
+        Self::try_from(&verified_report.report)
+    }
+}
+
+impl TryFrom<&dcap_qvl::quote::Report> for Measurements {
+    type Error = MeasurementsError;
+
+    fn try_from(report: &dcap_qvl::quote::Report) -> Result<Self, Self::Error> {
+        let td10 = report.as_td10().ok_or(MeasurementsError::NoTd10Report)?;
+
+        Self {
             rtmr0: td10.rt_mr0,
             rtmr1: td10.rt_mr1,
             rtmr2: td10.rt_mr2,
             mrtd: td10.mr_td,
-        })
+        }
+        .check_consistent()
+    }
+}
+
+impl Measurements {
+    /// Rejects measurements read from a report which was malformed or only partially parsed.
+    fn check_consistent(self) -> Result<Self, MeasurementsError> {
+        // A measured firmware implies a measured boot, so default RTMRs alongside a non-zero
+        // MRTD means the report was malformed or only partially parsed.
+        let rtmrs_are_default = [self.rtmr0, self.rtmr1, self.rtmr2]
+            .iter()
+            .all(|rtmr| *rtmr == [0; 48]);
+        if rtmrs_are_default && self.mrtd != [0; 48] {
+            return Err(MeasurementsError::InconsistentReport(
+                "all RTMRs are zero while MRTD is not",
+            ));
+        }
+        if self.looks_stubbed() {
+            return Err(MeasurementsError::SuspiciousUniformRegisters);
+        }
+        Ok(self)
     }
 }
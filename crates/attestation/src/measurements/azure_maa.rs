@@ -0,0 +1,71 @@
+//! Reads measurements from the claims of a Microsoft Azure Attestation (MAA) token for a TDX
+//! quote, so that nodes hosted on Azure can be checked against the same baselines.
+
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Serialize};
+
+use super::{Measurements, MeasurementsError, MeasurementsHex, REGISTER_SIZE};
+
+/// `x-ms-attestation-type` of a TDX attestation.
+pub const TDX_ATTESTATION_TYPE: &str = "tdxvm";
+
+/// The claims of an MAA token relevant to the measurements, as hex strings. The token must
+/// already be verified; its signature is not checked here.
+///
+/// Deserializes from the JSON payload of the token, ignoring the other claims.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AzureMaaClaims {
+    /// The TEE the token attests, e.g. `tdxvm` or `sevsnpvm`.
+    #[serde(rename = "x-ms-attestation-type")]
+    pub attestation_type: String,
+    #[serde(default)]
+    pub tdx_mrtd: Option<String>,
+    #[serde(default)]
+    pub tdx_rtmr0: Option<String>,
+    #[serde(default)]
+    pub tdx_rtmr1: Option<String>,
+    #[serde(default)]
+    pub tdx_rtmr2: Option<String>,
+}
+
+/// Maps the `tdx_mrtd` and `tdx_rtmr0` to `tdx_rtmr2` claims of a verified MAA token to
+/// measurements.
+///
+/// The key-provider and app compose digests of [`super::FullMeasurements`] are dstack events
+/// of RTMR3, which MAA does not report, so only the registers are returned.
+///
+/// # Errors
+///
+/// Returns [`MeasurementsError::UnsupportedAttestationType`] if the token does not attest a
+/// TDX VM, e.g. SEV-SNP, which has no such registers, [`MeasurementsError::InvalidHexValue`]
+/// or [`MeasurementsError::InvalidLength`] naming the claim that is missing or malformed, and
+/// [`MeasurementsError::InconsistentReport`] like the conversion from a
+/// [`dcap_qvl::verify::VerifiedReport`].
+pub fn from_azure_maa_claims(
+    claims: &AzureMaaClaims,
+) -> Result<MeasurementsHex, MeasurementsError> {
+    if claims.attestation_type != TDX_ATTESTATION_TYPE {
+        return Err(MeasurementsError::UnsupportedAttestationType(
+            claims.attestation_type.clone(),
+        ));
+    }
+
+    let measurements = Measurements {
+        mrtd: register("tdx_mrtd", claims.tdx_mrtd.as_deref())?,
+        rtmr0: register("tdx_rtmr0", claims.tdx_rtmr0.as_deref())?,
+        rtmr1: register("tdx_rtmr1", claims.tdx_rtmr1.as_deref())?,
+        rtmr2: register("tdx_rtmr2", claims.tdx_rtmr2.as_deref())?,
+    }
+    .check_consistent()?;
+    Ok(measurements.into())
+}
+
+fn register(name: &str, hex: Option<&str>) -> Result<[u8; REGISTER_SIZE], MeasurementsError> {
+    let hex = hex.ok_or_else(|| {
+        MeasurementsError::InvalidHexValue(String::from(name), String::from("missing"))
+    })?;
+    let bytes = hex::decode(hex)
+        .map_err(|e| MeasurementsError::InvalidHexValue(String::from(name), e.to_string()))?;
+    <[u8; REGISTER_SIZE]>::try_from(bytes.as_slice())
+        .map_err(|_| MeasurementsError::InvalidLength(String::from(name), bytes.len()))
+}
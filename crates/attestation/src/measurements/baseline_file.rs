@@ -0,0 +1,156 @@
+//! Baselines stored on disk next to a SHA-256 checksum file, so that a baseline edited by
+//! accident, or by someone without access to the checksum, is detected before it is used.
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use sha2::{Digest as _, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{Allowlist, FullMeasurements, FullMeasurementsHex, MeasurementsError};
+
+/// Extension appended to the baseline path to name its checksum file.
+pub const CHECKSUM_EXTENSION: &str = "sha256";
+
+/// A [`FullMeasurementsHex`] baseline stored as JSON at a path, with its SHA-256 checksum in a
+/// sidecar file of the same name with `.sha256` appended, e.g. `baseline.json.sha256`.
+///
+/// The sidecar uses the `sha256sum` format, `<hex digest>  <file name>`, so that the baseline
+/// can also be checked with `sha256sum --check`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaselineFile {
+    path: PathBuf,
+}
+
+impl BaselineFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path of the checksum file, the baseline path with [`CHECKSUM_EXTENSION`] appended.
+    pub fn checksum_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".");
+        path.push(CHECKSUM_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    /// Writes `measurements` as JSON, then its checksum file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::Io`] if either file cannot be written.
+    pub fn write(&self, measurements: &FullMeasurementsHex) -> Result<(), MeasurementsError> {
+        let json = serde_json::to_string_pretty(measurements)
+            .map_err(|e| MeasurementsError::InvalidJson(e.to_string()))?;
+        let file_name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let checksum = format!(
+            "{}  {file_name}\n",
+            hex::encode(Sha256::digest(json.as_bytes()))
+        );
+
+        fs::write(&self.path, json).map_err(|e| io_error(&self.path, &e))?;
+        let checksum_path = self.checksum_path();
+        fs::write(&checksum_path, checksum).map_err(|e| io_error(&checksum_path, &e))
+    }
+
+    /// Reads the baseline, checks it against its checksum file, and only then parses it,
+    /// rejecting duplicate keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::Io`] if either file cannot be read,
+    /// [`MeasurementsError::InvalidHexValue`] if the checksum file does not start with a
+    /// SHA-256 digest in hex, [`MeasurementsError::ChecksumMismatch`] if the baseline does not
+    /// match it, and [`MeasurementsError::InvalidJson`] if it is not a valid baseline.
+    pub fn load_verified(&self) -> Result<FullMeasurementsHex, MeasurementsError> {
+        let checksum_path = self.checksum_path();
+        let checksum_file =
+            fs::read_to_string(&checksum_path).map_err(|e| io_error(&checksum_path, &e))?;
+        let invalid_checksum = |reason: String| {
+            MeasurementsError::InvalidHexValue(checksum_path.display().to_string(), reason)
+        };
+        let expected = checksum_file
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| invalid_checksum(String::from("missing")))?;
+        let expected: [u8; 32] = hex::decode(expected)
+            .map_err(|e| invalid_checksum(e.to_string()))?
+            .try_into()
+            .map_err(|bytes: Vec<u8>| {
+                invalid_checksum(format!("expected 32 bytes, found {}", bytes.len()))
+            })?;
+
+        let baseline = fs::read(&self.path).map_err(|e| io_error(&self.path, &e))?;
+        let found: [u8; 32] = Sha256::digest(&baseline).into();
+        if found != expected {
+            return Err(MeasurementsError::ChecksumMismatch {
+                expected: hex::encode(expected),
+                found: hex::encode(found),
+            });
+        }
+
+        let json = core::str::from_utf8(&baseline)
+            .map_err(|e| MeasurementsError::InvalidJson(e.to_string()))?;
+        crate::strict_json::from_str(json)
+            .map_err(|e| MeasurementsError::InvalidJson(e.to_string()))
+    }
+}
+
+/// Reads every `*.json` file of `dir` as a [`FullMeasurementsHex`] baseline, e.g. one file per
+/// release, into one [`Allowlist`]. Baselines present in several files are accepted once.
+///
+/// Subdirectories and files with other extensions, such as the checksum files of
+/// [`BaselineFile`], are ignored. Checksums are not checked; use
+/// [`BaselineFile::load_verified`] for that.
+///
+/// # Errors
+///
+/// Returns [`MeasurementsError::Io`] if the directory or a file cannot be read, and
+/// [`MeasurementsError::InvalidBaselineFile`] with the path of the first file, in file name
+/// order, which is not a valid baseline.
+pub fn load_allowlist_dir(dir: &Path) -> Result<Allowlist, MeasurementsError> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| io_error(dir, &e))? {
+        let path = entry.map_err(|e| io_error(dir, &e))?.path();
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut entries = Vec::with_capacity(paths.len());
+    for path in paths {
+        let json = fs::read_to_string(&path).map_err(|e| io_error(&path, &e))?;
+        let baseline: FullMeasurementsHex = crate::strict_json::from_str(&json).map_err(|e| {
+            MeasurementsError::InvalidBaselineFile {
+                path: path.display().to_string(),
+                reason: Box::new(MeasurementsError::InvalidJson(e.to_string())),
+            }
+        })?;
+        entries.push(FullMeasurements::from(baseline));
+    }
+    Ok(Allowlist::new(entries))
+}
+
+fn io_error(path: &Path, error: &std::io::Error) -> MeasurementsError {
+    MeasurementsError::Io(format!("{}: {error}", path.display()))
+}
@@ -0,0 +1,317 @@
+//! Short, pronounceable names for measurements, for referring to an image by voice, e.g. in
+//! incident calls, where a hex fingerprint is impractical.
+//!
+//! The word lists and the mapping are part of the public interface: changing either renames
+//! every image, so neither may be edited.
+
+use alloc::{format, string::String};
+
+use super::FullMeasurements;
+
+impl FullMeasurements {
+    /// A deterministic `adjective-noun` name for these measurements, e.g. `"tiny-hazel"`: the
+    /// adjective is picked by the first byte of [`FullMeasurements::fingerprint`] and the noun
+    /// by the second.
+    ///
+    /// There are only 65536 names, so different measurements can share one. A codename is a
+    /// convenience for humans and must **not** be used to identify or accept measurements.
+    pub fn codename(&self) -> String {
+        let [adjective, noun, ..] = self.fingerprint();
+        format!(
+            "{}-{}",
+            ADJECTIVES[usize::from(adjective)],
+            NOUNS[usize::from(noun)]
+        )
+    }
+}
+
+/// Picked by the first byte of the fingerprint.
+const ADJECTIVES: [&str; 256] = [
+    "able", "active", "agile", "alert", "amber", "ample", "ancient", "arctic", "ardent", "autumn",
+    "azure", "balmy", "bold", "brave", "breezy", "bright", "brisk", "bronze", "calm", "candid",
+    "careful", "casual", "cheerful", "chilly", "civic", "clean", "clear", "clever", "cloudy",
+    "coastal", "cobalt", "cosmic", "cozy", "crimson", "crisp", "curious", "daring", "dapper",
+    "dawn", "deep", "deft", "devout", "dewy", "direct", "distant", "dusky", "dusty", "eager",
+    "early", "earnest", "eastern", "easy", "electric", "elegant", "emerald", "epic", "even",
+    "exact", "fair", "faithful", "famous", "fancy", "fast", "fearless", "fiery", "final", "firm",
+    "fluent", "flying", "fond", "frank", "free", "fresh", "friendly", "frosty", "gentle", "giant",
+    "gifted", "glad", "gleaming", "global", "golden", "graceful", "grand", "grateful", "green",
+    "gusty", "handy", "happy", "hardy", "hasty", "hazy", "hearty", "helpful", "hidden", "high",
+    "honest", "humble", "icy", "ideal", "idle", "indigo", "inner", "iron", "ivory", "jade",
+    "jolly", "jovial", "joyful", "keen", "kind", "lanky", "large", "lasting", "lavish", "lean",
+    "level", "light", "lively", "local", "lofty", "long", "loyal", "lucid", "lucky", "lunar",
+    "lush", "magic", "main", "major", "mellow", "merry", "mighty", "mild", "minty", "misty",
+    "modern", "modest", "mossy", "motley", "narrow", "native", "neat", "nimble", "noble",
+    "northern", "novel", "oaken", "ocean", "olive", "open", "orange", "orderly", "outer", "pale",
+    "patient", "peaceful", "pearly", "placid", "plain", "playful", "plucky", "polar", "polite",
+    "proud", "pure", "quick", "quiet", "radiant", "rapid", "rare", "ready", "regal", "robust",
+    "rosy", "round", "royal", "ruby", "rugged", "rustic", "sandy", "scarlet", "secret", "serene",
+    "sharp", "shiny", "silent", "silver", "simple", "sincere", "skilled", "sleek", "slim", "smart",
+    "smooth", "snowy", "soft", "solar", "solid", "sonic", "sound", "southern", "spare", "spry",
+    "stable", "steady", "stellar", "stoic", "stormy", "sturdy", "subtle", "sunny", "super", "sure",
+    "swift", "tall", "tame", "tawny", "tender", "thrifty", "tidal", "tidy", "timely", "tiny",
+    "topaz", "tranquil", "true", "trusty", "upbeat", "urban", "valiant", "vast", "velvet",
+    "verdant", "vital", "vivid", "warm", "wary", "western", "whole", "wild", "windy", "wise",
+    "witty", "woody", "young", "zany", "zealous", "zesty", "bouncy", "bubbly", "crafty", "dreamy",
+    "fabled", "frugal", "mystic",
+];
+
+/// Picked by the second byte of the fingerprint.
+const NOUNS: [&str; 256] = [
+    "acorn",
+    "albatross",
+    "alder",
+    "anchor",
+    "antelope",
+    "apple",
+    "arrow",
+    "aspen",
+    "aster",
+    "aurora",
+    "badger",
+    "balloon",
+    "bamboo",
+    "banner",
+    "barley",
+    "basil",
+    "beacon",
+    "bear",
+    "beaver",
+    "beetle",
+    "birch",
+    "bison",
+    "bobcat",
+    "boulder",
+    "breeze",
+    "brook",
+    "buffalo",
+    "bunting",
+    "cactus",
+    "camel",
+    "canyon",
+    "cardinal",
+    "caribou",
+    "cedar",
+    "chamois",
+    "cheetah",
+    "cherry",
+    "chestnut",
+    "cicada",
+    "cliff",
+    "clover",
+    "cobra",
+    "comet",
+    "condor",
+    "coral",
+    "cougar",
+    "coyote",
+    "crane",
+    "creek",
+    "cricket",
+    "crocus",
+    "crow",
+    "cypress",
+    "daisy",
+    "delta",
+    "dingo",
+    "dolphin",
+    "dove",
+    "dragon",
+    "drake",
+    "dune",
+    "eagle",
+    "egret",
+    "elk",
+    "elm",
+    "ember",
+    "falcon",
+    "fern",
+    "ferret",
+    "finch",
+    "fir",
+    "firefly",
+    "fjord",
+    "flamingo",
+    "fox",
+    "gazelle",
+    "gecko",
+    "geyser",
+    "glacier",
+    "gopher",
+    "granite",
+    "grove",
+    "gull",
+    "harbor",
+    "hare",
+    "hawk",
+    "hazel",
+    "heron",
+    "hickory",
+    "hill",
+    "hornet",
+    "horizon",
+    "husky",
+    "ibis",
+    "iguana",
+    "island",
+    "ivy",
+    "jackal",
+    "jaguar",
+    "jasmine",
+    "jay",
+    "juniper",
+    "kestrel",
+    "kite",
+    "koala",
+    "lagoon",
+    "lake",
+    "lantern",
+    "lark",
+    "laurel",
+    "lemur",
+    "leopard",
+    "lily",
+    "lion",
+    "lizard",
+    "llama",
+    "lotus",
+    "lynx",
+    "magpie",
+    "mallard",
+    "mango",
+    "maple",
+    "marlin",
+    "marmot",
+    "meadow",
+    "meerkat",
+    "mesa",
+    "meteor",
+    "mink",
+    "mole",
+    "moose",
+    "moth",
+    "nebula",
+    "newt",
+    "nightjar",
+    "oak",
+    "oasis",
+    "ocelot",
+    "octopus",
+    "orca",
+    "orchid",
+    "osprey",
+    "otter",
+    "owl",
+    "panda",
+    "panther",
+    "parrot",
+    "peak",
+    "pebble",
+    "pelican",
+    "penguin",
+    "pepper",
+    "petrel",
+    "pine",
+    "planet",
+    "plover",
+    "plum",
+    "pond",
+    "poplar",
+    "prairie",
+    "puffin",
+    "puma",
+    "quail",
+    "quartz",
+    "rabbit",
+    "raccoon",
+    "raven",
+    "reef",
+    "ridge",
+    "river",
+    "robin",
+    "rocket",
+    "rook",
+    "rowan",
+    "sage",
+    "salmon",
+    "sapling",
+    "seal",
+    "sequoia",
+    "shark",
+    "shrike",
+    "sierra",
+    "sparrow",
+    "spruce",
+    "squid",
+    "squirrel",
+    "starling",
+    "stork",
+    "summit",
+    "swallow",
+    "swan",
+    "swift",
+    "tapir",
+    "tern",
+    "thistle",
+    "thrush",
+    "tiger",
+    "toucan",
+    "trout",
+    "tulip",
+    "tundra",
+    "turtle",
+    "valley",
+    "venus",
+    "viper",
+    "vole",
+    "walnut",
+    "walrus",
+    "warbler",
+    "wasp",
+    "willow",
+    "wolf",
+    "wombat",
+    "wren",
+    "yak",
+    "yarrow",
+    "zebra",
+    "zephyr",
+    "bramble",
+    "cobble",
+    "dahlia",
+    "fennel",
+    "garnet",
+    "hyacinth",
+    "iris",
+    "kelp",
+    "lichen",
+    "mirage",
+    "nectar",
+    "opal",
+    "pansy",
+    "quince",
+    "rhubarb",
+    "saffron",
+    "tansy",
+    "umber",
+    "vetch",
+    "wisteria",
+    "yew",
+    "zinnia",
+    "acacia",
+    "bluebell",
+    "canary",
+    "dormouse",
+    "heather",
+    "marigold",
+    "osier",
+    "petunia",
+    "sorrel",
+    "tamarack",
+    "urchin",
+    "vireo",
+    "whimbrel",
+    "gannet",
+    "dunlin",
+    "pipit",
+];
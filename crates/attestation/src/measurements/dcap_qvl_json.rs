@@ -0,0 +1,87 @@
+//! Reads measurements from the JSON verified report printed by the `dcap-qvl` CLI, so that
+//! baselines can be bootstrapped without writing Rust.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde_json::Value;
+
+use super::{Measurements, MeasurementsError, REGISTER_SIZE};
+
+/// Paths of the TD10 report in the JSON of the supported `dcap-qvl` versions. TD15 reports
+/// extend a TD10 report, which holds the measurements.
+const TD10_POINTERS: [&str; 6] = [
+    "/report/TD10",
+    "/report/TD15/base",
+    "/report/td10",
+    "/report/td15/base",
+    "/TD10",
+    "/td10",
+];
+
+/// Accepted names of each register, as serialized by different `dcap-qvl` versions.
+const MRTD_NAMES: [&str; 3] = ["mr_td", "mrTd", "mrtd"];
+const RTMR0_NAMES: [&str; 3] = ["rt_mr0", "rtMr0", "rtmr0"];
+const RTMR1_NAMES: [&str; 3] = ["rt_mr1", "rtMr1", "rtmr1"];
+const RTMR2_NAMES: [&str; 3] = ["rt_mr2", "rtMr2", "rtmr2"];
+
+/// Parses the MRTD and RTMR0-2 from the verified report JSON printed by the `dcap-qvl` CLI,
+/// e.g. `dcap-qvl verify quote.bin`, reading its `TD10` report.
+///
+/// Register names are accepted in snake case (`mr_td`, `rt_mr0`), camel case (`mrTd`,
+/// `rtMr0`) and as named by this crate (`mrtd`, `rtmr0`), and values as hex strings, with an
+/// optional `0x` prefix, or as arrays of bytes.
+///
+/// # Errors
+///
+/// Returns [`MeasurementsError::InvalidJson`] if the input is not JSON,
+/// [`MeasurementsError::NoTd10Report`] if it has no TD10 report,
+/// [`MeasurementsError::InvalidHexValue`] or [`MeasurementsError::InvalidLength`] naming the
+/// register that is missing or malformed, and [`MeasurementsError::InconsistentReport`] like
+/// the conversion from a [`dcap_qvl::verify::VerifiedReport`].
+pub fn from_dcap_qvl_json(json: &str) -> Result<Measurements, MeasurementsError> {
+    let report: Value =
+        serde_json::from_str(json).map_err(|e| MeasurementsError::InvalidJson(e.to_string()))?;
+    let td10 = TD10_POINTERS
+        .iter()
+        .find_map(|pointer| report.pointer(pointer).filter(|td10| td10.is_object()))
+        .ok_or(MeasurementsError::NoTd10Report)?;
+
+    Measurements {
+        mrtd: register(td10, "mrtd", &MRTD_NAMES)?,
+        rtmr0: register(td10, "rtmr0", &RTMR0_NAMES)?,
+        rtmr1: register(td10, "rtmr1", &RTMR1_NAMES)?,
+        rtmr2: register(td10, "rtmr2", &RTMR2_NAMES)?,
+    }
+    .check_consistent()
+}
+
+/// Reads the register `name`, stored under the first of `keys` present in `td10`.
+fn register(
+    td10: &Value,
+    name: &str,
+    keys: &[&str],
+) -> Result<[u8; REGISTER_SIZE], MeasurementsError> {
+    let invalid = |reason: String| MeasurementsError::InvalidHexValue(String::from(name), reason);
+
+    let value = keys
+        .iter()
+        .find_map(|key| td10.get(key))
+        .ok_or_else(|| invalid(String::from("missing")))?;
+    let bytes: Vec<u8> = match value {
+        Value::String(hex) => {
+            let hex = hex.strip_prefix("0x").unwrap_or(hex);
+            hex::decode(hex).map_err(|e| invalid(e.to_string()))?
+        }
+        Value::Array(bytes) => bytes
+            .iter()
+            .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid(String::from("expected an array of bytes")))?,
+        _ => return Err(invalid(String::from("expected a hex string"))),
+    };
+
+    <[u8; REGISTER_SIZE]>::try_from(bytes.as_slice())
+        .map_err(|_| MeasurementsError::InvalidLength(String::from(name), bytes.len()))
+}
@@ -0,0 +1,31 @@
+//! Measurements including RTMR3, for platforms which measure into the fourth runtime register
+//! outside of the dstack event log.
+
+use super::{Measurements, MeasurementsError, REGISTER_SIZE};
+
+use dcap_qvl::quote::Report;
+
+/// [`Measurements`] along with the RTMR3 of the report, if it was extended.
+///
+/// [`Measurements`] keeps to MRTD and RTMR0-2, so that existing baselines are unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExtendedMeasurements {
+    pub measurements: Measurements,
+    /// RTMR3 (Runtime Measurement Register 3), `None` if the report's RTMR3 is all zeros, i.e.
+    /// it was never extended.
+    pub rtmr3: Option<[u8; REGISTER_SIZE]>,
+}
+
+impl TryFrom<&Report> for ExtendedMeasurements {
+    type Error = MeasurementsError;
+
+    fn try_from(report: &Report) -> Result<Self, Self::Error> {
+        let measurements = Measurements::try_from(report)?;
+        let td10 = report.as_td10().ok_or(MeasurementsError::NoTd10Report)?;
+
+        Ok(Self {
+            measurements,
+            rtmr3: (td10.rt_mr3 != [0; REGISTER_SIZE]).then_some(td10.rt_mr3),
+        })
+    }
+}
@@ -0,0 +1,230 @@
+//! Fetches a baseline from an operator-controlled HTTPS endpoint, so that centrally managed
+//! baselines can be updated without redeploying the verifier.
+
+use alloc::{format, string::ToString, vec::Vec};
+use core::time::Duration;
+
+use super::{FullMeasurementsHex, MeasurementsError};
+
+/// Timeout of [`fetch_baseline`], covering the whole request.
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum size of the body fetched by [`fetch_baseline`]. A baseline is well below 1 KiB.
+pub const MAX_BASELINE_SIZE: usize = 64 * 1024;
+
+const HTTPS_PREFIX: &str = "https://";
+
+/// Fetches the JSON baseline at `url` with a GET request and parses it like
+/// [`FullMeasurementsHex`], rejecting duplicate keys.
+///
+/// Only HTTPS URLs are accepted, also across redirects. The request times out after
+/// [`FETCH_TIMEOUT`], and bodies larger than [`MAX_BASELINE_SIZE`] are rejected without being
+/// read in full. The endpoint is trusted to serve the right baseline; use a signed baseline to
+/// not depend on it.
+///
+/// # Errors
+///
+/// Returns [`MeasurementsError::FetchFailed`] if the request fails or the response status is
+/// not a success, [`MeasurementsError::BaselineTooLarge`] if the body is too large, and
+/// [`MeasurementsError::InvalidJson`] if it is not a valid baseline.
+pub async fn fetch_baseline(url: &str) -> Result<FullMeasurementsHex, MeasurementsError> {
+    let failed = |e: reqwest::Error| MeasurementsError::FetchFailed(e.to_string());
+
+    check_url(url)?;
+    let client = reqwest::Client::builder()
+        .https_only(true)
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(failed)?;
+    let mut response = client.get(url).send().await.map_err(failed)?;
+    check_status(response.status().as_u16())?;
+    check_content_length(response.content_length())?;
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(failed)? {
+        append_chunk(&mut body, &chunk)?;
+    }
+    parse_baseline(&body)
+}
+
+/// Rejects URLs that are not HTTPS before any request is made; redirects are restricted to
+/// HTTPS by the client.
+fn check_url(url: &str) -> Result<(), MeasurementsError> {
+    let is_https = url
+        .get(..HTTPS_PREFIX.len())
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case(HTTPS_PREFIX));
+    if !is_https {
+        return Err(MeasurementsError::FetchFailed(format!(
+            "{url} is not an HTTPS URL"
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects responses whose status is not a success.
+fn check_status(status: u16) -> Result<(), MeasurementsError> {
+    if !(200..300).contains(&status) {
+        return Err(MeasurementsError::FetchFailed(format!(
+            "unsuccessful HTTP status {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects responses announcing a body larger than [`MAX_BASELINE_SIZE`] before reading it.
+fn check_content_length(content_length: Option<u64>) -> Result<(), MeasurementsError> {
+    if content_length
+        .is_some_and(|len| usize::try_from(len).map_or(true, |len| len > MAX_BASELINE_SIZE))
+    {
+        return Err(too_large());
+    }
+    Ok(())
+}
+
+/// Appends `chunk` to `body`, unless the body would grow larger than [`MAX_BASELINE_SIZE`].
+fn append_chunk(body: &mut Vec<u8>, chunk: &[u8]) -> Result<(), MeasurementsError> {
+    if body.len().saturating_add(chunk.len()) > MAX_BASELINE_SIZE {
+        return Err(too_large());
+    }
+    body.extend_from_slice(chunk);
+    Ok(())
+}
+
+/// Parses a fetched body like [`FullMeasurementsHex`], rejecting duplicate keys.
+fn parse_baseline(body: &[u8]) -> Result<FullMeasurementsHex, MeasurementsError> {
+    let json =
+        core::str::from_utf8(body).map_err(|e| MeasurementsError::InvalidJson(e.to_string()))?;
+    crate::strict_json::from_str(json).map_err(|e| MeasurementsError::InvalidJson(e.to_string()))
+}
+
+fn too_large() -> MeasurementsError {
+    MeasurementsError::BaselineTooLarge {
+        max: MAX_BASELINE_SIZE,
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+    use assert_matches::assert_matches;
+
+    const BASELINE_JSON: &str = r#"{
+        "rtmrs": {
+            "mrtd": "010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101",
+            "rtmr0": "020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202",
+            "rtmr1": "030303030303030303030303030303030303030303030303030303030303030303030303030303030303030303030303",
+            "rtmr2": "040404040404040404040404040404040404040404040404040404040404040404040404040404040404040404040404"
+        },
+        "key_provider_event_digest": "050505050505050505050505050505050505050505050505050505050505050505050505050505050505050505050505",
+        "app_compose_hash_payload": "0606060606060606060606060606060606060606060606060606060606060606"
+    }"#;
+
+    #[test]
+    fn check_url__should_accept_https_urls() {
+        // Given
+        let urls = ["https://example.com/baseline.json", "HTTPS://example.com"];
+
+        // When / Then
+        for url in urls {
+            assert_matches!(check_url(url), Ok(()));
+        }
+    }
+
+    #[test]
+    fn check_url__should_reject_other_schemes() {
+        // Given
+        let urls = [
+            "http://example.com/baseline.json",
+            "file:///baseline.json",
+            "https",
+            "",
+        ];
+
+        // When / Then
+        for url in urls {
+            assert_matches!(check_url(url), Err(MeasurementsError::FetchFailed(_)));
+        }
+    }
+
+    #[test]
+    fn check_status__should_accept_only_success_statuses() {
+        // When / Then
+        for status in [200, 204, 299] {
+            assert_matches!(check_status(status), Ok(()));
+        }
+        for status in [199, 301, 404, 500] {
+            assert_matches!(
+                check_status(status),
+                Err(MeasurementsError::FetchFailed(reason)) if reason.contains(&status.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn check_content_length__should_reject_announced_oversized_bodies() {
+        // Given
+        let max = u64::try_from(MAX_BASELINE_SIZE).unwrap();
+
+        // When / Then
+        assert_matches!(check_content_length(None), Ok(()));
+        assert_matches!(check_content_length(Some(max)), Ok(()));
+        assert_matches!(
+            check_content_length(Some(max + 1)),
+            Err(MeasurementsError::BaselineTooLarge {
+                max: MAX_BASELINE_SIZE
+            })
+        );
+        assert_matches!(
+            check_content_length(Some(u64::MAX)),
+            Err(MeasurementsError::BaselineTooLarge { .. })
+        );
+    }
+
+    #[test]
+    fn append_chunk__should_stop_at_max_baseline_size() {
+        // Given
+        let mut body = vec![0; MAX_BASELINE_SIZE - 1];
+
+        // When
+        let fits = append_chunk(&mut body, &[1]);
+        let overflows = append_chunk(&mut body, &[2]);
+
+        // Then
+        assert_matches!(fits, Ok(()));
+        assert_matches!(overflows, Err(MeasurementsError::BaselineTooLarge { .. }));
+        assert_eq!(body.len(), MAX_BASELINE_SIZE);
+    }
+
+    #[test]
+    fn parse_baseline__should_parse_full_measurements() {
+        // When
+        let baseline = parse_baseline(BASELINE_JSON.as_bytes()).unwrap();
+
+        // Then
+        assert_eq!(*baseline.rtmrs.rtmr1, [3; 48]);
+        assert_eq!(*baseline.app_compose_hash_payload, [6; 32]);
+    }
+
+    #[test]
+    fn parse_baseline__should_reject_duplicate_keys_and_invalid_bodies() {
+        // Given
+        // A second, otherwise valid, app compose hash ahead of the first.
+        let duplicated = BASELINE_JSON.replacen(
+            r#""rtmrs""#,
+            &format!(
+                r#""app_compose_hash_payload": "{}", "rtmrs""#,
+                "07".repeat(32)
+            ),
+            1,
+        );
+        let bodies: [&[u8]; 4] = [duplicated.as_bytes(), b"{}", b"not json", &[0xff, 0xfe]];
+
+        // When / Then
+        for body in bodies {
+            assert_matches!(parse_baseline(body), Err(MeasurementsError::InvalidJson(_)));
+        }
+    }
+}
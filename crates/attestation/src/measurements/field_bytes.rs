@@ -0,0 +1,92 @@
+//! Serde functions for the fixed-size byte fields of [`super::Measurements`] and
+//! [`super::FullMeasurements`]. They are serialized as bytes, as `serde_with::Bytes` does, and
+//! the deserializers name the field and its expected length when the input has the wrong
+//! number of bytes, e.g. `expected 48 bytes for rtmr0, got 47`.
+
+use alloc::format;
+use core::fmt;
+use serde::{
+    Deserializer, Serializer,
+    de::{self, SeqAccess, Visitor},
+};
+
+pub(super) fn serialize<S: Serializer, const N: usize>(
+    bytes: &[u8; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(bytes)
+}
+
+pub(super) fn mrtd<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 48], D::Error> {
+    deserialize_named(deserializer, "mrtd")
+}
+
+pub(super) fn rtmr0<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 48], D::Error> {
+    deserialize_named(deserializer, "rtmr0")
+}
+
+pub(super) fn rtmr1<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 48], D::Error> {
+    deserialize_named(deserializer, "rtmr1")
+}
+
+pub(super) fn rtmr2<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 48], D::Error> {
+    deserialize_named(deserializer, "rtmr2")
+}
+
+pub(super) fn key_provider_event_digest<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<[u8; 48], D::Error> {
+    deserialize_named(deserializer, "key_provider_event_digest")
+}
+
+pub(super) fn app_compose_hash_payload<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<[u8; 32], D::Error> {
+    deserialize_named(deserializer, "app_compose_hash_payload")
+}
+
+/// Deserializes a byte string or a sequence of bytes, as serialized by `serde_with::Bytes`.
+fn deserialize_named<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+    name: &'static str,
+) -> Result<[u8; N], D::Error> {
+    deserializer.deserialize_bytes(NamedBytesVisitor::<N> { name })
+}
+
+struct NamedBytesVisitor<const N: usize> {
+    name: &'static str,
+}
+
+impl<const N: usize> NamedBytesVisitor<N> {
+    fn wrong_length<E: de::Error>(&self, len: usize) -> E {
+        E::custom(format!("expected {N} bytes for {}, got {len}", self.name))
+    }
+}
+
+impl<'de, const N: usize> Visitor<'de> for NamedBytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{N} bytes for {}", self.name)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        <[u8; N]>::try_from(bytes).map_err(|_| self.wrong_length(bytes.len()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; N];
+        let mut len: usize = 0;
+        while let Some(byte) = seq.next_element::<u8>()? {
+            if let Some(slot) = bytes.get_mut(len) {
+                *slot = byte;
+            }
+            len = len.saturating_add(1);
+        }
+        if len == N {
+            Ok(bytes)
+        } else {
+            Err(self.wrong_length(len))
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! An allowlist of firmware versions, for verifying the firmware registers separately from the
+//! registers measuring the app.
+//!
+//! Cloud firmware updates change the MRTD and RTMR0 of every TD, while RTMR1 and RTMR2, which
+//! measure the kernel and the app image, stay the same. Listing the accepted firmware versions
+//! on their own avoids an allowlist entry for every combination of firmware and app release.
+
+use alloc::{string::String, vec::Vec};
+
+use super::{Measurements, MeasurementsError, RtmrRegister};
+
+/// The MRTD and RTMR0 of a firmware version, under a human-readable label.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FirmwareVersion {
+    pub label: String,
+    pub mrtd: [u8; 48],
+    pub rtmr0: [u8; 48],
+}
+
+/// Accepted [`FirmwareVersion`]s, ordered by label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirmwareAllowlist {
+    versions: Vec<FirmwareVersion>,
+}
+
+impl FirmwareAllowlist {
+    /// Creates an allowlist of `versions`. Of versions with the same label, the last is kept.
+    pub fn new(versions: impl IntoIterator<Item = FirmwareVersion>) -> Self {
+        let mut allowlist = Self::default();
+        for version in versions {
+            allowlist.insert(version);
+        }
+        allowlist
+    }
+
+    /// Adds `version`, returning the version with the same label it replaced, if any.
+    pub fn insert(&mut self, version: FirmwareVersion) -> Option<FirmwareVersion> {
+        match self
+            .versions
+            .binary_search_by(|existing| existing.label.cmp(&version.label))
+        {
+            Ok(index) => self
+                .versions
+                .get_mut(index)
+                .map(|existing| core::mem::replace(existing, version)),
+            Err(index) => {
+                self.versions.insert(index, version);
+                None
+            }
+        }
+    }
+
+    pub fn versions(&self) -> &[FirmwareVersion] {
+        &self.versions
+    }
+
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Returns the first version, in label order, with the MRTD and RTMR0 of `actual`. RTMR1
+    /// and RTMR2 are not compared.
+    pub fn matches(&self, actual: &Measurements) -> Option<&FirmwareVersion> {
+        self.versions
+            .iter()
+            .find(|version| version.mrtd == actual.mrtd && version.rtmr0 == actual.rtmr0)
+    }
+
+    /// Checks that `actual` runs an accepted firmware version, see [`FirmwareAllowlist::matches`],
+    /// and the app measured by `rtmr1` and `rtmr2`. Returns the matching firmware version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::BaselineMismatch`] listing RTMR1 and RTMR2 if either differs
+    /// from the app, and MRTD and RTMR0 if no firmware version matches.
+    pub fn verify(
+        &self,
+        actual: &Measurements,
+        rtmr1: &[u8; 48],
+        rtmr2: &[u8; 48],
+    ) -> Result<&FirmwareVersion, MeasurementsError> {
+        let app_registers: Vec<RtmrRegister> = [
+            (RtmrRegister::Rtmr1, &actual.rtmr1, rtmr1),
+            (RtmrRegister::Rtmr2, &actual.rtmr2, rtmr2),
+        ]
+        .into_iter()
+        .filter(|(_, actual, expected)| actual != expected)
+        .map(|(register, _, _)| register)
+        .collect();
+        if !app_registers.is_empty() {
+            return Err(MeasurementsError::BaselineMismatch {
+                registers: app_registers,
+            });
+        }
+
+        self.matches(actual)
+            .ok_or_else(|| MeasurementsError::BaselineMismatch {
+                registers: alloc::vec![RtmrRegister::Mrtd, RtmrRegister::Rtmr0],
+            })
+    }
+}
@@ -0,0 +1,97 @@
+//! An allowlist whose entries carry human-readable labels, e.g. release versions, so that a
+//! failed match can name the closest accepted entry.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use dcap_qvl::verify::VerifiedReport;
+
+use super::{FullMeasurements, Measurements, MeasurementsDiff, MeasurementsError};
+
+/// Accepted [`FullMeasurements`] by label, ordered by label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabeledAllowlist {
+    entries: BTreeMap<String, FullMeasurements>,
+}
+
+/// Why a report matched no entry of a [`LabeledAllowlist`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LabeledMismatch {
+    #[error("invalid report: {0}")]
+    InvalidReport(#[from] MeasurementsError),
+    #[error("the allowlist is empty")]
+    EmptyAllowlist,
+    #[error(
+        "no baseline matched, closest was '{closest}', which differs in {:?}",
+        .diff.changed_registers().collect::<Vec<_>>()
+    )]
+    NoMatch {
+        /// Label of the entry with the highest [`Measurements::similarity`] to the report.
+        closest: String,
+        /// Changes from the closest entry to the report.
+        diff: MeasurementsDiff,
+    },
+}
+
+impl LabeledAllowlist {
+    pub fn new(entries: impl IntoIterator<Item = (String, FullMeasurements)>) -> Self {
+        Self {
+            entries: entries.into_iter().collect(),
+        }
+    }
+
+    /// Adds `measurements` under `label`, returning the entry it replaced, if any.
+    pub fn insert(
+        &mut self,
+        label: String,
+        measurements: FullMeasurements,
+    ) -> Option<FullMeasurements> {
+        self.entries.insert(label, measurements)
+    }
+
+    pub fn get(&self, label: &str) -> Option<&FullMeasurements> {
+        self.entries.get(label)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the label of the first entry, in label order, whose MRTD and RTMR0-2 equal those
+    /// of `report`.
+    ///
+    /// Like [`crate::baseline::SignedBaseline::check_report`], only the registers are compared;
+    /// the key-provider and app compose digests are checked against the event log by
+    /// [`crate::attestation::DstackAttestation::verify`] or [`FullMeasurements::verify_report`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LabeledMismatch::NoMatch`] with the most similar entry, by
+    /// [`Measurements::similarity`] with ties going to the first label, if no entry matches.
+    pub fn verify(&self, report: &VerifiedReport) -> Result<&str, LabeledMismatch> {
+        let live = Measurements::try_from(report)?;
+        self.verify_measurements(&live)
+    }
+
+    /// Like [`LabeledAllowlist::verify`], for measurements that were already read from a report.
+    pub fn verify_measurements(&self, live: &Measurements) -> Result<&str, LabeledMismatch> {
+        let mut closest: Option<(&String, &FullMeasurements, f32)> = None;
+        for (label, entry) in &self.entries {
+            if entry.rtmrs == *live {
+                return Ok(label);
+            }
+            let similarity = entry.rtmrs.similarity(live);
+            if closest.is_none_or(|(_, _, best)| similarity > best) {
+                closest = Some((label, entry, similarity));
+            }
+        }
+
+        let (label, entry, _) = closest.ok_or(LabeledMismatch::EmptyAllowlist)?;
+        Err(LabeledMismatch::NoMatch {
+            closest: label.clone(),
+            diff: entry.rtmrs.diff(live),
+        })
+    }
+}
@@ -0,0 +1,117 @@
+//! Measurements compared only on selected bits of each register, for platforms where part of
+//! a register is expected to vary.
+
+use core::ops::Range;
+
+use super::{Measurements, MeasurementsError, REGISTER_SIZE, RtmrRegister};
+
+/// Expected [`Measurements`] along with a bit mask per register: only the bits set in the mask
+/// are compared, in the order of [`RtmrRegister::ALL`].
+///
+/// **Masking weakens attestation.** A measurement register is a hash: flipping any input bit
+/// changes it unpredictably, so a partially matching register is no evidence that any part of
+/// the measured software is the expected one. Masking must only be applied to registers whose
+/// bytes are not a hash of what they measure, or to select which registers are compared at all.
+/// A register whose mask is all zeros accepts any value, and an all zero mask accepts any
+/// report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaskedMeasurements {
+    pub expected: Measurements,
+    pub mask: [[u8; REGISTER_SIZE]; 4],
+}
+
+impl MaskedMeasurements {
+    /// Compares every bit of every register of `expected`, like equality.
+    pub fn exact(expected: Measurements) -> Self {
+        Self {
+            expected,
+            mask: [[0xff; REGISTER_SIZE]; 4],
+        }
+    }
+
+    /// Returns the mask of `register`.
+    pub fn register_mask(&self, register: RtmrRegister) -> &[u8; REGISTER_SIZE] {
+        match register {
+            RtmrRegister::Mrtd => &self.mask[0],
+            RtmrRegister::Rtmr0 => &self.mask[1],
+            RtmrRegister::Rtmr1 => &self.mask[2],
+            RtmrRegister::Rtmr2 => &self.mask[3],
+        }
+    }
+
+    /// Returns the mask of `register`, to edit it.
+    pub fn register_mask_mut(&mut self, register: RtmrRegister) -> &mut [u8; REGISTER_SIZE] {
+        match register {
+            RtmrRegister::Mrtd => &mut self.mask[0],
+            RtmrRegister::Rtmr0 => &mut self.mask[1],
+            RtmrRegister::Rtmr1 => &mut self.mask[2],
+            RtmrRegister::Rtmr2 => &mut self.mask[3],
+        }
+    }
+
+    /// Whether `actual` equals the expected measurements on every masked-in bit.
+    pub fn matches(&self, actual: &Measurements) -> bool {
+        RtmrRegister::ALL.into_iter().all(|register| {
+            self.expected
+                .register(register)
+                .iter()
+                .zip(actual.register(register))
+                .zip(self.register_mask(register))
+                .all(|((expected, actual), mask)| (expected ^ actual) & mask == 0)
+        })
+    }
+}
+
+/// A byte range of RTMR0 which is ignored when comparing measurements, for cloud firmware
+/// variants documented to differ from the expected RTMR0 only within it. Finer grained than
+/// masking out the whole register with [`MaskedMeasurements`].
+///
+/// **The tolerance weakens the RTMR0 check.** RTMR0 is a hash of the firmware configuration,
+/// so ignoring `n` of its bytes makes any configuration whose RTMR0 agrees on the other
+/// `48 - n` bytes acceptable: the security of the check drops to a `8 * (48 - n)` bit
+/// truncated hash. Keep the range as small as the documented difference, and prefer listing
+/// each firmware variant's measurements when they are known.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rtmr0Tolerance {
+    ignore_byte_range: Range<usize>,
+}
+
+impl Rtmr0Tolerance {
+    /// Ignores the bytes of RTMR0 in `ignore_byte_range`. An empty range compares RTMR0 exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::InvalidToleranceRange`] if the range is reversed or not
+    /// within `0..48`.
+    pub fn new(ignore_byte_range: Range<usize>) -> Result<Self, MeasurementsError> {
+        if ignore_byte_range.start > ignore_byte_range.end || ignore_byte_range.end > REGISTER_SIZE
+        {
+            return Err(MeasurementsError::InvalidToleranceRange {
+                start: ignore_byte_range.start,
+                end: ignore_byte_range.end,
+            });
+        }
+        Ok(Self { ignore_byte_range })
+    }
+
+    pub fn ignore_byte_range(&self) -> &Range<usize> {
+        &self.ignore_byte_range
+    }
+
+    /// Masks out the ignored bytes of RTMR0 from `masked`, keeping its other masks.
+    pub fn apply(&self, mut masked: MaskedMeasurements) -> MaskedMeasurements {
+        if let Some(ignored) = masked
+            .register_mask_mut(RtmrRegister::Rtmr0)
+            .get_mut(self.ignore_byte_range.clone())
+        {
+            ignored.fill(0);
+        }
+        masked
+    }
+
+    /// Whether `actual` equals `expected`, except within the ignored bytes of RTMR0.
+    pub fn matches(&self, expected: &Measurements, actual: &Measurements) -> bool {
+        self.apply(MaskedMeasurements::exact(*expected))
+            .matches(actual)
+    }
+}
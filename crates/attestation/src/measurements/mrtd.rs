@@ -0,0 +1,250 @@
+//! Predicts the MRTD of a TD from its TDVF firmware image, e.g. an OVMF build, so that
+//! operators building their own firmware can compute the MRTD to expect.
+//!
+//! The TDX module extends the MRTD as the VMM adds the initial pages of the TD, see
+//! `TDH.MEM.PAGE.ADD` and `TDH.MR.EXTEND` in the Intel TDX Module Base Architecture
+//! Specification. The pages are those of the sections listed in the TDVF metadata of the
+//! firmware, see the Intel TDX Virtual Firmware Design Guide.
+//!
+//! The tests run against synthetic TDVF images; the check against a published OVMF build
+//! needs the image, which is not vendored, and is ignored by default.
+
+use alloc::{format, string::String};
+use sha2::{Digest as _, Sha384};
+
+use super::{MeasurementsError, REGISTER_SIZE};
+
+/// Size of a TD page.
+const PAGE_SIZE: u64 = 0x1000;
+/// Bytes of a page measured by one `TDH.MR.EXTEND`.
+const MR_EXTEND_CHUNK_SIZE: usize = 0x100;
+/// `TDH.MR.EXTEND` calls measuring a page.
+const MR_EXTENDS_PER_PAGE: u64 = 16;
+/// [`MR_EXTEND_CHUNK_SIZE`], as a GPA offset.
+const MR_EXTEND_STRIDE: u64 = PAGE_SIZE / MR_EXTENDS_PER_PAGE;
+/// Size of the buffer hashed into the MRTD for each operation, before any page data.
+const MEASUREMENT_BUFFER_SIZE: usize = 128;
+
+/// Offset from the end of the firmware of the end of the OVMF GUIDed structure table, before
+/// the reset vector.
+const OVMF_TABLE_END_FROM_END: usize = 0x20;
+/// GUID `96b582de-1fb2-45f7-baea-a366c55a082d` of the footer of the OVMF table, in its byte
+/// order in memory.
+const OVMF_TABLE_FOOTER_GUID: [u8; 16] = [
+    0xde, 0x82, 0xb5, 0x96, 0xb2, 0x1f, 0xf7, 0x45, 0xba, 0xea, 0xa3, 0x66, 0xc5, 0x5a, 0x08, 0x2d,
+];
+/// GUID `e47a6535-984a-4798-865e-4685a7bf8ec2` of the OVMF table entry holding the offset of
+/// the TDVF metadata from the end of the firmware.
+const TDX_METADATA_OFFSET_GUID: [u8; 16] = [
+    0x35, 0x65, 0x7a, 0xe4, 0x4a, 0x98, 0x98, 0x47, 0x86, 0x5e, 0x46, 0x85, 0xa7, 0xbf, 0x8e, 0xc2,
+];
+/// Size of the GUID and length closing every entry of the OVMF table.
+const OVMF_ENTRY_TRAILER_SIZE: usize = 16 + 2;
+
+const TDVF_SIGNATURE: &[u8; 4] = b"TDVF";
+const TDVF_DESCRIPTOR_SIZE: usize = 16;
+const TDVF_SECTION_SIZE: usize = 32;
+/// The section is measured with `TDH.MR.EXTEND` as well as added.
+const TDVF_ATTRIBUTE_MR_EXTEND: u32 = 1 << 0;
+/// The section is accepted by the TD later with `TDH.MEM.PAGE.AUG`, and not measured.
+const TDVF_ATTRIBUTE_PAGE_AUG: u32 = 1 << 1;
+
+/// A section of the TDVF metadata.
+struct TdvfSection {
+    data_offset: u64,
+    raw_data_size: u64,
+    memory_address: u64,
+    memory_data_size: u64,
+    attributes: u32,
+}
+
+/// Computes the MRTD of a TD booted from the TDVF `firmware_image`, as the TDX module does
+/// when the VMM adds the firmware sections.
+///
+/// Every page of every section is added with `TDH.MEM.PAGE.ADD`, except for sections with
+/// the `PAGE.AUG` attribute, and each page of a section with the `MR.EXTEND` attribute is
+/// measured with `TDH.MR.EXTEND` right after it is added, from the section's data in the
+/// image, zero padded past its raw data. This is the order in which KVM adds the pages; a VMM
+/// which adds all pages before measuring any produces another MRTD.
+///
+/// # Errors
+///
+/// Returns [`MeasurementsError::InvalidFirmware`] if the image has no OVMF table or TDVF
+/// metadata, or a section is not page aligned or lies outside the image.
+pub fn mrtd_from_firmware(firmware_image: &[u8]) -> Result<[u8; REGISTER_SIZE], MeasurementsError> {
+    let metadata = tdvf_metadata(firmware_image)?;
+    let count = le_u32(metadata, 12).ok_or_else(|| invalid("truncated TDVF descriptor"))?;
+
+    let mut mrtd = Sha384::new();
+    for index in 0..count {
+        let offset = usize::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_mul(TDVF_SECTION_SIZE))
+            .and_then(|offset| offset.checked_add(TDVF_DESCRIPTOR_SIZE))
+            .ok_or_else(|| invalid("too many TDVF sections"))?;
+        let section = parse_section(metadata, offset)
+            .ok_or_else(|| invalid(format!("truncated TDVF section {index}")))?;
+        measure_section(&mut mrtd, firmware_image, &section)
+            .map_err(|reason| invalid(format!("TDVF section {index}: {reason}")))?;
+    }
+    Ok(mrtd.finalize().into())
+}
+
+fn measure_section(
+    mrtd: &mut Sha384,
+    firmware_image: &[u8],
+    section: &TdvfSection,
+) -> Result<(), String> {
+    if section.attributes & TDVF_ATTRIBUTE_PAGE_AUG != 0 {
+        return Ok(());
+    }
+    if section.memory_address % PAGE_SIZE != 0 || section.memory_data_size % PAGE_SIZE != 0 {
+        return Err(String::from("not page aligned"));
+    }
+    let data = usize::try_from(section.data_offset)
+        .ok()
+        .zip(usize::try_from(section.raw_data_size).ok())
+        .and_then(|(start, size)| firmware_image.get(start..start.checked_add(size)?))
+        .ok_or_else(|| String::from("raw data outside the image"))?;
+    let end = section
+        .memory_address
+        .checked_add(section.memory_data_size)
+        .ok_or_else(|| String::from("memory range overflows"))?;
+
+    let mut page = section.memory_address;
+    while page < end {
+        mrtd.update(measurement_buffer(b"MEM.PAGE.ADD", page));
+        if section.attributes & TDVF_ATTRIBUTE_MR_EXTEND != 0 {
+            for index in 0..MR_EXTENDS_PER_PAGE {
+                let chunk = page.saturating_add(index.saturating_mul(MR_EXTEND_STRIDE));
+                mrtd.update(measurement_buffer(b"MR.EXTEND", chunk));
+                mrtd.update(chunk_data(
+                    data,
+                    chunk.saturating_sub(section.memory_address),
+                ));
+            }
+        }
+        page = page.saturating_add(PAGE_SIZE);
+    }
+    Ok(())
+}
+
+/// The buffer hashed for an operation on the page or chunk at `gpa`: the operation name, zero
+/// padded to 16 bytes, the little endian GPA and zeros.
+fn measurement_buffer(operation: &[u8], gpa: u64) -> [u8; MEASUREMENT_BUFFER_SIZE] {
+    let mut buffer = [0; MEASUREMENT_BUFFER_SIZE];
+    buffer
+        .iter_mut()
+        .zip(
+            operation
+                .iter()
+                .chain(&[0; 16])
+                .take(16)
+                .chain(&gpa.to_le_bytes()),
+        )
+        .for_each(|(byte, value)| *byte = *value);
+    buffer
+}
+
+/// The chunk of `data` at `offset`, zero padded past the end of `data`.
+fn chunk_data(data: &[u8], offset: u64) -> [u8; MR_EXTEND_CHUNK_SIZE] {
+    let mut chunk = [0; MR_EXTEND_CHUNK_SIZE];
+    if let Some(rest) = usize::try_from(offset)
+        .ok()
+        .and_then(|offset| data.get(offset..))
+    {
+        chunk
+            .iter_mut()
+            .zip(rest)
+            .for_each(|(byte, value)| *byte = *value);
+    }
+    chunk
+}
+
+/// The TDVF metadata of the image, starting with its descriptor.
+fn tdvf_metadata(firmware_image: &[u8]) -> Result<&[u8], MeasurementsError> {
+    let offset_from_end = ovmf_table_entry(firmware_image, &TDX_METADATA_OFFSET_GUID)
+        .and_then(|data| le_u32(data, 0))
+        .ok_or_else(|| invalid("no TDX metadata entry in the OVMF table"))?;
+    let metadata = usize::try_from(offset_from_end)
+        .ok()
+        .and_then(|offset_from_end| firmware_image.len().checked_sub(offset_from_end))
+        .and_then(|offset| firmware_image.get(offset..))
+        .ok_or_else(|| invalid("TDVF metadata outside the image"))?;
+    (metadata.get(..4) == Some(TDVF_SIGNATURE.as_slice()))
+        .then_some(metadata)
+        .ok_or_else(|| invalid("no TDVF signature at the TDVF metadata offset"))
+}
+
+/// The data of the entry of the OVMF GUIDed structure table with `guid`.
+///
+/// The table ends `0x20` bytes before the end of the image with a footer entry whose length
+/// covers the table. Every entry is its data followed by its 16 bit length, which includes
+/// the length and GUID, and its GUID, so the table is walked from its end.
+fn ovmf_table_entry<'a>(firmware_image: &'a [u8], guid: &[u8; 16]) -> Option<&'a [u8]> {
+    let table_end = firmware_image.len().checked_sub(OVMF_TABLE_END_FROM_END)?;
+    let head = firmware_image.get(..table_end)?;
+    let (footer, footer_data_len) = split_entry_trailer(head)?;
+    if footer.guid != OVMF_TABLE_FOOTER_GUID {
+        return None;
+    }
+    let mut table = footer
+        .rest
+        .get(footer.rest.len().checked_sub(footer_data_len)?..)?;
+
+    while let Some((entry, data_len)) = split_entry_trailer(table) {
+        let data_start = entry.rest.len().checked_sub(data_len)?;
+        if entry.guid == *guid {
+            return entry.rest.get(data_start..);
+        }
+        table = entry.rest.get(..data_start)?;
+    }
+    None
+}
+
+/// The trailer of the last entry of `bytes`.
+struct EntryTrailer<'a> {
+    guid: [u8; 16],
+    /// The bytes before the trailer, ending with the data of the entry.
+    rest: &'a [u8],
+}
+
+/// Splits the trailer of the entry ending `bytes`, returning the length of its data.
+fn split_entry_trailer(bytes: &[u8]) -> Option<(EntryTrailer<'_>, usize)> {
+    let trailer_start = bytes.len().checked_sub(OVMF_ENTRY_TRAILER_SIZE)?;
+    let (rest, trailer) = bytes.split_at_checked(trailer_start)?;
+    let (length, guid) = trailer.split_at_checked(2)?;
+    let length = usize::from(u16::from_le_bytes(length.try_into().ok()?));
+    let data_len = length.checked_sub(OVMF_ENTRY_TRAILER_SIZE)?;
+    Some((
+        EntryTrailer {
+            guid: guid.try_into().ok()?,
+            rest,
+        },
+        data_len,
+    ))
+}
+
+fn parse_section(metadata: &[u8], offset: usize) -> Option<TdvfSection> {
+    Some(TdvfSection {
+        data_offset: u64::from(le_u32(metadata, offset)?),
+        raw_data_size: u64::from(le_u32(metadata, offset.checked_add(4)?)?),
+        memory_address: le_u64(metadata, offset.checked_add(8)?)?,
+        memory_data_size: le_u64(metadata, offset.checked_add(16)?)?,
+        attributes: le_u32(metadata, offset.checked_add(28)?)?,
+    })
+}
+
+fn le_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn le_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    let bytes = bytes.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn invalid(reason: impl Into<String>) -> MeasurementsError {
+    MeasurementsError::InvalidFirmware(reason.into())
+}
@@ -0,0 +1,87 @@
+//! Release manifests produced by CI, binding the measurements of a release to its version.
+
+use alloc::{string::String, vec::Vec};
+use borsh::{BorshDeserialize, BorshSerialize};
+use dcap_qvl::verify::VerifiedReport;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_with::{hex::Hex, serde_as};
+
+use super::{FullMeasurements, Measurements, MeasurementsError};
+
+/// Domain separator of the payload signed for a [`ReleaseManifest`].
+const SIGNING_DOMAIN: &[u8] = b"mpc-attestation:release-manifest:v1";
+
+/// The measurements of a release, signed with ed25519 by the CI release key.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ReleaseManifest {
+    /// The release version, e.g. `3.1.0`.
+    pub version: String,
+    pub measurements: FullMeasurements,
+    /// Ed25519 signature over [`ReleaseManifest::signing_payload`], hex encoded in JSON.
+    #[serde_as(as = "Hex")]
+    pub signature: Vec<u8>,
+}
+
+impl ReleaseManifest {
+    /// The payload covered by the signature:
+    /// `domain || version length (4 bytes little endian) || version || measurements fingerprint`,
+    /// where the fingerprint is [`FullMeasurements::fingerprint`]. The length prefix keeps the
+    /// version from running into the fingerprint.
+    pub fn signing_payload(version: &str, measurements: &FullMeasurements) -> Vec<u8> {
+        let version_len = u32::try_from(version.len()).unwrap_or(u32::MAX);
+        [
+            SIGNING_DOMAIN,
+            &version_len.to_le_bytes(),
+            version.as_bytes(),
+            &measurements.fingerprint(),
+        ]
+        .concat()
+    }
+
+    /// Checks that `signature` is a valid ed25519 signature by `signing_key` over
+    /// [`ReleaseManifest::signing_payload`].
+    pub fn verify_signature(&self, signing_key: &[u8; 32]) -> bool {
+        let Ok(signing_key) = VerifyingKey::from_bytes(signing_key) else {
+            return false;
+        };
+        let Ok(signature) = Signature::from_slice(&self.signature) else {
+            return false;
+        };
+        let payload = Self::signing_payload(&self.version, &self.measurements);
+
+        signing_key.verify_strict(&payload, &signature).is_ok()
+    }
+
+    /// Checks the signature of the manifest by the trusted CI key `signing_key`, and that the
+    /// MRTD and RTMR0-2 of `report` match it.
+    ///
+    /// As for [`crate::baseline::SignedBaseline::check_report`], the key-provider and app
+    /// compose digests are not part of the report; they are checked against the event log by
+    /// [`crate::attestation::DstackAttestation::verify`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::InvalidBaselineSignature`] if the signature is invalid and
+    /// [`MeasurementsError::BaselineMismatch`] if the registers differ.
+    pub fn verify_report(
+        &self,
+        report: &VerifiedReport,
+        signing_key: &[u8; 32],
+    ) -> Result<(), MeasurementsError> {
+        if !self.verify_signature(signing_key) {
+            return Err(MeasurementsError::InvalidBaselineSignature);
+        }
+
+        let live = Measurements::try_from(report)?;
+        let diff = self.measurements.rtmrs.diff(&live);
+        if diff.is_empty() {
+            Ok(())
+        } else {
+            Err(MeasurementsError::BaselineMismatch {
+                registers: diff.changed_registers().collect(),
+            })
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Reproduces measurement registers from the components they measure, so that operators can
+//! check what a node booted from its report alone.
+
+use alloc::vec::Vec;
+use sha2::{Digest as _, Sha384};
+
+use super::{REGISTER_SIZE, extend_register};
+
+/// Reproduces RTMR1 from the boot components of the TD, by extending an all zero register with,
+/// in order:
+///
+/// 1. `kernel_hash`, the SHA-384 Authenticode hash of the kernel image,
+/// 2. the SHA-384 hash of `cmdline`, encoded like EFI load options, i.e. as UTF-16LE followed
+///    by a two byte NUL terminator,
+/// 3. `initrd_hash`, the SHA-384 hash of the initrd.
+///
+/// Each step is `rtmr = sha384(rtmr || digest)`, see [`extend_register`]. Firmware which
+/// records additional events in RTMR1, e.g. separators, produces a different value; such
+/// registers should be checked by replaying the event log instead.
+pub fn rtmr1_from_boot(
+    kernel_hash: [u8; REGISTER_SIZE],
+    cmdline: &str,
+    initrd_hash: [u8; REGISTER_SIZE],
+) -> [u8; REGISTER_SIZE] {
+    let mut encoded_cmdline: Vec<u8> = cmdline.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    encoded_cmdline.extend_from_slice(&[0, 0]);
+    let cmdline_hash: [u8; REGISTER_SIZE] = Sha384::digest(&encoded_cmdline).into();
+
+    [kernel_hash, cmdline_hash, initrd_hash]
+        .iter()
+        .fold([0; REGISTER_SIZE], |rtmr, digest| {
+            extend_register(&rtmr, digest)
+        })
+}
+
+/// Maximum number of register extensions performed by [`find_event_sequence`], bounding the
+/// search to well under a second regardless of its inputs.
+pub const MAX_SEARCH_EXTENSIONS: usize = 1 << 20;
+
+/// Searches for a sequence of at most `max_len` digests of `candidates`, each usable any
+/// number of times, whose replay from an all zero register produces `target`, and returns
+/// their indices in `candidates`.
+///
+/// This is a debugging aid, to identify the boot events behind an unexpected register. The
+/// shortest sequence is returned, and among those the first in index order; the empty
+/// sequence if `target` is all zeros. The search is exhaustive, so it grows as
+/// `candidates.len()` to the power of `max_len`: it gives up, returning `None`, after
+/// [`MAX_SEARCH_EXTENSIONS`] extensions.
+pub fn find_event_sequence(
+    target: [u8; REGISTER_SIZE],
+    candidates: &[[u8; REGISTER_SIZE]],
+    max_len: usize,
+) -> Option<Vec<usize>> {
+    if target == [0; REGISTER_SIZE] {
+        return Some(Vec::new());
+    }
+
+    let mut extensions = 0usize;
+    // Iterative deepening, so that shorter sequences are found first.
+    for len in 1..=max_len.min(MAX_SEARCH_EXTENSIONS) {
+        // `path[i]` is the candidate at position `i`, and `registers[i + 1]` the register after
+        // replaying `path[..=i]`.
+        let mut path: Vec<usize> = Vec::with_capacity(len);
+        let mut registers = Vec::with_capacity(len.saturating_add(1));
+        registers.push([0; REGISTER_SIZE]);
+        let mut next = 0;
+        loop {
+            let register = registers.last()?;
+            if path.len() < len && next < candidates.len() {
+                extensions = extensions.saturating_add(1);
+                if extensions > MAX_SEARCH_EXTENSIONS {
+                    return None;
+                }
+                let extended = extend_register(register, candidates.get(next)?);
+                path.push(next);
+                registers.push(extended);
+                if path.len() == len && extended == target {
+                    return Some(path);
+                }
+                next = 0;
+            } else {
+                let Some(last) = path.pop() else {
+                    break;
+                };
+                registers.pop();
+                next = last.saturating_add(1);
+            }
+        }
+    }
+    None
+}
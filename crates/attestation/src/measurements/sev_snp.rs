@@ -0,0 +1,86 @@
+//! Reads the measurement of an AMD SEV-SNP attestation report, so that fleets mixing TDX and
+//! SEV-SNP nodes can handle both kinds of measurements side by side.
+//!
+//! Only the fields are extracted: the report signature and its VCEK certificate chain are not
+//! verified here, which must be done separately before the measurement is trusted.
+
+use super::MeasurementsError;
+
+/// Size of an SEV-SNP attestation report, including its signature, per the `ATTESTATION_REPORT`
+/// structure of the SEV Secure Nested Paging Firmware ABI Specification.
+pub const SEV_SNP_REPORT_SIZE: usize = 0x4A0;
+
+/// The oldest report version this parser reads. Versions 2 and up share the layout of the
+/// fields below.
+pub const MIN_SEV_SNP_REPORT_VERSION: u32 = 2;
+
+const VERSION_OFFSET: usize = 0x00;
+const GUEST_SVN_OFFSET: usize = 0x04;
+const POLICY_OFFSET: usize = 0x08;
+const VMPL_OFFSET: usize = 0x30;
+const REPORT_DATA_OFFSET: usize = 0x50;
+const MEASUREMENT_OFFSET: usize = 0x90;
+const HOST_DATA_OFFSET: usize = 0xC0;
+const ID_KEY_DIGEST_OFFSET: usize = 0xE0;
+const REPORTED_TCB_OFFSET: usize = 0x180;
+const CHIP_ID_OFFSET: usize = 0x1A0;
+
+/// The identifying fields of an SEV-SNP attestation report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SevSnpMeasurements {
+    pub version: u32,
+    pub guest_svn: u32,
+    /// The guest policy, e.g. whether debugging is allowed.
+    pub policy: u64,
+    /// The VM privilege level the report was requested from.
+    pub vmpl: u32,
+    /// The launch digest of the guest, the counterpart of the TDX MRTD.
+    pub measurement: [u8; 48],
+    pub report_data: [u8; 64],
+    /// Data provided by the hypervisor at launch.
+    pub host_data: [u8; 32],
+    /// SHA-384 digest of the ID key that signed the ID block, or zeros if there is none.
+    pub id_key_digest: [u8; 48],
+    pub reported_tcb: u64,
+    pub chip_id: [u8; 64],
+}
+
+/// Parses the fields of a raw SEV-SNP attestation report, as returned by the `SNP_GET_REPORT`
+/// guest request, e.g. through `/dev/sev-guest`.
+///
+/// # Errors
+///
+/// Returns [`MeasurementsError::WrongSize`] if `raw` is not [`SEV_SNP_REPORT_SIZE`] bytes, and
+/// [`MeasurementsError::UnsupportedSevSnpVersion`] if the report is older than
+/// [`MIN_SEV_SNP_REPORT_VERSION`].
+pub fn from_sev_snp_report(raw: &[u8]) -> Result<SevSnpMeasurements, MeasurementsError> {
+    let wrong_size = || MeasurementsError::WrongSize {
+        expected: SEV_SNP_REPORT_SIZE,
+        found: raw.len(),
+    };
+    if raw.len() != SEV_SNP_REPORT_SIZE {
+        return Err(wrong_size());
+    }
+    let version = u32::from_le_bytes(bytes(raw, VERSION_OFFSET).ok_or_else(wrong_size)?);
+    if version < MIN_SEV_SNP_REPORT_VERSION {
+        return Err(MeasurementsError::UnsupportedSevSnpVersion(version));
+    }
+
+    Ok(SevSnpMeasurements {
+        version,
+        guest_svn: u32::from_le_bytes(bytes(raw, GUEST_SVN_OFFSET).ok_or_else(wrong_size)?),
+        policy: u64::from_le_bytes(bytes(raw, POLICY_OFFSET).ok_or_else(wrong_size)?),
+        vmpl: u32::from_le_bytes(bytes(raw, VMPL_OFFSET).ok_or_else(wrong_size)?),
+        measurement: bytes(raw, MEASUREMENT_OFFSET).ok_or_else(wrong_size)?,
+        report_data: bytes(raw, REPORT_DATA_OFFSET).ok_or_else(wrong_size)?,
+        host_data: bytes(raw, HOST_DATA_OFFSET).ok_or_else(wrong_size)?,
+        id_key_digest: bytes(raw, ID_KEY_DIGEST_OFFSET).ok_or_else(wrong_size)?,
+        reported_tcb: u64::from_le_bytes(bytes(raw, REPORTED_TCB_OFFSET).ok_or_else(wrong_size)?),
+        chip_id: bytes(raw, CHIP_ID_OFFSET).ok_or_else(wrong_size)?,
+    })
+}
+
+/// The `N` bytes of `raw` at `offset`, if it is long enough.
+fn bytes<const N: usize>(raw: &[u8], offset: usize) -> Option<[u8; N]> {
+    raw.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
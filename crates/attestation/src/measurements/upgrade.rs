@@ -0,0 +1,66 @@
+//! The rolling-upgrade policy: a node may run the current baseline, or the previous one until
+//! a deadline.
+
+use dcap_qvl::verify::VerifiedReport;
+
+use super::{FullMeasurements, Measurements, MeasurementsError};
+
+/// The baselines accepted during an upgrade: [`UpgradeWindow::current`] at any time, and
+/// [`UpgradeWindow::previous`], if set, strictly before [`UpgradeWindow::deadline`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeWindow {
+    pub current: FullMeasurements,
+    pub previous: Option<FullMeasurements>,
+    /// Unix timestamp, in seconds, from which [`UpgradeWindow::previous`] is no longer
+    /// accepted.
+    pub deadline: u64,
+}
+
+/// The baseline of an [`UpgradeWindow`] that a report matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WhichBaseline {
+    Current,
+    Previous,
+}
+
+impl UpgradeWindow {
+    /// Returns which baseline the MRTD and RTMR0-2 of `report` equal at `now`, preferring
+    /// [`WhichBaseline::Current`].
+    ///
+    /// Like [`crate::baseline::SignedBaseline::check_report`], only the registers are compared;
+    /// the key-provider and app compose digests are checked against the event log by
+    /// [`crate::attestation::DstackAttestation::verify`] or [`FullMeasurements::verify_report`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::UpgradeDeadlinePassed`] if `report` matches only the
+    /// previous baseline and `now` is at or after the deadline, and
+    /// [`MeasurementsError::BaselineMismatch`], with the registers that differ from the current
+    /// baseline, if it matches neither.
+    pub fn accept(
+        &self,
+        report: &VerifiedReport,
+        now: u64,
+    ) -> Result<WhichBaseline, MeasurementsError> {
+        let live = Measurements::try_from(report)?;
+        let diff = self.current.rtmrs.diff(&live);
+        if diff.is_empty() {
+            return Ok(WhichBaseline::Current);
+        }
+
+        match &self.previous {
+            Some(previous) if previous.rtmrs == live => {
+                if now < self.deadline {
+                    Ok(WhichBaseline::Previous)
+                } else {
+                    Err(MeasurementsError::UpgradeDeadlinePassed {
+                        deadline: self.deadline,
+                    })
+                }
+            }
+            _ => Err(MeasurementsError::BaselineMismatch {
+                registers: diff.changed_registers().collect(),
+            }),
+        }
+    }
+}
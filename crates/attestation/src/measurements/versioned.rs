@@ -0,0 +1,125 @@
+//! An allowlist whose entries are release versions, so that operators accept a range of
+//! releases with a semantic version requirement, e.g. `>=1.2, <2.0`, rather than listing
+//! measurements.
+
+use alloc::{string::ToString, vec::Vec};
+use dcap_qvl::verify::VerifiedReport;
+use semver::{Version, VersionReq};
+
+use super::{FullMeasurements, Measurements, MeasurementsError};
+
+/// [`FullMeasurements`] by release version, ordered by version.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionedAllowlist {
+    entries: Vec<(Version, FullMeasurements)>,
+}
+
+impl VersionedAllowlist {
+    /// Creates an allowlist of `entries`. Of entries with the same version, the last is kept.
+    pub fn new(entries: impl IntoIterator<Item = (Version, FullMeasurements)>) -> Self {
+        let mut allowlist = Self::default();
+        for (version, measurements) in entries {
+            allowlist.insert(version, measurements);
+        }
+        allowlist
+    }
+
+    /// Creates an allowlist from a registry of measurements to version strings, e.g. as
+    /// published alongside releases.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::InvalidVersion`] if a version is not a semantic version.
+    pub fn from_registry<'a>(
+        registry: impl IntoIterator<Item = (FullMeasurements, &'a str)>,
+    ) -> Result<Self, MeasurementsError> {
+        let entries = registry
+            .into_iter()
+            .map(|(measurements, version)| {
+                Version::parse(version)
+                    .map(|version| (version, measurements))
+                    .map_err(|e| MeasurementsError::InvalidVersion(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(entries))
+    }
+
+    /// Adds `measurements` as `version`, returning the measurements it replaced, if any.
+    pub fn insert(
+        &mut self,
+        version: Version,
+        measurements: FullMeasurements,
+    ) -> Option<FullMeasurements> {
+        match self
+            .entries
+            .binary_search_by(|(existing, _)| existing.cmp(&version))
+        {
+            Ok(index) => self
+                .entries
+                .get_mut(index)
+                .map(|(_, existing)| core::mem::replace(existing, measurements)),
+            Err(index) => {
+                self.entries.insert(index, (version, measurements));
+                None
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &[(Version, FullMeasurements)] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The versions whose MRTD and RTMR0-2 equal `live`, in ascending order.
+    pub fn versions_of<'a>(&'a self, live: &'a Measurements) -> impl Iterator<Item = &'a Version> {
+        self.entries
+            .iter()
+            .filter(move |(_, measurements)| measurements.rtmrs == *live)
+            .map(|(version, _)| version)
+    }
+
+    /// Accepts `report` if its MRTD and RTMR0-2 are those of a version satisfying `req`, and
+    /// returns the highest such version.
+    ///
+    /// Like [`super::LabeledAllowlist::verify`], only the registers are compared; the
+    /// key-provider and app compose digests are checked against the event log by
+    /// [`crate::attestation::DstackAttestation::verify`] or [`FullMeasurements::verify_report`]
+    /// with the measurements of the returned version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MeasurementsError::NotInAllowlist`] if no version has the registers of
+    /// `report`, [`MeasurementsError::VersionNotAccepted`] with the highest version it has if
+    /// none satisfies `req`, and the errors of reading the registers of `report`.
+    pub fn accept_range(
+        &self,
+        report: &VerifiedReport,
+        req: &VersionReq,
+    ) -> Result<Version, MeasurementsError> {
+        let live = Measurements::try_from(report)?;
+        let mut highest = None;
+        let mut accepted = None;
+        for version in self.versions_of(&live) {
+            highest = Some(version);
+            if req.matches(version) {
+                accepted = Some(version);
+            }
+        }
+
+        match (accepted, highest) {
+            (Some(accepted), _) => Ok(accepted.clone()),
+            (None, Some(highest)) => Err(MeasurementsError::VersionNotAccepted {
+                version: highest.to_string(),
+                requirement: req.to_string(),
+            }),
+            (None, None) => Err(MeasurementsError::NotInAllowlist),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Reads expected measurements from YAML, e.g. deployment configs which share registers
+//! between environments with anchors.
+
+use alloc::string::ToString;
+use serde_yaml::Value;
+
+use super::{FullMeasurementsHex, MeasurementsError};
+
+/// Parses [`FullMeasurementsHex`] from a YAML document of the form
+///
+/// ```yaml
+/// rtmrs:
+///   mrtd: <96 hex characters>
+///   rtmr0: <96 hex characters>
+///   rtmr1: <96 hex characters>
+///   rtmr2: <96 hex characters>
+/// key_provider_event_digest: <96 hex characters>
+/// app_compose_hash_payload: <64 hex characters>
+/// ```
+///
+/// Aliases (`*name`) and merge keys (`<<: *name`) are resolved before the fields are read, so
+/// registers can be shared through anchors defined anywhere in the document. Other top level
+/// keys, such as the ones holding those anchors, are ignored.
+///
+/// ```
+/// let yaml = format!(
+///     "common: &common\n  mrtd: {r}\n  rtmr0: {r}\n  rtmr1: {r}\n\
+///      rtmrs:\n  <<: *common\n  rtmr2: {r}\n\
+///      key_provider_event_digest: {r}\n\
+///      app_compose_hash_payload: {d}\n",
+///     r = "ab".repeat(48),
+///     d = "cd".repeat(32),
+/// );
+///
+/// let measurements = attestation::measurements::from_yaml_str(&yaml).unwrap();
+/// assert_eq!(*measurements.rtmrs.rtmr2, [0xab; 48]);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`MeasurementsError::InvalidYaml`] if the input is not YAML, a merge key does not
+/// refer to a mapping, or a field is missing or is not hex of the expected length.
+pub fn from_yaml_str(s: &str) -> Result<FullMeasurementsHex, MeasurementsError> {
+    let invalid = |e: serde_yaml::Error| MeasurementsError::InvalidYaml(e.to_string());
+
+    let mut document: Value = serde_yaml::from_str(s).map_err(invalid)?;
+    document.apply_merge().map_err(invalid)?;
+    serde_yaml::from_value(document).map_err(invalid)
+}
@@ -1,7 +1,29 @@
-use alloc::vec::Vec;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
+use dcap_qvl::quote::{AuthData, Quote};
 use derive_more::{Deref, From, Into};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use crate::collateral::Fmspc;
+
+mod aggregate;
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod configfs;
+#[cfg(feature = "std")]
+mod tsm;
+
+pub use aggregate::{
+    AGGREGATE_MAGIC, MAX_AGGREGATE_QUOTES, encode_aggregate, parse_aggregate, split_aggregate,
+};
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use configfs::from_configfs;
+#[cfg(feature = "std")]
+pub use tsm::parse_tsm_report;
 
 #[derive(
     Debug,
@@ -18,5 +40,383 @@ use serde::{Deserialize, Serialize};
     PartialOrd,
     Ord,
 )]
-
 pub struct QuoteBytes(Vec<u8>);
+
+/// Size of the fixed quote header shared by SGX and TDX quotes.
+pub const QUOTE_HEADER_SIZE: usize = 48;
+
+/// Byte range of the little-endian `version` field within the quote header.
+const VERSION_RANGE: core::ops::Range<usize> = 0..2;
+
+/// Byte range of the little-endian `attestation_key_type` field within the quote header.
+const ATTESTATION_KEY_TYPE_RANGE: core::ops::Range<usize> = 2..4;
+
+/// Byte range of the little-endian `tee_type` field within the quote header.
+const TEE_TYPE_RANGE: core::ops::Range<usize> = 4..8;
+
+/// Byte offset of the little-endian `ISVSVN` in an SGX enclave report, such as the QE report.
+const ENCLAVE_REPORT_ISV_SVN_OFFSET: usize = 258;
+
+const TEE_TYPE_SGX: u32 = 0x0000_0000;
+const TEE_TYPE_TDX: u32 = 0x0000_0081;
+
+/// Sizes of the report bodies of SGX, TDX 1.0 and TDX 1.5 quotes.
+const SGX_REPORT_BODY_SIZE: usize = 384;
+const TD10_REPORT_BODY_SIZE: usize = 584;
+const TD15_REPORT_BODY_SIZE: usize = 648;
+
+/// Size of the type and size of the report body which precede it in a version 5 quote.
+const BODY_DESCRIPTOR_SIZE: usize = 2 + 4;
+
+/// Report body types of a version 5 quote.
+const BODY_TYPE_SGX: u16 = 1;
+const BODY_TYPE_TD10: u16 = 2;
+const BODY_TYPE_TD15: u16 = 3;
+
+/// The kind of TEE that produced a quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuoteType {
+    Sgx,
+    Tdx,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum QuoteError {
+    #[error("quote is {len} bytes, shorter than the {QUOTE_HEADER_SIZE} byte header")]
+    TooShort { len: usize },
+    #[error("unsupported TEE type {0:#010x}")]
+    UnsupportedTeeType(u32),
+    #[error("report body does not match the size of a report of TEE type {tee_type:#010x}")]
+    BodyTypeMismatch { tee_type: u32 },
+    #[error("failed to parse quote: {0}")]
+    Parse(String),
+    #[error("configfs-tsm quote generation failed: {0}")]
+    ConfigfsTsm(String),
+    #[error("invalid configfs-tsm report: {0}")]
+    TsmReport(String),
+    #[error("unsupported certification data type {0}")]
+    UnsupportedCertDataType(u16),
+    #[error("unsupported attestation key type {0}")]
+    UnsupportedSignatureAlgorithm(u16),
+    /// The input is a platform quote rather than this crate's aggregate envelope, see
+    /// [`split_aggregate`].
+    #[error("input is a single quote, not this crate's aggregate quote envelope")]
+    NotAggregate,
+    /// The input is neither a quote nor a well-formed aggregate envelope.
+    #[error("invalid aggregate quote envelope: {0}")]
+    InvalidAggregate(String),
+}
+
+/// The type of the certification data of a quote's signature, which carries the PCK
+/// certificate chain the quote is verified with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CertDataType {
+    /// Type 5: the PCK certificate chain itself, used by version 3 quotes.
+    PckCertChain,
+    /// Type 6: the QE report, its signature and QE authentication data, followed by nested
+    /// certification data of type 5. Used by version 4 and 5 quotes.
+    QeReportCertificationData,
+}
+
+impl CertDataType {
+    /// The numeric type of the certification data, as encoded in the quote.
+    pub fn as_u16(&self) -> u16 {
+        match self {
+            CertDataType::PckCertChain => 5,
+            CertDataType::QeReportCertificationData => 6,
+        }
+    }
+}
+
+impl TryFrom<u16> for CertDataType {
+    type Error = QuoteError;
+
+    /// Types 1 to 3 carry only the PPID, for use with a provisioning service, and cannot be
+    /// verified offline; they are rejected like any other type.
+    fn try_from(cert_type: u16) -> Result<Self, Self::Error> {
+        match cert_type {
+            5 => Ok(CertDataType::PckCertChain),
+            6 => Ok(CertDataType::QeReportCertificationData),
+            other => Err(QuoteError::UnsupportedCertDataType(other)),
+        }
+    }
+}
+
+/// The algorithm of the attestation key, which signs the quote, given by the
+/// `attestation_key_type` of the quote header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureAlgorithm {
+    /// Type 2: ECDSA with P-256 and SHA-256, the only algorithm DCAP quotes use so far.
+    EcdsaP256,
+}
+
+impl SignatureAlgorithm {
+    /// The numeric attestation key type, as encoded in the quote header.
+    pub fn as_u16(&self) -> u16 {
+        match self {
+            SignatureAlgorithm::EcdsaP256 => 2,
+        }
+    }
+}
+
+impl TryFrom<u16> for SignatureAlgorithm {
+    type Error = QuoteError;
+
+    /// Type 3, ECDSA with P-384, is reserved by Intel but not produced by any QE, and is
+    /// rejected like any other type.
+    fn try_from(key_type: u16) -> Result<Self, Self::Error> {
+        match key_type {
+            2 => Ok(SignatureAlgorithm::EcdsaP256),
+            other => Err(QuoteError::UnsupportedSignatureAlgorithm(other)),
+        }
+    }
+}
+
+/// The quote header fields relevant for triaging a failed verification, attached to
+/// [`crate::attestation::VerificationError::DcapVerification`] when the header could be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QuoteContext {
+    pub version: u16,
+    pub tee_type: u32,
+}
+
+impl QuoteContext {
+    /// Reads the version and TEE type from the quote header, without validating either.
+    pub fn from_header(raw: &[u8]) -> Result<Self, QuoteError> {
+        let header = raw
+            .get(..QUOTE_HEADER_SIZE)
+            .ok_or(QuoteError::TooShort { len: raw.len() })?;
+        let version = header
+            .get(VERSION_RANGE)
+            .and_then(|bytes| <[u8; 2]>::try_from(bytes).ok())
+            .map(u16::from_le_bytes)
+            .ok_or(QuoteError::TooShort { len: raw.len() })?;
+        let tee_type = header
+            .get(TEE_TYPE_RANGE)
+            .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+            .map(u32::from_le_bytes)
+            .ok_or(QuoteError::TooShort { len: raw.len() })?;
+
+        Ok(Self { version, tee_type })
+    }
+}
+
+impl core::fmt::Display for QuoteContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "quote version {}, TEE type {:#010x}",
+            self.version, self.tee_type
+        )
+    }
+}
+
+/// Reads the TEE type from the quote header, without parsing or verifying the rest of the quote.
+pub fn quote_type(raw: &[u8]) -> Result<QuoteType, QuoteError> {
+    let QuoteContext { tee_type, .. } = QuoteContext::from_header(raw)?;
+
+    match tee_type {
+        TEE_TYPE_SGX => Ok(QuoteType::Sgx),
+        TEE_TYPE_TDX => Ok(QuoteType::Tdx),
+        other => Err(QuoteError::UnsupportedTeeType(other)),
+    }
+}
+
+/// Checks that the report body of `raw` is the size of a report of `quote_type`, so that a
+/// quote with a TDX header and an SGX report body, or the reverse, is rejected rather than
+/// parsed from the wrong offsets.
+///
+/// A version 5 quote declares the type and size of its body, which must both match. Earlier
+/// versions imply the size from the TEE type, so the signature data length which follows the
+/// body must then fit in the quote. A quote too short to hold the signature data length is
+/// left to [`Quote::parse`] to reject.
+fn check_report_body(raw: &[u8], quote_type: QuoteType) -> Result<(), QuoteError> {
+    let QuoteContext { version, tee_type } = QuoteContext::from_header(raw)?;
+    let mismatch = QuoteError::BodyTypeMismatch { tee_type };
+
+    let (body_start, body_size) = if version == 5 {
+        let descriptor = raw
+            .get(QUOTE_HEADER_SIZE..)
+            .and_then(|rest| rest.get(..BODY_DESCRIPTOR_SIZE));
+        let Some((body_type, body_size)) = descriptor.and_then(|descriptor| {
+            let (body_type, body_size) = descriptor.split_at_checked(2)?;
+            Some((
+                u16::from_le_bytes(body_type.try_into().ok()?),
+                u32::from_le_bytes(body_size.try_into().ok()?),
+            ))
+        }) else {
+            return Ok(());
+        };
+        let expected_size = match (quote_type, body_type) {
+            (QuoteType::Sgx, BODY_TYPE_SGX) => SGX_REPORT_BODY_SIZE,
+            (QuoteType::Tdx, BODY_TYPE_TD10) => TD10_REPORT_BODY_SIZE,
+            (QuoteType::Tdx, BODY_TYPE_TD15) => TD15_REPORT_BODY_SIZE,
+            _ => return Err(mismatch),
+        };
+        if usize::try_from(body_size).ok() != Some(expected_size) {
+            return Err(mismatch);
+        }
+        (
+            QUOTE_HEADER_SIZE.saturating_add(BODY_DESCRIPTOR_SIZE),
+            expected_size,
+        )
+    } else {
+        let body_size = match quote_type {
+            QuoteType::Sgx => SGX_REPORT_BODY_SIZE,
+            QuoteType::Tdx => TD10_REPORT_BODY_SIZE,
+        };
+        (QUOTE_HEADER_SIZE, body_size)
+    };
+
+    let signature_data_start = body_start.saturating_add(body_size);
+    let Some(signature_data_len) = raw
+        .get(signature_data_start..)
+        .and_then(|rest| rest.get(..4))
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+        .map(u32::from_le_bytes)
+    else {
+        return Ok(());
+    };
+    let signature_data_end = usize::try_from(signature_data_len)
+        .ok()
+        .and_then(|len| signature_data_start.checked_add(4)?.checked_add(len));
+    match signature_data_end {
+        Some(end) if end <= raw.len() => Ok(()),
+        _ => Err(mismatch),
+    }
+}
+
+/// A structurally parsed, but not verified, quote.
+#[derive(Debug, Clone)]
+pub struct ParsedQuote {
+    quote_type: QuoteType,
+    signature_algorithm: SignatureAlgorithm,
+    cert_data_type: CertDataType,
+    quote: Quote,
+}
+
+impl ParsedQuote {
+    /// Parses `raw` without checking any signature or certificate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QuoteError::UnsupportedCertDataType`] if the certification data of the quote,
+    /// or the certification data nested in a [`CertDataType::QeReportCertificationData`], is
+    /// not of a type carrying the PCK certificate chain,
+    /// [`QuoteError::UnsupportedSignatureAlgorithm`] if the quote is not signed with a
+    /// [`SignatureAlgorithm`], [`QuoteError::BodyTypeMismatch`] if the report body is not the
+    /// size of a report of the TEE type of the header, and the errors of [`quote_type`].
+    pub fn parse(raw: &[u8]) -> Result<Self, QuoteError> {
+        let quote_type = quote_type(raw)?;
+        let signature_algorithm = raw
+            .get(ATTESTATION_KEY_TYPE_RANGE)
+            .and_then(|bytes| <[u8; 2]>::try_from(bytes).ok())
+            .map(u16::from_le_bytes)
+            .ok_or(QuoteError::TooShort { len: raw.len() })
+            .and_then(SignatureAlgorithm::try_from)?;
+        check_report_body(raw, quote_type)?;
+        let quote = Quote::parse(raw).map_err(|e| QuoteError::Parse(e.to_string()))?;
+        let cert_data_type = match &quote.auth_data {
+            AuthData::V3(auth_data) => {
+                CertDataType::try_from(auth_data.certification_data.cert_type)?
+            }
+            AuthData::V4(auth_data) => {
+                CertDataType::try_from(auth_data.certification_data.cert_type)?
+            }
+        };
+        let parsed = Self {
+            quote_type,
+            signature_algorithm,
+            cert_data_type,
+            quote,
+        };
+        // Checks the nested certification data, so that every parsed quote has a chain.
+        parsed.pck_cert_chain()?;
+        Ok(parsed)
+    }
+
+    pub fn quote_type(&self) -> QuoteType {
+        self.quote_type
+    }
+
+    /// The type of the outer certification data of the quote.
+    pub fn cert_data_type(&self) -> CertDataType {
+        self.cert_data_type
+    }
+
+    /// The PCK certificate chain carried by the quote, PEM encoded, read from the certification
+    /// data of either [`CertDataType`].
+    pub fn pck_cert_chain(&self) -> Result<&[u8], QuoteError> {
+        let certification_data = match (&self.quote.auth_data, self.cert_data_type) {
+            (AuthData::V3(auth_data), CertDataType::PckCertChain) => &auth_data.certification_data,
+            (AuthData::V4(auth_data), CertDataType::QeReportCertificationData) => {
+                &auth_data.qe_report_data.certification_data
+            }
+            (_, cert_data_type) => {
+                return Err(QuoteError::Parse(format!(
+                    "certification data of type {} in a version {} quote",
+                    cert_data_type.as_u16(),
+                    self.quote.header.version
+                )));
+            }
+        };
+        match CertDataType::try_from(certification_data.cert_type)? {
+            CertDataType::PckCertChain => Ok(&certification_data.body.data),
+            CertDataType::QeReportCertificationData => Err(QuoteError::UnsupportedCertDataType(
+                CertDataType::QeReportCertificationData.as_u16(),
+            )),
+        }
+    }
+
+    pub fn quote(&self) -> &Quote {
+        &self.quote
+    }
+
+    /// The FMSPC of the platform, read from the PCK certificate embedded in the quote.
+    pub fn fmspc(&self) -> Result<Fmspc, QuoteError> {
+        self.quote
+            .fmspc()
+            .map(Fmspc::from)
+            .map_err(|e| QuoteError::Parse(e.to_string()))
+    }
+}
+
+/// Returns the algorithm the quote is signed with. Quotes signed with any other algorithm are
+/// rejected by [`ParsedQuote::parse`], so they cannot be verified as if signed with this one.
+pub fn signature_algorithm(parsed: &ParsedQuote) -> SignatureAlgorithm {
+    parsed.signature_algorithm
+}
+
+/// Returns the QE authentication data of the quote.
+///
+/// The QE authentication data is chosen by the quote generation service and is at most
+/// `u16::MAX` bytes, as its size is encoded in two bytes. It is bound to the quote through the
+/// QE report, whose report data commits to `sha256(attestation_key || auth_data)`, and is thus
+/// covered by the QE report signature. It is **not** part of the TD report, so it is not
+/// reflected in the report data or in any RTMR.
+pub fn auth_data(parsed: &ParsedQuote) -> &[u8] {
+    match &parsed.quote.auth_data {
+        AuthData::V3(auth_data) => &auth_data.qe_auth_data.data,
+        AuthData::V4(auth_data) => &auth_data.qe_report_data.qe_auth_data.data,
+    }
+}
+
+/// Returns the ISV SVN of the Quoting Enclave, read from the QE report of the quote. It is
+/// matched against the `tcbLevels` of the QE identity, see
+/// [`crate::tcb_status::evaluate_qe_tcb`].
+pub fn qe_isv_svn(parsed: &ParsedQuote) -> u16 {
+    let qe_report = match &parsed.quote.auth_data {
+        AuthData::V3(auth_data) => &auth_data.qe_report,
+        AuthData::V4(auth_data) => &auth_data.qe_report_data.qe_report,
+    };
+    // The QE report is a fixed size array, so these constant indices are checked at compile time.
+    u16::from_le_bytes([
+        qe_report[ENCLAVE_REPORT_ISV_SVN_OFFSET],
+        qe_report[ENCLAVE_REPORT_ISV_SVN_OFFSET + 1],
+    ])
+}
+
+/// SHA-256 hash of [`auth_data`].
+pub fn auth_data_hash(parsed: &ParsedQuote) -> [u8; 32] {
+    Sha256::digest(auth_data(parsed)).into()
+}
@@ -0,0 +1,102 @@
+//! Aggregate quotes, which attest several TDs at once by embedding one quote per TD:
+//! `AGGREGATE_MAGIC || count (u32) || (len (u32) || quote) * count`, all little endian, with
+//! nothing after the last quote.
+//!
+//! The aggregate is an envelope defined by this crate to carry several quotes in one message,
+//! not a format of Intel DCAP, dstack or the TDX platform, and no platform produces it. It is
+//! not signed as a whole: each embedded quote is verified on its own.
+
+use alloc::{format, string::String, vec::Vec};
+
+use super::{ParsedQuote, QuoteError, quote_type};
+
+/// Magic bytes an aggregate quote starts with, chosen by this crate.
+pub const AGGREGATE_MAGIC: [u8; 4] = *b"AGGQ";
+
+/// Maximum number of quotes embedded in an aggregate quote.
+pub const MAX_AGGREGATE_QUOTES: usize = 64;
+
+/// Splits an aggregate quote into the raw quotes it embeds, in order, without parsing them.
+///
+/// # Errors
+///
+/// Returns [`QuoteError::NotAggregate`] if `raw` is a single quote, and
+/// [`QuoteError::InvalidAggregate`] if it is neither a quote nor a well-formed aggregate of
+/// one to [`MAX_AGGREGATE_QUOTES`] quotes.
+pub fn split_aggregate(raw: &[u8]) -> Result<Vec<&[u8]>, QuoteError> {
+    if quote_type(raw).is_ok() {
+        return Err(QuoteError::NotAggregate);
+    }
+    let invalid = |reason: &str| QuoteError::InvalidAggregate(String::from(reason));
+
+    let rest = raw
+        .strip_prefix(AGGREGATE_MAGIC.as_slice())
+        .ok_or_else(|| invalid("missing magic"))?;
+    let (count, mut rest) = read_u32(rest).ok_or_else(|| invalid("truncated count"))?;
+    if count == 0 || count > MAX_AGGREGATE_QUOTES {
+        return Err(QuoteError::InvalidAggregate(format!(
+            "{count} quotes, expected 1 to {MAX_AGGREGATE_QUOTES}"
+        )));
+    }
+
+    let mut quotes = Vec::with_capacity(count);
+    while quotes.len() < count {
+        let (len, remaining) = read_u32(rest).ok_or_else(|| invalid("truncated length"))?;
+        let (quote, remaining) = remaining
+            .split_at_checked(len)
+            .ok_or_else(|| invalid("truncated quote"))?;
+        quotes.push(quote);
+        rest = remaining;
+    }
+    if !rest.is_empty() {
+        return Err(QuoteError::InvalidAggregate(format!(
+            "{} trailing bytes",
+            rest.len()
+        )));
+    }
+    Ok(quotes)
+}
+
+/// Splits an aggregate quote with [`split_aggregate`], and parses each embedded quote with
+/// [`ParsedQuote::parse`].
+pub fn parse_aggregate(raw: &[u8]) -> Result<Vec<ParsedQuote>, QuoteError> {
+    split_aggregate(raw)?
+        .into_iter()
+        .map(ParsedQuote::parse)
+        .collect()
+}
+
+/// Builds an aggregate quote embedding `quotes`, the inverse of [`split_aggregate`].
+///
+/// # Errors
+///
+/// Returns [`QuoteError::InvalidAggregate`] if there are no quotes, more than
+/// [`MAX_AGGREGATE_QUOTES`], or a quote longer than `u32::MAX` bytes.
+pub fn encode_aggregate(quotes: &[&[u8]]) -> Result<Vec<u8>, QuoteError> {
+    if quotes.is_empty() || quotes.len() > MAX_AGGREGATE_QUOTES {
+        return Err(QuoteError::InvalidAggregate(format!(
+            "{} quotes, expected 1 to {MAX_AGGREGATE_QUOTES}",
+            quotes.len()
+        )));
+    }
+    let encode_len = |len: usize| {
+        u32::try_from(len)
+            .map(u32::to_le_bytes)
+            .map_err(|_| QuoteError::InvalidAggregate(format!("quote of {len} bytes")))
+    };
+
+    let mut aggregate = AGGREGATE_MAGIC.to_vec();
+    aggregate.extend_from_slice(&encode_len(quotes.len())?);
+    for quote in quotes {
+        aggregate.extend_from_slice(&encode_len(quote.len())?);
+        aggregate.extend_from_slice(quote);
+    }
+    Ok(aggregate)
+}
+
+/// Reads a little endian `u32` off the start of `bytes`, as a `usize`.
+fn read_u32(bytes: &[u8]) -> Option<(usize, &[u8])> {
+    let (value, rest) = bytes.split_first_chunk::<4>()?;
+    let value = usize::try_from(u32::from_le_bytes(*value)).ok()?;
+    Some((value, rest))
+}
@@ -0,0 +1,86 @@
+//! Quote generation through the Linux configfs-tsm interface, see
+//! <https://docs.kernel.org/ABI/testing/configfs-tsm-report>. This is only needed by a TDX
+//! guest attesting itself, and is not part of verification.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    format, fs,
+    path::{Path, PathBuf},
+    process,
+    string::String,
+    vec::Vec,
+};
+
+use super::QuoteError;
+use crate::report_data::REPORT_DATA_SIZE;
+
+const TSM_REPORT_ROOT: &str = "/sys/kernel/config/tsm/report";
+
+/// Distinguishes the report entries created concurrently by this process.
+static NEXT_ENTRY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Requests a quote over `report_data` from the TEE, by creating a configfs-tsm report entry,
+/// writing `report_data` to its `inblob` and reading the quote back from its `outblob`, which
+/// is unwrapped with [`super::parse_tsm_report`].
+///
+/// # Errors
+///
+/// Returns [`QuoteError::ConfigfsTsm`] if configfs-tsm is not available, the TEE fails to
+/// generate the quote, or the entry was modified concurrently while the quote was read, and
+/// [`QuoteError::TsmReport`] if the `outblob` does not hold a TDX quote.
+pub fn from_configfs(report_data: &[u8; REPORT_DATA_SIZE]) -> Result<Vec<u8>, QuoteError> {
+    let entry_id = NEXT_ENTRY_ID.fetch_add(1, Ordering::Relaxed);
+    let entry = ReportEntry::create(
+        Path::new(TSM_REPORT_ROOT).join(format!("mpc-attestation-{}-{entry_id}", process::id())),
+    )?;
+
+    entry.write("inblob", report_data)?;
+    let generation = entry.read_to_string("generation")?;
+    let quote = super::parse_tsm_report(&entry.read("outblob")?)?;
+    if entry.read_to_string("generation")? != generation {
+        return Err(QuoteError::ConfigfsTsm(String::from(
+            "report entry was modified while reading the quote",
+        )));
+    }
+
+    Ok(quote)
+}
+
+/// A configfs-tsm report entry, removed when dropped.
+struct ReportEntry {
+    path: PathBuf,
+}
+
+impl ReportEntry {
+    fn create(path: PathBuf) -> Result<Self, QuoteError> {
+        fs::create_dir(&path).map_err(|e| io_error(&path, e))?;
+        Ok(Self { path })
+    }
+
+    fn write(&self, attribute: &str, contents: &[u8]) -> Result<(), QuoteError> {
+        let path = self.path.join(attribute);
+        fs::write(&path, contents).map_err(|e| io_error(&path, e))
+    }
+
+    fn read(&self, attribute: &str) -> Result<Vec<u8>, QuoteError> {
+        let path = self.path.join(attribute);
+        fs::read(&path).map_err(|e| io_error(&path, e))
+    }
+
+    fn read_to_string(&self, attribute: &str) -> Result<String, QuoteError> {
+        let path = self.path.join(attribute);
+        fs::read_to_string(&path).map_err(|e| io_error(&path, e))
+    }
+}
+
+impl Drop for ReportEntry {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir(&self.path) {
+            tracing::warn!(path = %self.path.display(), "failed to remove configfs-tsm entry: {e}");
+        }
+    }
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> QuoteError {
+    QuoteError::ConfigfsTsm(format!("{}: {error}", path.display()))
+}
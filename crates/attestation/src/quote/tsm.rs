@@ -0,0 +1,107 @@
+//! Extraction of the quote from the `outblob` of a configfs-tsm report, whose layout depends on
+//! the kernel version of the TDX guest.
+//!
+//! Kernels with the configfs-tsm `tdx_guest` provider return the quote as is. Older kernels,
+//! and the `TDX_CMD_GET_QUOTE` ioctl of `/dev/tdx-guest`, return the GHCI quote buffer instead:
+//! `version (u64) || status (u64) || in_len (u32) || out_len (u32) || data`, all little endian,
+//! with the quote in the first `out_len` bytes of `data`.
+
+use alloc::{format, string::String, vec::Vec};
+
+use super::{QuoteContext, QuoteError, QuoteType, quote_type};
+
+/// Version of the GHCI quote buffer.
+const GHCI_QUOTE_BUFFER_VERSION: u64 = 1;
+
+/// Size of the GHCI quote buffer header, before the quote.
+const GHCI_QUOTE_BUFFER_HEADER_SIZE: usize = 24;
+
+/// Status of a GHCI quote buffer holding a generated quote.
+const GHCI_STATUS_SUCCESS: u64 = 0;
+
+/// Status of a GHCI quote buffer whose quote is still being generated.
+const GHCI_STATUS_IN_FLIGHT: u64 = u64::MAX;
+
+/// Quote versions returned by the `tdx_guest` provider.
+const TDX_QUOTE_VERSIONS: [u16; 2] = [4, 5];
+
+/// Extracts the TDX quote from a configfs-tsm `outblob`, accepting both the raw quote and the
+/// GHCI quote buffer returned by older kernels.
+///
+/// # Errors
+///
+/// Returns [`QuoteError::TsmReport`] if `blob` has neither layout, the GHCI buffer reports that
+/// the quote was not generated, or its `out_len` exceeds the buffer.
+pub fn parse_tsm_report(blob: &[u8]) -> Result<Vec<u8>, QuoteError> {
+    if is_tdx_quote(blob) {
+        return Ok(blob.to_vec());
+    }
+
+    let truncated = || QuoteError::TsmReport(String::from("truncated GHCI quote buffer"));
+    let (header, data) = blob
+        .split_at_checked(GHCI_QUOTE_BUFFER_HEADER_SIZE)
+        .ok_or_else(|| {
+            QuoteError::TsmReport(format!("unrecognized layout of {} bytes", blob.len()))
+        })?;
+    let version = read_u64(header, 0);
+    if version != Some(GHCI_QUOTE_BUFFER_VERSION) {
+        return Err(QuoteError::TsmReport(String::from(
+            "neither a TDX quote nor a GHCI quote buffer",
+        )));
+    }
+
+    match read_u64(header, 8) {
+        Some(GHCI_STATUS_SUCCESS) => {}
+        Some(GHCI_STATUS_IN_FLIGHT) => {
+            return Err(QuoteError::TsmReport(String::from(
+                "quote generation is still in flight",
+            )));
+        }
+        Some(status) => {
+            return Err(QuoteError::TsmReport(format!(
+                "quote generation failed with status {status:#018x}"
+            )));
+        }
+        None => return Err(truncated()),
+    }
+
+    let out_len = read_u32(header, 20)
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or_else(truncated)?;
+    let quote = data.get(..out_len).ok_or_else(|| {
+        QuoteError::TsmReport(format!(
+            "out_len {out_len} exceeds the {} byte buffer",
+            data.len()
+        ))
+    })?;
+    if !is_tdx_quote(quote) {
+        return Err(QuoteError::TsmReport(String::from(
+            "GHCI quote buffer does not hold a TDX quote",
+        )));
+    }
+
+    Ok(quote.to_vec())
+}
+
+fn is_tdx_quote(blob: &[u8]) -> bool {
+    let Ok(QuoteContext { version, .. }) = QuoteContext::from_header(blob) else {
+        return false;
+    };
+    TDX_QUOTE_VERSIONS.contains(&version) && quote_type(blob) == Ok(QuoteType::Tdx)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    let end = offset.checked_add(8)?;
+    bytes
+        .get(offset..end)
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_le_bytes)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let end = offset.checked_add(4)?;
+    bytes
+        .get(offset..end)
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+        .map(u32::from_le_bytes)
+}
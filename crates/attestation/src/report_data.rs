@@ -1,11 +1,487 @@
+use alloc::vec::Vec;
+use borsh::{BorshDeserialize, BorshSerialize};
+use dcap_qvl::quote::Report;
+use serde::{Deserialize, Serialize};
+use serde_with::{Bytes, serde_as};
+use sha2::{Digest as _, Sha256, Sha384, Sha512};
+
+use crate::svn::TeeTcbSvn;
+
 /// Number of bytes for the report data.
 pub const REPORT_DATA_SIZE: usize = 64;
 
-#[derive(Debug, Clone, derive_more::From)]
-pub struct ReportData([u8; REPORT_DATA_SIZE]);
+const VERSION_SIZE: usize = 2;
+const PUBLIC_KEY_HASH_OFFSET: usize = VERSION_SIZE;
+const PUBLIC_KEY_HASH_SIZE: usize = 48;
+const NONCE_OFFSET: usize = PUBLIC_KEY_HASH_OFFSET + PUBLIC_KEY_HASH_SIZE;
+/// Number of bytes trailing the public key hash in the report data.
+pub const NONCE_SIZE: usize = REPORT_DATA_SIZE - NONCE_OFFSET;
+
+/// The 64 bytes of user data committed to in a TEE quote.
+///
+/// The report data is laid out as:
+/// `[version (2 bytes big endian) || public key hash (48 bytes) || nonce (14 bytes)]`
+///
+/// Versions that don't use a nonce leave the trailing bytes zeroed.
+#[serde_as]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    derive_more::From,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+#[serde(transparent)]
+pub struct ReportData(#[serde_as(as = "Bytes")] [u8; REPORT_DATA_SIZE]);
 
 impl ReportData {
+    pub fn new(bytes: [u8; REPORT_DATA_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; REPORT_DATA_SIZE] {
+        &self.0
+    }
+
     pub fn to_bytes(&self) -> [u8; REPORT_DATA_SIZE] {
         self.0
     }
+
+    /// The hash of the public key(s) bound to the quote.
+    pub fn public_key_hash(&self) -> [u8; PUBLIC_KEY_HASH_SIZE] {
+        let mut hash = [0u8; PUBLIC_KEY_HASH_SIZE];
+        hash.copy_from_slice(&self.0[PUBLIC_KEY_HASH_OFFSET..NONCE_OFFSET]);
+        hash
+    }
+
+    /// The bytes trailing the public key hash.
+    pub fn nonce(&self) -> [u8; NONCE_SIZE] {
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce.copy_from_slice(&self.0[NONCE_OFFSET..]);
+        nonce
+    }
+}
+
+/// Version of the report data produced by [`for_public_key`].
+pub const PUBLIC_KEY_BINDING_VERSION: u16 = 2;
+
+/// Domain separator of the public key hash of [`for_public_key`].
+const PUBLIC_KEY_BINDING_DOMAIN: &[u8] = b"mpc-attestation:report-data:public-key";
+
+/// The signature scheme of a public key bound to the report data, see [`for_public_key`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum KeyScheme {
+    /// Ed25519, encoded as the 32 byte compressed point of RFC 8032.
+    Ed25519,
+    /// secp256k1, encoded as the 33 byte SEC1 compressed point, i.e. `0x02` or `0x03` followed
+    /// by the x coordinate. The uncompressed encoding is rejected, so that every key has a
+    /// single binding.
+    Secp256k1,
+    /// BLS12-381, encoded as a compressed G1 (48 bytes) or G2 (96 bytes) point in the ZCash
+    /// serialization, i.e. with the compression flag, the most significant bit, set.
+    Bls12381,
+}
+
+impl KeyScheme {
+    /// The byte identifying the scheme in the public key hash.
+    fn tag(self) -> u8 {
+        match self {
+            KeyScheme::Ed25519 => 0,
+            KeyScheme::Secp256k1 => 1,
+            KeyScheme::Bls12381 => 2,
+        }
+    }
+
+    /// Whether `public_key` has the encoding documented for the scheme. The point itself is
+    /// not validated.
+    fn is_valid_encoding(self, public_key: &[u8]) -> bool {
+        match self {
+            KeyScheme::Ed25519 => public_key.len() == 32,
+            KeyScheme::Secp256k1 => {
+                public_key.len() == 33 && matches!(public_key.first(), Some(0x02 | 0x03))
+            }
+            KeyScheme::Bls12381 => {
+                matches!(public_key.len(), 48 | 96)
+                    && public_key.first().is_some_and(|byte| byte & 0x80 != 0)
+            }
+        }
+    }
+}
+
+/// Report data binding a single public key of `scheme`:
+/// `[version (2 bytes big endian) || public key hash (48 bytes) || zero padding]`, where the
+/// version is [`PUBLIC_KEY_BINDING_VERSION`] and the public key hash is
+/// `sha384(domain || scheme tag (1 byte) || public key)`. The scheme tag separates keys of
+/// different schemes that happen to share an encoding.
+///
+/// See [`KeyScheme`] for the expected encoding of `public_key`.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::InvalidPublicKey`] if `public_key` is not encoded as expected.
+pub fn for_public_key(
+    scheme: KeyScheme,
+    public_key: &[u8],
+) -> Result<[u8; REPORT_DATA_SIZE], ReportDataError> {
+    if !scheme.is_valid_encoding(public_key) {
+        return Err(ReportDataError::InvalidPublicKey {
+            scheme,
+            len: public_key.len(),
+        });
+    }
+
+    let public_key_hash: [u8; PUBLIC_KEY_HASH_SIZE] = Sha384::new()
+        .chain_update(PUBLIC_KEY_BINDING_DOMAIN)
+        .chain_update([scheme.tag()])
+        .chain_update(public_key)
+        .finalize()
+        .into();
+
+    let mut report_data = [0u8; REPORT_DATA_SIZE];
+    report_data[..VERSION_SIZE].copy_from_slice(&PUBLIC_KEY_BINDING_VERSION.to_be_bytes());
+    report_data[PUBLIC_KEY_HASH_OFFSET..NONCE_OFFSET].copy_from_slice(&public_key_hash);
+    Ok(report_data)
+}
+
+/// Checks that `report_data` binds `public_key` of `scheme`, i.e. equals
+/// [`for_public_key`] of it.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::InvalidPublicKey`] if `public_key` is not encoded as expected,
+/// and [`ReportDataError::PublicKeyMismatch`] if `report_data` binds another key.
+pub fn verify_public_key(
+    report_data: &ReportData,
+    scheme: KeyScheme,
+    public_key: &[u8],
+) -> Result<(), ReportDataError> {
+    if for_public_key(scheme, public_key)? == report_data.0 {
+        Ok(())
+    } else {
+        Err(ReportDataError::PublicKeyMismatch { scheme })
+    }
+}
+
+/// Version of the report data produced by [`bind_keys`].
+pub const MULTI_KEY_BINDING_VERSION: u16 = 3;
+
+/// Domain separator of the commitment of [`bind_keys`].
+const MULTI_KEY_BINDING_DOMAIN: &[u8] = b"mpc-attestation:report-data:keys";
+
+/// Report data committing to the ordered list `keys`, e.g. a signing and an encryption key:
+/// `[version (2 bytes big endian) || commitment (48 bytes) || zero padding]`, where the version
+/// is [`MULTI_KEY_BINDING_VERSION`] and the commitment is
+/// `sha384(domain || sha384(keys[0]) || sha384(keys[1]) || ...)`.
+///
+/// Hashing every key first makes the commitment unambiguous regardless of the key lengths, and
+/// binds the order of the keys: the same keys in another order give another commitment. Keys
+/// are hashed as given; it is up to the caller to agree on their encoding, e.g. those of
+/// [`KeyScheme`].
+pub fn bind_keys(keys: &[&[u8]]) -> [u8; REPORT_DATA_SIZE] {
+    let commitment: [u8; PUBLIC_KEY_HASH_SIZE] = keys
+        .iter()
+        .fold(
+            Sha384::new().chain_update(MULTI_KEY_BINDING_DOMAIN),
+            |hasher, key| hasher.chain_update(Sha384::digest(key)),
+        )
+        .finalize()
+        .into();
+
+    let mut report_data = [0u8; REPORT_DATA_SIZE];
+    report_data[..VERSION_SIZE].copy_from_slice(&MULTI_KEY_BINDING_VERSION.to_be_bytes());
+    report_data[PUBLIC_KEY_HASH_OFFSET..NONCE_OFFSET].copy_from_slice(&commitment);
+    report_data
+}
+
+/// Checks that `report_data` commits to exactly the ordered list `keys`, i.e. equals
+/// [`bind_keys`] of it.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::KeysMismatch`] if `report_data` commits to other keys, or to the
+/// same keys in another order.
+pub fn verify_keys(report_data: &ReportData, keys: &[&[u8]]) -> Result<(), ReportDataError> {
+    if bind_keys(keys) == report_data.0 {
+        Ok(())
+    } else {
+        Err(ReportDataError::KeysMismatch)
+    }
+}
+
+/// Report data binding `payload` to the protocol `domain`: `sha512(domain || payload)`, whose
+/// 64 bytes fill the report data exactly.
+///
+/// Tagging the report data with the protocol stops a quote produced for one protocol from being
+/// accepted by another that happens to attest to the same payload. The tag is concatenated
+/// without a separator, so the domains of protocols sharing a TEE must be prefix free, e.g. all
+/// end with `:` like `mpc-attestation:resharing:`; otherwise `("ab", "c")` and `("a", "bc")`
+/// give the same report data.
+pub fn with_domain(domain: &[u8], payload: &[u8]) -> [u8; REPORT_DATA_SIZE] {
+    Sha512::new()
+        .chain_update(domain)
+        .chain_update(payload)
+        .finalize()
+        .into()
+}
+
+/// Checks that `report_data` binds `payload` to `domain`, i.e. equals [`with_domain`] of them.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::DomainMismatch`] if `report_data` binds another payload, or the
+/// same payload to another domain.
+pub fn verify_with_domain(
+    report_data: &ReportData,
+    domain: &[u8],
+    payload: &[u8],
+) -> Result<(), ReportDataError> {
+    if with_domain(domain, payload) == report_data.0 {
+        Ok(())
+    } else {
+        Err(ReportDataError::DomainMismatch)
+    }
+}
+
+/// Report data anchoring `payload_hash` to a recent block:
+/// `[block hash (32 bytes) || payload hash (32 bytes)]`.
+///
+/// A quote can only contain a block hash once the block exists, so a verifier that only accepts
+/// recent block hashes, e.g. a contract checking them against its own view of the chain, knows
+/// the quote is at least as recent as the block, without a clock. The payload hash binds what
+/// the quote attests to, e.g. `sha256` of the node's keys.
+pub fn with_block_anchor(block_hash: &[u8; 32], payload_hash: &[u8; 32]) -> [u8; REPORT_DATA_SIZE] {
+    let mut report_data = [0u8; REPORT_DATA_SIZE];
+    let (block, payload) = report_data.split_at_mut(32);
+    block.copy_from_slice(block_hash);
+    payload.copy_from_slice(payload_hash);
+    report_data
+}
+
+/// Checks that `report_data` is [`with_block_anchor`] of `expected_block_hash` and
+/// `expected_payload_hash`. Which block hashes are recent enough is up to the caller.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::BlockHashMismatch`] if `report_data` is anchored to another block,
+/// and [`ReportDataError::PayloadHashMismatch`] if it binds another payload.
+pub fn verify_block_anchor(
+    report_data: &ReportData,
+    expected_block_hash: &[u8; 32],
+    expected_payload_hash: &[u8; 32],
+) -> Result<(), ReportDataError> {
+    let (block_hash, payload_hash) = report_data.0.split_at(32);
+    if block_hash != expected_block_hash {
+        return Err(ReportDataError::BlockHashMismatch);
+    }
+    if payload_hash != expected_payload_hash {
+        return Err(ReportDataError::PayloadHashMismatch);
+    }
+    Ok(())
+}
+
+/// Report data committing to `payload` with a caller-chosen hash function:
+/// `[hasher(payload) || zero padding]`, i.e. the digest fills the report data from its first
+/// byte, and the remaining bytes are zero. A 64 byte digest, e.g. SHA-512, fills it exactly.
+///
+/// This lets protocol versions bind their payload with SHA-256, SHA-384, Keccak or any other
+/// hash of at most [`REPORT_DATA_SIZE`] bytes, see [`sha384_commitment`] for the default.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::DigestTooLong`] if the digest does not fit the report data.
+pub fn commitment(
+    payload: &[u8],
+    hasher: &dyn Fn(&[u8]) -> Vec<u8>,
+) -> Result<[u8; REPORT_DATA_SIZE], ReportDataError> {
+    let digest = hasher(payload);
+    let mut report_data = [0u8; REPORT_DATA_SIZE];
+    report_data
+        .get_mut(..digest.len())
+        .ok_or(ReportDataError::DigestTooLong { len: digest.len() })?
+        .copy_from_slice(&digest);
+    Ok(report_data)
+}
+
+/// Checks that `report_data` is [`commitment`] to `payload` with `hasher`, including that the
+/// bytes past the digest are zero.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::DigestTooLong`] if the digest does not fit the report data, and
+/// [`ReportDataError::CommitmentMismatch`] if `report_data` commits to another payload.
+pub fn verify_commitment(
+    report_data: &ReportData,
+    payload: &[u8],
+    hasher: &dyn Fn(&[u8]) -> Vec<u8>,
+) -> Result<(), ReportDataError> {
+    if commitment(payload, hasher)? == report_data.0 {
+        Ok(())
+    } else {
+        Err(ReportDataError::CommitmentMismatch)
+    }
+}
+
+/// [`commitment`] to `payload` with SHA-384, the hash of the public key bindings: the 48 byte
+/// digest followed by 16 zero bytes.
+pub fn sha384_commitment(payload: &[u8]) -> [u8; REPORT_DATA_SIZE] {
+    let mut report_data = [0u8; REPORT_DATA_SIZE];
+    report_data[..PUBLIC_KEY_HASH_SIZE].copy_from_slice(&Sha384::digest(payload));
+    report_data
+}
+
+/// [`verify_commitment`] with SHA-384, see [`sha384_commitment`].
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::CommitmentMismatch`] if `report_data` commits to another payload.
+pub fn verify_sha384_commitment(
+    report_data: &ReportData,
+    payload: &[u8],
+) -> Result<(), ReportDataError> {
+    verify_commitment(report_data, payload, &|payload| {
+        Sha384::digest(payload).to_vec()
+    })
+}
+
+/// Number of bytes of the claims hash at the start of the report data, see [`commit_claims`].
+pub const CLAIMS_HASH_SIZE: usize = 32;
+
+/// Report data committing to an application `claims` blob, e.g. metadata the application
+/// binds to its attestation and ships alongside the quote:
+/// `[sha256(claims) (32 bytes) || zero padding (32 bytes)]`.
+///
+/// [`verify_claims`] only checks the claims hash, so deployments may put other data, e.g. a
+/// nonce, in the last 32 bytes instead of the padding.
+pub fn commit_claims(claims: &[u8]) -> [u8; REPORT_DATA_SIZE] {
+    let mut report_data = [0u8; REPORT_DATA_SIZE];
+    let (claims_hash, _) = report_data.split_at_mut(CLAIMS_HASH_SIZE);
+    claims_hash.copy_from_slice(&Sha256::digest(claims));
+    report_data
+}
+
+/// Checks that the first [`CLAIMS_HASH_SIZE`] bytes of `report_data` are `sha256(claims)`, see
+/// [`commit_claims`]. The last 32 bytes are not checked.
+///
+/// # Errors
+///
+/// Returns [`ReportDataError::ClaimsMismatch`] if `report_data` commits to other claims.
+pub fn verify_claims(report_data: &ReportData, claims: &[u8]) -> Result<(), ReportDataError> {
+    let (claims_hash, _) = report_data.0.split_at(CLAIMS_HASH_SIZE);
+    let expected: [u8; CLAIMS_HASH_SIZE] = Sha256::digest(claims).into();
+    if claims_hash == expected {
+        Ok(())
+    } else {
+        Err(ReportDataError::ClaimsMismatch)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReportDataError {
+    #[error("report data is {len} bytes, expected at least {REPORT_DATA_SIZE}")]
+    TooShort { len: usize },
+    #[error(
+        "report data has a non-zero byte at offset {offset}, past the first {REPORT_DATA_SIZE}"
+    )]
+    NonZeroTrailingBytes { offset: usize },
+    #[error("invalid {scheme:?} public key encoding of {len} bytes")]
+    InvalidPublicKey { scheme: KeyScheme, len: usize },
+    #[error("report data does not bind the {scheme:?} public key")]
+    PublicKeyMismatch { scheme: KeyScheme },
+    #[error("report data does not commit to the keys")]
+    KeysMismatch,
+    #[error("report data does not bind the payload to the domain")]
+    DomainMismatch,
+    #[error("report data is anchored to another block")]
+    BlockHashMismatch,
+    #[error("report data does not bind the payload hash")]
+    PayloadHashMismatch,
+    #[error("digest of {len} bytes does not fit the {REPORT_DATA_SIZE} bytes of report data")]
+    DigestTooLong { len: usize },
+    #[error("report data does not commit to the payload")]
+    CommitmentMismatch,
+    #[error("report data does not commit to the claims")]
+    ClaimsMismatch,
+}
+
+/// Reads report data from `data`, which must be exactly [`REPORT_DATA_SIZE`] bytes, or longer
+/// with only zero bytes past the first [`REPORT_DATA_SIZE`]. This tolerates producers that pad
+/// the report data, without silently dropping any data.
+pub fn from_slice(data: &[u8]) -> Result<[u8; REPORT_DATA_SIZE], ReportDataError> {
+    let (report_data, padding) = data
+        .split_first_chunk::<REPORT_DATA_SIZE>()
+        .ok_or(ReportDataError::TooShort { len: data.len() })?;
+
+    if let Some(position) = padding.iter().position(|byte| *byte != 0) {
+        return Err(ReportDataError::NonZeroTrailingBytes {
+            offset: REPORT_DATA_SIZE.saturating_add(position),
+        });
+    }
+
+    Ok(*report_data)
+}
+
+/// Returns the measurement of the TDX service TD (`MRSERVICETD`) reported alongside the TD, which
+/// identifies the TDX module's service TDs. Only TD 1.5 reports carry it, so this is `None` for
+/// TD 1.0 and SGX reports.
+pub fn mr_service_td(report: &Report) -> Option<[u8; 48]> {
+    match report {
+        Report::TD15(report) => Some(report.mr_service_td),
+        Report::TD10(_) | Report::SgxEnclave(_) => None,
+    }
+}
+
+/// Returns the `MRCONFIGID` of a TD report, or `None` for SGX reports. It is set by the host
+/// when the TD is created, e.g. to a tenant identifier in multi-tenant deployments.
+pub fn mr_config_id(report: &Report) -> Option<[u8; 48]> {
+    match report {
+        Report::TD10(report) => Some(report.mr_config_id),
+        Report::TD15(report) => Some(report.base.mr_config_id),
+        Report::SgxEnclave(_) => None,
+    }
+}
+
+/// Returns the `MROWNER` of a TD report, or `None` for SGX reports. It is set by the host when
+/// the TD is created, e.g. to the hash of the key the TD is owned by.
+pub fn mr_owner(report: &Report) -> Option<[u8; 48]> {
+    match report {
+        Report::TD10(report) => Some(report.mr_owner),
+        Report::TD15(report) => Some(report.base.mr_owner),
+        Report::SgxEnclave(_) => None,
+    }
+}
+
+/// Returns the SVN of the TDX module (SEAM) a TD runs on, or `None` for SGX reports. The major
+/// version of the module is in the high byte and its SVN within that version in the low byte,
+/// see [`TeeTcbSvn::tdx_module_major_version`] and [`TeeTcbSvn::tdx_module_svn`], so that a
+/// newer module always has a higher SEAM SVN.
+pub fn seam_svn(report: &Report) -> Option<u16> {
+    tee_tcb_svn(report)
+        .map(|svn| u16::from_be_bytes([svn.tdx_module_major_version(), svn.tdx_module_svn()]))
+}
+
+/// Returns the TDX module TCB SVN (`TEE_TCB_SVN`) of a TD report, or `None` for SGX reports.
+/// See [`crate::tcb_status::evaluate_tdx_module_tcb`].
+pub fn tee_tcb_svn(report: &Report) -> Option<TeeTcbSvn> {
+    match report {
+        Report::TD10(report) => Some(TeeTcbSvn::from_report_bytes(report.tee_tcb_svn)),
+        Report::TD15(report) => Some(TeeTcbSvn::from_report_bytes(report.base.tee_tcb_svn)),
+        Report::SgxEnclave(_) => None,
+    }
 }
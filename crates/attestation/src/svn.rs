@@ -0,0 +1,78 @@
+//! Security version numbers (SVNs) of the TCB components reported in a quote.
+//!
+//! Multi-component SVNs are compared component-wise, as Intel specifies for TCB level
+//! matching: an SVN is at least a TCB level only if every component is. Two SVNs where
+//! some components are higher and others lower are not comparable, so [`TeeTcbSvn`] implements
+//! [`PartialOrd`], but not [`Ord`].
+//!
+//! The CPU and PCE SVNs of the platform are not in the TD report, but in the PCK certificate,
+//! see [`PckSvn`].
+
+use core::cmp::Ordering;
+
+/// Number of components of a [`TeeTcbSvn`].
+pub const SVN_COMPONENTS: usize = 16;
+
+/// SVN of the TDX module and its dependencies, `TEE_TCB_SVN` of a TD report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TeeTcbSvn([u8; SVN_COMPONENTS]);
+
+impl TeeTcbSvn {
+    /// Reads the SVN from its 16 report bytes. Each byte is one component, in TCB info order,
+    /// so there is no byte order to account for.
+    pub fn from_report_bytes(bytes: [u8; SVN_COMPONENTS]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn components(&self) -> &[u8; SVN_COMPONENTS] {
+        &self.0
+    }
+
+    /// The SVN of the TDX module within its major version, the first component.
+    pub fn tdx_module_svn(&self) -> u8 {
+        self.0[0]
+    }
+
+    /// The major version of the TDX module, the second component. It is zero for TDX modules
+    /// that predate TDX module identities in the TCB info.
+    pub fn tdx_module_major_version(&self) -> u8 {
+        self.0[1]
+    }
+
+    /// Returns the SVN with the first two components, which identify the TDX module itself, set
+    /// to zero. TCB levels of versioned TDX modules are matched on the remaining components.
+    pub fn without_tdx_module(&self) -> Self {
+        let mut components = self.0;
+        components[..2].fill(0);
+        Self(components)
+    }
+}
+
+/// SGX SVNs of the platform, from the Intel SGX extension of its PCK certificate. TCB levels
+/// are matched on these along with the [`TeeTcbSvn`], see
+/// [`crate::tcb_status::compare_tdx_module_tcb_levels`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PckSvn {
+    /// The CPU SVN, one byte per component, in TCB info `sgxtcbcomponents` order.
+    pub cpu_svn: [u8; SVN_COMPONENTS],
+    pub pce_svn: u16,
+}
+
+impl PartialOrd for TeeTcbSvn {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        componentwise_cmp(&self.0, &other.0)
+    }
+}
+
+fn componentwise_cmp(a: &[u8; SVN_COMPONENTS], b: &[u8; SVN_COMPONENTS]) -> Option<Ordering> {
+    a.iter()
+        .zip(b)
+        .try_fold(Ordering::Equal, |ordering, (a, b)| {
+            match (ordering, a.cmp(b)) {
+                (ordering, Ordering::Equal) => Some(ordering),
+                (Ordering::Equal, component) => Some(component),
+                (ordering, component) if ordering == component => Some(ordering),
+                _ => None,
+            }
+        })
+}
@@ -39,6 +39,9 @@ pub struct EventLog {
     pub event_payload: String,
 }
 
+/// Exactly `N` bytes, hex encoded in serde. Both the serde and the Borsh representations are
+/// backed by a `[u8; N]`, so truncated or oversized input fails to deserialize rather than
+/// producing a value of the wrong length.
 #[serde_as]
 #[derive(
     Debug,
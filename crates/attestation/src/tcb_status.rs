@@ -0,0 +1,719 @@
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::{fmt, str::FromStr};
+use dcap_qvl::{intel::parse_pck_extension, quote::Report, verify::VerifiedReport};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use x509_cert::der::Encode;
+
+use crate::{
+    attestation::OrErr as _,
+    collateral::{Certificates, Collateral},
+    report_data::tee_tcb_svn,
+    svn::{PckSvn, SVN_COMPONENTS, TeeTcbSvn},
+};
+
+/// TCB status of a platform, as reported by Intel in the TCB info and returned by DCAP
+/// verification. Variant names match Intel's strings exactly.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum TcbStatus {
+    /// The platform is patched to the latest TCB level.
+    UpToDate,
+    /// The platform is up to date, but software hardening is needed to mitigate the listed
+    /// advisories.
+    SWHardeningNeeded,
+    /// The platform is up to date, but additional configuration is needed.
+    ConfigurationNeeded,
+    /// The platform is up to date, but both additional configuration and software hardening
+    /// are needed.
+    ConfigurationAndSWHardeningNeeded,
+    /// The platform is not patched to the latest TCB level.
+    OutOfDate,
+    /// The platform is not patched to the latest TCB level and additional configuration is
+    /// needed.
+    OutOfDateConfigurationNeeded,
+    /// The platform's TCB level has been revoked.
+    Revoked,
+}
+
+impl TcbStatus {
+    pub const ALL: [TcbStatus; 7] = [
+        TcbStatus::UpToDate,
+        TcbStatus::SWHardeningNeeded,
+        TcbStatus::ConfigurationNeeded,
+        TcbStatus::ConfigurationAndSWHardeningNeeded,
+        TcbStatus::OutOfDate,
+        TcbStatus::OutOfDateConfigurationNeeded,
+        TcbStatus::Revoked,
+    ];
+
+    /// Intel's string for this status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TcbStatus::UpToDate => "UpToDate",
+            TcbStatus::SWHardeningNeeded => "SWHardeningNeeded",
+            TcbStatus::ConfigurationNeeded => "ConfigurationNeeded",
+            TcbStatus::ConfigurationAndSWHardeningNeeded => "ConfigurationAndSWHardeningNeeded",
+            TcbStatus::OutOfDate => "OutOfDate",
+            TcbStatus::OutOfDateConfigurationNeeded => "OutOfDateConfigurationNeeded",
+            TcbStatus::Revoked => "Revoked",
+        }
+    }
+}
+
+impl fmt::Display for TcbStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown TCB status `{0}`")]
+pub struct UnknownTcbStatus(pub String);
+
+impl FromStr for TcbStatus {
+    type Err = UnknownTcbStatus;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TcbStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| UnknownTcbStatus(String::from(s)))
+    }
+}
+
+/// An operator's rules for accepting the TCB of an attested platform: the TCB statuses it
+/// accepts, and which of the Intel security advisories reported along with a status it accepts.
+///
+/// The default policy only accepts [`TcbStatus::UpToDate`].
+///
+/// ```
+/// use attestation::tcb_status::{TcbPolicy, TcbStatus};
+///
+/// let policy = TcbPolicy::allowing([TcbStatus::UpToDate, TcbStatus::SWHardeningNeeded])
+///     .deny_advisory("INTEL-SA-00837");
+///
+/// assert!(policy.is_acceptable(TcbStatus::SWHardeningNeeded, &["INTEL-SA-00615".into()]));
+/// assert!(!policy.is_acceptable(TcbStatus::SWHardeningNeeded, &["INTEL-SA-00837".into()]));
+/// assert!(!policy.is_acceptable(TcbStatus::OutOfDate, &[]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TcbPolicy {
+    /// Accepted TCB statuses.
+    pub allowed_statuses: Vec<TcbStatus>,
+    /// If set, only these advisories are accepted. Defaults to `None`, accepting any advisory
+    /// not in [`TcbPolicy::denied_advisories`].
+    pub allowed_advisories: Option<Vec<String>>,
+    /// Advisories that are never accepted, regardless of the status.
+    pub denied_advisories: Vec<String>,
+}
+
+impl Default for TcbPolicy {
+    fn default() -> Self {
+        Self::allowing([TcbStatus::UpToDate])
+    }
+}
+
+impl TcbPolicy {
+    /// A policy accepting `statuses`, along with any advisory reported for them.
+    pub fn allowing(statuses: impl IntoIterator<Item = TcbStatus>) -> Self {
+        Self {
+            allowed_statuses: statuses.into_iter().collect(),
+            allowed_advisories: None,
+            denied_advisories: Vec::new(),
+        }
+    }
+
+    /// Adds `status` to [`TcbPolicy::allowed_statuses`], if not already allowed.
+    pub fn allow_status(mut self, status: TcbStatus) -> Self {
+        if !self.allowed_statuses.contains(&status) {
+            self.allowed_statuses.push(status);
+        }
+        self
+    }
+
+    /// Adds `advisory` to [`TcbPolicy::allowed_advisories`], restricting accepted advisories
+    /// to the allowlist if this is the first one.
+    pub fn allow_advisory(mut self, advisory: impl Into<String>) -> Self {
+        self.allowed_advisories
+            .get_or_insert_with(Vec::new)
+            .push(advisory.into());
+        self
+    }
+
+    /// Adds `advisory` to [`TcbPolicy::denied_advisories`].
+    pub fn deny_advisory(mut self, advisory: impl Into<String>) -> Self {
+        self.denied_advisories.push(advisory.into());
+        self
+    }
+
+    /// Whether `status` is one of [`TcbPolicy::allowed_statuses`].
+    pub fn allows_status(&self, status: TcbStatus) -> bool {
+        self.allowed_statuses.contains(&status)
+    }
+
+    /// Whether `advisory` is accepted, i.e. it is not denied and, if there is an allowlist, in
+    /// the allowlist.
+    pub fn allows_advisory(&self, advisory: &str) -> bool {
+        !self
+            .denied_advisories
+            .iter()
+            .any(|denied| denied == advisory)
+            && self
+                .allowed_advisories
+                .as_ref()
+                .is_none_or(|allowed| allowed.iter().any(|allowed| allowed == advisory))
+    }
+
+    /// Whether a platform with TCB `status` and outstanding `advisories` is acceptable.
+    ///
+    /// Advisories are never acceptable along with [`TcbStatus::UpToDate`], as an up to date
+    /// platform has none; Intel reporting some indicates inconsistent collateral.
+    pub fn is_acceptable(&self, status: TcbStatus, advisories: &[String]) -> bool {
+        self.allows_status(status)
+            && (advisories.is_empty()
+                || (status != TcbStatus::UpToDate
+                    && advisories
+                        .iter()
+                        .all(|advisory| self.allows_advisory(advisory))))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TcbEvaluationError {
+    #[error("invalid TCB info: {0}")]
+    InvalidTcbInfo(String),
+    #[error("SVN is below every TCB level")]
+    NoMatchingTcbLevel,
+    #[error("report is not a TD report")]
+    NotTdReport,
+    #[error("invalid PCK certificate: {0}")]
+    InvalidPckCertificate(String),
+    #[error("TDX module does not match the TDX module identity `{0}` of the TCB info")]
+    TdxModuleMismatch(String),
+}
+
+/// Reads the SGX SVNs of the platform from its PCK certificate, the first certificate of
+/// `pck_chain`, see [`Certificates::parse_any`].
+///
+/// # Errors
+///
+/// Returns [`TcbEvaluationError::InvalidPckCertificate`] if the chain is malformed or the PCK
+/// certificate has no readable Intel SGX extension.
+pub fn pck_svn(pck_chain: &[u8]) -> Result<PckSvn, TcbEvaluationError> {
+    let invalid = |reason: String| TcbEvaluationError::InvalidPckCertificate(reason);
+
+    let pck = Certificates::parse_any(pck_chain)
+        .map_err(|e| invalid(e.to_string()))?
+        .first()
+        .map(Encode::to_der)
+        .transpose()
+        .map_err(|e| invalid(e.to_string()))?
+        .ok_or_else(|| invalid("empty PCK certificate chain".to_string()))?;
+    let extension = parse_pck_extension(&pck).map_err(|e| invalid(e.to_string()))?;
+
+    Ok(PckSvn {
+        cpu_svn: extension.cpu_svn,
+        pce_svn: extension.pce_svn,
+    })
+}
+
+/// Evaluates the TDX TCB status of a TD report on a platform with the SGX SVNs `pck_svn`
+/// against the TCB info JSON of the collateral, as Intel's quote verification library does:
+///
+/// 1. the TCB level is the first (i.e. highest) level of the TCB info whose SGX and TDX
+///    components the platform is at, see [`compare_tdx_module_tcb_levels`];
+/// 2. a TDX module with a non-zero major version has its own TCB status, from the TDX module
+///    identities of the TCB info, see [`evaluate_tdx_module_identity`], which lowers the status
+///    of the level if the module is out of date or revoked, see
+///    [`converge_tdx_module_status`].
+///
+/// # Errors
+///
+/// Returns [`TcbEvaluationError::NotTdReport`] if the report is not a TD report,
+/// [`TcbEvaluationError::NoMatchingTcbLevel`] if the platform or the TDX module is below every
+/// TCB level, [`TcbEvaluationError::TdxModuleMismatch`] if the TDX module does not match its
+/// identity, and [`TcbEvaluationError::InvalidTcbInfo`] if the TCB info is malformed.
+pub fn evaluate_tdx_module_tcb(
+    report: &Report,
+    pck_svn: &PckSvn,
+    tcb_info: &str,
+) -> Result<TcbStatus, TcbEvaluationError> {
+    let tee_tcb_svn = tee_tcb_svn(report).ok_or(TcbEvaluationError::NotTdReport)?;
+    let level_status = compare_tdx_module_tcb_levels(pck_svn, &tee_tcb_svn, tcb_info)?
+        .into_iter()
+        .find(|level| level.passed)
+        .map(|level| level.status)
+        .ok_or(TcbEvaluationError::NoMatchingTcbLevel)?;
+    let module_status = evaluate_tdx_module_identity(report, tcb_info)?;
+
+    Ok(module_status.map_or(level_status, |module_status| {
+        converge_tdx_module_status(level_status, module_status)
+    }))
+}
+
+/// Evaluates the TDX module of a TD report against the TDX module identities of the TCB info
+/// JSON, returning the status of the first (i.e. highest) TCB level of its identity whose
+/// `isvsvn` is at most the module SVN, see [`TeeTcbSvn::tdx_module_svn`].
+///
+/// The identity of a module is the one with the id `TDX_<major version>`, e.g. `TDX_01`, whose
+/// `mrsigner` and masked `attributes` must match the `MRSIGNER_SEAM` and `SEAM_ATTRIBUTES` of
+/// the report. Modules with major version zero predate module identities: they are matched
+/// against the `tdxModule` of the TCB info instead, and have no TCB status of their own, so
+/// `None` is returned for them.
+///
+/// # Errors
+///
+/// Returns [`TcbEvaluationError::TdxModuleMismatch`] if the TCB info has no identity for the
+/// module or it does not match, [`TcbEvaluationError::NoMatchingTcbLevel`] if the module SVN
+/// is below every TCB level of its identity, [`TcbEvaluationError::NotTdReport`] if the report
+/// is not a TD report, and [`TcbEvaluationError::InvalidTcbInfo`] if the TCB info is
+/// malformed.
+pub fn evaluate_tdx_module_identity(
+    report: &Report,
+    tcb_info: &str,
+) -> Result<Option<TcbStatus>, TcbEvaluationError> {
+    let invalid = |reason: &str| TcbEvaluationError::InvalidTcbInfo(reason.to_string());
+
+    let td_report = match report {
+        Report::TD10(report) => report,
+        Report::TD15(report) => &report.base,
+        Report::SgxEnclave(_) => return Err(TcbEvaluationError::NotTdReport),
+    };
+    let tee_tcb_svn = TeeTcbSvn::from_report_bytes(td_report.tee_tcb_svn);
+    let tcb_info: Value = serde_json::from_str(tcb_info).map_err(|e| invalid(&e.to_string()))?;
+
+    let major_version = tee_tcb_svn.tdx_module_major_version();
+    if major_version == 0 {
+        let module = tcb_info
+            .get("tdxModule")
+            .ok_or_else(|| invalid("missing tdxModule"))?;
+        tdx_module_matches(
+            module,
+            &td_report.mr_signer_seam,
+            &td_report.seam_attributes,
+        )?
+        .or_err(|| TcbEvaluationError::TdxModuleMismatch("tdxModule".to_string()))?;
+        return Ok(None);
+    }
+
+    let id = format!("TDX_{major_version:02X}");
+    let identity = tcb_info
+        .get("tdxModuleIdentities")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing tdxModuleIdentities"))?
+        .iter()
+        .find(|identity| identity.get("id").and_then(Value::as_str) == Some(id.as_str()))
+        .ok_or_else(|| TcbEvaluationError::TdxModuleMismatch(id.clone()))?;
+    tdx_module_matches(
+        identity,
+        &td_report.mr_signer_seam,
+        &td_report.seam_attributes,
+    )?
+    .or_err(|| TcbEvaluationError::TdxModuleMismatch(id.clone()))?;
+
+    let tcb_levels = identity
+        .get("tcbLevels")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing TDX module identity tcbLevels"))?;
+    for level in tcb_levels {
+        let isv_svn = level
+            .pointer("/tcb/isvsvn")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| invalid("missing TDX module identity isvsvn"))?;
+
+        if u64::from(tee_tcb_svn.tdx_module_svn()) >= isv_svn {
+            return level
+                .get("tcbStatus")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("missing tcbStatus"))?
+                .parse()
+                .map(Some)
+                .map_err(|e: UnknownTcbStatus| invalid(&e.to_string()));
+        }
+    }
+
+    Err(TcbEvaluationError::NoMatchingTcbLevel)
+}
+
+/// Whether a TDX module with `mr_signer_seam` and `seam_attributes` matches `module`, a
+/// `tdxModule` or TDX module identity of the TCB info, i.e. it has the `mrsigner` and, under
+/// `attributesMask`, the `attributes` of `module`.
+fn tdx_module_matches(
+    module: &Value,
+    mr_signer_seam: &[u8; 48],
+    seam_attributes: &[u8; 8],
+) -> Result<bool, TcbEvaluationError> {
+    let field = |name: &str| {
+        module
+            .get(name)
+            .and_then(Value::as_str)
+            .and_then(|field| hex::decode(field).ok())
+            .ok_or_else(|| TcbEvaluationError::InvalidTcbInfo(format!("invalid TDX module {name}")))
+    };
+
+    let mr_signer = field("mrsigner")?;
+    let attributes = field("attributes")?;
+    let attributes_mask = field("attributesMask")?;
+    if attributes.len() != seam_attributes.len() || attributes_mask.len() != seam_attributes.len() {
+        return Err(TcbEvaluationError::InvalidTcbInfo(
+            "expected 8 bytes of TDX module attributes".to_string(),
+        ));
+    }
+
+    Ok(mr_signer == mr_signer_seam
+        && seam_attributes
+            .iter()
+            .zip(attributes_mask)
+            .zip(attributes)
+            .all(|((reported, mask), expected)| reported & mask == expected))
+}
+
+/// Lowers the TCB status of a TCB level by the TCB status of the TDX module, as Intel's quote
+/// verification library does: an out of date module makes the level out of date, still
+/// needing configuration if it did, and a revoked module revokes it. Any other module status
+/// leaves the status of the level.
+pub fn converge_tdx_module_status(level_status: TcbStatus, module_status: TcbStatus) -> TcbStatus {
+    match (level_status, module_status) {
+        (_, TcbStatus::Revoked) => TcbStatus::Revoked,
+        (TcbStatus::UpToDate | TcbStatus::SWHardeningNeeded, TcbStatus::OutOfDate) => {
+            TcbStatus::OutOfDate
+        }
+        (
+            TcbStatus::ConfigurationNeeded | TcbStatus::ConfigurationAndSWHardeningNeeded,
+            TcbStatus::OutOfDate,
+        ) => TcbStatus::OutOfDateConfigurationNeeded,
+        (level_status, _) => level_status,
+    }
+}
+
+/// One SVN component of a TCB level compared against the reported SVN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvnComparison {
+    /// Index of the component in the TCB info, and in the reported SVN.
+    pub index: usize,
+    pub reported: u8,
+    /// SVN of the component in the TCB level, the minimum the reported SVN must reach.
+    pub threshold: u8,
+    pub passed: bool,
+}
+
+/// The PCE SVN of the platform compared against the `pcesvn` of a TCB level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PceSvnComparison {
+    pub reported: u16,
+    /// PCE SVN of the TCB level, the minimum the reported SVN must reach.
+    pub threshold: u16,
+    pub passed: bool,
+}
+
+/// A TCB level of the TCB info, compared component by component against the reported SVNs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcbLevelComparison {
+    pub status: TcbStatus,
+    /// The CPU SVN components compared against the `sgxtcbcomponents` of the level.
+    pub sgx_components: Vec<SvnComparison>,
+    pub pce_svn: PceSvnComparison,
+    /// The TDX components compared against the `tdxtcbcomponents` of the level. Those which
+    /// identify a versioned TDX module are evaluated against its identity instead, see
+    /// [`evaluate_tdx_module_identity`], and are left out.
+    pub components: Vec<SvnComparison>,
+    /// Whether every component passed, i.e. the platform is at this TCB level.
+    pub passed: bool,
+}
+
+/// Compares the SGX SVNs `pck_svn` and the TDX SVN `tee_tcb_svn` of a platform against every
+/// TCB level of the TCB info JSON, from the highest level down to the first level it passes,
+/// which is the level [`evaluate_tdx_module_tcb`] picks. If no level passes, every level is
+/// returned.
+///
+/// A platform passes a level if its CPU SVN is at least the `sgxtcbcomponents`, its PCE SVN at
+/// least the `pcesvn` and its TDX SVN at least the `tdxtcbcomponents` of the level, every
+/// multi-component SVN compared component-wise.
+pub fn compare_tdx_module_tcb_levels(
+    pck_svn: &PckSvn,
+    tee_tcb_svn: &TeeTcbSvn,
+    tcb_info: &str,
+) -> Result<Vec<TcbLevelComparison>, TcbEvaluationError> {
+    let invalid = |reason: &str| TcbEvaluationError::InvalidTcbInfo(reason.to_string());
+
+    let tcb_info: Value = serde_json::from_str(tcb_info).map_err(|e| invalid(&e.to_string()))?;
+    let tcb_levels = tcb_info
+        .get("tcbLevels")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing tcbLevels"))?;
+
+    // The first two components identify a versioned TDX module, and are not compared.
+    let first_compared = if tee_tcb_svn.tdx_module_major_version() > 0 {
+        2
+    } else {
+        0
+    };
+
+    let mut comparisons = Vec::new();
+    for level in tcb_levels {
+        let sgx_thresholds = level_components(level, "sgxtcbcomponents")?;
+        let tdx_thresholds = level_components(level, "tdxtcbcomponents")?;
+        let pce_svn_threshold = level
+            .pointer("/tcb/pcesvn")
+            .and_then(Value::as_u64)
+            .and_then(|svn| u16::try_from(svn).ok())
+            .ok_or_else(|| invalid("invalid pcesvn"))?;
+        let status = level
+            .get("tcbStatus")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("missing tcbStatus"))?
+            .parse()
+            .map_err(|e: UnknownTcbStatus| invalid(&e.to_string()))?;
+
+        let sgx_components = compare_components(&pck_svn.cpu_svn, sgx_thresholds, 0);
+        let pce_svn = PceSvnComparison {
+            reported: pck_svn.pce_svn,
+            threshold: pce_svn_threshold,
+            passed: pck_svn.pce_svn >= pce_svn_threshold,
+        };
+        let components =
+            compare_components(tee_tcb_svn.components(), tdx_thresholds, first_compared);
+        let passed = pce_svn.passed
+            && sgx_components
+                .iter()
+                .chain(&components)
+                .all(|component| component.passed);
+        comparisons.push(TcbLevelComparison {
+            status,
+            sgx_components,
+            pce_svn,
+            components,
+            passed,
+        });
+        if passed {
+            break;
+        }
+    }
+
+    Ok(comparisons)
+}
+
+/// Reads the 16 component SVNs `name`, `sgxtcbcomponents` or `tdxtcbcomponents`, of a TCB
+/// level.
+fn level_components(level: &Value, name: &str) -> Result<[u8; SVN_COMPONENTS], TcbEvaluationError> {
+    let invalid = |reason: String| TcbEvaluationError::InvalidTcbInfo(reason);
+
+    let thresholds: Vec<u8> = level
+        .get("tcb")
+        .and_then(|tcb| tcb.get(name))
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid(format!("missing {name}")))?
+        .iter()
+        .map(|component| {
+            component
+                .get("svn")
+                .and_then(Value::as_u64)
+                .and_then(|svn| u8::try_from(svn).ok())
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(|| invalid(format!("invalid {name} svn")))?;
+    <[u8; SVN_COMPONENTS]>::try_from(thresholds).map_err(|_| invalid(format!("expected 16 {name}")))
+}
+
+fn compare_components(
+    reported: &[u8; SVN_COMPONENTS],
+    thresholds: [u8; SVN_COMPONENTS],
+    first_compared: usize,
+) -> Vec<SvnComparison> {
+    reported
+        .iter()
+        .zip(thresholds)
+        .enumerate()
+        .skip(first_compared)
+        .map(|(index, (reported, threshold))| SvnComparison {
+            index,
+            reported: *reported,
+            threshold,
+            passed: *reported >= threshold,
+        })
+        .collect()
+}
+
+/// Every SVN comparison behind the TCB status of a verified report, see [`explain_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcbExplanation {
+    /// The platform TCB status determined by DCAP verification.
+    pub platform_status: TcbStatus,
+    /// The TCB levels compared, see [`compare_tdx_module_tcb_levels`].
+    pub tdx_module_levels: Vec<TcbLevelComparison>,
+    /// The TDX TCB status, see [`evaluate_tdx_module_tcb`]: that of the last level if it
+    /// passed, lowered by the TDX module TCB status, `None` if no level did.
+    pub tdx_module_status: Option<TcbStatus>,
+}
+
+/// Lists the SVN comparisons that determine the TCB status of `report`, on a platform with the
+/// SGX SVNs `pck_svn`, see [`pck_svn`], to see e.g. which component is below the threshold of
+/// a platform reported as [`TcbStatus::OutOfDate`].
+///
+/// # Errors
+///
+/// Returns [`TcbEvaluationError::InvalidTcbInfo`] if the status of the report or the TCB info
+/// of `collateral` cannot be read, [`TcbEvaluationError::NotTdReport`] if the report is not a
+/// TD report, and the errors of [`evaluate_tdx_module_identity`] for the TDX module of a
+/// passed level.
+pub fn explain_status(
+    report: &VerifiedReport,
+    pck_svn: &PckSvn,
+    collateral: &Collateral,
+) -> Result<TcbExplanation, TcbEvaluationError> {
+    let platform_status = report
+        .status
+        .parse()
+        .map_err(|e: UnknownTcbStatus| TcbEvaluationError::InvalidTcbInfo(e.to_string()))?;
+    let tee_tcb_svn = tee_tcb_svn(&report.report).ok_or(TcbEvaluationError::NotTdReport)?;
+    let tdx_module_levels =
+        compare_tdx_module_tcb_levels(pck_svn, &tee_tcb_svn, &collateral.tcb_info)?;
+    let tdx_module_status = match tdx_module_levels.last().filter(|level| level.passed) {
+        Some(level) => Some(
+            evaluate_tdx_module_identity(&report.report, &collateral.tcb_info)?
+                .map_or(level.status, |module_status| {
+                    converge_tdx_module_status(level.status, module_status)
+                }),
+        ),
+        None => None,
+    };
+
+    Ok(TcbExplanation {
+        platform_status,
+        tdx_module_levels,
+        tdx_module_status,
+    })
+}
+
+/// Returns the TCB status and advisory IDs of every TCB level of the TCB info JSON of the
+/// collateral, in the order of the TCB info, i.e. from the highest level down.
+///
+/// Platforms at a lower level are affected by that level's advisories, so this shows which
+/// additional advisories are accepted along with a lower TCB status. Levels without
+/// `advisoryIDs` have none.
+pub fn advisories_by_level(
+    tcb_info: &str,
+) -> Result<Vec<(TcbStatus, Vec<String>)>, TcbEvaluationError> {
+    let invalid = |reason: &str| TcbEvaluationError::InvalidTcbInfo(reason.to_string());
+
+    let tcb_info: Value = serde_json::from_str(tcb_info).map_err(|e| invalid(&e.to_string()))?;
+    let tcb_levels = tcb_info
+        .get("tcbLevels")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing tcbLevels"))?;
+
+    tcb_levels
+        .iter()
+        .map(|level| {
+            let status = level
+                .get("tcbStatus")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("missing tcbStatus"))?
+                .parse()
+                .map_err(|e: UnknownTcbStatus| invalid(&e.to_string()))?;
+            let advisories = match level.get("advisoryIDs") {
+                None => Vec::new(),
+                Some(advisories) => advisories
+                    .as_array()
+                    .and_then(|advisories| {
+                        advisories
+                            .iter()
+                            .map(|advisory| advisory.as_str().map(String::from))
+                            .collect()
+                    })
+                    .ok_or_else(|| invalid("invalid advisoryIDs"))?,
+            };
+            Ok((status, advisories))
+        })
+        .collect()
+}
+
+/// Returns the advisory IDs a platform at TCB status `lower` is affected by, but one at
+/// `higher` is not, sorted and without duplicates. This lists the advisories accepted along
+/// with `lower`, e.g. when accepting it during a grace window.
+///
+/// A status can label several TCB levels, e.g. [`TcbStatus::OutOfDate`], so the advisories of
+/// a status are those of every level with that status, see [`advisories_by_level`]. A status
+/// with no level in the TCB info has no advisories.
+///
+/// # Errors
+///
+/// Returns [`TcbEvaluationError::InvalidTcbInfo`] if the TCB info of `collateral` is malformed.
+pub fn advisory_delta(
+    higher: TcbStatus,
+    lower: TcbStatus,
+    collateral: &Collateral,
+) -> Result<Vec<String>, TcbEvaluationError> {
+    let levels = advisories_by_level(&collateral.tcb_info)?;
+    let advisories_of = |status: TcbStatus| -> BTreeSet<&String> {
+        levels
+            .iter()
+            .filter(|(level_status, _)| *level_status == status)
+            .flat_map(|(_, advisories)| advisories)
+            .collect()
+    };
+
+    let higher = advisories_of(higher);
+    Ok(advisories_of(lower)
+        .into_iter()
+        .filter(|advisory| !higher.contains(advisory))
+        .cloned()
+        .collect())
+}
+
+/// Evaluates the ISV SVN of the Quoting Enclave against the QE identity JSON of the
+/// collateral, returning the status of the first (i.e. highest) TCB level whose `isvsvn` is at
+/// most `qe_isv_svn`. See [`crate::quote::qe_isv_svn`].
+///
+/// This is the QE TCB status, which is independent of the platform TCB status evaluated from
+/// the TCB info.
+pub fn evaluate_qe_tcb(
+    qe_isv_svn: u16,
+    qe_identity: &str,
+) -> Result<TcbStatus, TcbEvaluationError> {
+    let invalid = |reason: &str| TcbEvaluationError::InvalidTcbInfo(reason.to_string());
+
+    let qe_identity: Value =
+        serde_json::from_str(qe_identity).map_err(|e| invalid(&e.to_string()))?;
+    let tcb_levels = qe_identity
+        .get("tcbLevels")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing tcbLevels"))?;
+
+    for level in tcb_levels {
+        let isv_svn = level
+            .pointer("/tcb/isvsvn")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| invalid("missing isvsvn"))?;
+
+        if u64::from(qe_isv_svn) >= isv_svn {
+            return level
+                .get("tcbStatus")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("missing tcbStatus"))?
+                .parse()
+                .map_err(|e: UnknownTcbStatus| invalid(&e.to_string()));
+        }
+    }
+
+    Err(TcbEvaluationError::NoMatchingTcbLevel)
+}
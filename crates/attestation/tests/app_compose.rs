@@ -1,7 +1,18 @@
 use dstack_sdk_types::dstack::TcbInfo as DstackTcbInfo;
 use serde_json::Value;
 
-use attestation::app_compose::AppCompose;
+#[cfg(any(feature = "docker-compose", feature = "std"))]
+use assert_matches::assert_matches;
+#[cfg(any(feature = "docker-compose", feature = "std"))]
+use attestation::app_compose::AppComposeError;
+#[cfg(feature = "std")]
+use attestation::app_compose::reproduce_and_compare;
+use attestation::app_compose::{
+    AppCompose, DEFAULT_REGISTRY, affected_measurements, canonicalize, registry_host,
+};
+#[cfg(feature = "docker-compose")]
+use attestation::app_compose::{validate, validate_registries};
+use attestation::measurements::MeasurementField;
 use test_utils::attestation::{
     TEST_APP_COMPOSE_STRING, TEST_APP_COMPOSE_WITH_SERVICES_STRING,
     TEST_LAUNCHER_IMAGE_COMPOSE_STRING, TEST_TCB_INFO_STRING,
@@ -48,3 +59,320 @@ fn test_launcher_compose_from_app_compose() {
         .unwrap();
     assert_eq!(launcher_compose, TEST_LAUNCHER_IMAGE_COMPOSE_STRING);
 }
+
+#[test]
+fn test_affected_measurements_identical_compose() {
+    let app_compose: AppCompose = serde_json::from_str(TEST_APP_COMPOSE_STRING).unwrap();
+
+    let affected = affected_measurements(&app_compose, &app_compose.clone());
+
+    assert!(affected.is_empty());
+}
+
+#[test]
+fn test_affected_measurements_docker_compose_change() {
+    let old: AppCompose = serde_json::from_str(TEST_APP_COMPOSE_STRING).unwrap();
+    let new = AppCompose {
+        docker_compose_file: String::from("services: {}").into(),
+        ..old.clone()
+    };
+
+    let affected = affected_measurements(&old, &new);
+
+    assert!(affected.app_compose_hash_payload);
+    assert!(!affected.key_provider_event_digest);
+    assert!(affected.rtmr3);
+    assert_eq!(
+        affected.fields(),
+        vec![MeasurementField::AppComposeHashPayload]
+    );
+}
+
+#[test]
+fn test_affected_measurements_key_provider_change() {
+    let old: AppCompose = serde_json::from_str(TEST_APP_COMPOSE_STRING).unwrap();
+    let new = AppCompose {
+        kms_enabled: true,
+        local_key_provider_enabled: false,
+        ..old.clone()
+    };
+
+    let affected = affected_measurements(&old, &new);
+
+    assert_eq!(
+        affected.fields(),
+        vec![
+            MeasurementField::KeyProviderEventDigest,
+            MeasurementField::AppComposeHashPayload,
+        ]
+    );
+}
+
+fn minimal_app_compose() -> AppCompose {
+    AppCompose {
+        manifest_version: 2,
+        name: String::from("app"),
+        runner: String::from("docker-compose"),
+        docker_compose_file: String::from("services:\n  app: {}\n").into(),
+        kms_enabled: true,
+        tproxy_enabled: None,
+        gateway_enabled: Some(false),
+        public_logs: true,
+        public_sysinfo: false,
+        local_key_provider_enabled: false,
+        key_provider_id: None,
+        allowed_envs: vec![String::from("B"), String::from("A")],
+        no_instance_id: false,
+        secure_time: None,
+        pre_launch_script: None,
+    }
+}
+
+#[test]
+fn test_canonicalize_sorts_keys_without_whitespace() {
+    let expected = concat!(
+        r#"{"allowed_envs":["B","A"],"#,
+        r#""docker_compose_file":"services:\n  app: {}\n","#,
+        r#""gateway_enabled":false,"key_provider_id":null,"kms_enabled":true,"#,
+        r#""local_key_provider_enabled":false,"manifest_version":2,"name":"app","#,
+        r#""no_instance_id":false,"pre_launch_script":null,"public_logs":true,"#,
+        r#""public_sysinfo":false,"runner":"docker-compose","secure_time":null,"#,
+        r#""tproxy_enabled":null}"#,
+    );
+
+    assert_eq!(canonicalize(&minimal_app_compose()).unwrap(), expected);
+}
+
+#[test]
+fn test_canonicalize_is_independent_of_input_formatting() {
+    let app_compose: AppCompose = serde_json::from_str(TEST_APP_COMPOSE_STRING).unwrap();
+    let canonical = canonicalize(&app_compose).unwrap();
+
+    let mut reordered: Vec<(String, Value)> = serde_json::from_str::<Value>(&canonical)
+        .unwrap()
+        .as_object()
+        .unwrap()
+        .clone()
+        .into_iter()
+        .collect();
+    reordered.reverse();
+    let reordered: serde_json::Map<String, Value> = reordered.into_iter().collect();
+    let pretty = serde_json::to_string_pretty(&reordered).unwrap();
+    let reparsed: AppCompose = serde_json::from_str(&pretty).unwrap();
+
+    assert_eq!(reparsed, app_compose);
+    assert_eq!(canonicalize(&reparsed).unwrap(), canonical);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_reproduce_and_compare_accepts_reformatted_app_compose() {
+    use sha2::{Digest as _, Sha256};
+
+    let canonical = canonicalize(&minimal_app_compose()).unwrap();
+    let expected: [u8; 32] = Sha256::digest(canonical.as_bytes()).into();
+    let pretty =
+        serde_json::to_string_pretty(&serde_json::from_str::<Value>(&canonical).unwrap()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app-compose.json");
+    std::fs::write(&path, pretty).unwrap();
+
+    assert_matches!(reproduce_and_compare(&path, &expected), Ok(()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_reproduce_and_compare_reports_computed_hash() {
+    use sha2::{Digest as _, Sha256};
+
+    let canonical = canonicalize(&minimal_app_compose()).unwrap();
+    let computed = hex::encode(Sha256::digest(canonical.as_bytes()));
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app-compose.json");
+    std::fs::write(&path, &canonical).unwrap();
+
+    assert_matches!(
+        reproduce_and_compare(&path, &[6; 32]),
+        Err(AppComposeError::HashMismatch { expected, computed: found })
+            if expected == hex::encode([6; 32]) && found == computed
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_reproduce_and_compare_rejects_missing_or_invalid_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app-compose.json");
+
+    assert_matches!(
+        reproduce_and_compare(&path, &[6; 32]),
+        Err(AppComposeError::Io(_))
+    );
+    std::fs::write(&path, r#"{"name":"a","name":"b"}"#).unwrap();
+    assert_matches!(
+        reproduce_and_compare(&path, &[6; 32]),
+        Err(AppComposeError::InvalidAppCompose(_))
+    );
+}
+
+#[test]
+fn test_registry_host_follows_docker_normalization() {
+    assert_eq!(registry_host("ubuntu"), Some(DEFAULT_REGISTRY));
+    assert_eq!(
+        registry_host("library/ubuntu:24.04"),
+        Some(DEFAULT_REGISTRY)
+    );
+    assert_eq!(
+        registry_host("index.docker.io/nearone/mpc-node:latest"),
+        Some(DEFAULT_REGISTRY)
+    );
+    assert_eq!(
+        registry_host("ghcr.io/near/mpc@sha256:0123abcd"),
+        Some("ghcr.io")
+    );
+    assert_eq!(
+        registry_host("registry.example.com:5000/team/app:v1"),
+        Some("registry.example.com:5000")
+    );
+    assert_eq!(registry_host("localhost/app"), Some("localhost"));
+}
+
+#[test]
+fn test_registry_host_rejects_invalid_references() {
+    for image in ["", "${MPC_IMAGE}", "ghcr.io/", "UPPER/case", "a//b"] {
+        assert_eq!(registry_host(image), None, "{image}");
+    }
+}
+
+#[cfg(feature = "docker-compose")]
+fn app_compose_with_images(services: &str) -> AppCompose {
+    AppCompose {
+        docker_compose_file: format!("services:\n{services}").into(),
+        ..minimal_app_compose()
+    }
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_registries_accepts_test_app_compose() {
+    let app_compose: AppCompose = serde_json::from_str(TEST_APP_COMPOSE_STRING).unwrap();
+
+    assert_matches!(validate_registries(&app_compose, &["docker.io"]), Ok(()));
+    assert_matches!(
+        validate_registries(&app_compose, &["ghcr.io"]),
+        Err(AppComposeError::DisallowedRegistry { service, registry, .. })
+            if service == "launcher" && registry == "docker.io"
+    );
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_registries_names_offending_service() {
+    let app_compose = app_compose_with_images(
+        "  app:\n    image: ghcr.io/near/app:v1\n  sidecar:\n    image: quay.io/other/sidecar\n",
+    );
+
+    assert_matches!(
+        validate_registries(&app_compose, &["GHCR.io"]),
+        Err(AppComposeError::DisallowedRegistry { service, image, registry })
+            if service == "sidecar" && image == "quay.io/other/sidecar" && registry == "quay.io"
+    );
+    assert_matches!(
+        validate_registries(&app_compose, &["ghcr.io", "quay.io"]),
+        Ok(())
+    );
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_registries_rejects_services_without_pinned_image() {
+    let built = app_compose_with_images("  app:\n    build: .\n");
+    let interpolated = app_compose_with_images("  app:\n    image: ${IMAGE}\n");
+
+    assert_matches!(
+        validate_registries(&built, &["docker.io"]),
+        Err(AppComposeError::MissingImage { service }) if service == "app"
+    );
+    assert_matches!(
+        validate_registries(&interpolated, &["docker.io"]),
+        Err(AppComposeError::InvalidImageReference { service, .. }) if service == "app"
+    );
+    assert_matches!(
+        validate_registries(&app_compose_with_images(" - not a mapping"), &["docker.io"]),
+        Err(AppComposeError::InvalidDockerCompose(_))
+    );
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_accepts_test_app_compose() {
+    let app_compose: AppCompose = serde_json::from_str(TEST_APP_COMPOSE_STRING).unwrap();
+
+    assert_matches!(validate(&app_compose), Ok(()));
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_rejects_empty_fields() {
+    let unnamed = AppCompose {
+        name: String::new(),
+        ..app_compose_with_images("  app:\n    image: nearone/app\n")
+    };
+    let without_compose = AppCompose {
+        docker_compose_file: String::from(" ").into(),
+        ..minimal_app_compose()
+    };
+
+    assert_matches!(validate(&unnamed), Err(AppComposeError::EmptyField("name")));
+    assert_matches!(
+        validate(&without_compose),
+        Err(AppComposeError::EmptyField("docker_compose_file"))
+    );
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_rejects_missing_or_empty_services() {
+    let without_services = AppCompose {
+        docker_compose_file: String::from("version: '3'\n").into(),
+        ..minimal_app_compose()
+    };
+
+    assert_matches!(
+        validate(&without_services),
+        Err(AppComposeError::InvalidDockerCompose(_))
+    );
+    assert_matches!(
+        validate(&app_compose_with_images("  {}\n")),
+        Err(AppComposeError::NoServices)
+    );
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_rejects_duplicate_service() {
+    let app_compose = app_compose_with_images(
+        "  app:\n    image: nearone/app:v1\n  app:\n    image: nearone/app:v2\n",
+    );
+
+    assert_matches!(
+        validate(&app_compose),
+        Err(AppComposeError::DuplicateService { service }) if service == "app"
+    );
+}
+
+#[cfg(feature = "docker-compose")]
+#[test]
+fn test_validate_rejects_service_without_image() {
+    let app_compose =
+        app_compose_with_images("  app:\n    image: nearone/app\n  sidecar:\n    build: .\n");
+
+    assert_matches!(
+        validate(&app_compose),
+        Err(AppComposeError::MissingImage { service }) if service == "sidecar"
+    );
+    assert_matches!(
+        validate(&minimal_app_compose()),
+        Err(AppComposeError::MissingImage { service }) if service == "app"
+    );
+}
@@ -0,0 +1,1747 @@
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{
+        AttestationSummary, Bundle, CollateralId, DstackAttestation, MeasurementChange,
+        QuickCheckResult, QuoteVerifyOptions, RECOMMENDED_ALLOWED_SKEW_SECS, SuggestedRelaxation,
+        VerificationError, assert_production_root, check_collateral_quote_time,
+        detect_measurement_change, diagnose_failure, quick_check, stability_window,
+        verify_aggregate, verify_app_compose_present, verify_app_identity, verify_batch,
+        verify_expected_rtmr3, verify_mr_config_id, verify_mr_owner, verify_not_debug,
+        verify_participant_keys, verify_participants, verify_quote, verify_report_policy,
+        verify_resharing_set, verify_same_app, verify_seam_svn, verify_signature_only,
+        verify_stream, verify_with_collateral_fn, verify_with_collateral_set,
+        verify_with_committed_collateral,
+    },
+    collateral::{
+        CaType, Collateral, CollateralError, CollateralValidity, Fmspc, INTEL_SGX_ROOT_CA_DER,
+        INTEL_SGX_ROOT_CA_SHA256, PreparedCollateral, missing_collateral_hint,
+    },
+    measurements::{FullMeasurements, Measurements, RtmrRegister},
+    quote::{ParsedQuote, QuoteContext, QuoteType, encode_aggregate},
+    report_data::{KeyScheme, ReportData, for_public_key, seam_svn},
+    tcb_status::{TcbPolicy, TcbStatus},
+};
+use dcap_qvl::{QuoteCollateralV3, quote::Report, verify::VerifiedReport};
+use serde_json::json;
+use sha2::{Digest as _, Sha256};
+use std::collections::HashMap;
+use test_utils::attestation::{
+    TEST_CRL_CHAIN_PEM, TEST_PUBLIC_DATA_STRING, TEST_TCB_INFO_STRING, collateral, quote,
+};
+
+/// A timestamp at which the test quote and collateral are valid.
+const VALID_TIMESTAMP_SECONDS: u64 = 1763626832;
+
+fn prepared_collateral() -> PreparedCollateral {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    PreparedCollateral::new(collateral, &QuoteVerifyOptions::default()).unwrap()
+}
+
+fn extended_options() -> QuoteVerifyOptions {
+    QuoteVerifyOptions::builder()
+        .extended_collateral_checks(true)
+        .build()
+}
+
+#[test]
+fn test_prepared_collateral_checks_trust_anchor_of_options() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let options = QuoteVerifyOptions {
+        trusted_root_ca_der: vec![1, 2, 3],
+        ..QuoteVerifyOptions::default()
+    };
+
+    assert_matches!(
+        PreparedCollateral::new(collateral, &options),
+        Err(CollateralError::UntrustedRoot(_))
+    );
+}
+
+#[test]
+fn test_verify_batch_rejects_options_with_another_trust_anchor() {
+    let collateral = prepared_collateral();
+    let options = QuoteVerifyOptions {
+        trusted_root_ca_der: vec![1, 2, 3],
+        ..QuoteVerifyOptions::default()
+    };
+
+    let results = verify_batch(
+        &[quote().as_slice()],
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &options,
+    );
+
+    assert_eq!(collateral.trusted_root_ca_der(), INTEL_SGX_ROOT_CA_DER);
+    assert_matches!(&results[0], Err(VerificationError::Collateral(_)));
+}
+
+#[test]
+fn test_verify_quote_checks_trust_anchor_only_with_extended_collateral_checks() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let options = QuoteVerifyOptions {
+        trusted_root_ca_der: vec![1, 2, 3],
+        ..QuoteVerifyOptions::default()
+    };
+    let extended = QuoteVerifyOptions {
+        extended_collateral_checks: true,
+        ..options.clone()
+    };
+
+    assert_matches!(
+        verify_quote(&quote(), &collateral, VALID_TIMESTAMP_SECONDS, &options),
+        Ok(_)
+    );
+    assert_matches!(
+        verify_quote(&quote(), &collateral, VALID_TIMESTAMP_SECONDS, &extended),
+        Err(VerificationError::Collateral(reason)) if reason.contains("root")
+    );
+}
+
+#[test]
+fn test_verify_batch_preserves_input_order() {
+    let quote = quote();
+    let garbage = [0u8; 16];
+    let quotes = [quote.as_slice(), &garbage, quote.as_slice()];
+
+    let results = verify_batch(
+        &quotes,
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn test_dcap_verification_error_includes_quote_context() {
+    let mut quote: Vec<u8> = quote().into();
+    // Corrupt the signed TD report, keeping the header intact.
+    quote[200] ^= 0xff;
+
+    let results = verify_batch(
+        &[quote.as_slice()],
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(
+        &results[0],
+        Err(VerificationError::DcapVerification {
+            context: Some(QuoteContext {
+                version: 4,
+                tee_type: 0x81
+            }),
+            ..
+        })
+    );
+    let message = results[0].as_ref().unwrap_err().to_string();
+    assert!(message.contains("quote version 4, TEE type 0x00000081"));
+}
+
+#[test]
+fn test_dcap_verification_error_without_readable_header_has_no_context() {
+    let results = verify_batch(
+        &[&[0u8; 16]],
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(
+        &results[0],
+        Err(VerificationError::DcapVerification { context: None, .. })
+    );
+}
+
+#[test]
+fn test_verify_rejects_collateral_below_min_tcb_eval_number() {
+    let quote = quote();
+    let collateral = prepared_collateral();
+    let verify = |min_tcb_eval_number| {
+        let options = QuoteVerifyOptions {
+            min_tcb_eval_number,
+            ..QuoteVerifyOptions::default()
+        };
+        verify_batch(
+            &[quote.as_slice()],
+            &collateral,
+            VALID_TIMESTAMP_SECONDS,
+            &options,
+        )
+        .remove(0)
+    };
+
+    assert_matches!(verify(Some(18)), Ok(_));
+    assert_matches!(
+        verify(Some(19)),
+        Err(VerificationError::TcbEvaluationTooOld {
+            found: 18,
+            minimum: 19
+        })
+    );
+}
+
+fn summary() -> AttestationSummary {
+    AttestationSummary {
+        measurements: FullMeasurements {
+            rtmrs: Measurements {
+                mrtd: [1; 48],
+                rtmr0: [2; 48],
+                rtmr1: [3; 48],
+                rtmr2: [4; 48],
+            },
+            key_provider_event_digest: [5; 48],
+            app_compose_hash_payload: [6; 32],
+        },
+        ppid: [7; 16].into(),
+        fmspc: Fmspc::from([0xb0, 0xc0, 0x6f, 0, 0, 0]),
+        platform_tcb_status: TcbStatus::UpToDate,
+        qe_tcb_status: Some(TcbStatus::UpToDate),
+        mr_config_id: [0; 48],
+        mr_owner: [0; 48],
+        report_data: ReportData::new([9; 64]),
+        advisory_ids: Vec::new(),
+        verified_at: VALID_TIMESTAMP_SECONDS,
+        tcb_info_issued_at: 0,
+        tcb_info_next_update: 0,
+    }
+}
+
+#[test]
+fn test_full_measurements_from_summary_keeps_event_digests() {
+    let summary = summary();
+
+    assert_eq!(FullMeasurements::from(&summary), summary.measurements);
+}
+
+#[test]
+fn test_node_identity_is_stable() {
+    // sha256(fingerprint of the measurements || [9; 48]), the public key hash of the summary.
+    assert_eq!(
+        hex::encode(summary().node_identity()),
+        "15710fb0510a59c8e43271b9c4e699a53dae70e6b385a33036b32130df4cb50c"
+    );
+}
+
+#[test]
+fn test_node_identity_changes_with_code_or_key() {
+    let mut upgraded = summary();
+    upgraded.measurements.app_compose_hash_payload = [7; 32];
+    let mut rotated = summary();
+    rotated.report_data = ReportData::new([8; 64]);
+
+    assert_ne!(upgraded.node_identity(), summary().node_identity());
+    assert_ne!(rotated.node_identity(), summary().node_identity());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_to_metrics_labels_every_metric() {
+    use attestation::attestation::{
+        ADVISORIES_METRIC, TCB_INFO_NEXT_UPDATE_METRIC, VERIFIED_AT_METRIC, VERIFIED_METRIC,
+    };
+
+    let summary = AttestationSummary {
+        platform_tcb_status: TcbStatus::OutOfDate,
+        advisory_ids: vec!["INTEL-SA-00615".to_string(), "INTEL-SA-00837".to_string()],
+        tcb_info_next_update: 1766086694,
+        ..summary()
+    };
+
+    let metrics = summary.to_metrics();
+
+    let expected_labels = vec![
+        ("fmspc".to_string(), "b0c06f000000".to_string()),
+        ("tcb_status".to_string(), "OutOfDate".to_string()),
+        ("accepted".to_string(), "true".to_string()),
+    ];
+    assert!(
+        metrics
+            .iter()
+            .all(|(_, _, labels)| *labels == expected_labels)
+    );
+    let values: Vec<_> = metrics
+        .iter()
+        .map(|(name, value, _)| (name.as_str(), *value))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            (VERIFIED_METRIC, 1.0),
+            (ADVISORIES_METRIC, 2.0),
+            (VERIFIED_AT_METRIC, 1763626832.0),
+            (TCB_INFO_NEXT_UPDATE_METRIC, 1766086694.0),
+        ]
+    );
+}
+
+#[test]
+fn test_to_db_row_matches_coordinator_schema() {
+    let summary = AttestationSummary {
+        platform_tcb_status: TcbStatus::SWHardeningNeeded,
+        qe_tcb_status: None,
+        advisory_ids: vec!["INTEL-SA-00615".to_string()],
+        ..summary()
+    };
+
+    assert_eq!(
+        serde_json::to_value(summary.to_db_row()).unwrap(),
+        json!({
+            "mrtd": hex::encode([1; 48]),
+            "rtmr0": hex::encode([2; 48]),
+            "rtmr1": hex::encode([3; 48]),
+            "rtmr2": hex::encode([4; 48]),
+            "key_provider_event_digest": hex::encode([5; 48]),
+            "app_compose_hash_payload": hex::encode([6; 32]),
+            "ppid": hex::encode([7; 16]),
+            "mr_config_id": hex::encode([0; 48]),
+            "mr_owner": hex::encode([0; 48]),
+            "platform_tcb_status": "SWHardeningNeeded",
+            "qe_tcb_status": null,
+            "advisory_ids": ["INTEL-SA-00615"],
+            "verified_at": VALID_TIMESTAMP_SECONDS,
+            "accepted": true,
+        })
+    );
+}
+
+#[test]
+fn test_bundle_parse_splits_node_public_data() {
+    let (bundle_quote, bundle_collateral) =
+        Bundle::parse(TEST_PUBLIC_DATA_STRING.as_bytes()).unwrap();
+
+    assert_eq!(bundle_quote, *quote());
+    assert_eq!(bundle_collateral.to_json(), collateral());
+}
+
+#[test]
+fn test_bundle_parse_splits_dstack_attestation() {
+    let raw = json!({ "quote": *quote(), "collateral": collateral() }).to_string();
+
+    let (bundle_quote, bundle_collateral) = Bundle::parse(raw.as_bytes()).unwrap();
+
+    assert_eq!(bundle_quote, *quote());
+    assert_eq!(bundle_collateral.to_json(), collateral());
+}
+
+#[test]
+fn test_bundle_parse_rejects_bundle_without_collateral() {
+    let raw = json!({ "quote": *quote() }).to_string();
+
+    assert_matches!(
+        Bundle::parse(raw.as_bytes()),
+        Err(VerificationError::InvalidBundle(_))
+    );
+    assert_matches!(
+        Bundle::parse(b"not json"),
+        Err(VerificationError::InvalidBundle(_))
+    );
+}
+
+/// The measurements of the test quote, with arbitrary event log digests.
+fn test_quote_measurements() -> FullMeasurements {
+    let tcb_info: attestation::tcb_info::TcbInfo =
+        serde_json::from_str(TEST_TCB_INFO_STRING).unwrap();
+    FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: *tcb_info.mrtd,
+            rtmr0: *tcb_info.rtmr0,
+            rtmr1: *tcb_info.rtmr1,
+            rtmr2: *tcb_info.rtmr2,
+        },
+        key_provider_event_digest: [0; 48],
+        app_compose_hash_payload: [0; 32],
+    }
+}
+
+#[test]
+fn test_quick_check_matches_test_quote() {
+    assert_eq!(
+        quick_check(&quote(), &test_quote_measurements()),
+        QuickCheckResult::MeasurementsMatch
+    );
+}
+
+#[test]
+fn test_quick_check_reports_mismatching_registers() {
+    let mut expected = test_quote_measurements();
+    expected.rtmrs.rtmr2 = [0; 48];
+
+    assert_matches!(
+        quick_check(&quote(), &expected),
+        QuickCheckResult::MeasurementsMismatch(diff)
+            if diff.changed_registers().collect::<Vec<_>>() == [RtmrRegister::Rtmr2]
+    );
+}
+
+#[test]
+fn test_quick_check_rejects_garbage() {
+    assert_matches!(
+        quick_check(&[0; 16], &test_quote_measurements()),
+        QuickCheckResult::Unparseable(_)
+    );
+}
+
+#[test]
+fn test_explain_is_richer_than_display() {
+    let errors = [
+        VerificationError::TcbStatusNotAllowed(String::from("OutOfDate")),
+        VerificationError::Replayed { last_seen: 1000 },
+        VerificationError::DcapVerification {
+            reason: String::from("bad signature"),
+            context: None,
+        },
+    ];
+
+    for error in errors {
+        let explanation = error.explain();
+        assert_ne!(explanation, error.to_string());
+        assert!(explanation.len() > error.to_string().len());
+    }
+}
+
+#[test]
+fn test_explain_includes_remediation() {
+    let explanation = VerificationError::TcbStatusNotAllowed(String::from("OutOfDate")).explain();
+
+    assert!(explanation.contains("`OutOfDate`"));
+    assert!(explanation.contains("apply the latest microcode"));
+}
+
+#[test]
+fn test_quote_verify_options_builder_sets_every_option() {
+    let options = QuoteVerifyOptions::builder()
+        .allowed_skew_secs(RECOMMENDED_ALLOWED_SKEW_SECS)
+        .allowed_statuses([TcbStatus::OutOfDate])
+        .allow_status(TcbStatus::UpToDate)
+        .allow_status(TcbStatus::OutOfDate)
+        .expected_mr_service_td([7; 48])
+        .expected_mr_config_id([8; 48])
+        .expected_mr_owner([9; 48])
+        .report_data_domain(b"mpc:".to_vec())
+        .trusted_root_ca_der(vec![1, 2, 3])
+        .min_tcb_eval_number(18)
+        .skip_qe_identity(true)
+        .reject_debug_td(true)
+        .max_collateral_age_secs(86400)
+        .min_seam_svn(0x0103)
+        .expected_rtmr3([10; 48])
+        .require_app_compose(true)
+        .extended_collateral_checks(true)
+        .build();
+
+    assert_eq!(
+        options,
+        QuoteVerifyOptions {
+            allowed_skew_secs: RECOMMENDED_ALLOWED_SKEW_SECS,
+            tcb_policy: TcbPolicy::allowing([TcbStatus::OutOfDate, TcbStatus::UpToDate]),
+            expected_mr_service_td: Some([7; 48]),
+            expected_mr_config_id: Some([8; 48]),
+            expected_mr_owner: Some([9; 48]),
+            report_data_domain: Some(b"mpc:".to_vec()),
+            trusted_root_ca_der: vec![1, 2, 3],
+            min_tcb_eval_number: Some(18),
+            skip_qe_identity: true,
+            reject_debug_td: true,
+            max_collateral_age_secs: Some(86400),
+            min_seam_svn: Some(0x0103),
+            expected_rtmr3: Some([10; 48]),
+            require_app_compose: true,
+            extended_collateral_checks: true,
+        }
+    );
+    assert_eq!(
+        QuoteVerifyOptions::builder().build(),
+        QuoteVerifyOptions::strict()
+    );
+}
+
+#[test]
+fn test_rejected_qe_tcb_passes_only_with_skip_qe_identity() {
+    let mut json_value = collateral();
+    let mut qe_identity: serde_json::Value =
+        serde_json::from_str(json_value["qe_identity"].as_str().unwrap()).unwrap();
+    for level in qe_identity["tcbLevels"].as_array_mut().unwrap() {
+        level["tcbStatus"] = json!("OutOfDate");
+    }
+    json_value["qe_identity"] = json!(qe_identity.to_string());
+    // Editing the QE identity invalidates its signature, so the report is verified against the
+    // original collateral.
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+    let report = test_report();
+
+    assert_matches!(
+        verify_report_policy(&quote(), &collateral, &report, &QuoteVerifyOptions::default()),
+        Err(VerificationError::TcbStatusNotAllowed(status)) if status == "QE OutOfDate"
+    );
+    assert_matches!(
+        verify_report_policy(
+            &quote(),
+            &collateral,
+            &report,
+            &QuoteVerifyOptions::builder().skip_qe_identity(true).build(),
+        ),
+        Ok((_, None))
+    );
+}
+
+#[test]
+fn test_quote_verify_options_presets() {
+    assert_eq!(QuoteVerifyOptions::strict(), QuoteVerifyOptions::default());
+
+    let permissive = QuoteVerifyOptions::permissive();
+    assert!(permissive.tcb_policy.allows_status(TcbStatus::OutOfDate));
+    assert!(!permissive.tcb_policy.allows_status(TcbStatus::Revoked));
+
+    let monitoring = QuoteVerifyOptions::monitoring();
+    assert_eq!(monitoring.tcb_policy.allowed_statuses, TcbStatus::ALL);
+    assert!(
+        [
+            QuoteVerifyOptions::strict(),
+            permissive.clone(),
+            monitoring.clone()
+        ]
+        .iter()
+        .all(|options| !options.skip_qe_identity)
+    );
+    assert_eq!(
+        monitoring.trusted_root_ca_der,
+        QuoteVerifyOptions::strict().trusted_root_ca_der
+    );
+}
+
+#[test]
+fn test_verify_stream_matches_batch_without_abort() {
+    let quote = quote();
+    let garbage = [0u8; 16];
+    let quotes = [quote.as_slice(), &garbage, quote.as_slice()];
+    let collateral = prepared_collateral();
+    let options = QuoteVerifyOptions::default();
+
+    let results: Vec<_> = verify_stream(
+        quotes,
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &options,
+        false,
+    )
+    .collect();
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn test_verify_aggregate_verifies_each_embedded_quote() {
+    let quote = quote();
+    let garbage = [0u8; 16];
+    let aggregate = encode_aggregate(&[quote.as_slice(), &garbage, quote.as_slice()]).unwrap();
+    let collateral = prepared_collateral();
+    let options = QuoteVerifyOptions::default();
+
+    let results =
+        verify_aggregate(&aggregate, &collateral, VALID_TIMESTAMP_SECONDS, &options).unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+    assert_matches!(
+        verify_aggregate(
+            quote.as_slice(),
+            &collateral,
+            VALID_TIMESTAMP_SECONDS,
+            &options
+        ),
+        Err(VerificationError::InvalidInput(_))
+    );
+}
+
+#[test]
+fn test_verify_stream_stops_after_first_failure_when_aborting() {
+    let quote = quote();
+    let garbage = [0u8; 16];
+    let quotes = [quote.as_slice(), &garbage, quote.as_slice()];
+    let collateral = prepared_collateral();
+    let options = QuoteVerifyOptions::default();
+
+    let results: Vec<_> =
+        verify_stream(quotes, &collateral, VALID_TIMESTAMP_SECONDS, &options, true).collect();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_verify_batch_parallel_matches_sequential() {
+    use attestation::attestation::verify_batch_parallel;
+    use dcap_qvl::verify::VerifiedReport;
+
+    fn statuses(
+        results: &[Result<VerifiedReport, VerificationError>],
+    ) -> Vec<Result<String, VerificationError>> {
+        results
+            .iter()
+            .map(|result| {
+                result
+                    .as_ref()
+                    .map(|report| report.status.clone())
+                    .map_err(Clone::clone)
+            })
+            .collect()
+    }
+
+    let quote = quote();
+    let garbage: &[u8] = &[0u8; 16];
+    let quotes = [garbage, quote.as_slice(), garbage, quote.as_slice()];
+    let collateral = prepared_collateral();
+    let options = QuoteVerifyOptions::default();
+
+    let sequential = verify_batch(&quotes, &collateral, VALID_TIMESTAMP_SECONDS, &options);
+    let parallel = verify_batch_parallel(&quotes, &collateral, VALID_TIMESTAMP_SECONDS, &options);
+
+    assert_eq!(statuses(&sequential), statuses(&parallel));
+}
+
+#[test]
+fn test_verify_resharing_set_returns_report_data_of_every_participant() {
+    let quote = quote();
+    let quotes = [quote.as_slice(), quote.as_slice()];
+
+    let report_data = verify_resharing_set(
+        &quotes,
+        &test_quote_measurements(),
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(report_data.len(), 2);
+    assert_eq!(report_data[0], report_data[1]);
+}
+
+#[test]
+fn test_verify_resharing_set_fails_if_any_participant_fails() {
+    let quote = quote();
+    let garbage = [0u8; 16];
+    let quotes = [quote.as_slice(), &garbage];
+
+    let result = verify_resharing_set(
+        &quotes,
+        &test_quote_measurements(),
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(
+        result,
+        Err(VerificationError::ResharingParticipant { index: 1, .. })
+    );
+}
+
+#[test]
+fn test_verify_resharing_set_rejects_diverging_measurements() {
+    let quote = quote();
+    let mut expected = test_quote_measurements();
+    expected.rtmrs.rtmr2 = [7; 48];
+
+    let result = verify_resharing_set(
+        &[quote.as_slice()],
+        &expected,
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(
+        result,
+        Err(VerificationError::ResharingParticipant { index: 0, error })
+            if matches!(*error, VerificationError::WrongHash { name: "rtmr2", .. })
+    );
+}
+
+#[test]
+fn test_verify_resharing_set_rejects_empty_set() {
+    let result = verify_resharing_set(
+        &[],
+        &test_quote_measurements(),
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(result, Err(VerificationError::Custom(_)));
+}
+
+fn participant(key: [u8; 32]) -> ReportData {
+    ReportData::new(for_public_key(KeyScheme::Ed25519, &key).unwrap())
+}
+
+#[test]
+fn test_verify_participant_keys_accepts_expected_participants_in_any_order() {
+    let report_data = [participant([2; 32]), participant([1; 32])];
+
+    assert_matches!(
+        verify_participant_keys(&report_data, &[[1; 32], [2; 32], [1; 32]]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_verify_participant_keys_rejects_missing_participant() {
+    let report_data = [participant([1; 32])];
+
+    assert_matches!(
+        verify_participant_keys(&report_data, &[[1; 32], [2; 32]]),
+        Err(VerificationError::MissingParticipant { key }) if key == hex::encode([2; 32])
+    );
+}
+
+#[test]
+fn test_verify_participant_keys_rejects_unexpected_participant() {
+    let report_data = [participant([1; 32]), participant([3; 32])];
+
+    assert_matches!(
+        verify_participant_keys(&report_data, &[[1; 32], [2; 32]]),
+        Err(VerificationError::UnexpectedParticipant { index: 1, report_data })
+            if report_data == hex::encode(participant([3; 32]).as_bytes())
+    );
+}
+
+#[test]
+fn test_verify_participant_keys_rejects_duplicate_participant() {
+    let report_data = [
+        participant([1; 32]),
+        participant([2; 32]),
+        participant([1; 32]),
+    ];
+
+    assert_matches!(
+        verify_participant_keys(&report_data, &[[1; 32], [2; 32]]),
+        Err(VerificationError::DuplicateParticipant { first: 0, index: 2, key })
+            if key == hex::encode([1; 32])
+    );
+}
+
+#[test]
+fn test_verify_participants_checks_keys_of_verified_quotes() {
+    let quote = quote();
+
+    let result = verify_participants(
+        &[quote.as_slice()],
+        &[[1; 32]],
+        &test_quote_measurements(),
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    // The test quote binds no Ed25519 key.
+    assert_matches!(
+        result,
+        Err(VerificationError::UnexpectedParticipant { index: 0, .. })
+    );
+}
+
+#[test]
+fn test_verify_participants_rejects_invalid_quote_before_checking_keys() {
+    let garbage = [0u8; 16];
+
+    assert_matches!(
+        verify_participants(
+            &[&garbage],
+            &[[1; 32]],
+            &test_quote_measurements(),
+            &prepared_collateral(),
+            VALID_TIMESTAMP_SECONDS,
+            &QuoteVerifyOptions::default(),
+        ),
+        Err(VerificationError::ResharingParticipant { index: 0, .. })
+    );
+}
+
+#[test]
+fn test_verify_signature_only_accepts_genuine_quote() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+
+    assert!(report.report.as_td10().is_some());
+}
+
+#[test]
+fn test_verify_signature_only_rejects_tampered_quote() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let mut quote: Vec<u8> = quote().into();
+    // Corrupt the signed TD report, keeping the header intact.
+    quote[200] ^= 0xff;
+
+    assert_matches!(
+        verify_signature_only(&quote, &collateral, VALID_TIMESTAMP_SECONDS),
+        Err(VerificationError::DcapVerification { .. })
+    );
+}
+
+/// The test collateral, with the TEE type ids of an SGX collateral.
+fn sgx_collateral() -> Collateral {
+    let mut json_value = collateral();
+    for (field, id) in [("tcb_info", "SGX"), ("qe_identity", "QE")] {
+        let mut document: serde_json::Value =
+            serde_json::from_str(json_value[field].as_str().unwrap()).unwrap();
+        document["id"] = json!(id);
+        json_value[field] = json!(document.to_string());
+    }
+    Collateral::try_from_json(json_value).unwrap()
+}
+
+#[test]
+fn test_collateral_tee_type() {
+    let tdx_collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_eq!(tdx_collateral.tee_type(), Some(QuoteType::Tdx));
+    assert_eq!(sgx_collateral().tee_type(), Some(QuoteType::Sgx));
+}
+
+#[test]
+fn test_verify_quote_rejects_tdx_quote_with_sgx_collateral() {
+    let result = verify_quote(
+        &quote(),
+        &sgx_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &extended_options(),
+    );
+
+    assert_matches!(
+        result,
+        Err(VerificationError::TeeTypeMismatch {
+            quote_tee: QuoteType::Tdx,
+            collateral_tee: QuoteType::Sgx,
+        })
+    );
+}
+
+#[test]
+fn test_verify_quote_rejects_sgx_quote_with_tdx_collateral() {
+    let mut quote: Vec<u8> = quote().into();
+    // The little-endian TEE type of the header, 0 for SGX.
+    quote[4..8].copy_from_slice(&0u32.to_le_bytes());
+
+    let result = verify_batch(
+        &[quote.as_slice()],
+        &prepared_collateral(),
+        VALID_TIMESTAMP_SECONDS,
+        &extended_options(),
+    )
+    .remove(0);
+
+    assert_matches!(
+        result,
+        Err(VerificationError::TeeTypeMismatch {
+            quote_tee: QuoteType::Sgx,
+            collateral_tee: QuoteType::Tdx,
+        })
+    );
+}
+
+#[test]
+fn test_verify_quote_rejects_tcb_info_for_other_fmspc() {
+    let mut json_value = collateral();
+    let tcb_info = json_value["tcb_info"]
+        .as_str()
+        .unwrap()
+        .replace("b0c06f000000", "00606a000000");
+    json_value["tcb_info"] = json!(tcb_info);
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+
+    let result = verify_quote(
+        &quote(),
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &extended_options(),
+    );
+
+    assert_matches!(
+        result,
+        Err(VerificationError::Collateral(reason))
+            if reason.contains("FMSPC 00606a000000") && reason.contains("FMSPC b0c06f000000")
+    );
+}
+
+#[test]
+fn test_dstack_attestation_default_constructs() {
+    let attestation = DstackAttestation::default();
+
+    assert!(attestation.quote.is_empty());
+    assert!(attestation.collateral.tcb_info.is_empty());
+}
+
+#[test]
+fn test_verify_quote_accepts_real_quote_and_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let result = verify_quote(
+        &quote(),
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(result, Ok(_));
+}
+
+fn test_tcb_info() -> attestation::tcb_info::TcbInfo {
+    serde_json::from_str(TEST_TCB_INFO_STRING).unwrap()
+}
+
+#[test]
+fn test_verify_app_identity_accepts_deployed_compose() {
+    let tcb_info = test_tcb_info();
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+
+    let result = verify_app_identity(&tcb_info.app_compose, &tcb_info.event_log, &report);
+
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn test_verify_app_identity_rejects_other_compose() {
+    let tcb_info = test_tcb_info();
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+    let other_compose = format!("{} ", tcb_info.app_compose);
+
+    let result = verify_app_identity(&other_compose, &tcb_info.event_log, &report);
+
+    assert_matches!(
+        result,
+        Err(VerificationError::WrongHash {
+            name: "app_compose_payload",
+            ..
+        })
+    );
+}
+
+#[test]
+fn test_verify_app_identity_rejects_event_log_not_in_rtmr3() {
+    let tcb_info = test_tcb_info();
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+    let event_log: Vec<_> = tcb_info
+        .event_log
+        .into_iter()
+        .filter(|event| event.imr != 3 || event.event == "compose-hash")
+        .collect();
+
+    let result = verify_app_identity(&tcb_info.app_compose, &event_log, &report);
+
+    assert_matches!(
+        result,
+        Err(VerificationError::WrongHash {
+            name: "event_log",
+            ..
+        })
+    );
+}
+
+#[test]
+fn test_detect_measurement_change_accepts_unchanged_report() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+
+    assert_eq!(
+        detect_measurement_change(&test_quote_measurements(), &report),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_detect_measurement_change_reports_changed_registers() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+    let mut previous = test_quote_measurements();
+    previous.rtmrs.rtmr1 = [0; 48];
+
+    let result = detect_measurement_change(&previous, &report);
+
+    assert_matches!(
+        result,
+        Err(MeasurementChange::Changed(diff))
+            if diff.changed_registers().eq([RtmrRegister::Rtmr1])
+    );
+}
+
+#[test]
+fn test_verify_mr_config_id_pins_tenant() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+    let mr_config_id = report.report.as_td10().unwrap().mr_config_id;
+    let mut other_tenant = mr_config_id;
+    other_tenant[0] ^= 0xff;
+
+    assert_eq!(verify_mr_config_id(&report.report, None), Ok(()));
+    assert_eq!(
+        verify_mr_config_id(&report.report, Some(mr_config_id)),
+        Ok(())
+    );
+    assert_matches!(
+        verify_mr_config_id(&report.report, Some(other_tenant)),
+        Err(VerificationError::ConfigIdMismatch { expected, found })
+            if expected == hex::encode(other_tenant) && found == hex::encode(mr_config_id)
+    );
+}
+
+#[test]
+fn test_verify_mr_owner_requires_governance_owner() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let report = verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap();
+    let mr_owner = report.report.as_td10().unwrap().mr_owner;
+    let mut other_owner = mr_owner;
+    other_owner[0] ^= 0xff;
+
+    assert_eq!(verify_mr_owner(&report.report, None), Ok(()));
+    assert_eq!(verify_mr_owner(&report.report, Some(mr_owner)), Ok(()));
+    assert_matches!(
+        verify_mr_owner(&report.report, Some(other_owner)),
+        Err(VerificationError::OwnerMismatch { expected, found })
+            if expected == hex::encode(other_owner) && found == hex::encode(mr_owner)
+    );
+}
+
+/// Validity period of the PCK certificate of the test quote.
+const PCK_NOT_BEFORE: u64 = 1762414654;
+const PCK_NOT_AFTER: u64 = 1983339454;
+
+#[test]
+fn test_check_collateral_quote_time_accepts_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_eq!(
+        check_collateral_quote_time(&quote(), &collateral, collateral.validity_window().unwrap()),
+        Ok(())
+    );
+    // A window touching the certificate validity at either end still overlaps it.
+    for (not_before, next_update) in [(0, PCK_NOT_BEFORE), (PCK_NOT_AFTER, u64::MAX)] {
+        let validity = CollateralValidity {
+            not_before,
+            next_update,
+        };
+        assert_eq!(
+            check_collateral_quote_time(&quote(), &collateral, validity),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn test_check_collateral_quote_time_rejects_collateral_of_another_era() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    for (not_before, next_update) in [
+        (PCK_NOT_BEFORE - 60 * 86400, PCK_NOT_BEFORE - 30 * 86400),
+        (PCK_NOT_AFTER + 1, PCK_NOT_AFTER + 30 * 86400),
+    ] {
+        let validity = CollateralValidity {
+            not_before,
+            next_update,
+        };
+
+        assert_eq!(
+            check_collateral_quote_time(&quote(), &collateral, validity),
+            Err(VerificationError::CollateralQuoteTimeMismatch {
+                pck_not_before: PCK_NOT_BEFORE,
+                pck_not_after: PCK_NOT_AFTER,
+                collateral_not_before: not_before,
+                collateral_next_update: next_update,
+            })
+        );
+    }
+}
+
+#[test]
+fn test_check_collateral_quote_time_rejects_unreadable_pck_certificate() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let validity = CollateralValidity {
+        not_before: 0,
+        next_update: u64::MAX,
+    };
+    let malformed_chain = with_pck_cert_chain(&quote(), b"-----BEGIN CERTIFICATE-----\n");
+
+    assert_matches!(
+        check_collateral_quote_time(&malformed_chain, &collateral, validity),
+        Err(VerificationError::Collateral(_))
+    );
+}
+
+#[test]
+fn test_check_collateral_quote_time_falls_back_to_collateral_chain() {
+    let without_chain = Collateral::try_from_json(collateral()).unwrap();
+    let validity = without_chain.validity_window().unwrap();
+    let embedded_chain = ParsedQuote::parse(&quote())
+        .unwrap()
+        .pck_cert_chain()
+        .unwrap()
+        .to_vec();
+    let mut with_chain = QuoteCollateralV3::from(without_chain.clone());
+    with_chain.pck_certificate_chain = Some(String::from_utf8(embedded_chain).unwrap());
+    let with_chain = Collateral::from(with_chain);
+    // Certification data of type 3, which embeds no PCK certificate chain.
+    let quote = with_pck_cert_data_type(&quote(), 3);
+
+    assert_eq!(
+        check_collateral_quote_time(&quote, &with_chain, validity),
+        Ok(())
+    );
+    assert_matches!(
+        check_collateral_quote_time(&quote, &without_chain, validity),
+        Err(VerificationError::InvalidInput(_))
+    );
+}
+
+fn test_report() -> VerifiedReport {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    verify_signature_only(&quote(), &collateral, VALID_TIMESTAMP_SECONDS).unwrap()
+}
+
+fn with_td10(
+    mut report: VerifiedReport,
+    edit: impl FnOnce(&mut dcap_qvl::quote::TDReport10),
+) -> VerifiedReport {
+    let Report::TD10(td10) = &mut report.report else {
+        panic!("the test quote has a TD 1.0 report");
+    };
+    edit(td10);
+    report
+}
+
+#[test]
+fn test_verify_not_debug_accepts_production_report() {
+    assert_eq!(verify_not_debug(&test_report().report), Ok(()));
+}
+
+#[test]
+fn test_verify_not_debug_rejects_debug_td_and_module() {
+    let debug_td = with_td10(test_report(), |td10| td10.td_attributes[0] |= 0x01);
+    let debug_module = with_td10(test_report(), |td10| td10.seam_attributes[0] = 0x01);
+
+    assert_matches!(
+        verify_not_debug(&debug_td.report),
+        Err(VerificationError::DebugTdNotAllowed(reason)) if reason.contains("debug TD")
+    );
+    assert_matches!(
+        verify_not_debug(&debug_module.report),
+        Err(VerificationError::DebugTdNotAllowed(reason)) if reason.contains("0100000000000000")
+    );
+}
+
+#[test]
+fn test_verify_same_app_tolerates_different_firmware() {
+    let report = test_report();
+    let other_host = with_td10(test_report(), |td10| td10.rt_mr0 = [9; 48]);
+
+    assert_eq!(verify_same_app(&[&report, &other_host, &report]), Ok(()));
+    assert_eq!(verify_same_app(&[&report]), Ok(()));
+}
+
+#[test]
+fn test_verify_same_app_names_diverging_participant() {
+    let report = test_report();
+    let other_app = with_td10(test_report(), |td10| td10.rt_mr2 = [9; 48]);
+
+    assert_matches!(
+        verify_same_app(&[&report, &report, &other_app]),
+        Err(VerificationError::ResharingParticipant { index: 2, error })
+            if matches!(*error, VerificationError::WrongHash { name: "rtmr2", .. })
+    );
+    assert_matches!(verify_same_app(&[]), Err(VerificationError::Custom(_)));
+}
+
+/// `quote`, a version 4 TDX quote, with its embedded PCK certificate chain replaced by `chain`.
+fn with_pck_cert_chain(quote: &[u8], chain: &[u8]) -> Vec<u8> {
+    const SIGNATURE_DATA_LEN_OFFSET: usize = 632;
+    const CERTIFICATION_DATA_LEN_OFFSET: usize = 766;
+
+    let start = quote
+        .windows(10)
+        .position(|window| window == b"-----BEGIN")
+        .unwrap();
+    let old_len = u32::from_le_bytes(quote[start - 4..start].try_into().unwrap());
+    let delta = i64::try_from(chain.len()).unwrap() - i64::from(old_len);
+
+    let mut quote = quote.to_vec();
+    quote.splice(
+        start..start + usize::try_from(old_len).unwrap(),
+        chain.iter().copied(),
+    );
+    for offset in [
+        SIGNATURE_DATA_LEN_OFFSET,
+        CERTIFICATION_DATA_LEN_OFFSET,
+        start - 4,
+    ] {
+        let len = u32::from_le_bytes(quote[offset..offset + 4].try_into().unwrap());
+        let len = u32::try_from(i64::from(len) + delta).unwrap();
+        quote[offset..offset + 4].copy_from_slice(&len.to_le_bytes());
+    }
+    quote
+}
+
+/// `quote`, a version 4 TDX quote, with the type of its embedded PCK certification data
+/// replaced by `cert_data_type`.
+fn with_pck_cert_data_type(quote: &[u8], cert_data_type: u16) -> Vec<u8> {
+    let start = quote
+        .windows(10)
+        .position(|window| window == b"-----BEGIN")
+        .unwrap();
+
+    let mut quote = quote.to_vec();
+    quote[start - 6..start - 4].copy_from_slice(&cert_data_type.to_le_bytes());
+    quote
+}
+
+#[test]
+fn test_intel_sgx_root_ca_sha256_is_fingerprint_of_pinned_root() {
+    assert_eq!(
+        hex::encode(Sha256::digest(INTEL_SGX_ROOT_CA_DER)),
+        INTEL_SGX_ROOT_CA_SHA256
+    );
+}
+
+#[test]
+fn test_assert_production_root_accepts_test_quote() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_matches!(assert_production_root(&quote(), &collateral), Ok(()));
+}
+
+#[test]
+fn test_assert_production_root_reads_chain_embedded_in_quote() {
+    let mut without_chain =
+        QuoteCollateralV3::from(Collateral::try_from_json(collateral()).unwrap());
+    without_chain.pck_certificate_chain = None;
+    let collateral = Collateral::from(without_chain);
+
+    assert_matches!(assert_production_root(&quote(), &collateral), Ok(()));
+}
+
+#[test]
+fn test_assert_production_root_rejects_chain_of_another_root() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let quote = with_pck_cert_chain(&quote(), TEST_CRL_CHAIN_PEM);
+
+    assert_matches!(
+        assert_production_root(&quote, &collateral),
+        Err(VerificationError::NonProductionRoot { fingerprint })
+            if fingerprint != INTEL_SGX_ROOT_CA_SHA256
+    );
+}
+
+#[test]
+fn test_assert_production_root_rejects_unparsable_quote() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_matches!(
+        assert_production_root(&[0; 16], &collateral),
+        Err(VerificationError::InvalidInput(_))
+    );
+}
+
+/// The test collateral with the FMSPC and `tcbEvaluationDataNumber` of its documents replaced.
+/// Editing the documents invalidates their signatures.
+fn edited_collateral(fmspc: &str, tcb_evaluation_number: u32) -> Collateral {
+    let mut json_value = collateral();
+    for field in ["tcb_info", "qe_identity"] {
+        let document = json_value[field]
+            .as_str()
+            .unwrap()
+            .replace("b0c06f000000", fmspc)
+            .replace(
+                "\"tcbEvaluationDataNumber\":18",
+                &format!("\"tcbEvaluationDataNumber\":{tcb_evaluation_number}"),
+            );
+        json_value[field] = json!(document);
+    }
+    Collateral::try_from_json(json_value).unwrap()
+}
+
+#[test]
+fn test_verify_with_collateral_set_falls_back_to_old_collateral() {
+    let old = Collateral::try_from_json(collateral()).unwrap();
+    // Tried first as it is newer, but its documents no longer verify.
+    let new = edited_collateral("b0c06f000000", 19);
+
+    let (_, id) = verify_with_collateral_set(
+        &quote(),
+        &[old, new],
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        id,
+        CollateralId {
+            index: 0,
+            fmspc: Fmspc::from([0xb0, 0xc0, 0x6f, 0, 0, 0]),
+            tcb_evaluation_number: 18,
+        }
+    );
+}
+
+#[test]
+fn test_verify_with_collateral_set_prefers_first_of_equally_new_collaterals() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let (_, id) = verify_with_collateral_set(
+        &quote(),
+        &[collateral.clone(), collateral],
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(id.index, 0);
+}
+
+#[test]
+fn test_verify_with_collateral_set_skips_collateral_for_other_fmspc() {
+    let other_fmspc = edited_collateral("00606a000000", 19);
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let (_, id) = verify_with_collateral_set(
+        &quote(),
+        &[other_fmspc, collateral],
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(id.index, 1);
+}
+
+#[test]
+fn test_verify_with_collateral_set_returns_error_of_newest_collateral() {
+    let result = verify_with_collateral_set(
+        &quote(),
+        &[edited_collateral("b0c06f000000", 19)],
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(result, Err(VerificationError::DcapVerification { .. }));
+}
+
+#[test]
+fn test_verify_with_collateral_set_rejects_set_without_fitting_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    for collaterals in [vec![], vec![edited_collateral("00606a000000", 18)]] {
+        assert_matches!(
+            verify_with_collateral_set(
+                &quote(),
+                &collaterals,
+                VALID_TIMESTAMP_SECONDS,
+                &QuoteVerifyOptions::default(),
+            ),
+            Err(VerificationError::Collateral(reason)) if reason.contains("b0c06f000000")
+        );
+    }
+
+    // Expired at the verification time.
+    assert_matches!(
+        verify_with_collateral_set(
+            &quote(),
+            &[collateral],
+            u64::MAX,
+            &QuoteVerifyOptions::default(),
+        ),
+        Err(VerificationError::Collateral(_))
+    );
+}
+
+/// Collateral by FMSPC and PCK CA, as a caller might cache it.
+fn collateral_store(entries: Vec<(Fmspc, Collateral)>) -> HashMap<(Fmspc, CaType), Collateral> {
+    let ca = missing_collateral_hint(&ParsedQuote::parse(&quote()).unwrap())
+        .unwrap()
+        .ca_type()
+        .unwrap();
+    entries
+        .into_iter()
+        .map(|(fmspc, collateral)| ((fmspc, ca), collateral))
+        .collect()
+}
+
+fn fetch_from(
+    store: &HashMap<(Fmspc, CaType), Collateral>,
+) -> impl FnMut(Fmspc, CaType) -> Result<Collateral, CollateralError> + '_ {
+    move |fmspc, ca| {
+        store
+            .get(&(fmspc, ca))
+            .cloned()
+            .ok_or_else(|| CollateralError::MissingField(format!("collateral for {fmspc} {ca}")))
+    }
+}
+
+#[test]
+fn test_verify_with_collateral_fn_fetches_collateral_for_quote() {
+    let other_fmspc = Fmspc::from([0, 0x60, 0x6a, 0, 0, 0]);
+    let store = collateral_store(vec![
+        (other_fmspc, edited_collateral("00606a000000", 18)),
+        (
+            Fmspc::from([0xb0, 0xc0, 0x6f, 0, 0, 0]),
+            Collateral::try_from_json(collateral()).unwrap(),
+        ),
+    ]);
+    let mut fetched = Vec::new();
+    let mut fetch = fetch_from(&store);
+
+    let report = verify_with_collateral_fn(
+        &quote(),
+        |fmspc, ca| {
+            fetched.push(fmspc);
+            fetch(fmspc, ca)
+        },
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(fetched, [Fmspc::from([0xb0, 0xc0, 0x6f, 0, 0, 0])]);
+    let expected = verify_quote(
+        &quote(),
+        &Collateral::try_from_json(collateral()).unwrap(),
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(report.status, expected.status);
+}
+
+#[test]
+fn test_verify_with_collateral_fn_rejects_missing_collateral() {
+    let store = collateral_store(vec![]);
+
+    assert_matches!(
+        verify_with_collateral_fn(
+            &quote(),
+            fetch_from(&store),
+            VALID_TIMESTAMP_SECONDS,
+            &QuoteVerifyOptions::default(),
+        ),
+        Err(VerificationError::Collateral(reason)) if reason.contains("b0c06f000000")
+    );
+}
+
+#[test]
+fn test_verify_with_collateral_fn_rejects_collateral_for_other_fmspc() {
+    let collateral = edited_collateral("00606a000000", 18);
+
+    assert_matches!(
+        verify_with_collateral_fn(
+            &quote(),
+            |_, _| Ok(collateral.clone()),
+            VALID_TIMESTAMP_SECONDS,
+            &QuoteVerifyOptions::default(),
+        ),
+        Err(VerificationError::Collateral(reason)) if reason.contains("00606a000000")
+    );
+}
+
+#[test]
+fn test_verify_with_collateral_fn_rejects_invalid_quote() {
+    let mut called = false;
+
+    assert_matches!(
+        verify_with_collateral_fn(
+            &[0; 16],
+            |_, _| {
+                called = true;
+                Err(CollateralError::MissingField(String::from("unused")))
+            },
+            VALID_TIMESTAMP_SECONDS,
+            &QuoteVerifyOptions::default(),
+        ),
+        Err(VerificationError::InvalidInput(_))
+    );
+    assert!(!called);
+}
+
+#[test]
+fn test_verify_quote_enforces_max_collateral_age() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let issued_at = collateral.tcb_info_validity().unwrap().not_before;
+    // The collateral has not expired at the verification time, but is this old.
+    let age_secs = VALID_TIMESTAMP_SECONDS - issued_at;
+    let verify = |max_age_secs| {
+        verify_quote(
+            &quote(),
+            &collateral,
+            VALID_TIMESTAMP_SECONDS,
+            &QuoteVerifyOptions::builder()
+                .max_collateral_age_secs(max_age_secs)
+                .build(),
+        )
+    };
+
+    assert_matches!(verify(age_secs), Ok(_));
+    assert_matches!(
+        verify(age_secs - 1),
+        Err(VerificationError::CollateralTooOld { issued_at: found, age_secs: age, max_age_secs })
+            if found == issued_at && age == age_secs && max_age_secs == age_secs - 1
+    );
+}
+
+#[test]
+fn test_diagnose_failure_is_empty_when_verification_passes() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let suggestions = diagnose_failure(
+        &quote(),
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::permissive(),
+    );
+
+    assert!(suggestions.is_empty());
+}
+
+#[test]
+fn test_diagnose_failure_suggests_relaxation_of_failing_option() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let age_secs = VALID_TIMESTAMP_SECONDS - collateral.tcb_info_validity().unwrap().not_before;
+    let strict = |options: QuoteVerifyOptions| QuoteVerifyOptions {
+        // Holds for the test quote, so only the option under test is in the way.
+        expected_mr_config_id: Some(test_report().report.as_td10().unwrap().mr_config_id),
+        ..options
+    };
+    let cases = [
+        (
+            QuoteVerifyOptions {
+                max_collateral_age_secs: Some(age_secs - 1),
+                ..QuoteVerifyOptions::permissive()
+            },
+            SuggestedRelaxation::RemoveMaxCollateralAge,
+        ),
+        (
+            QuoteVerifyOptions {
+                // The test quote's TDX module has SVN 0x010b.
+                min_seam_svn: Some(0x010c),
+                ..QuoteVerifyOptions::permissive()
+            },
+            SuggestedRelaxation::RemoveMinSeamSvn,
+        ),
+        (
+            QuoteVerifyOptions {
+                expected_mr_owner: Some([9; 48]),
+                ..QuoteVerifyOptions::permissive()
+            },
+            SuggestedRelaxation::RemoveExpectedMrOwner,
+        ),
+    ];
+
+    for (options, relaxation) in cases {
+        assert_eq!(
+            diagnose_failure(
+                &quote(),
+                &collateral,
+                VALID_TIMESTAMP_SECONDS,
+                &strict(options)
+            ),
+            [relaxation]
+        );
+    }
+}
+
+#[test]
+fn test_diagnose_failure_suggests_least_skew_for_expired_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let next_update = collateral.validity_window().unwrap().next_update;
+    let options = QuoteVerifyOptions::permissive();
+    let late_secs = options.allowed_skew_secs + 1000;
+
+    let suggestions = diagnose_failure(&quote(), &collateral, next_update + late_secs, &options);
+
+    assert_matches!(
+        suggestions.as_slice(),
+        [SuggestedRelaxation::RaiseAllowedSkew { secs }] if *secs >= late_secs
+    );
+    assert_matches!(
+        verify_quote(
+            &quote(),
+            &collateral,
+            next_update + late_secs,
+            &suggestions[0].apply(&options),
+        ),
+        Ok(_)
+    );
+}
+
+#[test]
+fn test_suggested_relaxation_applies_to_options() {
+    let relaxation = SuggestedRelaxation::AllowStatus(TcbStatus::OutOfDate);
+
+    let relaxed = relaxation.apply(&QuoteVerifyOptions::strict());
+
+    assert!(relaxed.tcb_policy.allows_status(TcbStatus::OutOfDate));
+    assert!(relaxed.tcb_policy.allows_status(TcbStatus::UpToDate));
+    assert_eq!(relaxation.to_string(), "allow the OutOfDate TCB status");
+    assert!(relaxation.security_cost().contains("advisories"));
+}
+
+#[test]
+fn test_seam_svn_of_test_report() {
+    // TEE_TCB_SVN starts with 0b 01: SVN 11 of TDX module major version 1.
+    assert_eq!(seam_svn(&test_report().report), Some(0x010b));
+}
+
+#[test]
+fn test_verify_seam_svn_rejects_outdated_module() {
+    let report = test_report();
+    let low_seam = with_td10(test_report(), |td10| td10.tee_tcb_svn[0] = 2);
+
+    assert_eq!(verify_seam_svn(&report.report, None), Ok(()));
+    assert_eq!(verify_seam_svn(&report.report, Some(0x010b)), Ok(()));
+    assert_eq!(
+        verify_seam_svn(&low_seam.report, Some(0x010b)),
+        Err(VerificationError::SeamSvnTooLow {
+            found: 0x0102,
+            minimum: 0x010b
+        })
+    );
+    // A module of an older major version is outdated whatever its SVN.
+    let old_major = with_td10(test_report(), |td10| {
+        td10.tee_tcb_svn[0] = 0xff;
+        td10.tee_tcb_svn[1] = 0;
+    });
+    assert_matches!(
+        verify_seam_svn(&old_major.report, Some(0x0100)),
+        Err(VerificationError::SeamSvnTooLow { found: 0x00ff, .. })
+    );
+}
+
+#[test]
+fn test_verify_with_committed_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let committed_hash = collateral.canonical_hash();
+    let mut other_hash = committed_hash;
+    other_hash[0] ^= 0xff;
+
+    assert_matches!(
+        verify_with_committed_collateral(
+            &quote(),
+            &collateral,
+            committed_hash,
+            VALID_TIMESTAMP_SECONDS
+        ),
+        Ok(_)
+    );
+    assert_matches!(
+        verify_with_committed_collateral(&quote(), &collateral, other_hash, VALID_TIMESTAMP_SECONDS),
+        Err(VerificationError::CollateralHashMismatch { expected, found })
+            if expected == hex::encode(other_hash) && found == hex::encode(committed_hash)
+    );
+}
+
+#[test]
+fn test_stability_window_bounds_verification_result() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    for options in [QuoteVerifyOptions::default(), extended_options()] {
+        let (earliest, latest) =
+            stability_window(&quote(), &collateral, VALID_TIMESTAMP_SECONDS, &options);
+
+        assert!(earliest <= VALID_TIMESTAMP_SECONDS && VALID_TIMESTAMP_SECONDS <= latest);
+        for timestamp in [earliest, latest] {
+            assert_matches!(
+                verify_quote(&quote(), &collateral, timestamp, &options),
+                Ok(_)
+            );
+        }
+        for timestamp in [earliest - 1, latest + 1] {
+            assert_matches!(
+                verify_quote(&quote(), &collateral, timestamp, &options),
+                Err(_)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_stability_window_ends_at_max_collateral_age() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let issued_at = collateral.tcb_info_validity().unwrap().not_before;
+    let max_age_secs = VALID_TIMESTAMP_SECONDS - issued_at + 60;
+    let options = QuoteVerifyOptions::builder()
+        .max_collateral_age_secs(max_age_secs)
+        .build();
+
+    let (_, latest) = stability_window(&quote(), &collateral, VALID_TIMESTAMP_SECONDS, &options);
+
+    assert_eq!(latest, issued_at + max_age_secs);
+    assert_matches!(
+        verify_quote(&quote(), &collateral, latest + 1, &options),
+        Err(VerificationError::CollateralTooOld { .. })
+    );
+}
+
+#[test]
+fn test_stability_window_of_unreadable_quote_is_unbounded() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_eq!(
+        stability_window(
+            &[0; 16],
+            &collateral,
+            VALID_TIMESTAMP_SECONDS,
+            &QuoteVerifyOptions::default()
+        ),
+        (0, u64::MAX)
+    );
+}
+
+#[test]
+fn test_verify_expected_rtmr3() {
+    let report = with_td10(test_report(), |td10| td10.rt_mr3 = [10; 48]);
+    let never_extended = with_td10(test_report(), |td10| td10.rt_mr3 = [0; 48]);
+
+    assert_eq!(verify_expected_rtmr3(&report.report, None), Ok(()));
+    assert_eq!(verify_expected_rtmr3(&never_extended.report, None), Ok(()));
+    assert_eq!(
+        verify_expected_rtmr3(&report.report, Some([10; 48])),
+        Ok(())
+    );
+    assert_matches!(
+        verify_expected_rtmr3(&report.report, Some([11; 48])),
+        Err(VerificationError::WrongHash { name: "rtmr3", .. })
+    );
+    assert_eq!(
+        verify_expected_rtmr3(&never_extended.report, Some([10; 48])),
+        Err(VerificationError::MissingRtmr3)
+    );
+}
+
+#[test]
+fn test_verify_app_compose_present() {
+    let unfilled = test_quote_measurements();
+    let pinned = FullMeasurements {
+        app_compose_hash_payload: [1; 32],
+        ..unfilled
+    };
+
+    assert_eq!(verify_app_compose_present(&pinned), Ok(()));
+    assert_eq!(
+        verify_app_compose_present(&unfilled),
+        Err(VerificationError::MissingAppCompose)
+    );
+    assert!(!QuoteVerifyOptions::default().require_app_compose);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::measurements::{
+    AzureMaaClaims, Measurements, MeasurementsError, MeasurementsHex, from_azure_maa_claims,
+};
+use serde_json::json;
+
+fn claims(attestation_type: &str) -> AzureMaaClaims {
+    serde_json::from_value(json!({
+        "x-ms-attestation-type": attestation_type,
+        "x-ms-ver": "1.0",
+        "tdx_mrtd": "01".repeat(48),
+        "tdx_rtmr0": "02".repeat(48),
+        "tdx_rtmr1": "03".repeat(48),
+        "tdx_rtmr2": "04".repeat(48),
+        "tdx_rtmr3": "05".repeat(48),
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_from_azure_maa_claims_maps_tdx_registers() {
+    let expected = Measurements::from_arrays([1; 48], [2; 48], [3; 48], [4; 48]);
+
+    let measurements = from_azure_maa_claims(&claims("tdxvm"));
+
+    assert_eq!(measurements, Ok(MeasurementsHex::from(expected)));
+}
+
+#[test]
+fn test_from_azure_maa_claims_rejects_sev_snp() {
+    assert_matches!(
+        from_azure_maa_claims(&claims("sevsnpvm")),
+        Err(MeasurementsError::UnsupportedAttestationType(attestation_type))
+            if attestation_type == "sevsnpvm"
+    );
+}
+
+#[test]
+fn test_from_azure_maa_claims_names_malformed_claim() {
+    let mut missing = claims("tdxvm");
+    missing.tdx_rtmr1 = None;
+    let mut truncated = claims("tdxvm");
+    truncated.tdx_rtmr2 = Some("04".repeat(47));
+
+    assert_matches!(
+        from_azure_maa_claims(&missing),
+        Err(MeasurementsError::InvalidHexValue(name, _)) if name == "tdx_rtmr1"
+    );
+    assert_matches!(
+        from_azure_maa_claims(&truncated),
+        Err(MeasurementsError::InvalidLength(name, 47)) if name == "tdx_rtmr2"
+    );
+}
@@ -0,0 +1,308 @@
+#![cfg(feature = "signed-baseline")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    baseline::{QuorumSignedBaseline, SignedBaseline},
+    measurements::{
+        FullMeasurements, Measurements, MeasurementsError, ReleaseManifest, RtmrRegister,
+    },
+};
+use dcap_qvl::{
+    quote::{Report, TDReport10},
+    verify::VerifiedReport,
+};
+use ed25519_dalek::{Signer as _, SigningKey};
+
+fn full_measurements() -> FullMeasurements {
+    FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: [1; 48],
+            rtmr0: [2; 48],
+            rtmr1: [3; 48],
+            rtmr2: [4; 48],
+        },
+        key_provider_event_digest: [5; 48],
+        app_compose_hash_payload: [6; 32],
+    }
+}
+
+fn signed_baseline(version: u32, measurements: FullMeasurements) -> SignedBaseline {
+    let signing_key = SigningKey::from_bytes(&[42; 32]);
+    let payload = SignedBaseline::signing_payload(version, &measurements);
+    SignedBaseline {
+        version,
+        measurements,
+        signature: signing_key.sign(&payload).to_bytes(),
+        signer: signing_key.verifying_key().to_bytes(),
+    }
+}
+
+fn release_manifest(version: &str, measurements: FullMeasurements) -> ReleaseManifest {
+    let signing_key = SigningKey::from_bytes(&[42; 32]);
+    let payload = ReleaseManifest::signing_payload(version, &measurements);
+    ReleaseManifest {
+        version: String::from(version),
+        measurements,
+        signature: signing_key.sign(&payload).to_vec(),
+    }
+}
+
+fn release_key() -> [u8; 32] {
+    SigningKey::from_bytes(&[42; 32]).verifying_key().to_bytes()
+}
+
+fn verified_report(measurements: &Measurements) -> VerifiedReport {
+    VerifiedReport {
+        status: String::from("UpToDate"),
+        advisory_ids: Vec::new(),
+        report: Report::TD10(TDReport10 {
+            tee_tcb_svn: [0; 16],
+            mr_seam: [0; 48],
+            mr_signer_seam: [0; 48],
+            seam_attributes: [0; 8],
+            td_attributes: [0; 8],
+            xfam: [0; 8],
+            mr_td: measurements.mrtd,
+            mr_config_id: [0; 48],
+            mr_owner: [0; 48],
+            mr_owner_config: [0; 48],
+            rt_mr0: measurements.rtmr0,
+            rt_mr1: measurements.rtmr1,
+            rt_mr2: measurements.rtmr2,
+            rt_mr3: [0; 48],
+            report_data: [0; 64],
+        }),
+        ppid: Vec::new(),
+    }
+}
+
+#[test]
+fn test_signed_baseline_signature_covers_version_and_measurements() {
+    let baseline = signed_baseline(3, full_measurements());
+
+    assert!(baseline.verify_signature());
+    assert!(
+        !SignedBaseline {
+            version: 4,
+            ..baseline.clone()
+        }
+        .verify_signature()
+    );
+    assert!(
+        !SignedBaseline {
+            measurements: FullMeasurements {
+                app_compose_hash_payload: [7; 32],
+                ..full_measurements()
+            },
+            ..baseline
+        }
+        .verify_signature()
+    );
+}
+
+#[test]
+fn test_signed_baseline_check_report() {
+    let baseline = signed_baseline(1, full_measurements());
+    let matching = verified_report(&full_measurements().rtmrs);
+    let drifted = verified_report(&Measurements {
+        rtmr1: [9; 48],
+        ..full_measurements().rtmrs
+    });
+
+    assert_eq!(baseline.check_report(&matching), Ok(()));
+    assert_eq!(
+        baseline.check_report(&drifted),
+        Err(MeasurementsError::BaselineMismatch {
+            registers: vec![RtmrRegister::Rtmr1]
+        })
+    );
+}
+
+#[test]
+fn test_signed_baseline_check_report_rejects_bad_signature() {
+    let baseline = SignedBaseline {
+        signature: [0; 64],
+        ..signed_baseline(1, full_measurements())
+    };
+
+    assert_matches!(
+        baseline.check_report(&verified_report(&full_measurements().rtmrs)),
+        Err(MeasurementsError::InvalidBaselineSignature)
+    );
+}
+
+#[test]
+fn test_release_manifest_signature_covers_version_and_measurements() {
+    let manifest = release_manifest("3.1.0", full_measurements());
+
+    assert!(manifest.verify_signature(&release_key()));
+    assert!(
+        !manifest.verify_signature(&SigningKey::from_bytes(&[7; 32]).verifying_key().to_bytes())
+    );
+    assert!(
+        !ReleaseManifest {
+            version: String::from("3.1.1"),
+            ..manifest.clone()
+        }
+        .verify_signature(&release_key())
+    );
+    assert!(
+        !ReleaseManifest {
+            signature: manifest.signature[..63].to_vec(),
+            ..manifest
+        }
+        .verify_signature(&release_key())
+    );
+}
+
+#[test]
+fn test_release_manifest_verify_report() {
+    let manifest = release_manifest("3.1.0", full_measurements());
+    let drifted = verified_report(&Measurements {
+        rtmr2: [9; 48],
+        ..full_measurements().rtmrs
+    });
+
+    assert_eq!(
+        manifest.verify_report(&verified_report(&full_measurements().rtmrs), &release_key()),
+        Ok(())
+    );
+    assert_eq!(
+        manifest.verify_report(&drifted, &release_key()),
+        Err(MeasurementsError::BaselineMismatch {
+            registers: vec![RtmrRegister::Rtmr2]
+        })
+    );
+    assert_matches!(
+        manifest.verify_report(&verified_report(&full_measurements().rtmrs), &[0; 32]),
+        Err(MeasurementsError::InvalidBaselineSignature)
+    );
+}
+
+#[test]
+fn test_release_manifest_roundtrips_through_json() {
+    let manifest = release_manifest("3.1.0", full_measurements());
+
+    let json = serde_json::to_value(&manifest).unwrap();
+
+    assert_eq!(json["signature"], hex::encode(&manifest.signature));
+    assert_eq!(
+        serde_json::from_value::<ReleaseManifest>(json).unwrap(),
+        manifest
+    );
+}
+
+fn governance_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+/// A baseline with signatures by the governance keys of `seeds`.
+fn quorum_signed_baseline(threshold: u64, seeds: &[u8]) -> QuorumSignedBaseline {
+    let measurements = full_measurements();
+    let payload = QuorumSignedBaseline::signing_payload(7, threshold, &measurements);
+    QuorumSignedBaseline {
+        version: 7,
+        measurements,
+        signatures: seeds
+            .iter()
+            .map(|seed| {
+                let key = governance_key(*seed);
+                (
+                    key.verifying_key().to_bytes(),
+                    key.sign(&payload).to_bytes(),
+                )
+            })
+            .collect(),
+        threshold,
+    }
+}
+
+fn authorized_signers() -> Vec<[u8; 32]> {
+    (1..=3)
+        .map(|seed| governance_key(seed).verifying_key().to_bytes())
+        .collect()
+}
+
+#[test]
+fn test_quorum_signed_baseline_accepts_threshold_of_authorized_signers() {
+    let baseline = quorum_signed_baseline(2, &[1, 3]);
+
+    assert_matches!(baseline.verify(&authorized_signers(), 2), Ok(()));
+}
+
+#[test]
+fn test_quorum_signed_baseline_rejects_below_threshold() {
+    let baseline = quorum_signed_baseline(2, &[1]);
+
+    assert_matches!(
+        baseline.verify(&authorized_signers(), 2),
+        Err(MeasurementsError::QuorumNotReached {
+            valid: 1,
+            threshold: 2
+        })
+    );
+}
+
+#[test]
+fn test_quorum_signed_baseline_ignores_unauthorized_signers() {
+    let baseline = quorum_signed_baseline(2, &[1, 9]);
+
+    assert_matches!(
+        baseline.verify(&authorized_signers(), 2),
+        Err(MeasurementsError::QuorumNotReached { valid: 1, .. })
+    );
+}
+
+#[test]
+fn test_quorum_signed_baseline_counts_each_signer_once() {
+    let baseline = quorum_signed_baseline(2, &[1, 1]);
+
+    assert_matches!(
+        baseline.verify(&authorized_signers(), 2),
+        Err(MeasurementsError::QuorumNotReached { valid: 1, .. })
+    );
+}
+
+#[test]
+fn test_quorum_signed_baseline_rejects_tampered_fields() {
+    let mut lowered_threshold = quorum_signed_baseline(2, &[1]);
+    lowered_threshold.threshold = 1;
+    let mut tampered_measurements = quorum_signed_baseline(2, &[1, 2]);
+    tampered_measurements.measurements.rtmrs.rtmr2 = [9; 48];
+    let mut invalid_signature = quorum_signed_baseline(1, &[1]);
+    invalid_signature.signatures[0].1[0] ^= 1;
+
+    for baseline in [lowered_threshold, tampered_measurements, invalid_signature] {
+        assert_matches!(
+            baseline.verify(&authorized_signers(), 1),
+            Err(MeasurementsError::QuorumNotReached { valid: 0, .. })
+        );
+    }
+}
+
+#[test]
+fn test_quorum_signed_baseline_rejects_zero_threshold() {
+    let baseline = quorum_signed_baseline(0, &[]);
+
+    assert_matches!(
+        baseline.verify(&authorized_signers(), 0),
+        Err(MeasurementsError::QuorumNotReached {
+            valid: 0,
+            threshold: 0
+        })
+    );
+}
+
+#[test]
+fn test_quorum_signed_baseline_rejects_threshold_below_minimum() {
+    let baseline = quorum_signed_baseline(1, &[1]);
+
+    assert_matches!(
+        baseline.verify(&authorized_signers(), 2),
+        Err(MeasurementsError::ThresholdBelowMinimum {
+            threshold: 1,
+            min_threshold: 2
+        })
+    );
+    assert_matches!(baseline.verify(&authorized_signers(), 1), Ok(()));
+}
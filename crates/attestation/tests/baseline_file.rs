@@ -0,0 +1,131 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::measurements::{
+    BaselineFile, FullMeasurements, FullMeasurementsHex, Measurements, MeasurementsError,
+    load_allowlist_dir,
+};
+
+fn baseline() -> FullMeasurementsHex {
+    FullMeasurementsHex::from(FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: [1; 48],
+            rtmr0: [2; 48],
+            rtmr1: [3; 48],
+            rtmr2: [4; 48],
+        },
+        key_provider_event_digest: [5; 48],
+        app_compose_hash_payload: [6; 32],
+    })
+}
+
+#[test]
+fn test_baseline_file_roundtrip() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = BaselineFile::new(dir.path().join("baseline.json"));
+
+    file.write(&baseline()).unwrap();
+
+    assert_eq!(
+        file.checksum_path(),
+        dir.path().join("baseline.json.sha256")
+    );
+    assert!(
+        std::fs::read_to_string(file.checksum_path())
+            .unwrap()
+            .ends_with("  baseline.json\n")
+    );
+    assert_eq!(file.load_verified(), Ok(baseline()));
+}
+
+#[test]
+fn test_baseline_file_rejects_corrupted_baseline() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = BaselineFile::new(dir.path().join("baseline.json"));
+    file.write(&baseline()).unwrap();
+
+    let json = std::fs::read_to_string(file.path()).unwrap();
+    std::fs::write(file.path(), json.replacen("01", "02", 1)).unwrap();
+
+    assert_matches!(
+        file.load_verified(),
+        Err(MeasurementsError::ChecksumMismatch { .. })
+    );
+}
+
+#[test]
+fn test_baseline_file_rejects_missing_or_malformed_checksum_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = BaselineFile::new(dir.path().join("baseline.json"));
+    file.write(&baseline()).unwrap();
+
+    std::fs::write(file.checksum_path(), "not a checksum  baseline.json\n").unwrap();
+    assert_matches!(
+        file.load_verified(),
+        Err(MeasurementsError::InvalidHexValue(..))
+    );
+
+    std::fs::remove_file(file.checksum_path()).unwrap();
+    assert_matches!(file.load_verified(), Err(MeasurementsError::Io(_)));
+}
+
+fn write_json(path: &std::path::Path, measurements: &FullMeasurementsHex) {
+    std::fs::write(path, serde_json::to_string(measurements).unwrap()).unwrap();
+}
+
+fn other_baseline() -> FullMeasurementsHex {
+    let mut measurements = FullMeasurements::from(baseline());
+    measurements.app_compose_hash_payload = [7; 32];
+    FullMeasurementsHex::from(measurements)
+}
+
+#[test]
+fn test_load_allowlist_dir_merges_and_deduplicates_baselines() {
+    let dir = tempfile::tempdir().unwrap();
+    write_json(&dir.path().join("v1.json"), &baseline());
+    write_json(&dir.path().join("v1-copy.json"), &baseline());
+    write_json(&dir.path().join("v2.json"), &other_baseline());
+    // Checksum files, other files and subdirectories are ignored.
+    BaselineFile::new(dir.path().join("v3.json"))
+        .write(&baseline())
+        .unwrap();
+    std::fs::write(dir.path().join("README.md"), "not a baseline").unwrap();
+    std::fs::create_dir(dir.path().join("archive.json")).unwrap();
+
+    let allowlist = load_allowlist_dir(dir.path()).unwrap();
+
+    assert_eq!(allowlist.len(), 2);
+    assert!(allowlist.contains(&FullMeasurements::from(baseline())));
+    assert!(allowlist.contains(&FullMeasurements::from(other_baseline())));
+}
+
+#[test]
+fn test_load_allowlist_dir_names_invalid_file() {
+    let dir = tempfile::tempdir().unwrap();
+    write_json(&dir.path().join("v1.json"), &baseline());
+    std::fs::write(dir.path().join("v2.json"), r#"{"rtmrs": {}}"#).unwrap();
+
+    assert_matches!(
+        load_allowlist_dir(dir.path()),
+        Err(MeasurementsError::InvalidBaselineFile { path, reason })
+            if path.ends_with("v2.json")
+                && matches!(*reason, MeasurementsError::InvalidJson(_))
+    );
+}
+
+#[test]
+fn test_load_allowlist_dir_of_empty_dir_is_empty() {
+    let dir = tempfile::tempdir().unwrap();
+
+    assert!(load_allowlist_dir(dir.path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_load_allowlist_dir_rejects_missing_dir() {
+    let dir = tempfile::tempdir().unwrap();
+
+    assert_matches!(
+        load_allowlist_dir(&dir.path().join("missing")),
+        Err(MeasurementsError::Io(_))
+    );
+}
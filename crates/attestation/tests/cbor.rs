@@ -0,0 +1,75 @@
+#![cfg(feature = "cbor")]
+
+use assert_matches::assert_matches;
+use attestation::measurements::{Measurements, MeasurementsError, MeasurementsHex};
+
+/// CBOR header of a 48 byte byte string: major type 2 with a one byte length.
+const REGISTER_HEADER: [u8; 2] = [0x58, 0x30];
+
+fn measurements_hex() -> MeasurementsHex {
+    MeasurementsHex::from(Measurements {
+        mrtd: [1; 48],
+        rtmr0: [2; 48],
+        rtmr1: [3; 48],
+        rtmr2: [4; 48],
+    })
+}
+
+#[test]
+fn test_cbor_roundtrip() {
+    let hex = measurements_hex();
+
+    let bytes = hex.to_cbor().unwrap();
+
+    assert_eq!(MeasurementsHex::from_cbor(&bytes), Ok(hex));
+}
+
+#[test]
+fn test_cbor_encodes_registers_as_byte_strings() {
+    let bytes = measurements_hex().to_cbor().unwrap();
+
+    // A map of four entries, starting with the `mrtd` text key and its raw register.
+    let mut expected_prefix = vec![0xa4, 0x64];
+    expected_prefix.extend_from_slice(b"mrtd");
+    expected_prefix.extend_from_slice(&REGISTER_HEADER);
+    expected_prefix.extend_from_slice(&[1; 48]);
+
+    assert!(bytes.starts_with(&expected_prefix));
+    // Each entry is a text key of one byte header, then 2 header bytes and 48 register bytes.
+    assert_eq!(bytes.len(), 1 + (1 + 4) + 3 * (1 + 5) + 4 * (2 + 48));
+}
+
+#[test]
+fn test_cbor_rejects_trailing_bytes() {
+    let mut bytes = measurements_hex().to_cbor().unwrap();
+    bytes.push(0);
+
+    let result = MeasurementsHex::from_cbor(&bytes);
+
+    assert_matches!(result, Err(MeasurementsError::InvalidCbor(message)) if message.contains("trailing"));
+}
+
+#[test]
+fn test_cbor_rejects_truncated_input() {
+    let bytes = measurements_hex().to_cbor().unwrap();
+
+    for len in 0..bytes.len() {
+        assert_matches!(
+            MeasurementsHex::from_cbor(&bytes[..len]),
+            Err(MeasurementsError::InvalidCbor(_))
+        );
+    }
+}
+
+#[test]
+fn test_cbor_rejects_wrong_register_length() {
+    let mut bytes = measurements_hex().to_cbor().unwrap();
+    // Shorten the `mrtd` byte string by one, and drop one of its bytes to keep the map framing.
+    let length_offset = 1 + 1 + 4 + 1;
+    bytes[length_offset] = 0x2f;
+    bytes.remove(length_offset + 1);
+
+    let result = MeasurementsHex::from_cbor(&bytes);
+
+    assert_matches!(result, Err(MeasurementsError::InvalidCbor(_)));
+}
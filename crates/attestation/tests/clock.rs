@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{
+        QuoteVerifyOptions, SystemTimeSource, TimeSource, VerificationError, verify_now,
+        verify_quote_with_time_source,
+    },
+    collateral::Collateral,
+};
+use test_utils::attestation::{collateral, quote};
+
+/// A timestamp at which the test quote and collateral are valid.
+const VALID_TIMESTAMP_SECONDS: u64 = 1763626832;
+
+struct FixedTime(u64);
+
+impl TimeSource for FixedTime {
+    fn now_seconds(&self) -> u64 {
+        self.0
+    }
+}
+
+#[test]
+fn test_verify_quote_with_time_source_uses_injected_time() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let options = QuoteVerifyOptions::default();
+
+    assert_matches!(
+        verify_quote_with_time_source(
+            &quote(),
+            &collateral,
+            &options,
+            &FixedTime(VALID_TIMESTAMP_SECONDS)
+        ),
+        Ok(_)
+    );
+    assert_matches!(
+        verify_quote_with_time_source(&quote(), &collateral, &options, &FixedTime(0)),
+        Err(VerificationError::Collateral(_))
+    );
+}
+
+#[test]
+fn test_system_time_source_reads_current_time() {
+    assert!(SystemTimeSource.now_seconds() > VALID_TIMESTAMP_SECONDS);
+}
+
+#[test]
+fn test_verify_now_rejects_expired_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert!(verify_now(&quote(), &collateral, &QuoteVerifyOptions::default()).is_err());
+}
@@ -1,9 +1,26 @@
 use std::str::FromStr;
 
-use attestation::collateral::{Collateral, CollateralError};
+use assert_matches::assert_matches;
+use attestation::{
+    collateral::{
+        Certificates, Collateral, CollateralError, CollateralExpiry, CollateralLimits,
+        CollateralParts, DEFAULT_MAX_CERTS, Fmspc, INTEL_SGX_ROOT_CA_DER, MAX_DER_NESTING_DEPTH,
+        MAX_PCK_CHAIN_LEN, PCS_BASE_URL, assert_fmspc_consistency, check_pck_crl,
+        check_root_ca_crl, check_tcb_signing_chain, is_intel_pck_ca, minimize_for_quote,
+        missing_collateral_hint,
+    },
+    quote::{ParsedQuote, QuoteType},
+};
+use base64::{Engine as _, prelude::BASE64_STANDARD};
 use dcap_qvl::QuoteCollateralV3;
+use ring::signature::{ECDSA_P256_SHA256_ASN1, UnparsedPublicKey};
+use rstest::rstest;
 use serde_json::json;
-use test_utils::attestation::collateral;
+use test_utils::attestation::{
+    TEST_CRL_CHAIN_DER, TEST_CRL_CHAIN_PEM, TEST_ROOT_CA_EMPTY_CRL_DER,
+    TEST_ROOT_CA_REVOKED_INTERMEDIATE_CRL_DER, collateral, quote,
+};
+use x509_cert::der::Encode;
 
 #[test]
 fn test_collateral_missing_field() {
@@ -117,3 +134,968 @@ fn test_from_str_invalid_json() {
         _ => panic!("Expected InvalidJson error"),
     }
 }
+
+// `issueDate` of the TCB info in the test collateral.
+const TEST_COLLATERAL_NOT_BEFORE: u64 = 1763494694;
+// `nextUpdate` of the QE identity in the test collateral.
+const TEST_COLLATERAL_NEXT_UPDATE: u64 = 1766086276;
+
+#[test]
+fn test_validity_window_uses_most_restrictive_dates() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let validity = collateral.validity_window().unwrap();
+
+    assert_eq!(validity.not_before, TEST_COLLATERAL_NOT_BEFORE);
+    assert_eq!(validity.next_update, TEST_COLLATERAL_NEXT_UPDATE);
+}
+
+#[test]
+fn test_check_expiration_without_skew_is_strict() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert!(
+        collateral
+            .check_expiration(TEST_COLLATERAL_NEXT_UPDATE, 0)
+            .is_ok()
+    );
+    assert_matches!(
+        collateral.check_expiration(TEST_COLLATERAL_NEXT_UPDATE + 1, 0),
+        Err(CollateralError::Expired { .. })
+    );
+    assert_matches!(
+        collateral.check_expiration(TEST_COLLATERAL_NOT_BEFORE - 1, 0),
+        Err(CollateralError::Expired { .. })
+    );
+}
+
+#[test]
+fn test_check_expiration_tolerates_skew_on_both_ends() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let skew = 60;
+
+    assert!(
+        collateral
+            .check_expiration(TEST_COLLATERAL_NEXT_UPDATE + skew, skew)
+            .is_ok()
+    );
+    assert!(
+        collateral
+            .check_expiration(TEST_COLLATERAL_NOT_BEFORE - skew, skew)
+            .is_ok()
+    );
+    assert_matches!(
+        collateral.check_expiration(TEST_COLLATERAL_NEXT_UPDATE + skew + 1, skew),
+        Err(CollateralError::Expired { .. })
+    );
+}
+
+#[test]
+fn test_validity_window_clamps_skewed_timestamp_into_window() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let validity = collateral.validity_window().unwrap();
+
+    assert_eq!(
+        validity.clamp(TEST_COLLATERAL_NEXT_UPDATE + 10),
+        TEST_COLLATERAL_NEXT_UPDATE
+    );
+    assert_eq!(
+        validity.clamp(TEST_COLLATERAL_NOT_BEFORE - 10),
+        TEST_COLLATERAL_NOT_BEFORE
+    );
+}
+
+#[test]
+fn test_validity_window_invalid_date() {
+    let mut json_value = collateral();
+    json_value["qe_identity"] = json!(r#"{"issueDate":"yesterday","nextUpdate":"tomorrow"}"#);
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+
+    assert_matches!(
+        collateral.validity_window(),
+        Err(CollateralError::InvalidDate { field, key }) if field == "qe_identity" && key == "issueDate"
+    );
+}
+
+#[rstest]
+#[case("2024-02-31T00:00:00Z")]
+#[case("2023-02-29T00:00:00Z")]
+#[case("2100-02-29T00:00:00Z")]
+#[case("2024-04-31T00:00:00Z")]
+#[case("2024-01-00T00:00:00Z")]
+#[case("2024-13-01T00:00:00Z")]
+#[case("2024-01-01T24:00:00Z")]
+#[case("1969-12-31T23:59:59Z")]
+fn test_validity_window_rejects_impossible_date(#[case] date: &str) {
+    let mut json_value = collateral();
+    json_value["qe_identity"] = json!(format!(
+        r#"{{"issueDate":"{date}","nextUpdate":"2100-01-01T00:00:00Z"}}"#
+    ));
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+
+    assert_matches!(
+        collateral.validity_window(),
+        Err(CollateralError::InvalidDate { field, key }) if field == "qe_identity" && key == "issueDate"
+    );
+}
+
+#[test]
+fn test_validity_window_accepts_leap_days() {
+    for (date, timestamp) in [
+        ("2024-02-29T00:00:00Z", 1_709_164_800),
+        ("2000-02-29T12:30:15Z", 951_827_415),
+    ] {
+        let mut json_value = collateral();
+        json_value["tcb_info"] = json!(format!(
+            r#"{{"issueDate":"{date}","nextUpdate":"2100-01-01T00:00:00Z"}}"#
+        ));
+        let collateral = Collateral::try_from_json(json_value).unwrap();
+
+        assert_eq!(
+            collateral.tcb_info_validity().unwrap().not_before,
+            timestamp
+        );
+    }
+}
+
+#[test]
+fn test_check_root_ca_crl_accepts_chain_without_revocations() {
+    assert_matches!(
+        check_root_ca_crl(TEST_CRL_CHAIN_PEM, TEST_ROOT_CA_EMPTY_CRL_DER),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_check_root_ca_crl_rejects_revoked_intermediate() {
+    let result = check_root_ca_crl(
+        TEST_CRL_CHAIN_PEM,
+        TEST_ROOT_CA_REVOKED_INTERMEDIATE_CRL_DER,
+    );
+
+    assert_matches!(result, Err(CollateralError::RevokedCertificate { serial }) if serial == "1234");
+}
+
+#[test]
+fn test_check_root_ca_crl_rejects_crl_of_another_root() {
+    let intel_collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let result = check_root_ca_crl(TEST_CRL_CHAIN_PEM, &intel_collateral.root_ca_crl);
+
+    assert_matches!(result, Err(CollateralError::CrlSignature));
+}
+
+#[test]
+fn test_check_root_ca_crl_rejects_malformed_input() {
+    assert_matches!(
+        check_root_ca_crl(b"not a certificate", TEST_ROOT_CA_EMPTY_CRL_DER),
+        Err(CollateralError::InvalidCertificateChain(_))
+    );
+    assert_matches!(
+        check_root_ca_crl(TEST_CRL_CHAIN_PEM, &[0x30, 0x00]),
+        Err(CollateralError::RootCrlInvalid(_))
+    );
+}
+
+#[test]
+fn test_check_pck_crl_accepts_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_matches!(collateral.check_pck_crl(INTEL_SGX_ROOT_CA_DER), Ok(()));
+}
+
+#[test]
+fn test_check_pck_crl_rejects_crl_not_issued_by_the_chain() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    // The root CA CRL is signed by the root, not by the PCK CA that issues the PCK CRL.
+    let result = check_pck_crl(
+        collateral.pck_crl_issuer_chain.as_bytes(),
+        &collateral.root_ca_crl,
+        INTEL_SGX_ROOT_CA_DER,
+    );
+
+    assert_matches!(result, Err(CollateralError::CrlSignatureInvalid));
+}
+
+#[test]
+fn test_check_pck_crl_rejects_tampered_crl() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let mut pck_crl = collateral.pck_crl.clone();
+    let last = pck_crl.len() - 1;
+    pck_crl[last] ^= 1;
+
+    let result = check_pck_crl(
+        collateral.pck_crl_issuer_chain.as_bytes(),
+        &pck_crl,
+        INTEL_SGX_ROOT_CA_DER,
+    );
+
+    assert_matches!(
+        result,
+        Err(CollateralError::CrlSignatureInvalid | CollateralError::PckCrlInvalid(_))
+    );
+}
+
+#[test]
+fn test_check_pck_crl_rejects_chain_of_another_intel_ca() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    // The TCB signing chain verifies up to the Intel root, but its leaf is not a PCK CA.
+    let result = check_pck_crl(
+        collateral.tcb_info_issuer_chain.as_bytes(),
+        &collateral.pck_crl,
+        INTEL_SGX_ROOT_CA_DER,
+    );
+
+    assert_matches!(
+        result,
+        Err(CollateralError::UnauthorizedSigner { field, subject })
+            if field == "pck_crl_issuer_chain" && subject.contains("TCB Signing")
+    );
+}
+
+#[test]
+fn test_is_intel_pck_ca_accepts_pck_crl_issuer_of_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let chain = Certificates::parse_any(collateral.pck_crl_issuer_chain.as_bytes()).unwrap();
+
+    assert!(is_intel_pck_ca(&chain[0]));
+}
+
+#[test]
+fn test_is_intel_pck_ca_rejects_other_certificates() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let pck_crl_issuer_chain =
+        Certificates::parse_any(collateral.pck_crl_issuer_chain.as_bytes()).unwrap();
+    let tcb_info_issuer_chain =
+        Certificates::parse_any(collateral.tcb_info_issuer_chain.as_bytes()).unwrap();
+    let test_chain = Certificates::parse_any(TEST_CRL_CHAIN_PEM).unwrap();
+
+    // The Intel SGX Root CA, the Intel SGX TCB Signing certificate and a test CA.
+    for certificate in [
+        &pck_crl_issuer_chain[1],
+        &tcb_info_issuer_chain[0],
+        &test_chain[0],
+    ] {
+        assert!(
+            !is_intel_pck_ca(certificate),
+            "{}",
+            certificate.tbs_certificate.subject
+        );
+    }
+}
+
+#[test]
+fn test_check_issuer_chains_accepts_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_matches!(
+        collateral.check_issuer_chains(INTEL_SGX_ROOT_CA_DER),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_check_issuer_chains_rejects_chain_of_another_signer() {
+    let mut json_value = collateral();
+    json_value["qe_identity_issuer_chain"] = json_value["pck_crl_issuer_chain"].clone();
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+
+    assert_matches!(
+        collateral.check_issuer_chains(INTEL_SGX_ROOT_CA_DER),
+        Err(CollateralError::UnauthorizedSigner { field, subject })
+            if field == "qe_identity_issuer_chain" && subject.contains("PCK Platform CA")
+    );
+}
+
+#[test]
+fn test_check_tcb_signing_chain_rejects_broken_link() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let end = "-----END CERTIFICATE-----";
+    let tcb_signing = collateral
+        .tcb_info_issuer_chain
+        .split_inclusive(end)
+        .next()
+        .unwrap();
+    // The TCB signing certificate is issued by the root CA, not by the PCK CA.
+    let chain = [tcb_signing, &collateral.pck_crl_issuer_chain].concat();
+
+    let result = check_tcb_signing_chain(
+        "tcb_info_issuer_chain",
+        chain.as_bytes(),
+        INTEL_SGX_ROOT_CA_DER,
+    );
+
+    assert_matches!(
+        result,
+        Err(CollateralError::IssuerChainSignatureInvalid(field)) if field == "tcb_info_issuer_chain"
+    );
+}
+
+#[test]
+fn test_check_pck_crl_rejects_chain_of_another_root() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let result = check_pck_crl(
+        TEST_CRL_CHAIN_PEM,
+        &collateral.pck_crl,
+        INTEL_SGX_ROOT_CA_DER,
+    );
+
+    assert_matches!(result, Err(CollateralError::UntrustedRoot(field)) if field == "pck_crl_issuer_chain");
+}
+
+#[test]
+fn test_collateral_root_ca_revocations_pass_for_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_matches!(collateral.check_root_ca_revocations(), Ok(()));
+}
+
+#[test]
+fn test_minimize_for_quote_keeps_signed_documents() {
+    let full = Collateral::try_from_json(collateral()).unwrap();
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    let minimized = minimize_for_quote(&full, &quote).unwrap();
+
+    assert_eq!(minimized.tcb_info, full.tcb_info);
+    assert_eq!(minimized.qe_identity, full.qe_identity);
+    assert_eq!(minimized.pck_crl, full.pck_crl);
+    assert_eq!(minimized.root_ca_crl, full.root_ca_crl);
+    assert_eq!(minimized.pck_certificate_chain, None);
+}
+
+#[test]
+fn test_minimize_for_quote_rejects_pck_crl_of_another_issuer() {
+    let mut json_value = collateral();
+    json_value["pck_crl_issuer_chain"] = json_value["tcb_info_issuer_chain"].clone();
+    let full = Collateral::try_from_json(json_value).unwrap();
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    let result = minimize_for_quote(&full, &quote);
+
+    assert_matches!(
+        result,
+        Err(CollateralError::CaMismatch { collateral, .. })
+            if collateral.contains("CN=Intel SGX TCB Signing")
+    );
+}
+
+/// The test collateral, with TCB info for the FMSPC `00606a000000` instead of the quote's.
+fn collateral_for_other_fmspc() -> Collateral {
+    let mut json_value = collateral();
+    let tcb_info = json_value["tcb_info"]
+        .as_str()
+        .unwrap()
+        .replace("b0c06f000000", "00606a000000");
+    json_value["tcb_info"] = json!(tcb_info);
+    Collateral::try_from_json(json_value).unwrap()
+}
+
+#[test]
+fn test_assert_fmspc_consistency_accepts_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    assert_matches!(assert_fmspc_consistency(&quote, &collateral), Ok(()));
+}
+
+#[test]
+fn test_assert_fmspc_consistency_rejects_tcb_info_for_other_fmspc() {
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    let result = assert_fmspc_consistency(&quote, &collateral_for_other_fmspc());
+
+    assert_matches!(
+        result,
+        Err(CollateralError::FmspcMismatch { collateral, hint })
+            if collateral.to_hex() == "00606a000000" && hint.fmspc.to_hex() == "b0c06f000000"
+    );
+}
+
+#[test]
+fn test_minimize_for_quote_rejects_tcb_info_for_other_fmspc() {
+    let full = collateral_for_other_fmspc();
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    let result = minimize_for_quote(&full, &quote);
+
+    assert_matches!(result, Err(CollateralError::FmspcMismatch { collateral, .. }) if collateral.to_hex() == "00606a000000");
+}
+
+#[test]
+fn test_missing_collateral_hint_for_test_quote() {
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    let hint = missing_collateral_hint(&quote).unwrap();
+
+    assert_eq!(hint.quote_type, QuoteType::Tdx);
+    assert_eq!(hint.fmspc.to_hex(), "b0c06f000000");
+    assert_eq!(
+        hint.tcb_info_path,
+        "/tdx/certification/v4/tcb?fmspc=b0c06f000000"
+    );
+    assert_eq!(hint.qe_identity_path, "/tdx/certification/v4/qe/identity");
+    assert_eq!(hint.ca_type().unwrap().as_str(), hint.ca);
+    assert_eq!(
+        hint.pck_crl_path,
+        format!("/sgx/certification/v4/pckcrl?ca={}&encoding=der", hint.ca)
+    );
+    assert!(
+        hint.to_string()
+            .contains(&format!("{PCS_BASE_URL}{}", hint.tcb_info_path))
+    );
+}
+
+#[test]
+fn test_certificates_parse_any_pem_chain() {
+    let certificates = Certificates::parse_any(TEST_CRL_CHAIN_PEM).unwrap();
+
+    assert_eq!(certificates.len(), 2);
+}
+
+#[test]
+fn test_certificates_parse_any_quote_pck_chain() {
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    let certificates = Certificates::parse_any(quote.pck_cert_chain().unwrap()).unwrap();
+
+    assert_eq!(certificates.len(), 3);
+}
+
+#[test]
+fn test_certificates_parse_any_skips_trailing_nul_padding() {
+    let padded = [TEST_CRL_CHAIN_PEM, b"\n\0\0"].concat();
+
+    assert_eq!(
+        Certificates::parse_any(&padded).unwrap(),
+        Certificates::parse_any(TEST_CRL_CHAIN_PEM).unwrap()
+    );
+}
+
+#[test]
+fn test_certificates_parse_any_der_chain_matches_pem() {
+    let from_der = Certificates::parse_any(TEST_CRL_CHAIN_DER).unwrap();
+    let from_pem = Certificates::parse_any(TEST_CRL_CHAIN_PEM).unwrap();
+
+    assert_eq!(from_der, from_pem);
+}
+
+#[test]
+fn test_certificates_parse_any_rejects_mixed_bundle() {
+    let der_then_pem = [TEST_CRL_CHAIN_DER, TEST_CRL_CHAIN_PEM].concat();
+    let pem_then_der = [TEST_CRL_CHAIN_PEM, TEST_CRL_CHAIN_DER].concat();
+
+    assert_matches!(
+        Certificates::parse_any(&der_then_pem),
+        Err(CollateralError::InvalidCertificateChain(_))
+    );
+    assert_matches!(
+        Certificates::parse_any(&pem_then_der),
+        Err(CollateralError::InvalidCertificateChain(_))
+    );
+    assert_matches!(
+        Certificates::parse_any(&[]),
+        Err(CollateralError::InvalidCertificateChain(_))
+    );
+}
+
+/// The certificates of the PCK chain of the test quote, the PCK certificate, the PCK platform
+/// CA and the root CA, picked in the order of `indices`.
+fn pck_chain(indices: &[usize]) -> Certificates {
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+    let chain = Certificates::parse_any(quote.pck_cert_chain().unwrap()).unwrap();
+    let der: Vec<u8> = indices
+        .iter()
+        .flat_map(|index| chain[*index].to_der().unwrap())
+        .collect();
+    Certificates::parse_any(&der).unwrap()
+}
+
+#[test]
+fn test_check_constraints_accepts_pck_chain_of_test_quote() {
+    assert_matches!(
+        pck_chain(&[0, 1, 2]).check_constraints(MAX_PCK_CHAIN_LEN),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_check_constraints_rejects_leaf_marked_as_ca() {
+    assert_matches!(
+        pck_chain(&[1, 2]).check_constraints(MAX_PCK_CHAIN_LEN),
+        Err(CollateralError::LeafIsCa)
+    );
+}
+
+#[test]
+fn test_check_constraints_rejects_over_long_chain() {
+    let chain = pck_chain(&[0, 1, 1, 2]);
+
+    assert_matches!(
+        chain.check_constraints(MAX_PCK_CHAIN_LEN),
+        Err(CollateralError::TooManyCerts {
+            max: MAX_PCK_CHAIN_LEN
+        })
+    );
+    // The platform CA has a path length constraint of zero.
+    assert_matches!(
+        chain.check_constraints(4),
+        Err(CollateralError::PathLenExceeded { index: 2 })
+    );
+}
+
+#[test]
+fn test_check_constraints_rejects_issuer_which_is_not_a_ca() {
+    assert_matches!(
+        pck_chain(&[0, 0, 2]).check_constraints(MAX_PCK_CHAIN_LEN),
+        Err(CollateralError::IssuerNotCa { index: 1 })
+    );
+}
+
+#[test]
+fn test_check_root_ca_crl_detects_revocation_in_der_chain() {
+    let result = check_root_ca_crl(
+        TEST_CRL_CHAIN_DER,
+        TEST_ROOT_CA_REVOKED_INTERMEDIATE_CRL_DER,
+    );
+
+    assert_matches!(result, Err(CollateralError::RevokedCertificate { .. }));
+}
+
+fn collateral_parts(collateral: &Collateral) -> CollateralParts {
+    CollateralParts {
+        tcb_info_issuer_chain: BASE64_STANDARD.encode(&collateral.tcb_info_issuer_chain),
+        tcb_info: BASE64_STANDARD.encode(&collateral.tcb_info),
+        tcb_info_signature: BASE64_STANDARD.encode(&collateral.tcb_info_signature),
+        qe_identity_issuer_chain: BASE64_STANDARD.encode(&collateral.qe_identity_issuer_chain),
+        qe_identity: BASE64_STANDARD.encode(&collateral.qe_identity),
+        qe_identity_signature: BASE64_STANDARD.encode(&collateral.qe_identity_signature),
+        pck_certificate_chain: collateral
+            .pck_certificate_chain
+            .as_ref()
+            .map(|chain| BASE64_STANDARD.encode(chain)),
+        pck_crl_issuer_chain: BASE64_STANDARD.encode(&collateral.pck_crl_issuer_chain),
+        root_ca_crl: BASE64_STANDARD.encode(&collateral.root_ca_crl),
+        pck_crl: BASE64_STANDARD.encode(&collateral.pck_crl),
+    }
+}
+
+#[test]
+fn test_collateral_from_base64_parts_matches_json() {
+    let expected = Collateral::try_from_json(collateral()).unwrap();
+
+    let decoded = Collateral::from_base64_parts(&collateral_parts(&expected)).unwrap();
+
+    assert_eq!(
+        borsh::to_vec(&decoded).unwrap(),
+        borsh::to_vec(&expected).unwrap()
+    );
+}
+
+#[test]
+fn test_collateral_from_base64_parts_rejects_invalid_base64() {
+    let parts = CollateralParts {
+        tcb_info: String::from("not base64!"),
+        ..collateral_parts(&Collateral::try_from_json(collateral()).unwrap())
+    };
+
+    let result = Collateral::from_base64_parts(&parts);
+
+    assert_matches!(result, Err(CollateralError::Base64Decode { field, .. }) if field == "tcb_info");
+}
+
+#[test]
+fn test_collateral_from_base64_parts_rejects_malformed_chain() {
+    let parts = CollateralParts {
+        pck_crl_issuer_chain: BASE64_STANDARD.encode("not a certificate chain"),
+        ..collateral_parts(&Collateral::try_from_json(collateral()).unwrap())
+    };
+
+    let result = Collateral::from_base64_parts(&parts);
+
+    assert_matches!(result, Err(CollateralError::InvalidCertificateChain(_)));
+}
+
+#[test]
+fn test_intel_sgx_root_ca_is_valid_self_signed_root() {
+    let certificates = Certificates::parse_any(INTEL_SGX_ROOT_CA_DER).unwrap();
+    let [root] = certificates.as_slice() else {
+        panic!("expected a single certificate");
+    };
+    let tbs = &root.tbs_certificate;
+
+    assert_eq!(tbs.subject, tbs.issuer);
+    assert!(tbs.subject.to_string().contains("CN=Intel SGX Root CA"));
+
+    let public_key = tbs.subject_public_key_info.subject_public_key.raw_bytes();
+    let signature = root.signature.raw_bytes();
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, public_key)
+        .verify(&tbs.to_der().unwrap(), signature)
+        .expect("root CA should be self-signed");
+}
+
+#[test]
+fn test_collateral_check_trust_anchor() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let test_root = Certificates::parse_any(TEST_CRL_CHAIN_PEM)
+        .unwrap()
+        .last()
+        .unwrap()
+        .to_der()
+        .unwrap();
+
+    assert_matches!(collateral.check_trust_anchor(INTEL_SGX_ROOT_CA_DER), Ok(()));
+    assert_matches!(
+        collateral.check_trust_anchor(&test_root),
+        Err(CollateralError::UntrustedRoot(field)) if field == "tcb_info_issuer_chain"
+    );
+}
+
+#[test]
+fn test_tcb_evaluation_number_of_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_matches!(collateral.tcb_evaluation_number(), Ok(18));
+}
+
+#[test]
+fn test_tcb_evaluation_number_missing() {
+    let mut json_value = collateral();
+    json_value["qe_identity"] = json!(json!({ "id": "TD_QE" }).to_string());
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+
+    assert_matches!(
+        collateral.tcb_evaluation_number(),
+        Err(CollateralError::MissingField(field)) if field == "qe_identity.tcbEvaluationDataNumber"
+    );
+}
+
+#[test]
+fn test_collateral_json_round_trip() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let cached = serde_json::to_string(&collateral).unwrap();
+    let loaded: Collateral = serde_json::from_str(&cached).unwrap();
+
+    assert_eq!(loaded.to_json(), collateral.to_json());
+    assert_eq!(
+        borsh::to_vec(&loaded).unwrap(),
+        borsh::to_vec(&collateral).unwrap()
+    );
+}
+
+#[test]
+fn test_collateral_to_json_matches_source_json() {
+    let json = collateral();
+
+    assert_eq!(
+        Collateral::try_from_json(json.clone()).unwrap().to_json(),
+        json
+    );
+}
+
+#[test]
+fn test_collateral_borsh_round_trip() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let bytes = borsh::to_vec(&collateral).unwrap();
+    let loaded: Collateral = borsh::from_slice(&bytes).unwrap();
+
+    assert_eq!(loaded.to_json(), collateral.to_json());
+}
+
+#[test]
+fn test_collateral_rejects_oversized_certificate_chain() {
+    let mut json_value = collateral();
+    let chain = json_value["pck_crl_issuer_chain"]
+        .as_str()
+        .unwrap()
+        .repeat(DEFAULT_MAX_CERTS);
+    json_value["pck_crl_issuer_chain"] = json!(chain);
+
+    let result = Collateral::try_from_json(json_value.clone());
+
+    assert_matches!(
+        result,
+        Err(CollateralError::TooManyCerts {
+            max: DEFAULT_MAX_CERTS
+        })
+    );
+
+    let limits = CollateralLimits {
+        max_certs: 4 * DEFAULT_MAX_CERTS,
+        ..CollateralLimits::default()
+    };
+    assert_matches!(
+        Collateral::try_from_json_with_limits(json_value, &limits),
+        Ok(_)
+    );
+}
+
+#[test]
+fn test_collateral_rejects_too_many_tcb_levels() {
+    let mut json_value = collateral();
+    let mut tcb_info: serde_json::Value =
+        serde_json::from_str(json_value["tcb_info"].as_str().unwrap()).unwrap();
+    let level = tcb_info["tcbLevels"][0].clone();
+    tcb_info["tcbLevels"] = json!(vec![level; 100]);
+    json_value["tcb_info"] = json!(tcb_info.to_string());
+
+    let result = Collateral::try_from_json(json_value);
+
+    assert_matches!(result, Err(CollateralError::TooManyTcbLevels { field, max: 64 }) if field == "tcb_info");
+}
+
+#[test]
+fn test_collateral_limits_skip_empty_documents() {
+    let mut json_value = collateral();
+    json_value["tcb_info"] = json!("");
+    json_value["qe_identity"] = json!("");
+
+    assert_matches!(Collateral::try_from_json(json_value), Ok(_));
+}
+
+#[test]
+fn test_collateral_rejects_invalid_documents() {
+    let mut json_value = collateral();
+    json_value["qe_identity"] = json!("not json");
+
+    let result = Collateral::try_from_json(json_value);
+
+    assert_matches!(result, Err(CollateralError::InvalidDocument(field)) if field == "qe_identity");
+}
+
+#[test]
+fn test_certificates_parse_any_rejects_oversized_chains() {
+    let pem = TEST_CRL_CHAIN_PEM.repeat(DEFAULT_MAX_CERTS);
+    let der = TEST_CRL_CHAIN_DER.repeat(DEFAULT_MAX_CERTS);
+
+    assert_matches!(
+        Certificates::parse_any(&pem),
+        Err(CollateralError::TooManyCerts { .. })
+    );
+    assert_matches!(
+        Certificates::parse_any(&der),
+        Err(CollateralError::TooManyCerts { .. })
+    );
+}
+
+#[test]
+fn test_fmspc_hex_roundtrip() {
+    let fmspc = Fmspc::from([0xb0, 0xc0, 0x6f, 0, 0, 0]);
+
+    assert_eq!(fmspc.to_hex(), "b0c06f000000");
+    assert_eq!(fmspc.to_string(), "b0c06f000000");
+    assert_matches!(Fmspc::from_hex("B0C06F000000"), Ok(parsed) if parsed == fmspc);
+    assert_eq!(serde_json::to_value(fmspc).unwrap(), json!("b0c06f000000"));
+}
+
+#[test]
+fn test_fmspc_from_hex_rejects_wrong_length() {
+    for hex in ["", "b0c06f", "b0c06f00000", "b0c06f00000000"] {
+        assert_matches!(
+            Fmspc::from_hex(hex),
+            Err(CollateralError::HexDecode { field, .. }) if field == "fmspc",
+            "{hex}"
+        );
+    }
+    assert_matches!(
+        Fmspc::from_hex("b0c06f00000g"),
+        Err(CollateralError::HexDecode { .. })
+    );
+}
+
+#[test]
+fn test_parsed_quote_fmspc() {
+    let quote = ParsedQuote::parse(&quote()).unwrap();
+
+    assert_eq!(quote.fmspc().unwrap().to_hex(), "b0c06f000000");
+}
+
+#[test]
+fn test_expiry_dates_of_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let expiry = collateral.expiry_dates().unwrap();
+
+    assert_eq!(
+        expiry,
+        CollateralExpiry {
+            pck_not_after: None,
+            // The TCB signing certificate, 2032-05-06T09:25:00Z.
+            intermediate_not_after: 1967448300,
+            tcb_info_next_update: 1766086694,
+            qe_identity_next_update: TEST_COLLATERAL_NEXT_UPDATE,
+        }
+    );
+    assert_eq!(expiry.earliest(), TEST_COLLATERAL_NEXT_UPDATE);
+}
+
+#[test]
+fn test_valid_until_is_the_last_timestamp_passing_check_expiration() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let valid_until = collateral.valid_until().unwrap();
+
+    assert!(valid_until <= collateral.expiry_dates().unwrap().earliest());
+    assert_matches!(collateral.check_expiration(valid_until, 0), Ok(_));
+}
+
+#[test]
+fn test_valid_until_rejects_malformed_crl() {
+    let mut json_value = collateral();
+    json_value["pck_crl"] = json!("3000");
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+
+    assert_matches!(
+        collateral.valid_until(),
+        Err(CollateralError::PckCrlInvalid(_))
+    );
+}
+
+#[test]
+fn test_check_crls_accepts_test_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    assert_matches!(collateral.check_crls(1763626832, 0), Ok(()));
+}
+
+#[test]
+fn test_check_crls_rejects_expired_pck_crl() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    // After the next update of the PCK CRL, 2025-12-18, but within the root CA CRL.
+    assert_matches!(
+        collateral.check_crls(1767225600, 0),
+        Err(CollateralError::PckCrlInvalid(_))
+    );
+}
+
+#[test]
+fn test_check_crls_rejects_expired_root_ca_crl() {
+    // Swapped, the root CA CRL field holds the PCK CRL, which expires first.
+    let mut json_value = collateral();
+    let pck_crl = json_value["pck_crl"].clone();
+    json_value["pck_crl"] = json_value["root_ca_crl"].clone();
+    json_value["root_ca_crl"] = pck_crl;
+    let collateral = Collateral::try_from_json(json_value).unwrap();
+
+    assert_matches!(
+        collateral.check_crls(1767225600, 0),
+        Err(CollateralError::RootCrlInvalid(_))
+    );
+}
+
+#[test]
+fn test_check_crls_rejects_malformed_crls() {
+    let mut malformed_pck_crl = collateral();
+    malformed_pck_crl["pck_crl"] = json!("3000");
+    let mut malformed_root_ca_crl = collateral();
+    malformed_root_ca_crl["root_ca_crl"] = json!("3000");
+
+    assert_matches!(
+        Collateral::try_from_json(malformed_pck_crl)
+            .unwrap()
+            .check_crls(1763626832, 0),
+        Err(CollateralError::PckCrlInvalid(_))
+    );
+    assert_matches!(
+        Collateral::try_from_json(malformed_root_ca_crl)
+            .unwrap()
+            .check_crls(1763626832, 0),
+        Err(CollateralError::RootCrlInvalid(_))
+    );
+}
+
+/// `depth` DER sequences nested in each other, the innermost one empty.
+fn nested_sequences(depth: usize) -> Vec<u8> {
+    (0..depth).fold(Vec::new(), |inner, _| {
+        [
+            &[0x30, u8::try_from(inner.len()).unwrap()],
+            inner.as_slice(),
+        ]
+        .concat()
+    })
+}
+
+#[test]
+fn test_certificates_parse_any_rejects_deeply_nested_der() {
+    let der = nested_sequences(MAX_DER_NESTING_DEPTH + 1);
+    let pem = format!(
+        "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+        BASE64_STANDARD.encode(&der)
+    );
+
+    assert_matches!(
+        Certificates::parse_any(&der),
+        Err(CollateralError::NestingTooDeep {
+            max: MAX_DER_NESTING_DEPTH
+        })
+    );
+    assert_matches!(
+        Certificates::parse_any(pem.as_bytes()),
+        Err(CollateralError::NestingTooDeep { .. })
+    );
+}
+
+#[test]
+fn test_certificates_parse_any_accepts_nesting_up_to_the_limit() {
+    let der = nested_sequences(MAX_DER_NESTING_DEPTH);
+
+    // Not a certificate, but only rejected by the decoder.
+    assert_matches!(
+        Certificates::parse_any(&der),
+        Err(CollateralError::InvalidCertificateChain(_))
+    );
+}
+
+#[test]
+fn test_canonical_hash_survives_json_and_borsh_roundtrips() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let from_json = Collateral::try_from_json(collateral.to_json()).unwrap();
+    let from_borsh: Collateral = borsh::from_slice(&borsh::to_vec(&collateral).unwrap()).unwrap();
+
+    assert_eq!(from_json.canonical_hash(), collateral.canonical_hash());
+    assert_eq!(from_borsh.canonical_hash(), collateral.canonical_hash());
+}
+
+#[test]
+fn test_canonical_hash_commits_to_every_field() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let hash = collateral.canonical_hash();
+    let edits: [fn(&mut QuoteCollateralV3); 10] = [
+        |c| c.tcb_info_issuer_chain.push(' '),
+        |c| c.tcb_info.push(' '),
+        |c| c.tcb_info_signature.push(0),
+        |c| c.qe_identity_issuer_chain.push(' '),
+        |c| c.qe_identity.push(' '),
+        |c| c.qe_identity_signature.push(0),
+        |c| c.pck_crl_issuer_chain.push(' '),
+        |c| c.root_ca_crl.push(0),
+        |c| c.pck_crl.push(0),
+        |c| c.pck_certificate_chain = Some(String::new()),
+    ];
+
+    for edit in edits {
+        let mut edited = QuoteCollateralV3::from(collateral.clone());
+        edit(&mut edited);
+        assert_ne!(Collateral::from(edited).canonical_hash(), hash);
+    }
+}
+
+#[test]
+fn test_canonical_hash_separates_adjacent_fields() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let mut moved = QuoteCollateralV3::from(collateral.clone());
+    // Moves the last byte of one field to the start of the next.
+    let byte = moved.root_ca_crl.pop().unwrap();
+    moved.pck_crl.insert(0, byte);
+
+    assert_ne!(
+        Collateral::from(moved).canonical_hash(),
+        collateral.canonical_hash()
+    );
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::measurements::{Measurements, MeasurementsError, from_dcap_qvl_json};
+use serde_json::json;
+
+fn measurements() -> Measurements {
+    Measurements {
+        mrtd: [1; 48],
+        rtmr0: [2; 48],
+        rtmr1: [3; 48],
+        rtmr2: [4; 48],
+    }
+}
+
+fn verified_report(report: serde_json::Value) -> String {
+    json!({
+        "status": "UpToDate",
+        "advisory_ids": [],
+        "report": report,
+    })
+    .to_string()
+}
+
+#[test]
+fn test_from_dcap_qvl_json_reads_td10_report() {
+    let json = verified_report(json!({
+        "TD10": {
+            "tee_tcb_svn": hex::encode([0; 16]),
+            "mr_td": hex::encode([1; 48]),
+            "rt_mr0": hex::encode([2; 48]),
+            "rt_mr1": hex::encode([3; 48]),
+            "rt_mr2": hex::encode([4; 48]),
+            "rt_mr3": hex::encode([5; 48]),
+        }
+    }));
+
+    assert_eq!(from_dcap_qvl_json(&json), Ok(measurements()));
+}
+
+#[test]
+fn test_from_dcap_qvl_json_accepts_other_field_names_and_encodings() {
+    let json = verified_report(json!({
+        "TD15": {
+            "base": {
+                "mrTd": format!("0x{}", hex::encode([1; 48])),
+                "rtMr0": vec![2; 48],
+                "rtmr1": hex::encode([3; 48]),
+                "rt_mr2": hex::encode([4; 48]),
+            }
+        }
+    }));
+
+    assert_eq!(from_dcap_qvl_json(&json), Ok(measurements()));
+}
+
+#[test]
+fn test_from_dcap_qvl_json_rejects_reports_without_td10() {
+    let json = verified_report(json!({ "SgxEnclave": { "mr_enclave": hex::encode([1; 32]) } }));
+
+    assert_matches!(
+        from_dcap_qvl_json(&json),
+        Err(MeasurementsError::NoTd10Report)
+    );
+    assert_matches!(
+        from_dcap_qvl_json("not json"),
+        Err(MeasurementsError::InvalidJson(_))
+    );
+}
+
+#[test]
+fn test_from_dcap_qvl_json_names_malformed_register() {
+    let json = verified_report(json!({
+        "TD10": {
+            "mr_td": hex::encode([1; 48]),
+            "rt_mr0": hex::encode([2; 48]),
+            "rt_mr1": hex::encode([3; 47]),
+            "rt_mr2": hex::encode([4; 48]),
+        }
+    }));
+
+    assert_matches!(
+        from_dcap_qvl_json(&json),
+        Err(MeasurementsError::InvalidLength(name, 47)) if name == "rtmr1"
+    );
+}
+
+#[test]
+fn test_from_dcap_qvl_json_names_missing_register() {
+    let json = verified_report(json!({
+        "TD10": {
+            "mr_td": hex::encode([1; 48]),
+            "rt_mr0": hex::encode([2; 48]),
+            "rt_mr1": hex::encode([3; 48]),
+        }
+    }));
+
+    assert_matches!(
+        from_dcap_qvl_json(&json),
+        Err(MeasurementsError::InvalidHexValue(name, _)) if name == "rtmr2"
+    );
+}
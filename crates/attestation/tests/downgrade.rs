@@ -0,0 +1,90 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{
+        DowngradeGuard, InMemoryTcbEvaluation, QuoteVerifyOptions, TcbEvaluationStore,
+        VerificationError,
+    },
+    collateral::Collateral,
+};
+use test_utils::attestation::{collateral, quote};
+
+/// A timestamp at which the test quote and collateral are valid.
+const VALID_TIMESTAMP_SECONDS: u64 = 1763626832;
+
+/// The test collateral, with a `tcbEvaluationDataNumber` of 18 replaced by `number`.
+fn collateral_with_evaluation_number(number: u32) -> Collateral {
+    let mut json_value = collateral();
+    for field in ["tcb_info", "qe_identity"] {
+        let document = json_value[field].as_str().unwrap().replace(
+            r#""tcbEvaluationDataNumber":18"#,
+            &format!(r#""tcbEvaluationDataNumber":{number}"#),
+        );
+        json_value[field] = serde_json::Value::String(document);
+    }
+    Collateral::try_from_json(json_value).unwrap()
+}
+
+#[test]
+fn test_downgrade_guard_rejects_older_collateral() {
+    let mut guard = DowngradeGuard::new();
+
+    assert_matches!(
+        guard.observe(&collateral_with_evaluation_number(18)),
+        Ok(18)
+    );
+    assert_matches!(
+        guard.observe(&collateral_with_evaluation_number(17)),
+        Err(VerificationError::TcbDowngrade {
+            found: 17,
+            highest: 18
+        })
+    );
+    assert_matches!(
+        guard.observe(&collateral_with_evaluation_number(19)),
+        Ok(19)
+    );
+    assert_eq!(guard.store().highest(), Some(19));
+}
+
+#[test]
+fn test_downgrade_guard_verify_quote_with_seeded_store() {
+    let mut store = InMemoryTcbEvaluation::default();
+    store.record(19);
+    let mut guard = DowngradeGuard::with_store(store);
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let result = guard.verify_quote(
+        &quote(),
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(
+        result,
+        Err(VerificationError::TcbDowngrade {
+            found: 18,
+            highest: 19
+        })
+    );
+}
+
+#[test]
+fn test_downgrade_guard_records_only_verified_collateral() {
+    let mut guard = DowngradeGuard::new();
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let mut tampered: Vec<u8> = quote().into();
+    // Corrupt the signed TD report, keeping the header intact.
+    tampered[200] ^= 0xff;
+    let options = QuoteVerifyOptions::default();
+
+    let rejected = guard.verify_quote(&tampered, &collateral, VALID_TIMESTAMP_SECONDS, &options);
+    assert_matches!(rejected, Err(VerificationError::DcapVerification { .. }));
+    assert_eq!(guard.store().highest(), None);
+
+    let accepted = guard.verify_quote(&quote(), &collateral, VALID_TIMESTAMP_SECONDS, &options);
+    assert_matches!(accepted, Ok(_));
+    assert_eq!(guard.store().highest(), Some(18));
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "std")]
+
+use attestation::{
+    attestation::{AttestationSummary, summarize_fleet},
+    collateral::Fmspc,
+    measurements::{FullMeasurements, Measurements},
+    report_data::ReportData,
+    tcb_info::HexBytes,
+    tcb_status::TcbStatus,
+};
+
+fn measurements(seed: u8) -> FullMeasurements {
+    FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: [1; 48],
+            rtmr0: [2; 48],
+            rtmr1: [3; 48],
+            rtmr2: [4; 48],
+        },
+        key_provider_event_digest: [5; 48],
+        app_compose_hash_payload: [seed; 32],
+    }
+}
+
+fn summary(measurements: FullMeasurements, ppid: u8) -> AttestationSummary {
+    AttestationSummary {
+        measurements,
+        ppid: HexBytes::from([ppid; 16]),
+        fmspc: Fmspc::from([0; 6]),
+        platform_tcb_status: TcbStatus::UpToDate,
+        qe_tcb_status: Some(TcbStatus::UpToDate),
+        mr_config_id: [0; 48],
+        mr_owner: [0; 48],
+        report_data: ReportData::new([0; 64]),
+        advisory_ids: vec![],
+        verified_at: 0,
+        tcb_info_issued_at: 0,
+        tcb_info_next_update: 0,
+    }
+}
+
+#[test]
+fn test_summarize_fleet_groups_nodes_by_measurements() {
+    let summaries = [
+        summary(measurements(1), 1),
+        summary(measurements(2), 2),
+        summary(measurements(2), 3),
+    ];
+
+    let report = summarize_fleet(&summaries);
+
+    assert_eq!(report.node_count, 3);
+    assert_eq!(report.images.len(), 2);
+    assert_eq!(report.images[0].node_count, 2);
+    assert_eq!(*report.images[0].fingerprint, measurements(2).fingerprint());
+    assert_eq!(
+        FullMeasurements::from(report.images[1].measurements.clone()),
+        measurements(1)
+    );
+    assert_eq!(report.test_images().count(), 0);
+}
+
+#[test]
+fn test_summarize_fleet_of_no_nodes_is_empty() {
+    let report = summarize_fleet(&[]);
+
+    assert_eq!(report.node_count, 0);
+    assert!(report.images.is_empty());
+}
+
+#[test]
+fn test_fleet_report_flags_test_images() {
+    let summaries = [summary(measurements(1), 1), summary(measurements(2), 2)];
+
+    let report = summarize_fleet(&summaries).flag_test_images(&[measurements(2), measurements(3)]);
+
+    let test_images: Vec<_> = report.test_images().collect();
+    assert_eq!(test_images.len(), 1);
+    assert_eq!(*test_images[0].fingerprint, measurements(2).fingerprint());
+}
+
+#[test]
+fn test_fleet_report_serializes_fingerprints_as_hex() {
+    let report = summarize_fleet(&[summary(measurements(1), 1)]);
+
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert_eq!(
+        json["images"][0]["fingerprint"],
+        hex::encode(measurements(1).fingerprint())
+    );
+    assert_eq!(json["images"][0]["node_count"], 1);
+    assert_eq!(json["images"][0]["test_image"], false);
+}
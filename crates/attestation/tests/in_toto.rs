@@ -0,0 +1,115 @@
+#![cfg(feature = "in-toto")]
+
+use attestation::{
+    attestation::{AttestationSummary, InTotoStatement, TDX_VERIFICATION_PREDICATE_TYPE},
+    collateral::Fmspc,
+    measurements::{FullMeasurements, Measurements},
+    report_data::ReportData,
+    tcb_info::HexBytes,
+    tcb_status::TcbStatus,
+};
+use serde_json::json;
+
+fn summary() -> AttestationSummary {
+    AttestationSummary {
+        measurements: FullMeasurements {
+            rtmrs: Measurements {
+                mrtd: [1; 48],
+                rtmr0: [2; 48],
+                rtmr1: [3; 48],
+                rtmr2: [4; 48],
+            },
+            key_provider_event_digest: [5; 48],
+            app_compose_hash_payload: [6; 32],
+        },
+        ppid: HexBytes::from([7; 16]),
+        fmspc: Fmspc::from([0xb0, 0xc0, 0x6f, 0, 0, 0]),
+        platform_tcb_status: TcbStatus::SWHardeningNeeded,
+        qe_tcb_status: Some(TcbStatus::UpToDate),
+        mr_config_id: [8; 48],
+        mr_owner: [9; 48],
+        report_data: ReportData::new([10; 64]),
+        advisory_ids: vec!["INTEL-SA-00615".to_string()],
+        verified_at: 1_763_626_832,
+        tcb_info_issued_at: 1_763_600_000,
+        tcb_info_next_update: 1_766_192_000,
+    }
+}
+
+#[test]
+fn test_to_in_toto_serializes_to_statement() {
+    let summary = summary();
+
+    let statement = serde_json::to_value(summary.to_in_toto()).unwrap();
+
+    assert_eq!(
+        statement,
+        json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [
+                {
+                    "name": "app-compose.json",
+                    "digest": { "sha256": "06".repeat(32) },
+                },
+                {
+                    "name": "tdx-measurements",
+                    "digest": { "sha256": hex::encode(summary.measurements.fingerprint()) },
+                },
+            ],
+            "predicateType": TDX_VERIFICATION_PREDICATE_TYPE,
+            "predicate": {
+                "measurements": {
+                    "rtmrs": {
+                        "mrtd": "01".repeat(48),
+                        "rtmr0": "02".repeat(48),
+                        "rtmr1": "03".repeat(48),
+                        "rtmr2": "04".repeat(48),
+                    },
+                    "key_provider_event_digest": "05".repeat(48),
+                    "app_compose_hash_payload": "06".repeat(32),
+                },
+                "fmspc": "b0c06f000000",
+                "ppid": "07".repeat(16),
+                "mr_config_id": "08".repeat(48),
+                "mr_owner": "09".repeat(48),
+                "report_data": "0a".repeat(64),
+                "verification": {
+                    "accepted": true,
+                    "platform_tcb_status": "SWHardeningNeeded",
+                    "qe_tcb_status": "UpToDate",
+                    "advisory_ids": ["INTEL-SA-00615"],
+                    "verified_at": 1_763_626_832,
+                    "tcb_info_issued_at": 1_763_600_000,
+                    "tcb_info_next_update": 1_766_192_000,
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_in_toto_statement_roundtrips() {
+    let statement = summary().to_in_toto();
+
+    let json = serde_json::to_string(&statement).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<InTotoStatement>(&json).unwrap(),
+        statement
+    );
+}
+
+#[test]
+fn test_to_in_toto_without_qe_tcb_status() {
+    let summary = AttestationSummary {
+        qe_tcb_status: None,
+        ..summary()
+    };
+
+    let statement = serde_json::to_value(summary.to_in_toto()).unwrap();
+
+    assert_eq!(
+        statement["predicate"]["verification"]["qe_tcb_status"],
+        json!(null)
+    );
+}
@@ -0,0 +1,1315 @@
+use assert_matches::assert_matches;
+use attestation::{
+    measurements::{
+        Allowlist, AllowlistDelta, ExtendedMeasurements, FirmwareAllowlist, FirmwareVersion,
+        FullMeasurements, FullMeasurementsHex, LabeledAllowlist, LabeledMismatch,
+        MEASUREMENTS_SIZE, MaskedMeasurements, MeasurementField, Measurements, MeasurementsError,
+        MeasurementsHex, MeasurementsNearJson, MerkleProof, PACKED_SIZE, Rtmr0Tolerance,
+        RtmrRegister, UpgradeWindow, WhichBaseline, distinguishing_registers, extend_register,
+        replay, to_near_call_args, verify_allowlist_membership, verify_app_compose_in_rtmr2,
+    },
+    quote::ParsedQuote,
+    tcb_info::TcbInfo,
+};
+use dcap_qvl::{
+    quote::{Report, TDReport10},
+    verify::VerifiedReport,
+};
+use rstest::rstest;
+use std::{collections::HashSet, ops::Range};
+use test_utils::attestation::{TEST_TCB_INFO_STRING, quote};
+
+fn measurements() -> Measurements {
+    Measurements {
+        mrtd: [1; 48],
+        rtmr0: [2; 48],
+        rtmr1: [3; 48],
+        rtmr2: [4; 48],
+    }
+}
+
+fn full_measurements() -> FullMeasurements {
+    FullMeasurements {
+        rtmrs: measurements(),
+        key_provider_event_digest: [5; 48],
+        app_compose_hash_payload: [6; 32],
+    }
+}
+
+#[test]
+fn test_measurements_diff_identical_is_empty() {
+    let diff = measurements().diff(&measurements());
+
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn test_measurements_diff_reports_changed_registers() {
+    let old = measurements();
+    let new = Measurements {
+        rtmr1: [9; 48],
+        ..old
+    };
+
+    let diff = old.diff(&new);
+
+    assert_eq!(
+        diff.changed_registers().collect::<Vec<_>>(),
+        vec![RtmrRegister::Rtmr1]
+    );
+    let (_, change) = diff.changes[0];
+    assert_eq!(change.old, [3; 48]);
+    assert_eq!(change.new, [9; 48]);
+}
+
+#[test]
+fn test_full_measurements_diff_only_app_compose_changed() {
+    let old = full_measurements();
+    let new = FullMeasurements {
+        app_compose_hash_payload: [7; 32],
+        ..old
+    };
+
+    let diff = old.diff(&new);
+
+    assert!(diff.only_app_compose_hash_payload_changed());
+    assert_eq!(
+        diff.changed_fields(),
+        vec![MeasurementField::AppComposeHashPayload]
+    );
+}
+
+#[test]
+fn test_full_measurements_diff_covers_all_fields() {
+    let old = full_measurements();
+    let new = FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: [0; 48],
+            ..old.rtmrs
+        },
+        key_provider_event_digest: [0; 48],
+        app_compose_hash_payload: [0; 32],
+    };
+
+    let diff = old.diff(&new);
+
+    assert!(!diff.only_app_compose_hash_payload_changed());
+    assert_eq!(
+        diff.changed_fields(),
+        vec![
+            MeasurementField::Register(RtmrRegister::Mrtd),
+            MeasurementField::KeyProviderEventDigest,
+            MeasurementField::AppComposeHashPayload,
+        ]
+    );
+}
+
+#[test]
+fn test_full_measurements_diff_identical_is_empty() {
+    let diff = full_measurements().diff(&full_measurements());
+
+    assert!(diff.is_empty());
+    assert!(diff.changed_fields().is_empty());
+}
+
+/// [`full_measurements`] with every field of `changed` set to another value.
+fn with_changed_fields(changed: &[MeasurementField]) -> FullMeasurements {
+    let mut measurements = full_measurements();
+    for field in changed {
+        let value: &mut [u8] = match field {
+            MeasurementField::Register(RtmrRegister::Mrtd) => &mut measurements.rtmrs.mrtd,
+            MeasurementField::Register(RtmrRegister::Rtmr0) => &mut measurements.rtmrs.rtmr0,
+            MeasurementField::Register(RtmrRegister::Rtmr1) => &mut measurements.rtmrs.rtmr1,
+            MeasurementField::Register(RtmrRegister::Rtmr2) => &mut measurements.rtmrs.rtmr2,
+            MeasurementField::KeyProviderEventDigest => &mut measurements.key_provider_event_digest,
+            MeasurementField::AppComposeHashPayload => &mut measurements.app_compose_hash_payload,
+        };
+        value.fill(0xee);
+    }
+    measurements
+}
+
+const RTMR2: MeasurementField = MeasurementField::Register(RtmrRegister::Rtmr2);
+const APP_COMPOSE: MeasurementField = MeasurementField::AppComposeHashPayload;
+
+#[rstest]
+#[case(&[], false)]
+#[case(&[RTMR2], true)]
+#[case(&[APP_COMPOSE], true)]
+#[case(&[RTMR2, APP_COMPOSE], true)]
+#[case(&[MeasurementField::Register(RtmrRegister::Mrtd)], false)]
+#[case(&[MeasurementField::Register(RtmrRegister::Rtmr0)], false)]
+#[case(&[MeasurementField::Register(RtmrRegister::Rtmr1)], false)]
+#[case(&[MeasurementField::KeyProviderEventDigest], false)]
+#[case(&[MeasurementField::Register(RtmrRegister::Mrtd), RTMR2], false)]
+#[case(&[MeasurementField::Register(RtmrRegister::Rtmr1), APP_COMPOSE], false)]
+#[case(&[MeasurementField::KeyProviderEventDigest, RTMR2, APP_COMPOSE], false)]
+fn test_differs_only_in_app(#[case] changed: &[MeasurementField], #[case] app_only: bool) {
+    let old = full_measurements();
+    let new = with_changed_fields(changed);
+
+    assert_eq!(old.differs_only_in_app(&new), app_only);
+    assert_eq!(new.differs_only_in_app(&old), app_only);
+}
+
+fn verified_report(mrtd: [u8; 48], rtmr: [u8; 48]) -> VerifiedReport {
+    VerifiedReport {
+        status: String::from("UpToDate"),
+        advisory_ids: Vec::new(),
+        report: Report::TD10(TDReport10 {
+            tee_tcb_svn: [0; 16],
+            mr_seam: [0; 48],
+            mr_signer_seam: [0; 48],
+            seam_attributes: [0; 8],
+            td_attributes: [0; 8],
+            xfam: [0; 8],
+            mr_td: mrtd,
+            mr_config_id: [0; 48],
+            mr_owner: [0; 48],
+            mr_owner_config: [0; 48],
+            rt_mr0: rtmr,
+            rt_mr1: rtmr,
+            rt_mr2: rtmr,
+            rt_mr3: rtmr,
+            report_data: [0; 64],
+        }),
+        ppid: Vec::new(),
+    }
+}
+
+#[test]
+fn test_measurements_from_report_with_default_rtmrs_is_inconsistent() {
+    let report = verified_report([1; 48], [0; 48]);
+
+    let result = Measurements::try_from(report);
+
+    assert_matches!(result, Err(MeasurementsError::InconsistentReport(_)));
+}
+
+#[test]
+fn test_measurements_from_report_with_uniform_registers_is_suspicious() {
+    let report = verified_report([7; 48], [7; 48]);
+
+    assert!(Measurements::try_from(&report.report).is_err());
+    assert_matches!(
+        Measurements::try_from(report),
+        Err(MeasurementsError::SuspiciousUniformRegisters)
+    );
+}
+
+#[test]
+fn test_looks_stubbed_requires_all_four_registers_equal() {
+    let uniform = Measurements {
+        mrtd: [7; 48],
+        rtmr0: [7; 48],
+        rtmr1: [7; 48],
+        rtmr2: [7; 48],
+    };
+
+    assert!(uniform.looks_stubbed());
+    assert!(
+        !Measurements {
+            rtmr2: [8; 48],
+            ..uniform
+        }
+        .looks_stubbed()
+    );
+    assert!(!Measurements::default().looks_stubbed());
+}
+
+#[test]
+fn test_measurements_from_consistent_report() {
+    let report = verified_report([1; 48], [2; 48]);
+
+    let measurements = Measurements::try_from(report).unwrap();
+
+    assert_eq!(measurements.mrtd, [1; 48]);
+    assert_eq!(measurements.rtmr0, [2; 48]);
+}
+
+/// The registers of the JSON the contract returns for a `contract_interface::types::TcbInfo`,
+/// with the values of `assets/tcb_info.json`.
+const CONTRACT_MEASUREMENTS_JSON: &str = r#"{"mrtd":"f06dfda6dce1cf904d4e2bab1dc370634cf95cefa2ceb2de2eee127c9382698090d7a4a13e14c536ec6c9c3c8fa87077","rtmr0":"e673be2f70beefb70b48a6109eed4715d7270d4683b3bf356fa25fafbf1aa76e39e9127e6e688ccda98bdab1d4d47f46","rtmr1":"a7b523278d4f914ee8df0ec80cd1c3d498cbf1152b0c5eaf65bad9425072874a3fcf891e8b01713d3d9937e3e0d26c15","rtmr2":"dbf4924c07f5066f3dc6859844184344306aa3263817153dcaee85af97d23e0c0b96efe0731d8865a8747e51b9e351ac"}"#;
+
+#[test]
+fn test_measurements_near_json_round_trip() {
+    let near_json: MeasurementsNearJson = serde_json::from_str(CONTRACT_MEASUREMENTS_JSON).unwrap();
+
+    let hex = MeasurementsHex::from(near_json.clone());
+    let serialized = serde_json::to_string(&MeasurementsNearJson::from(hex.clone())).unwrap();
+
+    assert_eq!(serialized, CONTRACT_MEASUREMENTS_JSON);
+    assert_eq!(
+        hex::encode(*hex.rtmr1),
+        "a7b523278d4f914ee8df0ec80cd1c3d498cbf1152b0c5eaf65bad9425072874a3fcf891e8b01713d3d9937e3e0d26c15"
+    );
+}
+
+#[test]
+fn test_measurements_near_json_reads_contract_tcb_info() {
+    let tcb_info: serde_json::Value =
+        serde_json::from_str(include_str!("../assets/tcb_info.json")).unwrap();
+
+    let near_json: MeasurementsNearJson = serde_json::from_value(tcb_info).unwrap();
+
+    assert_eq!(
+        near_json,
+        serde_json::from_str(CONTRACT_MEASUREMENTS_JSON).unwrap()
+    );
+}
+
+#[test]
+fn test_measurements_near_json_rejects_pascal_case_fields() {
+    let pascal_case = CONTRACT_MEASUREMENTS_JSON
+        .replace("mrtd", "Mrtd")
+        .replace("rtmr", "Rtmr");
+
+    let result: Result<MeasurementsNearJson, _> = serde_json::from_str(&pascal_case);
+
+    assert!(result.is_err());
+}
+
+fn allowlist_entry(seed: u8) -> FullMeasurements {
+    FullMeasurements {
+        app_compose_hash_payload: [seed; 32],
+        ..full_measurements()
+    }
+}
+
+#[test]
+fn test_allowlist_deduplicates_entries() {
+    let allowlist = Allowlist::new([allowlist_entry(1), allowlist_entry(2), allowlist_entry(1)]);
+
+    assert_eq!(allowlist.len(), 2);
+    assert!(allowlist.contains(&allowlist_entry(1)));
+    assert!(!allowlist.contains(&allowlist_entry(3)));
+}
+
+#[test]
+fn test_allowlist_merkle_root_is_order_independent() {
+    let forward = Allowlist::new((0..5).map(allowlist_entry));
+    let backward = Allowlist::new((0..5).rev().map(allowlist_entry));
+
+    assert_eq!(forward.merkle_root(), backward.merkle_root());
+    assert_ne!(
+        forward.merkle_root(),
+        Allowlist::new((0..4).map(allowlist_entry)).merkle_root()
+    );
+}
+
+#[test]
+fn test_allowlist_membership_proofs_verify_for_every_entry() {
+    for size in 1..=7 {
+        let allowlist = Allowlist::new((0..size).map(allowlist_entry));
+        let root = allowlist.merkle_root();
+
+        for entry in allowlist.entries() {
+            let proof = allowlist.merkle_proof(entry).unwrap();
+            assert_eq!(verify_allowlist_membership(entry, &proof, root), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn test_allowlist_membership_rejects_foreign_entry_and_wrong_root() {
+    let allowlist = Allowlist::new((0..5).map(allowlist_entry));
+    let member = allowlist_entry(2);
+    let proof = allowlist.merkle_proof(&member).unwrap();
+
+    assert_eq!(allowlist.merkle_proof(&allowlist_entry(9)), None);
+    assert_eq!(
+        verify_allowlist_membership(&allowlist_entry(9), &proof, allowlist.merkle_root()),
+        Err(MeasurementsError::NotInAllowlist)
+    );
+    assert_eq!(
+        verify_allowlist_membership(&member, &proof, [0; 32]),
+        Err(MeasurementsError::NotInAllowlist)
+    );
+    assert_eq!(
+        verify_allowlist_membership(&member, &MerkleProof::default(), allowlist.merkle_root()),
+        Err(MeasurementsError::NotInAllowlist)
+    );
+}
+
+#[test]
+fn test_verify_against_fingerprint() {
+    let measurements = full_measurements();
+    let other = allowlist_entry(9);
+
+    assert_eq!(
+        measurements.verify_against_fingerprint(measurements.fingerprint()),
+        Ok(())
+    );
+    assert_eq!(
+        measurements.verify_against_fingerprint(other.fingerprint()),
+        Err(MeasurementsError::FingerprintMismatch {
+            expected: hex::encode(other.fingerprint()),
+            found: hex::encode(measurements.fingerprint()),
+        })
+    );
+}
+
+#[test]
+fn test_measurements_similarity() {
+    let old = measurements();
+    let one_register_changed = Measurements {
+        rtmr2: [9; 48],
+        ..old
+    };
+    let all_changed = Measurements {
+        mrtd: [9; 48],
+        rtmr0: [9; 48],
+        rtmr1: [9; 48],
+        rtmr2: [9; 48],
+    };
+
+    assert_eq!(old.similarity(&old), 1.0);
+    assert_eq!(old.similarity(&one_register_changed), 0.75);
+    assert_eq!(old.similarity(&all_changed), 0.0);
+}
+
+#[test]
+fn test_measurements_fixed_size_encoding_roundtrip() {
+    let bytes = measurements().to_bytes();
+
+    assert_eq!(bytes[..48], [1; 48]);
+    assert_eq!(bytes[144..], [4; 48]);
+    assert_eq!(Measurements::from(bytes).to_bytes(), bytes);
+    assert_eq!(
+        Measurements::try_from(bytes.as_slice()).unwrap().to_bytes(),
+        bytes
+    );
+}
+
+#[test]
+fn test_measurements_try_from_slice_rejects_wrong_length() {
+    let bytes = [0u8; MEASUREMENTS_SIZE + 1];
+
+    assert_matches!(
+        Measurements::try_from(&bytes[..MEASUREMENTS_SIZE - 1]),
+        Err(MeasurementsError::WrongSize {
+            expected: MEASUREMENTS_SIZE,
+            found: 191
+        })
+    );
+    assert_matches!(
+        Measurements::try_from(bytes.as_slice()),
+        Err(MeasurementsError::WrongSize { found: 193, .. })
+    );
+}
+
+#[test]
+fn test_verify_app_compose_in_rtmr2() {
+    let app_compose_hash_payload = [6; 32];
+    let prior_rtmr2 = [4; 48];
+    let mut padded = [0; 48];
+    padded[..32].copy_from_slice(&app_compose_hash_payload);
+    let rtmr2 = extend_register(&prior_rtmr2, &padded);
+
+    assert!(verify_app_compose_in_rtmr2(
+        &app_compose_hash_payload,
+        &rtmr2,
+        &prior_rtmr2
+    ));
+    assert!(!verify_app_compose_in_rtmr2(&[7; 32], &rtmr2, &prior_rtmr2));
+    assert!(!verify_app_compose_in_rtmr2(
+        &app_compose_hash_payload,
+        &rtmr2,
+        &[0; 48]
+    ));
+}
+
+const MEASUREMENTS_HEX_JSON: &str = r#"{
+    "mrtd": "010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101",
+    "rtmr0": "020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202",
+    "rtmr1": "030303030303030303030303030303030303030303030303030303030303030303030303030303030303030303030303",
+    "rtmr2": "040404040404040404040404040404040404040404040404040404040404040404040404040404040404040404040404"
+}"#;
+
+#[test]
+fn test_measurements_hex_from_json_strict_accepts_valid_document() {
+    let strict = MeasurementsHex::from_json_strict(MEASUREMENTS_HEX_JSON).unwrap();
+
+    assert_eq!(strict, MeasurementsHex::from(measurements()));
+}
+
+#[test]
+fn test_measurements_hex_from_json_strict_rejects_duplicate_register() {
+    let duplicated = MEASUREMENTS_HEX_JSON.replacen(
+        r#""rtmr1""#,
+        r#""rtmr0": "090909090909090909090909090909090909090909090909090909090909090909090909090909090909090909090909",
+    "rtmr1""#,
+        1,
+    );
+    // A `serde_json::Value` silently keeps the last value.
+    let value: serde_json::Value = serde_json::from_str(&duplicated).unwrap();
+    assert!(value["rtmr0"].as_str().unwrap().starts_with("09"));
+
+    let result = MeasurementsHex::from_json_strict(&duplicated);
+
+    assert_matches!(result, Err(MeasurementsError::InvalidJson(message)) if message.contains("duplicate key `rtmr0`"));
+}
+
+#[test]
+fn test_measurements_hex_from_hex_strs() {
+    let register = |byte: u8| hex::encode([byte; 48]);
+
+    let parsed =
+        MeasurementsHex::from_hex_strs(&register(1), &register(2), &register(3), &register(4));
+
+    assert_eq!(parsed, Ok(MeasurementsHex::from(measurements())));
+}
+
+#[test]
+fn test_measurements_hex_from_hex_strs_names_the_invalid_register() {
+    let valid = hex::encode([1; 48]);
+
+    let not_hex = MeasurementsHex::from_hex_strs(&valid, &valid, "zz", &valid);
+    let too_short = MeasurementsHex::from_hex_strs(&valid, &valid, &valid, &hex::encode([1; 47]));
+
+    assert_matches!(not_hex, Err(MeasurementsError::InvalidHexValue(name, _)) if name == "rtmr1");
+    assert_eq!(
+        too_short,
+        Err(MeasurementsError::InvalidLength("rtmr2".to_string(), 47))
+    );
+}
+
+#[test]
+fn test_measurements_hex_from_hex_strs_recognizes_digest_in_register() {
+    let valid = hex::encode([1; 48]);
+    let app_compose_hash = hex::encode([2; 32]);
+
+    let error =
+        MeasurementsHex::from_hex_strs(&app_compose_hash, &valid, &valid, &valid).unwrap_err();
+
+    assert_eq!(
+        error,
+        MeasurementsError::DigestInRegisterField("mrtd".to_string())
+    );
+    assert!(
+        error
+            .to_string()
+            .contains("got 64 hex characters, expected 96")
+    );
+}
+
+#[test]
+fn test_measurements_in_hash_set() {
+    let other = Measurements {
+        rtmr2: [9; 48],
+        ..measurements()
+    };
+    let allowlist: HashSet<Measurements> = [measurements(), other, measurements()].into();
+
+    assert_eq!(allowlist.len(), 2);
+    assert!(allowlist.contains(&measurements()));
+    assert!(allowlist.contains(&other));
+    assert!(!allowlist.contains(&Measurements::default()));
+}
+
+#[test]
+fn test_full_measurements_in_hash_set() {
+    let other = FullMeasurements {
+        app_compose_hash_payload: [9; 32],
+        ..full_measurements()
+    };
+    let allowlist: HashSet<FullMeasurements> = [full_measurements(), other].into();
+
+    assert!(allowlist.contains(&full_measurements()));
+    assert!(allowlist.contains(&other));
+    assert!(!allowlist.contains(&FullMeasurements {
+        key_provider_event_digest: [9; 48],
+        ..full_measurements()
+    }));
+}
+
+/// The verified report of the test quote, with the event log and baseline of its TCB info.
+fn test_report_and_baseline() -> (VerifiedReport, TcbInfo, FullMeasurements) {
+    let report = VerifiedReport {
+        status: String::from("UpToDate"),
+        advisory_ids: Vec::new(),
+        report: ParsedQuote::parse(&quote()).unwrap().quote().report.clone(),
+        ppid: Vec::new(),
+    };
+    let tcb_info: TcbInfo = serde_json::from_str(TEST_TCB_INFO_STRING).unwrap();
+    let key_provider = tcb_info
+        .event_log
+        .iter()
+        .find(|event| event.event == "key-provider")
+        .unwrap();
+    let baseline = FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: *tcb_info.mrtd,
+            rtmr0: *tcb_info.rtmr0,
+            rtmr1: *tcb_info.rtmr1,
+            rtmr2: *tcb_info.rtmr2,
+        },
+        key_provider_event_digest: *key_provider.digest,
+        app_compose_hash_payload: *tcb_info.compose_hash,
+    };
+    (report, tcb_info, baseline)
+}
+
+#[test]
+fn test_verify_report_accepts_matching_baseline() {
+    let (report, tcb_info, baseline) = test_report_and_baseline();
+
+    assert_matches!(baseline.verify_report(&report, &tcb_info.event_log), Ok(()));
+}
+
+#[test]
+fn test_verify_report_rejects_other_registers() {
+    let (report, tcb_info, mut baseline) = test_report_and_baseline();
+    baseline.rtmrs.rtmr1 = [0; 48];
+
+    assert_matches!(
+        baseline.verify_report(&report, &tcb_info.event_log),
+        Err(MeasurementsError::BaselineMismatch { registers }) if registers == [RtmrRegister::Rtmr1]
+    );
+}
+
+#[test]
+fn test_verify_report_rejects_other_app_compose() {
+    let (report, tcb_info, mut baseline) = test_report_and_baseline();
+    baseline.app_compose_hash_payload = [0; 32];
+
+    assert_matches!(
+        baseline.verify_report(&report, &tcb_info.event_log),
+        Err(MeasurementsError::EventMismatch("compose-hash"))
+    );
+}
+
+#[test]
+fn test_verify_report_rejects_missing_key_provider_event() {
+    let (report, mut tcb_info, baseline) = test_report_and_baseline();
+    tcb_info
+        .event_log
+        .retain(|event| event.event != "key-provider");
+
+    assert_matches!(
+        baseline.verify_report(&report, &tcb_info.event_log),
+        Err(MeasurementsError::EventCount("key-provider", 0))
+    );
+}
+
+#[test]
+fn test_verify_report_rejects_event_log_not_replaying_to_report() {
+    let (report, mut tcb_info, baseline) = test_report_and_baseline();
+    let firmware_event = tcb_info
+        .event_log
+        .iter_mut()
+        .find(|event| event.imr == 0)
+        .unwrap();
+    firmware_event.digest = [0; 48].into();
+
+    assert_matches!(
+        baseline.verify_report(&report, &tcb_info.event_log),
+        Err(MeasurementsError::EventLogReplayMismatch { imr: 0 })
+    );
+}
+
+#[test]
+fn test_rtmr1_from_boot_matches_reference_vector() {
+    let rtmr1 = replay::rtmr1_from_boot([0x11; 48], "console=ttyS0 root=/dev/vda1", [0x22; 48]);
+
+    assert_eq!(
+        hex::encode(rtmr1),
+        "4b9a457c8c4319cd3b12b14a37550d1bea651aab183647a6cdfd417555471a4d67413065b59d71a2eb977c32db11fdfe"
+    );
+}
+
+#[test]
+fn test_rtmr1_from_boot_measures_empty_cmdline_terminator() {
+    let rtmr1 = replay::rtmr1_from_boot([0x11; 48], "", [0x22; 48]);
+
+    assert_eq!(
+        hex::encode(rtmr1),
+        "e7b7ed74bb54394e95fa2cca7b4f5b23e2dd5f490fddb3a64ae4a2fa6f5322b31489ac926e1a8bc4fd1c04966a63f457"
+    );
+}
+
+#[test]
+fn test_rtmr1_from_boot_depends_on_order() {
+    let rtmr1 = replay::rtmr1_from_boot([0x11; 48], "quiet", [0x22; 48]);
+    let swapped = replay::rtmr1_from_boot([0x22; 48], "quiet", [0x11; 48]);
+
+    assert_ne!(rtmr1, swapped);
+}
+
+#[test]
+fn test_find_event_sequence_recovers_replayed_order() {
+    let candidates = [[0x11; 48], [0x22; 48], [0x33; 48]];
+    let target = [2, 0, 2].iter().fold([0; 48], |register, &i| {
+        extend_register(&register, &candidates[i])
+    });
+
+    assert_eq!(
+        replay::find_event_sequence(target, &candidates, 4),
+        Some(vec![2, 0, 2])
+    );
+}
+
+#[test]
+fn test_find_event_sequence_respects_max_len() {
+    let candidates = [[0x11; 48], [0x22; 48]];
+    let target = [0, 1, 1].iter().fold([0; 48], |register, &i| {
+        extend_register(&register, &candidates[i])
+    });
+
+    assert_eq!(replay::find_event_sequence(target, &candidates, 2), None);
+    assert_eq!(replay::find_event_sequence([7; 48], &[], 8), None);
+}
+
+#[test]
+fn test_find_event_sequence_matches_zero_register_with_no_events() {
+    assert_eq!(
+        replay::find_event_sequence([0; 48], &[[0x11; 48]], 3),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn test_masked_measurements_exact_is_equality() {
+    let masked = MaskedMeasurements::exact(measurements());
+    let mut other = measurements();
+    other.rtmr2[47] ^= 0x01;
+
+    assert!(masked.matches(&measurements()));
+    assert!(!masked.matches(&other));
+}
+
+#[test]
+fn test_masked_measurements_ignore_masked_out_bits() {
+    let mut masked = MaskedMeasurements::exact(measurements());
+    // Ignore the low nibble of the first byte of RTMR1, and all of RTMR2.
+    masked.mask[2][0] = 0xf0;
+    masked.mask[3] = [0; 48];
+
+    let mut varied = measurements();
+    varied.rtmr1[0] ^= 0x0f;
+    varied.rtmr2 = [0xaa; 48];
+    assert!(masked.matches(&varied));
+
+    varied.rtmr1[0] ^= 0x10;
+    assert!(!masked.matches(&varied));
+}
+
+#[test]
+fn test_masked_measurements_compare_each_register_under_its_own_mask() {
+    let mut masked = MaskedMeasurements::exact(measurements());
+    masked.mask[0] = [0; 48];
+
+    let mut varied = measurements();
+    varied.mrtd = [0xaa; 48];
+    assert!(masked.matches(&varied));
+
+    varied.rtmr0[10] ^= 0x80;
+    assert!(!masked.matches(&varied));
+}
+
+#[test]
+fn test_rtmr0_tolerance_ignores_only_its_range() {
+    let mut varied = measurements();
+    varied.rtmr0[..8].copy_from_slice(&[0xaa; 8]);
+
+    assert!(!MaskedMeasurements::exact(measurements()).matches(&varied));
+    let tolerance = Rtmr0Tolerance::new(0..8).unwrap();
+    assert!(tolerance.matches(&measurements(), &varied));
+
+    varied.rtmr0[8] ^= 0x01;
+    assert!(!tolerance.matches(&measurements(), &varied));
+}
+
+#[test]
+fn test_rtmr0_tolerance_compares_other_registers() {
+    let tolerance = Rtmr0Tolerance::new(0..48).unwrap();
+    let changes: [fn(&mut Measurements); 3] = [
+        |varied| varied.mrtd[0] ^= 0x01,
+        |varied| varied.rtmr1[0] ^= 0x01,
+        |varied| varied.rtmr2[47] ^= 0x01,
+    ];
+
+    for change in changes {
+        let mut varied = measurements();
+        change(&mut varied);
+
+        assert!(!tolerance.matches(&measurements(), &varied));
+    }
+}
+
+#[test]
+fn test_rtmr0_tolerance_keeps_other_masks() {
+    let mut masked = MaskedMeasurements::exact(measurements());
+    *masked.register_mask_mut(RtmrRegister::Rtmr2) = [0; 48];
+
+    let masked = Rtmr0Tolerance::new(40..48).unwrap().apply(masked);
+
+    let mut varied = measurements();
+    varied.rtmr0[40..].copy_from_slice(&[0xaa; 8]);
+    varied.rtmr2 = [0xaa; 48];
+    assert!(masked.matches(&varied));
+    assert_eq!(masked.register_mask(RtmrRegister::Rtmr0)[..40], [0xff; 40]);
+}
+
+#[test]
+fn test_rtmr0_tolerance_of_empty_range_is_exact() {
+    let mut varied = measurements();
+    varied.rtmr0[0] ^= 0x01;
+
+    assert!(
+        !Rtmr0Tolerance::new(5..5)
+            .unwrap()
+            .matches(&measurements(), &varied)
+    );
+}
+
+#[rstest]
+#[case(0..49)]
+#[case(48..49)]
+#[case(Range { start: 10, end: 4 })]
+fn test_rtmr0_tolerance_rejects_range_outside_register(#[case] range: Range<usize>) {
+    assert_eq!(
+        Rtmr0Tolerance::new(range.clone()),
+        Err(MeasurementsError::InvalidToleranceRange {
+            start: range.start,
+            end: range.end,
+        })
+    );
+}
+
+#[test]
+fn test_env_vars_roundtrip() {
+    let hex = FullMeasurementsHex::from(full_measurements());
+
+    let vars = hex.to_env_vars("MPC_BASELINE");
+
+    assert_eq!(vars[0], ("MPC_BASELINE_MRTD".to_string(), "01".repeat(48)));
+    assert_eq!(
+        vars[5],
+        (
+            "MPC_BASELINE_APP_COMPOSE_HASH_PAYLOAD".to_string(),
+            "06".repeat(32)
+        )
+    );
+    let unrelated = ("PATH".to_string(), "/usr/bin".to_string());
+    assert_eq!(
+        FullMeasurementsHex::from_env_vars("MPC_BASELINE", vars.into_iter().chain([unrelated])),
+        Ok(hex)
+    );
+}
+
+#[test]
+fn test_from_env_vars_names_missing_or_malformed_variable() {
+    let mut vars = FullMeasurementsHex::from(full_measurements()).to_env_vars("PREFIX");
+    vars.retain(|(name, _)| name != "PREFIX_RTMR1");
+
+    assert_eq!(
+        FullMeasurementsHex::from_env_vars("PREFIX", vars.clone()),
+        Err(MeasurementsError::InvalidHexValue(
+            "PREFIX_RTMR1".to_string(),
+            "missing".to_string()
+        ))
+    );
+    vars.push(("PREFIX_RTMR1".to_string(), "01".repeat(32)));
+    assert_eq!(
+        FullMeasurementsHex::from_env_vars("PREFIX", vars),
+        Err(MeasurementsError::DigestInRegisterField(
+            "PREFIX_RTMR1".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_measurements_from_arrays_in_const_context() {
+    const EXPECTED: Measurements = Measurements::from_arrays([1; 48], [2; 48], [3; 48], [4; 48]);
+
+    assert_eq!(EXPECTED, measurements());
+}
+
+#[test]
+fn test_to_near_call_args_matches_pinned_args() {
+    let expected = format!(
+        r#"{{"measurements":{{"rtmrs":{{"mrtd":"{}","rtmr0":"{}","rtmr1":"{}","rtmr2":"{}"}},"key_provider_event_digest":"{}","app_compose_hash_payload":"{}"}}}}"#,
+        "01".repeat(48),
+        "02".repeat(48),
+        "03".repeat(48),
+        "04".repeat(48),
+        "05".repeat(48),
+        "06".repeat(32),
+    );
+
+    let args = to_near_call_args(&full_measurements());
+
+    assert_eq!(args, expected);
+    let value: serde_json::Value = serde_json::from_str(&args).unwrap();
+    let decoded: FullMeasurementsHex =
+        serde_json::from_value(value["measurements"].clone()).unwrap();
+    assert_eq!(FullMeasurements::from(decoded), full_measurements());
+}
+
+#[test]
+fn test_canonical_signing_bytes_match_independent_serialization() {
+    let measurements = full_measurements();
+    // The keys are listed in byte order, as `serde_json` keeps insertion order when a dependency
+    // enables its `preserve_order` feature.
+    let value = serde_json::json!({
+        "app_compose_hash_payload": hex::encode([6; 32]),
+        "key_provider_event_digest": hex::encode([5; 48]),
+        "rtmrs": {
+            "mrtd": hex::encode([1; 48]),
+            "rtmr0": hex::encode([2; 48]),
+            "rtmr1": hex::encode([3; 48]),
+            "rtmr2": hex::encode([4; 48]),
+        },
+    });
+    let expected = serde_json::to_vec(&value).unwrap();
+
+    let bytes = measurements.canonical_signing_bytes();
+
+    assert_eq!(bytes, expected);
+    let decoded: FullMeasurementsHex = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        FullMeasurements::from(decoded).canonical_signing_bytes(),
+        bytes
+    );
+}
+
+fn allowlist(seeds: impl IntoIterator<Item = u8>) -> Allowlist {
+    Allowlist::new(seeds.into_iter().map(allowlist_entry))
+}
+
+#[test]
+fn test_allowlist_delta_add_only() {
+    let delta = allowlist(0..2).delta(&allowlist(0..4));
+
+    assert_eq!(delta.added, allowlist(2..4).entries());
+    assert!(delta.removed.is_empty());
+}
+
+#[test]
+fn test_allowlist_delta_remove_only() {
+    let delta = allowlist(0..4).delta(&allowlist([1, 3]));
+
+    assert!(delta.added.is_empty());
+    assert_eq!(delta.removed, allowlist([0, 2]).entries());
+}
+
+#[test]
+fn test_allowlist_delta_mixed() {
+    let delta = allowlist(0..4).delta(&allowlist(2..6));
+
+    assert_eq!(delta.added, allowlist(4..6).entries());
+    assert_eq!(delta.removed, allowlist(0..2).entries());
+    assert!(!delta.is_empty());
+}
+
+#[test]
+fn test_allowlist_delta_of_equal_allowlists_is_empty() {
+    let delta = allowlist(0..4).delta(&allowlist((0..4).rev()));
+
+    assert_eq!(delta, AllowlistDelta::default());
+    assert!(delta.is_empty());
+}
+
+#[test]
+fn test_measurements_diff_to_unified() {
+    let old = measurements();
+    let new = Measurements {
+        mrtd: [8; 48],
+        rtmr1: [9; 48],
+        ..old
+    };
+
+    let unified = old.diff(&new).to_unified();
+
+    assert_eq!(
+        unified,
+        format!(
+            "- mrtd: {}\n+ mrtd: {}\n- rtmr1: {}\n+ rtmr1: {}\n",
+            "01".repeat(48),
+            "08".repeat(48),
+            "03".repeat(48),
+            "09".repeat(48),
+        )
+    );
+    assert_eq!(old.diff(&old).to_unified(), "");
+}
+
+fn labeled_entry(rtmrs: Measurements) -> FullMeasurements {
+    FullMeasurements {
+        rtmrs,
+        ..full_measurements()
+    }
+}
+
+#[test]
+fn test_labeled_allowlist_returns_matching_label() {
+    let allowlist = LabeledAllowlist::new([
+        (
+            String::from("v1.0.0"),
+            labeled_entry(Measurements::from_arrays(
+                [1; 48], [9; 48], [9; 48], [9; 48],
+            )),
+        ),
+        (
+            String::from("v1.1.0"),
+            labeled_entry(Measurements::from_arrays(
+                [1; 48], [8; 48], [8; 48], [8; 48],
+            )),
+        ),
+    ]);
+
+    let label = allowlist.verify(&verified_report([1; 48], [8; 48]));
+
+    assert_eq!(label, Ok("v1.1.0"));
+}
+
+#[test]
+fn test_labeled_allowlist_mismatch_names_closest_label() {
+    let allowlist = LabeledAllowlist::new([
+        (
+            String::from("v0.1.0"),
+            labeled_entry(Measurements::from_arrays(
+                [2; 48], [2; 48], [2; 48], [2; 48],
+            )),
+        ),
+        (
+            String::from("v1.2.3"),
+            labeled_entry(Measurements::from_arrays(
+                [1; 48], [9; 48], [9; 48], [8; 48],
+            )),
+        ),
+    ]);
+
+    let result = allowlist.verify(&verified_report([1; 48], [9; 48]));
+
+    let error = result.unwrap_err();
+    assert_matches!(
+        &error,
+        LabeledMismatch::NoMatch { closest, diff }
+            if closest == "v1.2.3" && diff.changed_registers().eq([RtmrRegister::Rtmr2])
+    );
+    assert!(
+        error
+            .to_string()
+            .contains("closest was 'v1.2.3', which differs in [Rtmr2]")
+    );
+}
+
+#[test]
+fn test_labeled_allowlist_rejects_report_when_empty() {
+    let allowlist = LabeledAllowlist::default();
+
+    let result = allowlist.verify(&verified_report([1; 48], [9; 48]));
+
+    assert_eq!(result, Err(LabeledMismatch::EmptyAllowlist));
+}
+
+#[test]
+fn test_packed_layout_pins_field_offsets() {
+    let packed = full_measurements().to_packed();
+
+    assert_eq!(PACKED_SIZE, 272);
+    for (offset, len, value) in [
+        (0, 48, 1),
+        (48, 48, 2),
+        (96, 48, 3),
+        (144, 48, 4),
+        (192, 48, 5),
+        (240, 32, 6),
+    ] {
+        assert_eq!(packed[offset..offset + len], vec![value; len]);
+    }
+    assert_eq!(packed[..MEASUREMENTS_SIZE], measurements().to_bytes());
+}
+
+#[test]
+fn test_packed_roundtrip() {
+    let packed = full_measurements().to_packed();
+
+    assert_eq!(FullMeasurements::from_packed(&packed), full_measurements());
+}
+
+fn firmware_version(label: &str, seed: u8) -> FirmwareVersion {
+    FirmwareVersion {
+        label: label.to_string(),
+        mrtd: [seed; 48],
+        rtmr0: [seed + 1; 48],
+    }
+}
+
+#[test]
+fn test_firmware_allowlist_matches_either_firmware_version() {
+    let allowlist = FirmwareAllowlist::new([
+        firmware_version("2025-06", 1),
+        firmware_version("2025-09", 7),
+    ]);
+    let updated = Measurements {
+        mrtd: [7; 48],
+        rtmr0: [8; 48],
+        ..measurements()
+    };
+
+    assert_eq!(
+        allowlist.matches(&measurements()),
+        Some(&firmware_version("2025-06", 1))
+    );
+    assert_eq!(
+        allowlist.verify(&updated, &[3; 48], &[4; 48]),
+        Ok(&firmware_version("2025-09", 7))
+    );
+    let unknown = Measurements {
+        rtmr0: [9; 48],
+        ..updated
+    };
+    assert_eq!(allowlist.matches(&unknown), None);
+    assert_eq!(
+        allowlist.verify(&unknown, &[3; 48], &[4; 48]),
+        Err(MeasurementsError::BaselineMismatch {
+            registers: vec![RtmrRegister::Mrtd, RtmrRegister::Rtmr0]
+        })
+    );
+}
+
+#[test]
+fn test_firmware_allowlist_verify_requires_the_app() {
+    let allowlist = FirmwareAllowlist::new([
+        firmware_version("2025-06", 1),
+        firmware_version("2025-09", 7),
+    ]);
+
+    assert_eq!(
+        allowlist.verify(&measurements(), &[3; 48], &[5; 48]),
+        Err(MeasurementsError::BaselineMismatch {
+            registers: vec![RtmrRegister::Rtmr2]
+        })
+    );
+}
+
+#[test]
+fn test_firmware_allowlist_replaces_version_with_same_label() {
+    let mut allowlist = FirmwareAllowlist::new([firmware_version("2025-06", 1)]);
+
+    let replaced = allowlist.insert(firmware_version("2025-06", 7));
+
+    assert_eq!(replaced, Some(firmware_version("2025-06", 1)));
+    assert_eq!(allowlist.len(), 1);
+    assert_eq!(allowlist.matches(&measurements()), None);
+}
+
+#[test]
+fn test_matches_any_permutation_detects_swapped_registers() {
+    let swapped = Measurements {
+        rtmr1: [4; 48],
+        rtmr2: [3; 48],
+        ..measurements()
+    };
+
+    assert_eq!(
+        measurements().matches_any_permutation(&measurements()),
+        Some([0, 1, 2, 3])
+    );
+    assert_eq!(
+        measurements().matches_any_permutation(&swapped),
+        Some([0, 1, 3, 2])
+    );
+}
+
+#[test]
+fn test_matches_any_permutation_rejects_other_measurements() {
+    let other = Measurements {
+        rtmr2: [9; 48],
+        ..measurements()
+    };
+
+    assert_eq!(measurements().matches_any_permutation(&other), None);
+}
+
+const UPGRADE_DEADLINE: u64 = 1_000;
+
+/// An upgrade from the baseline of `verified_report([1; 48], [2; 48])` to that of
+/// `verified_report([1; 48], [3; 48])`.
+fn upgrade_window(previous: bool) -> UpgradeWindow {
+    let baseline = |rtmr| labeled_entry(Measurements::from_arrays([1; 48], rtmr, rtmr, rtmr));
+    UpgradeWindow {
+        current: baseline([3; 48]),
+        previous: previous.then(|| baseline([2; 48])),
+        deadline: UPGRADE_DEADLINE,
+    }
+}
+
+#[test]
+fn test_upgrade_window_accepts_current_baseline_at_any_time() {
+    let report = verified_report([1; 48], [3; 48]);
+
+    for now in [0, UPGRADE_DEADLINE, u64::MAX] {
+        assert_eq!(
+            upgrade_window(true).accept(&report, now),
+            Ok(WhichBaseline::Current)
+        );
+    }
+}
+
+#[test]
+fn test_upgrade_window_accepts_previous_baseline_until_deadline() {
+    let report = verified_report([1; 48], [2; 48]);
+    let window = upgrade_window(true);
+
+    assert_eq!(window.accept(&report, 0), Ok(WhichBaseline::Previous));
+    assert_eq!(
+        window.accept(&report, UPGRADE_DEADLINE - 1),
+        Ok(WhichBaseline::Previous)
+    );
+    for now in [UPGRADE_DEADLINE, UPGRADE_DEADLINE + 1] {
+        assert_eq!(
+            window.accept(&report, now),
+            Err(MeasurementsError::UpgradeDeadlinePassed {
+                deadline: UPGRADE_DEADLINE
+            })
+        );
+    }
+}
+
+#[test]
+fn test_upgrade_window_rejects_other_baselines() {
+    let report = verified_report([1; 48], [9; 48]);
+
+    for window in [upgrade_window(true), upgrade_window(false)] {
+        assert_matches!(
+            window.accept(&report, 0),
+            Err(MeasurementsError::BaselineMismatch { registers })
+                if registers == [RtmrRegister::Rtmr0, RtmrRegister::Rtmr1, RtmrRegister::Rtmr2]
+        );
+    }
+    assert_matches!(
+        upgrade_window(false).accept(&verified_report([1; 48], [2; 48]), 0),
+        Err(MeasurementsError::BaselineMismatch { .. })
+    );
+}
+
+/// [`full_measurements`] with the given register values replaced.
+fn image(registers: &[(RtmrRegister, u8)]) -> FullMeasurements {
+    let mut rtmrs = measurements();
+    for (register, value) in registers {
+        let field = match register {
+            RtmrRegister::Mrtd => &mut rtmrs.mrtd,
+            RtmrRegister::Rtmr0 => &mut rtmrs.rtmr0,
+            RtmrRegister::Rtmr1 => &mut rtmrs.rtmr1,
+            RtmrRegister::Rtmr2 => &mut rtmrs.rtmr2,
+        };
+        *field = [*value; 48];
+    }
+    FullMeasurements {
+        rtmrs,
+        ..full_measurements()
+    }
+}
+
+#[test]
+fn test_distinguishing_registers_picks_single_differing_register() {
+    let images = [
+        image(&[]),
+        image(&[(RtmrRegister::Rtmr2, 9)]),
+        image(&[(RtmrRegister::Rtmr2, 10)]),
+    ];
+
+    assert_eq!(distinguishing_registers(&images), [RtmrRegister::Rtmr2]);
+}
+
+#[test]
+fn test_distinguishing_registers_prefers_register_that_differs_everywhere() {
+    // RTMR1 only tells the first image apart, RTMR2 tells all three apart.
+    let images = [
+        image(&[(RtmrRegister::Rtmr1, 9), (RtmrRegister::Rtmr2, 9)]),
+        image(&[(RtmrRegister::Rtmr2, 10)]),
+        image(&[]),
+    ];
+
+    assert_eq!(distinguishing_registers(&images), [RtmrRegister::Rtmr2]);
+}
+
+#[test]
+fn test_distinguishing_registers_combines_registers() {
+    // No single register tells the four images apart, but MRTD and RTMR0 together do.
+    let images = [
+        image(&[]),
+        image(&[(RtmrRegister::Mrtd, 9)]),
+        image(&[(RtmrRegister::Rtmr0, 9)]),
+        image(&[(RtmrRegister::Mrtd, 9), (RtmrRegister::Rtmr0, 9)]),
+    ];
+
+    assert_eq!(
+        distinguishing_registers(&images),
+        [RtmrRegister::Mrtd, RtmrRegister::Rtmr0]
+    );
+}
+
+#[test]
+fn test_distinguishing_registers_returns_every_register_if_images_are_not_distinguishable() {
+    let images = [
+        full_measurements(),
+        FullMeasurements {
+            app_compose_hash_payload: [9; 32],
+            ..full_measurements()
+        },
+    ];
+
+    assert_eq!(distinguishing_registers(&images), RtmrRegister::ALL);
+}
+
+#[test]
+fn test_distinguishing_registers_of_fewer_than_two_images_is_empty() {
+    assert!(distinguishing_registers(&[]).is_empty());
+    assert!(distinguishing_registers(&[full_measurements()]).is_empty());
+}
+
+#[test]
+fn test_codename_is_stable() {
+    // Pinned: these must never change, codenames are quoted in runbooks and incident notes.
+    assert_eq!(full_measurements().codename(), "tiny-hazel");
+    assert_eq!(
+        FullMeasurements::from(FullMeasurementsHex::default()).codename(),
+        "upbeat-zebra"
+    );
+}
+
+#[test]
+fn test_codename_is_an_adjective_noun_pair() {
+    let codename = full_measurements().codename();
+    let words: Vec<_> = codename.split('-').collect();
+
+    assert_eq!(words.len(), 2);
+    assert!(
+        words
+            .iter()
+            .all(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_lowercase()))
+    );
+}
+
+#[test]
+fn test_extended_measurements_from_report_with_rtmr3() {
+    let mut report = verified_report([1; 48], [2; 48]);
+    if let Report::TD10(td10) = &mut report.report {
+        td10.rt_mr3 = [3; 48];
+    }
+
+    let extended = ExtendedMeasurements::try_from(&report.report).unwrap();
+
+    assert_eq!(
+        extended.measurements,
+        Measurements::try_from(&report.report).unwrap()
+    );
+    assert_eq!(extended.rtmr3, Some([3; 48]));
+}
+
+#[test]
+fn test_extended_measurements_from_report_without_rtmr3() {
+    let mut report = verified_report([1; 48], [2; 48]);
+    if let Report::TD10(td10) = &mut report.report {
+        td10.rt_mr3 = [0; 48];
+    }
+
+    let extended = ExtendedMeasurements::try_from(&report.report).unwrap();
+
+    assert_eq!(extended.rtmr3, None);
+}
@@ -0,0 +1,146 @@
+use assert_matches::assert_matches;
+use attestation::{
+    measurements::{MeasurementsError, mrtd_from_firmware},
+    tcb_info::TcbInfo,
+};
+use test_utils::attestation::TEST_TCB_INFO_STRING;
+
+const IMAGE_SIZE: usize = 0x4000;
+const METADATA_OFFSET: usize = 0x1000;
+
+const OVMF_TABLE_FOOTER_GUID: [u8; 16] = [
+    0xde, 0x82, 0xb5, 0x96, 0xb2, 0x1f, 0xf7, 0x45, 0xba, 0xea, 0xa3, 0x66, 0xc5, 0x5a, 0x08, 0x2d,
+];
+const TDX_METADATA_OFFSET_GUID: [u8; 16] = [
+    0x35, 0x65, 0x7a, 0xe4, 0x4a, 0x98, 0x98, 0x47, 0x86, 0x5e, 0x46, 0x85, 0xa7, 0xbf, 0x8e, 0xc2,
+];
+
+/// `(data_offset, raw_data_size, memory_address, memory_data_size, type, attributes)`
+type Section = (u32, u32, u64, u64, u32, u32);
+
+/// A boot firmware volume of one page of data measured into two pages, a temporary memory
+/// page which is only added, and a permanent memory page accepted later.
+const SECTIONS: [Section; 3] = [
+    (0, 0x1000, 0x10_0000, 0x2000, 0, 1),
+    (0, 0, 0x80_0000, 0x1000, 3, 0),
+    (0, 0, 0x90_0000, 0x1000, 4, 2),
+];
+
+/// A 16 KiB TDVF image with `sections`, their data in the first page, the TDVF metadata in
+/// the second and the OVMF table at the end.
+fn firmware(sections: &[Section]) -> Vec<u8> {
+    let mut image = vec![0; IMAGE_SIZE];
+    for (index, byte) in image[..0x1000].iter_mut().enumerate() {
+        *byte = u8::try_from(index % 251).unwrap();
+    }
+
+    let count = u32::try_from(sections.len()).unwrap();
+    let mut metadata = b"TDVF".to_vec();
+    metadata.extend_from_slice(&(16 + 32 * count).to_le_bytes());
+    metadata.extend_from_slice(&1u32.to_le_bytes());
+    metadata.extend_from_slice(&count.to_le_bytes());
+    for &(data_offset, raw_data_size, memory_address, memory_data_size, kind, attributes) in
+        sections
+    {
+        metadata.extend_from_slice(&data_offset.to_le_bytes());
+        metadata.extend_from_slice(&raw_data_size.to_le_bytes());
+        metadata.extend_from_slice(&memory_address.to_le_bytes());
+        metadata.extend_from_slice(&memory_data_size.to_le_bytes());
+        metadata.extend_from_slice(&kind.to_le_bytes());
+        metadata.extend_from_slice(&attributes.to_le_bytes());
+    }
+    image[METADATA_OFFSET..METADATA_OFFSET + metadata.len()].copy_from_slice(&metadata);
+
+    let mut table = u32::try_from(IMAGE_SIZE - METADATA_OFFSET)
+        .unwrap()
+        .to_le_bytes()
+        .to_vec();
+    table.extend_from_slice(&22u16.to_le_bytes());
+    table.extend_from_slice(&TDX_METADATA_OFFSET_GUID);
+    table.extend_from_slice(&(u16::try_from(table.len()).unwrap() + 18).to_le_bytes());
+    table.extend_from_slice(&OVMF_TABLE_FOOTER_GUID);
+    let table_end = IMAGE_SIZE - 0x20;
+    image[table_end - table.len()..table_end].copy_from_slice(&table);
+    image
+}
+
+#[test]
+fn test_mrtd_from_firmware_matches_reference() {
+    // Computed independently from the TDX module's MRTD extension of each added and measured
+    // page. The image is synthetic, see
+    // `test_mrtd_from_firmware_matches_published_firmware` for a real one.
+    assert_eq!(
+        hex::encode(mrtd_from_firmware(&firmware(&SECTIONS)).unwrap()),
+        "445e15fba1fa60e29c5b27dc1e8ff12b8efc14138fcf31f8102e5add7dd5c645\
+         d30973d6a14b23d6460376957c295525"
+    );
+}
+
+/// Checks against the OVMF build of the dstack OS image that produced the test quote, whose
+/// MRTD is the one in `tcb_info.json`. The image is several megabytes and is not vendored:
+/// point `TDVF_FIRMWARE_IMAGE` at the `ovmf.fd` of that dstack OS release and run with
+/// `--ignored`.
+#[test]
+#[ignore = "needs the published OVMF image in TDVF_FIRMWARE_IMAGE"]
+fn test_mrtd_from_firmware_matches_published_firmware() {
+    let path = std::env::var("TDVF_FIRMWARE_IMAGE").expect("TDVF_FIRMWARE_IMAGE is not set");
+    let image = std::fs::read(path).unwrap();
+    let tcb_info: TcbInfo = serde_json::from_str(TEST_TCB_INFO_STRING).unwrap();
+
+    assert_eq!(mrtd_from_firmware(&image).unwrap(), *tcb_info.mrtd);
+}
+
+#[test]
+fn test_mrtd_from_firmware_of_single_added_page() {
+    assert_eq!(
+        hex::encode(mrtd_from_firmware(&firmware(&SECTIONS[1..2])).unwrap()),
+        "73e66eb2f63d5a2c92c756c54b86b24f1a3d87c07a191518580ba4b227f9edda\
+         1faeb4223ffb2dd70c789055f9af5e7c"
+    );
+}
+
+#[test]
+fn test_mrtd_from_firmware_depends_on_measured_data() {
+    let mut image = firmware(&SECTIONS);
+    let mrtd = mrtd_from_firmware(&image).unwrap();
+
+    image[0x80] ^= 0xff;
+
+    assert_ne!(mrtd_from_firmware(&image).unwrap(), mrtd);
+}
+
+#[test]
+fn test_mrtd_from_firmware_rejects_image_without_ovmf_table() {
+    assert_matches!(
+        mrtd_from_firmware(&[0; IMAGE_SIZE]),
+        Err(MeasurementsError::InvalidFirmware(_))
+    );
+    assert_matches!(
+        mrtd_from_firmware(&[]),
+        Err(MeasurementsError::InvalidFirmware(_))
+    );
+}
+
+#[test]
+fn test_mrtd_from_firmware_rejects_bad_signature() {
+    let mut image = firmware(&SECTIONS);
+    image[METADATA_OFFSET] = b'X';
+
+    assert_matches!(
+        mrtd_from_firmware(&image),
+        Err(MeasurementsError::InvalidFirmware(reason)) if reason.contains("signature")
+    );
+}
+
+#[test]
+fn test_mrtd_from_firmware_rejects_invalid_sections() {
+    let outside_image: [Section; 1] = [(0x3000, 0x2000, 0x10_0000, 0x1000, 0, 1)];
+    let unaligned: [Section; 1] = [(0, 0, 0x10_0800, 0x1000, 3, 0)];
+
+    for sections in [&outside_image, &unaligned] {
+        assert_matches!(
+            mrtd_from_firmware(&firmware(sections)),
+            Err(MeasurementsError::InvalidFirmware(reason)) if reason.contains("section 0")
+        );
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "near")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{
+        NearPublicKey, QuoteVerifyOptions, VerificationError, verify_against_contract_commitment,
+        verify_to_near_public_key,
+    },
+    collateral::Collateral,
+    quote::ParsedQuote,
+    tcb_status::TcbStatus,
+};
+use test_utils::attestation::{collateral, quote};
+
+/// A timestamp at which the test quote and collateral are valid.
+const VALID_TIMESTAMP_SECONDS: u64 = 1763626832;
+
+#[test]
+fn test_near_public_key_from_ed25519() {
+    let key = NearPublicKey::from_ed25519(&[0; 32]);
+
+    assert_eq!(key.as_str(), "ed25519:11111111111111111111111111111111");
+    assert_eq!(key.to_string(), key.as_str());
+}
+
+#[test]
+fn test_verify_to_near_public_key_rejects_unbound_key() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let result = verify_to_near_public_key(
+        &quote(),
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &[7; 32],
+        &QuoteVerifyOptions::default(),
+    );
+
+    assert_matches!(
+        result,
+        Err(VerificationError::WrongHash {
+            name: "report_data",
+            ..
+        })
+    );
+}
+
+fn report_data() -> [u8; 64] {
+    let quote = quote();
+    let parsed = ParsedQuote::parse(&quote).unwrap();
+    parsed.quote().report.as_td10().unwrap().report_data
+}
+
+#[test]
+fn test_verify_against_contract_commitment_accepts_committed_report_data() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let summary = verify_against_contract_commitment(
+        &quote(),
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &report_data(),
+    )
+    .unwrap();
+
+    assert_eq!(summary.report_data.to_bytes(), report_data());
+    assert_eq!(summary.platform_tcb_status, TcbStatus::UpToDate);
+    assert_eq!(summary.measurements.key_provider_event_digest, [0; 48]);
+    assert_eq!(summary.verified_at, VALID_TIMESTAMP_SECONDS);
+}
+
+#[test]
+fn test_verify_against_contract_commitment_rejects_other_commitment() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let mut commitment = report_data();
+    commitment[63] ^= 0xff;
+
+    assert_eq!(
+        verify_against_contract_commitment(
+            &quote(),
+            &collateral,
+            VALID_TIMESTAMP_SECONDS,
+            &commitment,
+        )
+        .unwrap_err(),
+        VerificationError::ReportDataMismatch {
+            expected: hex::encode(commitment),
+            found: hex::encode(report_data()),
+        }
+    );
+}
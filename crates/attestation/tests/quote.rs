@@ -0,0 +1,281 @@
+use assert_matches::assert_matches;
+use attestation::quote::{
+    AGGREGATE_MAGIC, CertDataType, MAX_AGGREGATE_QUOTES, ParsedQuote, QUOTE_HEADER_SIZE,
+    QuoteContext, QuoteError, QuoteType, SignatureAlgorithm, auth_data, auth_data_hash,
+    encode_aggregate, parse_aggregate, qe_isv_svn, quote_type, signature_algorithm,
+    split_aggregate,
+};
+use sha2::{Digest as _, Sha256};
+use test_utils::attestation::quote;
+
+/// Offset of the outer certification data type in a version 4 TDX quote: the header, the TD
+/// report, the signature data length, the ECDSA signature and the attestation key.
+const CERT_DATA_TYPE_OFFSET: usize = QUOTE_HEADER_SIZE + 584 + 4 + 64 + 64;
+
+/// Offset of the certification data nested in the QE report certification data, which follows
+/// the outer type and size, the QE report, its signature and the QE authentication data.
+fn nested_cert_data_type_offset(quote: &[u8]) -> usize {
+    let auth_data_size_offset = CERT_DATA_TYPE_OFFSET + 2 + 4 + 384 + 64;
+    let auth_data_size = u16::from_le_bytes(
+        quote[auth_data_size_offset..auth_data_size_offset + 2]
+            .try_into()
+            .unwrap(),
+    );
+    auth_data_size_offset + 2 + usize::from(auth_data_size)
+}
+
+fn header_with_tee_type(tee_type: u32) -> [u8; QUOTE_HEADER_SIZE] {
+    let mut header = [0u8; QUOTE_HEADER_SIZE];
+    header[..2].copy_from_slice(&4u16.to_le_bytes());
+    header[4..8].copy_from_slice(&tee_type.to_le_bytes());
+    header
+}
+
+#[test]
+fn test_quote_type_detects_tdx_test_quote() {
+    assert_eq!(quote_type(&quote()), Ok(QuoteType::Tdx));
+}
+
+#[test]
+fn test_quote_type_detects_sgx_header() {
+    assert_eq!(quote_type(&header_with_tee_type(0)), Ok(QuoteType::Sgx));
+}
+
+#[test]
+fn test_quote_type_rejects_unknown_tee_type() {
+    assert_eq!(
+        quote_type(&header_with_tee_type(0x42)),
+        Err(QuoteError::UnsupportedTeeType(0x42))
+    );
+}
+
+#[test]
+fn test_quote_type_rejects_truncated_header() {
+    let header = header_with_tee_type(0x81);
+
+    assert_eq!(
+        quote_type(&header[..QUOTE_HEADER_SIZE - 1]),
+        Err(QuoteError::TooShort {
+            len: QUOTE_HEADER_SIZE - 1
+        })
+    );
+}
+
+#[test]
+fn test_parsed_quote_exposes_auth_data_and_hash() {
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+
+    let auth_data = auth_data(&parsed);
+
+    assert_eq!(parsed.quote_type(), QuoteType::Tdx);
+    assert!(!auth_data.is_empty());
+    assert_eq!(
+        auth_data_hash(&parsed),
+        <[u8; 32]>::from(Sha256::digest(auth_data))
+    );
+}
+
+#[test]
+fn test_parsed_quote_rejects_truncated_quote() {
+    let quote = quote();
+
+    let result = ParsedQuote::parse(&quote[..QUOTE_HEADER_SIZE + 16]);
+
+    assert_matches!(result, Err(QuoteError::Parse(_)));
+}
+
+#[test]
+fn test_quote_context_reads_header_of_test_quote() {
+    assert_eq!(
+        QuoteContext::from_header(&quote()),
+        Ok(QuoteContext {
+            version: 4,
+            tee_type: 0x81
+        })
+    );
+}
+
+#[test]
+fn test_qe_isv_svn_of_test_quote() {
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+
+    assert_eq!(qe_isv_svn(&parsed), 6);
+}
+
+#[test]
+fn test_cert_data_type_from_u16() {
+    assert_eq!(CertDataType::try_from(5), Ok(CertDataType::PckCertChain));
+    assert_eq!(
+        CertDataType::try_from(6),
+        Ok(CertDataType::QeReportCertificationData)
+    );
+    assert_eq!(
+        CertDataType::try_from(1),
+        Err(QuoteError::UnsupportedCertDataType(1))
+    );
+    for cert_data_type in [
+        CertDataType::PckCertChain,
+        CertDataType::QeReportCertificationData,
+    ] {
+        assert_eq!(
+            CertDataType::try_from(cert_data_type.as_u16()),
+            Ok(cert_data_type)
+        );
+    }
+}
+
+#[test]
+fn test_parsed_quote_reads_nested_pck_cert_chain() {
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+
+    assert_eq!(
+        parsed.cert_data_type(),
+        CertDataType::QeReportCertificationData
+    );
+    assert!(
+        parsed
+            .pck_cert_chain()
+            .unwrap()
+            .starts_with(b"-----BEGIN CERTIFICATE-----")
+    );
+}
+
+#[test]
+fn test_signature_algorithm_of_test_quote_is_p256() {
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+
+    assert_eq!(signature_algorithm(&parsed), SignatureAlgorithm::EcdsaP256);
+    assert_eq!(SignatureAlgorithm::EcdsaP256.as_u16(), 2);
+}
+
+#[test]
+fn test_parsed_quote_rejects_unknown_signature_algorithm() {
+    let mut quote = quote().to_vec();
+    // ECDSA with P-384.
+    quote[2..4].copy_from_slice(&3u16.to_le_bytes());
+
+    assert_matches!(
+        ParsedQuote::parse(&quote),
+        Err(QuoteError::UnsupportedSignatureAlgorithm(3))
+    );
+}
+
+#[test]
+fn test_parsed_quote_rejects_unknown_cert_data_type() {
+    let mut quote = quote().to_vec();
+    quote[CERT_DATA_TYPE_OFFSET..CERT_DATA_TYPE_OFFSET + 2].copy_from_slice(&7u16.to_le_bytes());
+
+    assert_matches!(
+        ParsedQuote::parse(&quote),
+        Err(QuoteError::UnsupportedCertDataType(7))
+    );
+}
+
+#[test]
+fn test_parsed_quote_rejects_unknown_nested_cert_data_type() {
+    let mut quote = quote().to_vec();
+    let offset = nested_cert_data_type_offset(&quote);
+    quote[offset..offset + 2].copy_from_slice(&1u16.to_le_bytes());
+
+    assert_matches!(
+        ParsedQuote::parse(&quote),
+        Err(QuoteError::UnsupportedCertDataType(1))
+    );
+}
+
+/// The test quote with its TDX header and signature data around an SGX sized report body.
+fn tdx_quote_with_sgx_sized_body() -> Vec<u8> {
+    let quote = quote();
+    let body_start = QUOTE_HEADER_SIZE;
+    [
+        &quote[..body_start],
+        &quote[body_start..body_start + 384],
+        &quote[body_start + 584..],
+    ]
+    .concat()
+}
+
+#[test]
+fn test_parsed_quote_rejects_tdx_header_with_sgx_sized_body() {
+    assert_matches!(
+        ParsedQuote::parse(&tdx_quote_with_sgx_sized_body()),
+        Err(QuoteError::BodyTypeMismatch { tee_type: 0x81 })
+    );
+}
+
+#[test]
+fn test_parsed_quote_rejects_version_5_tdx_header_with_sgx_body() {
+    let quote = quote();
+    let mut header = quote[..QUOTE_HEADER_SIZE].to_vec();
+    header[..2].copy_from_slice(&5u16.to_le_bytes());
+
+    for (body_type, body_size) in [(1u16, 384u32), (2, 384)] {
+        let as_version_5 = [
+            header.as_slice(),
+            &body_type.to_le_bytes(),
+            &body_size.to_le_bytes(),
+            &quote[QUOTE_HEADER_SIZE..QUOTE_HEADER_SIZE + 384],
+            &quote[QUOTE_HEADER_SIZE + 584..],
+        ]
+        .concat();
+
+        assert_matches!(
+            ParsedQuote::parse(&as_version_5),
+            Err(QuoteError::BodyTypeMismatch { tee_type: 0x81 })
+        );
+    }
+}
+
+#[test]
+fn test_parse_aggregate_returns_each_embedded_quote() {
+    let quote = quote();
+    let aggregate = encode_aggregate(&[quote.as_slice(), quote.as_slice()]).unwrap();
+
+    assert_eq!(&aggregate[..4], AGGREGATE_MAGIC.as_slice());
+    assert_eq!(
+        split_aggregate(&aggregate).unwrap(),
+        vec![quote.as_slice(), quote.as_slice()]
+    );
+    let parsed = parse_aggregate(&aggregate).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert!(
+        parsed
+            .iter()
+            .all(|parsed| parsed.quote_type() == QuoteType::Tdx)
+    );
+}
+
+#[test]
+fn test_parse_aggregate_rejects_single_quote() {
+    assert_matches!(parse_aggregate(&quote()), Err(QuoteError::NotAggregate));
+}
+
+#[test]
+fn test_parse_aggregate_rejects_malformed_aggregate() {
+    let aggregate = encode_aggregate(&[b"quote"]).unwrap();
+    let mut empty = AGGREGATE_MAGIC.to_vec();
+    empty.extend_from_slice(&0u32.to_le_bytes());
+    let mut trailing = aggregate.clone();
+    trailing.push(0);
+
+    for invalid in [
+        &b"not an aggregate"[..],
+        empty.as_slice(),
+        &aggregate[..aggregate.len() - 1],
+        trailing.as_slice(),
+    ] {
+        assert_matches!(
+            split_aggregate(invalid),
+            Err(QuoteError::InvalidAggregate(_))
+        );
+    }
+    assert_matches!(
+        parse_aggregate(&aggregate),
+        Err(QuoteError::TooShort { len: 5 })
+    );
+    assert_matches!(encode_aggregate(&[]), Err(QuoteError::InvalidAggregate(_)));
+    assert_matches!(
+        encode_aggregate(&vec![b"quote".as_slice(); MAX_AGGREGATE_QUOTES + 1]),
+        Err(QuoteError::InvalidAggregate(_))
+    );
+}
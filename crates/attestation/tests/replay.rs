@@ -0,0 +1,71 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::attestation::{ReplayGuard, SeenQuoteStore, VerificationError};
+use std::collections::BTreeMap;
+
+const WINDOW_SECS: u64 = 60;
+
+#[test]
+fn test_replay_guard_rejects_quote_seen_within_window() {
+    let mut guard = ReplayGuard::new(WINDOW_SECS);
+
+    assert_matches!(guard.check_and_record(b"quote", 1000), Ok(()));
+    assert_matches!(
+        guard.check_and_record(b"quote", 1000 + WINDOW_SECS - 1),
+        Err(VerificationError::Replayed { last_seen: 1000 })
+    );
+    assert_matches!(guard.check_and_record(b"other quote", 1001), Ok(()));
+}
+
+#[test]
+fn test_replay_guard_accepts_quote_after_window() {
+    let mut guard = ReplayGuard::new(WINDOW_SECS);
+
+    assert_matches!(guard.check_and_record(b"quote", 1000), Ok(()));
+    assert_matches!(guard.check_and_record(b"quote", 1000 + WINDOW_SECS), Ok(()));
+    assert_matches!(
+        guard.check_and_record(b"quote", 1000 + WINDOW_SECS + 1),
+        Err(VerificationError::Replayed { last_seen: 1060 })
+    );
+}
+
+#[test]
+fn test_replay_guard_prune_forgets_expired_quotes() {
+    let mut guard = ReplayGuard::new(WINDOW_SECS);
+    guard.check_and_record(b"old quote", 1000).unwrap();
+    guard.check_and_record(b"new quote", 1050).unwrap();
+
+    guard.prune(1000 + WINDOW_SECS + 1);
+
+    assert_eq!(guard.store().len(), 1);
+}
+
+#[derive(Default)]
+struct OrderedStore(BTreeMap<[u8; 32], u64>);
+
+impl SeenQuoteStore for OrderedStore {
+    fn last_seen(&self, quote_hash: &[u8; 32]) -> Option<u64> {
+        self.0.get(quote_hash).copied()
+    }
+
+    fn record(&mut self, quote_hash: [u8; 32], seen_at: u64) {
+        self.0.insert(quote_hash, seen_at);
+    }
+
+    fn prune(&mut self, cutoff: u64) {
+        self.0.retain(|_, seen_at| *seen_at >= cutoff);
+    }
+}
+
+#[test]
+fn test_replay_guard_with_custom_store() {
+    let mut guard = ReplayGuard::with_store(OrderedStore::default(), WINDOW_SECS);
+
+    assert_matches!(guard.check_and_record(b"quote", 1000), Ok(()));
+    assert_matches!(
+        guard.check_and_record(b"quote", 1001),
+        Err(VerificationError::Replayed { .. })
+    );
+    assert_eq!(guard.store().0.len(), 1);
+}
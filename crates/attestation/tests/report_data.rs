@@ -0,0 +1,392 @@
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{VerificationError, verify_mr_service_td},
+    report_data::{
+        CLAIMS_HASH_SIZE, KeyScheme, MULTI_KEY_BINDING_VERSION, NONCE_SIZE,
+        PUBLIC_KEY_BINDING_VERSION, REPORT_DATA_SIZE, ReportData, ReportDataError, bind_keys,
+        commit_claims, commitment, for_public_key, from_slice, mr_service_td, sha384_commitment,
+        verify_block_anchor, verify_claims, verify_commitment, verify_keys, verify_public_key,
+        verify_sha384_commitment, verify_with_domain, with_block_anchor, with_domain,
+    },
+};
+use dcap_qvl::quote::{Report, TDReport10, TDReport15};
+use rstest::rstest;
+use sha2::{Digest as _, Sha256, Sha384, Sha512};
+
+fn report_data_bytes() -> [u8; REPORT_DATA_SIZE] {
+    let mut bytes = [0u8; REPORT_DATA_SIZE];
+    bytes[..2].copy_from_slice(&[0, 1]);
+    bytes[2..50].copy_from_slice(&[7; 48]);
+    bytes[50..].copy_from_slice(&[9; NONCE_SIZE]);
+    bytes
+}
+
+#[test]
+fn test_report_data_accessors_follow_layout() {
+    let report_data = ReportData::new(report_data_bytes());
+
+    assert_eq!(report_data.as_bytes(), &report_data_bytes());
+    assert_eq!(report_data.public_key_hash(), [7; 48]);
+    assert_eq!(report_data.nonce(), [9; NONCE_SIZE]);
+}
+
+#[test]
+fn test_report_data_serde_round_trip() {
+    let report_data = ReportData::new(report_data_bytes());
+
+    let json = serde_json::to_string(&report_data).unwrap();
+    let decoded: ReportData = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(decoded, report_data);
+}
+
+#[test]
+fn test_report_data_borsh_round_trip() {
+    let report_data = ReportData::new(report_data_bytes());
+
+    let encoded = borsh::to_vec(&report_data).unwrap();
+    let decoded: ReportData = borsh::from_slice(&encoded).unwrap();
+
+    assert_eq!(encoded.len(), REPORT_DATA_SIZE);
+    assert_eq!(decoded, report_data);
+}
+
+fn td10_report() -> TDReport10 {
+    TDReport10 {
+        tee_tcb_svn: [0; 16],
+        mr_seam: [0; 48],
+        mr_signer_seam: [0; 48],
+        seam_attributes: [0; 8],
+        td_attributes: [0; 8],
+        xfam: [0; 8],
+        mr_td: [0; 48],
+        mr_config_id: [0; 48],
+        mr_owner: [0; 48],
+        mr_owner_config: [0; 48],
+        rt_mr0: [0; 48],
+        rt_mr1: [0; 48],
+        rt_mr2: [0; 48],
+        rt_mr3: [0; 48],
+        report_data: [0; 64],
+    }
+}
+
+fn td15_report(mr_service_td: [u8; 48]) -> Report {
+    Report::TD15(TDReport15 {
+        base: td10_report(),
+        tee_tcb_svn2: [0; 16],
+        mr_service_td,
+    })
+}
+
+#[test]
+fn test_mr_service_td_only_for_td15_reports() {
+    assert_eq!(mr_service_td(&td15_report([3; 48])), Some([3; 48]));
+    assert_eq!(mr_service_td(&Report::TD10(td10_report())), None);
+}
+
+#[test]
+fn test_verify_mr_service_td_when_pinned() {
+    assert_matches!(
+        verify_mr_service_td(&Report::TD10(td10_report()), None),
+        Ok(())
+    );
+    assert_matches!(
+        verify_mr_service_td(&td15_report([3; 48]), Some([3; 48])),
+        Ok(())
+    );
+    assert_matches!(
+        verify_mr_service_td(&td15_report([3; 48]), Some([4; 48])),
+        Err(VerificationError::WrongHash {
+            name: "mr_service_td",
+            ..
+        })
+    );
+    assert_matches!(
+        verify_mr_service_td(&Report::TD10(td10_report()), Some([3; 48])),
+        Err(VerificationError::MissingMrServiceTd)
+    );
+}
+
+#[test]
+fn test_from_slice_accepts_exact_length() {
+    assert_eq!(from_slice(&report_data_bytes()), Ok(report_data_bytes()));
+}
+
+#[test]
+fn test_from_slice_accepts_zero_padding() {
+    let padded = [report_data_bytes().as_slice(), &[0; 16]].concat();
+
+    assert_eq!(from_slice(&padded), Ok(report_data_bytes()));
+}
+
+#[test]
+fn test_from_slice_rejects_non_zero_trailing_bytes() {
+    let padded = [report_data_bytes().as_slice(), &[0, 0, 1, 0]].concat();
+
+    assert_eq!(
+        from_slice(&padded),
+        Err(ReportDataError::NonZeroTrailingBytes {
+            offset: REPORT_DATA_SIZE + 2
+        })
+    );
+}
+
+#[test]
+fn test_from_slice_rejects_short_input() {
+    assert_eq!(
+        from_slice(&report_data_bytes()[..REPORT_DATA_SIZE - 1]),
+        Err(ReportDataError::TooShort {
+            len: REPORT_DATA_SIZE - 1
+        })
+    );
+}
+
+#[rstest]
+#[case(KeyScheme::Ed25519, vec![1; 32])]
+#[case(KeyScheme::Secp256k1, [vec![0x02], vec![1; 32]].concat())]
+#[case(KeyScheme::Bls12381, [vec![0x80], vec![1; 47]].concat())]
+#[case(KeyScheme::Bls12381, [vec![0xa0], vec![1; 95]].concat())]
+fn test_for_public_key_binds_key(#[case] scheme: KeyScheme, #[case] public_key: Vec<u8>) {
+    let report_data = ReportData::new(for_public_key(scheme, &public_key).unwrap());
+
+    assert_eq!(
+        report_data.as_bytes()[..2],
+        PUBLIC_KEY_BINDING_VERSION.to_be_bytes()
+    );
+    assert_eq!(report_data.nonce(), [0; NONCE_SIZE]);
+    assert_eq!(verify_public_key(&report_data, scheme, &public_key), Ok(()));
+
+    let mut other_key = public_key.clone();
+    other_key[1] ^= 1;
+    assert_eq!(
+        verify_public_key(&report_data, scheme, &other_key),
+        Err(ReportDataError::PublicKeyMismatch { scheme })
+    );
+}
+
+#[test]
+fn test_for_public_key_separates_schemes() {
+    let bls = [vec![0x02], vec![1; 47]].concat();
+    let secp256k1 = [vec![0x02], vec![1; 32]].concat();
+
+    assert_ne!(
+        for_public_key(KeyScheme::Ed25519, &secp256k1[1..]).unwrap(),
+        for_public_key(KeyScheme::Secp256k1, &secp256k1).unwrap()
+    );
+    assert_eq!(
+        for_public_key(KeyScheme::Bls12381, &bls),
+        Err(ReportDataError::InvalidPublicKey {
+            scheme: KeyScheme::Bls12381,
+            len: 48
+        })
+    );
+}
+
+#[rstest]
+#[case(KeyScheme::Ed25519, vec![1; 33])]
+#[case(KeyScheme::Secp256k1, [vec![0x04], vec![1; 64]].concat())]
+#[case(KeyScheme::Secp256k1, [vec![0x04], vec![1; 32]].concat())]
+#[case(KeyScheme::Bls12381, vec![0x80; 64])]
+fn test_for_public_key_rejects_invalid_encoding(
+    #[case] scheme: KeyScheme,
+    #[case] public_key: Vec<u8>,
+) {
+    assert_eq!(
+        for_public_key(scheme, &public_key),
+        Err(ReportDataError::InvalidPublicKey {
+            scheme,
+            len: public_key.len()
+        })
+    );
+}
+
+#[test]
+fn test_bind_keys_commits_to_ordered_keys() {
+    let signing: &[u8] = &[1; 32];
+    let encryption: &[u8] = &[2; 33];
+    let report_data = ReportData::new(bind_keys(&[signing, encryption]));
+
+    assert_eq!(
+        report_data.as_bytes()[..2],
+        MULTI_KEY_BINDING_VERSION.to_be_bytes()
+    );
+    assert_eq!(report_data.nonce(), [0; NONCE_SIZE]);
+    assert_eq!(verify_keys(&report_data, &[signing, encryption]), Ok(()));
+    assert_eq!(
+        verify_keys(&report_data, &[encryption, signing]),
+        Err(ReportDataError::KeysMismatch)
+    );
+    assert_eq!(
+        verify_keys(&report_data, &[signing]),
+        Err(ReportDataError::KeysMismatch)
+    );
+}
+
+#[test]
+fn test_bind_keys_is_unambiguous_across_key_boundaries() {
+    let split: [&[u8]; 2] = [&[1, 2], &[3]];
+    let moved: [&[u8]; 2] = [&[1], &[2, 3]];
+
+    let empty: &[u8] = &[];
+
+    assert_ne!(bind_keys(&split), bind_keys(&moved));
+    assert_ne!(bind_keys(&[]), bind_keys(&[empty]));
+}
+
+#[test]
+fn test_with_domain_hashes_domain_and_payload() {
+    let payload = [3; 32];
+    let report_data = ReportData::new(with_domain(b"mpc:resharing:", &payload));
+
+    let expected: [u8; REPORT_DATA_SIZE] =
+        Sha512::digest([b"mpc:resharing:".as_slice(), &payload].concat()).into();
+    assert_eq!(report_data.to_bytes(), expected);
+    assert_eq!(
+        verify_with_domain(&report_data, b"mpc:resharing:", &payload),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_verify_with_domain_rejects_other_domain_or_payload() {
+    let payload = [3; 32];
+    let report_data = ReportData::new(with_domain(b"mpc:resharing:", &payload));
+
+    assert_eq!(
+        verify_with_domain(&report_data, b"mpc:signing:", &payload),
+        Err(ReportDataError::DomainMismatch)
+    );
+    assert_eq!(
+        verify_with_domain(&report_data, b"mpc:resharing:", &[4; 32]),
+        Err(ReportDataError::DomainMismatch)
+    );
+    assert_eq!(
+        verify_with_domain(&ReportData::new([0; REPORT_DATA_SIZE]), b"", &[]),
+        Err(ReportDataError::DomainMismatch)
+    );
+}
+
+#[test]
+fn test_with_block_anchor_layout() {
+    let report_data = with_block_anchor(&[1; 32], &[2; 32]);
+
+    assert_eq!(report_data[..32], [1; 32]);
+    assert_eq!(report_data[32..], [2; 32]);
+    assert_eq!(
+        verify_block_anchor(&ReportData::new(report_data), &[1; 32], &[2; 32]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_verify_block_anchor_rejects_other_block_or_payload() {
+    let report_data = ReportData::new(with_block_anchor(&[1; 32], &[2; 32]));
+
+    assert_eq!(
+        verify_block_anchor(&report_data, &[3; 32], &[2; 32]),
+        Err(ReportDataError::BlockHashMismatch)
+    );
+    assert_eq!(
+        verify_block_anchor(&report_data, &[1; 32], &[3; 32]),
+        Err(ReportDataError::PayloadHashMismatch)
+    );
+}
+
+fn sha256(payload: &[u8]) -> Vec<u8> {
+    Sha256::digest(payload).to_vec()
+}
+
+#[test]
+fn test_commitment_places_digest_at_the_start() {
+    let report_data = commitment(b"payload", &sha256).unwrap();
+
+    assert_eq!(report_data[..32], Sha256::digest(b"payload")[..]);
+    assert_eq!(report_data[32..], [0; 32]);
+    assert_eq!(
+        commitment(b"payload", &|payload| Sha512::digest(payload).to_vec()),
+        Ok(Sha512::digest(b"payload").into())
+    );
+    assert_eq!(
+        commitment(b"payload", &|_| vec![0; REPORT_DATA_SIZE + 1]),
+        Err(ReportDataError::DigestTooLong {
+            len: REPORT_DATA_SIZE + 1
+        })
+    );
+}
+
+#[test]
+fn test_verify_commitment_with_caller_hash() {
+    let report_data = ReportData::new(commitment(b"payload", &sha256).unwrap());
+
+    assert_eq!(verify_commitment(&report_data, b"payload", &sha256), Ok(()));
+    assert_eq!(
+        verify_commitment(&report_data, b"other", &sha256),
+        Err(ReportDataError::CommitmentMismatch)
+    );
+    // The same payload committed with another hash does not verify.
+    assert_eq!(
+        verify_sha384_commitment(&report_data, b"payload"),
+        Err(ReportDataError::CommitmentMismatch)
+    );
+}
+
+#[test]
+fn test_verify_commitment_rejects_non_zero_padding() {
+    let mut bytes = commitment(b"payload", &sha256).unwrap();
+    bytes[REPORT_DATA_SIZE - 1] = 1;
+
+    assert_eq!(
+        verify_commitment(&ReportData::new(bytes), b"payload", &sha256),
+        Err(ReportDataError::CommitmentMismatch)
+    );
+}
+
+#[test]
+fn test_sha384_commitment_is_the_default_commitment() {
+    let report_data = sha384_commitment(b"payload");
+
+    assert_eq!(
+        commitment(b"payload", &|payload| Sha384::digest(payload).to_vec()),
+        Ok(report_data)
+    );
+    assert_eq!(
+        verify_sha384_commitment(&ReportData::new(report_data), b"payload"),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_commit_claims_layout() {
+    let claims = br#"{"region":"eu-west-1","build":"1.4.2"}"#;
+
+    let report_data = commit_claims(claims);
+
+    assert_eq!(report_data[..CLAIMS_HASH_SIZE], Sha256::digest(claims)[..]);
+    assert_eq!(report_data[CLAIMS_HASH_SIZE..], [0; 32]);
+}
+
+#[test]
+fn test_verify_claims_accepts_matching_claims() {
+    let claims = br#"{"region":"eu-west-1","build":"1.4.2"}"#;
+    let mut report_data = commit_claims(claims);
+
+    assert_eq!(verify_claims(&ReportData::new(report_data), claims), Ok(()));
+    // The bytes past the claims hash are left to the deployment.
+    report_data[CLAIMS_HASH_SIZE..].copy_from_slice(&[7; 32]);
+    assert_eq!(verify_claims(&ReportData::new(report_data), claims), Ok(()));
+}
+
+#[test]
+fn test_verify_claims_rejects_tampered_claims() {
+    let report_data = ReportData::new(commit_claims(br#"{"region":"eu-west-1"}"#));
+
+    assert_eq!(
+        verify_claims(&report_data, br#"{"region":"us-east-1"}"#),
+        Err(ReportDataError::ClaimsMismatch)
+    );
+    assert_eq!(
+        verify_claims(&report_data, b""),
+        Err(ReportDataError::ClaimsMismatch)
+    );
+}
@@ -0,0 +1,239 @@
+//! Checks that the Borsh and serde representations of the public measurement types stay
+//! consistent with each other and with the type definitions.
+
+use attestation::{
+    measurements::{
+        Allowlist, FullMeasurements, FullMeasurementsHex, Measurements, MeasurementsHex,
+        MeasurementsNearJson, MerkleProof,
+    },
+    report_data::{REPORT_DATA_SIZE, ReportData},
+    tcb_info::HexBytes,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Serialize, de::DeserializeOwned};
+
+const REGISTER_SIZE: usize = 48;
+const MEASUREMENTS_BORSH_SIZE: usize = 4 * REGISTER_SIZE;
+const FULL_MEASUREMENTS_BORSH_SIZE: usize = MEASUREMENTS_BORSH_SIZE + REGISTER_SIZE + 32;
+
+fn measurements() -> Measurements {
+    Measurements {
+        mrtd: [1; 48],
+        rtmr0: [2; 48],
+        rtmr1: [3; 48],
+        rtmr2: [4; 48],
+    }
+}
+
+fn full_measurements() -> FullMeasurements {
+    FullMeasurements {
+        rtmrs: measurements(),
+        key_provider_event_digest: [5; 48],
+        app_compose_hash_payload: [6; 32],
+    }
+}
+
+fn borsh_roundtrip<T: BorshSerialize + BorshDeserialize>(value: &T) -> T {
+    let bytes = borsh::to_vec(value).unwrap();
+    borsh::from_slice(&bytes).unwrap()
+}
+
+fn json_roundtrip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    let json = serde_json::to_string(value).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+/// The number of top level fields in the JSON representation of `value`.
+fn json_field_count<T: Serialize>(value: &T) -> usize {
+    serde_json::to_value(value)
+        .unwrap()
+        .as_object()
+        .expect("value should serialize to a JSON object")
+        .len()
+}
+
+fn borsh_len<T: BorshSerialize>(value: &T) -> usize {
+    borsh::to_vec(value).unwrap().len()
+}
+
+#[test]
+fn test_measurements_roundtrip_through_borsh_and_json() {
+    let measurements = measurements();
+    let expected = borsh::to_vec(&measurements).unwrap();
+
+    assert_eq!(
+        borsh::to_vec(&borsh_roundtrip(&measurements)).unwrap(),
+        expected
+    );
+    assert_eq!(
+        borsh::to_vec(&json_roundtrip(&measurements)).unwrap(),
+        expected
+    );
+    assert_eq!(json_field_count(&measurements), 4);
+    assert_eq!(borsh_len(&measurements), MEASUREMENTS_BORSH_SIZE);
+}
+
+#[test]
+fn test_full_measurements_roundtrip_through_borsh_and_json() {
+    let full_measurements = full_measurements();
+    let expected = borsh::to_vec(&full_measurements).unwrap();
+
+    assert_eq!(
+        borsh::to_vec(&borsh_roundtrip(&full_measurements)).unwrap(),
+        expected
+    );
+    assert_eq!(
+        borsh::to_vec(&json_roundtrip(&full_measurements)).unwrap(),
+        expected
+    );
+    assert_eq!(json_field_count(&full_measurements), 3);
+    assert_eq!(borsh_len(&full_measurements), FULL_MEASUREMENTS_BORSH_SIZE);
+}
+
+#[test]
+fn test_measurements_hex_roundtrip_through_borsh_and_json() {
+    let hex = MeasurementsHex::from(measurements());
+
+    assert_eq!(borsh_roundtrip(&hex), hex);
+    assert_eq!(json_roundtrip(&hex), hex);
+    assert_eq!(json_field_count(&hex), json_field_count(&measurements()));
+    assert_eq!(
+        borsh::to_vec(&hex).unwrap(),
+        borsh::to_vec(&measurements()).unwrap()
+    );
+}
+
+#[test]
+fn test_full_measurements_hex_roundtrip_through_borsh_and_json() {
+    let hex = FullMeasurementsHex::from(full_measurements());
+
+    assert_eq!(borsh_roundtrip(&hex), hex);
+    assert_eq!(json_roundtrip(&hex), hex);
+    assert_eq!(
+        json_field_count(&hex),
+        json_field_count(&full_measurements())
+    );
+    assert_eq!(
+        borsh::to_vec(&hex).unwrap(),
+        borsh::to_vec(&full_measurements()).unwrap()
+    );
+}
+
+#[test]
+fn test_measurements_near_json_roundtrip_through_json() {
+    let near_json = MeasurementsNearJson::from(MeasurementsHex::from(measurements()));
+
+    assert_eq!(json_roundtrip(&near_json), near_json);
+    assert_eq!(
+        json_field_count(&near_json),
+        json_field_count(&measurements())
+    );
+}
+
+#[test]
+fn test_merkle_proof_roundtrip_through_borsh_and_json() {
+    let entries = (0..3).map(|seed| FullMeasurements {
+        app_compose_hash_payload: [seed; 32],
+        ..full_measurements()
+    });
+    let allowlist = Allowlist::new(entries);
+    let proof = allowlist.merkle_proof(&allowlist.entries()[0]).unwrap();
+
+    assert_eq!(borsh_roundtrip(&proof), proof);
+    assert_eq!(json_roundtrip(&proof), proof);
+    assert_eq!(json_field_count(&proof), 1);
+    // Borsh length prefix, then a one byte variant tag and a 32 byte hash per node.
+    assert_eq!(borsh_len(&proof), 4 + proof.path.len() * 33);
+    assert_eq!(borsh_len(&MerkleProof::default()), 4);
+}
+
+#[test]
+fn test_report_data_roundtrip_through_borsh_and_json() {
+    let report_data = ReportData::new([7; REPORT_DATA_SIZE]);
+
+    assert_eq!(borsh_roundtrip(&report_data), report_data);
+    assert_eq!(json_roundtrip(&report_data), report_data);
+    assert_eq!(borsh_len(&report_data), REPORT_DATA_SIZE);
+}
+
+#[test]
+fn test_hex_bytes_borsh_encoding_is_unprefixed() {
+    let hex_bytes = HexBytes::from([7u8; 48]);
+
+    let bytes = borsh::to_vec(&hex_bytes).unwrap();
+
+    assert_eq!(bytes, [7u8; 48]);
+    assert_eq!(
+        borsh::from_slice::<HexBytes<48>>(&bytes).unwrap(),
+        hex_bytes
+    );
+}
+
+#[test]
+fn test_truncated_or_extended_borsh_input_is_rejected() {
+    let full_measurements = borsh::to_vec(&FullMeasurementsHex::from(full_measurements())).unwrap();
+    let hex_bytes = borsh::to_vec(&HexBytes::from([7u8; 48])).unwrap();
+
+    for len in 0..full_measurements.len() {
+        assert!(borsh::from_slice::<FullMeasurementsHex>(&full_measurements[..len]).is_err());
+        assert!(borsh::from_slice::<FullMeasurements>(&full_measurements[..len]).is_err());
+    }
+    for len in 0..hex_bytes.len() {
+        assert!(borsh::from_slice::<HexBytes<48>>(&hex_bytes[..len]).is_err());
+    }
+
+    let extended = [hex_bytes.as_slice(), &[0]].concat();
+    assert!(borsh::from_slice::<HexBytes<48>>(&extended).is_err());
+}
+
+/// The JSON form of `value`, with the byte array of `field` replaced by `len` bytes.
+fn json_with_field_len<T: Serialize>(value: &T, path: &[&str], len: usize) -> String {
+    let mut json = serde_json::to_value(value).unwrap();
+    let mut field = &mut json;
+    for key in path {
+        field = field.get_mut(*key).unwrap();
+    }
+    *field = serde_json::to_value(vec![0u8; len]).unwrap();
+    json.to_string()
+}
+
+#[test]
+fn test_measurements_reject_wrong_length_naming_the_field() {
+    for field in ["mrtd", "rtmr0", "rtmr1", "rtmr2"] {
+        for len in [0, REGISTER_SIZE - 1, REGISTER_SIZE + 1] {
+            let json = json_with_field_len(&measurements(), &[field], len);
+
+            let error = serde_json::from_str::<Measurements>(&json).unwrap_err();
+
+            assert!(
+                error
+                    .to_string()
+                    .contains(&format!("expected 48 bytes for {field}, got {len}")),
+                "{error}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_full_measurements_reject_wrong_length_naming_the_field() {
+    for (path, size) in [
+        (&["rtmrs", "rtmr1"][..], REGISTER_SIZE),
+        (&["key_provider_event_digest"], REGISTER_SIZE),
+        (&["app_compose_hash_payload"], 32),
+    ] {
+        let field = path.last().unwrap();
+        for len in [size - 1, size + 1] {
+            let json = json_with_field_len(&full_measurements(), path, len);
+
+            let error = serde_json::from_str::<FullMeasurements>(&json).unwrap_err();
+
+            assert!(
+                error
+                    .to_string()
+                    .contains(&format!("expected {size} bytes for {field}, got {len}")),
+                "{error}"
+            );
+        }
+    }
+}
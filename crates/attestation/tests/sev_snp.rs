@@ -0,0 +1,69 @@
+use assert_matches::assert_matches;
+use attestation::measurements::{
+    MeasurementsError, from_sev_snp_report,
+    sev_snp::{SEV_SNP_REPORT_SIZE, SevSnpMeasurements},
+};
+
+/// A synthetic version 3 report, with each field set to a distinct value at its offset in the
+/// `ATTESTATION_REPORT` structure.
+fn report() -> Vec<u8> {
+    let mut raw = vec![0u8; SEV_SNP_REPORT_SIZE];
+    raw[0x00..0x04].copy_from_slice(&3u32.to_le_bytes());
+    raw[0x04..0x08].copy_from_slice(&7u32.to_le_bytes());
+    raw[0x08..0x10].copy_from_slice(&0x3_0000u64.to_le_bytes());
+    raw[0x30..0x34].copy_from_slice(&1u32.to_le_bytes());
+    raw[0x50..0x90].copy_from_slice(&[2; 64]);
+    raw[0x90..0xC0].copy_from_slice(&[3; 48]);
+    raw[0xC0..0xE0].copy_from_slice(&[4; 32]);
+    raw[0xE0..0x110].copy_from_slice(&[5; 48]);
+    raw[0x180..0x188].copy_from_slice(&0x1122_3344_5566_7788u64.to_le_bytes());
+    raw[0x1A0..0x1E0].copy_from_slice(&[6; 64]);
+    // The signature, which is not read.
+    raw[0x2A0..].fill(0xff);
+    raw
+}
+
+#[test]
+fn test_from_sev_snp_report_reads_fields() {
+    assert_eq!(
+        from_sev_snp_report(&report()).unwrap(),
+        SevSnpMeasurements {
+            version: 3,
+            guest_svn: 7,
+            policy: 0x3_0000,
+            vmpl: 1,
+            measurement: [3; 48],
+            report_data: [2; 64],
+            host_data: [4; 32],
+            id_key_digest: [5; 48],
+            reported_tcb: 0x1122_3344_5566_7788,
+            chip_id: [6; 64],
+        }
+    );
+}
+
+#[test]
+fn test_from_sev_snp_report_rejects_wrong_size() {
+    let report = report();
+
+    for len in [0, SEV_SNP_REPORT_SIZE - 1, SEV_SNP_REPORT_SIZE + 1] {
+        let mut raw = report.clone();
+        raw.resize(len, 0);
+
+        assert_matches!(
+            from_sev_snp_report(&raw),
+            Err(MeasurementsError::WrongSize { expected: SEV_SNP_REPORT_SIZE, found }) if found == len
+        );
+    }
+}
+
+#[test]
+fn test_from_sev_snp_report_rejects_old_version() {
+    let mut raw = report();
+    raw[0..4].copy_from_slice(&1u32.to_le_bytes());
+
+    assert_matches!(
+        from_sev_snp_report(&raw),
+        Err(MeasurementsError::UnsupportedSevSnpVersion(1))
+    );
+}
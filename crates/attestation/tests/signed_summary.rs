@@ -0,0 +1,90 @@
+#![cfg(feature = "signed-summary")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::AttestationSummary,
+    collateral::Fmspc,
+    measurements::{FullMeasurements, Measurements},
+    report_data::ReportData,
+    tcb_info::HexBytes,
+    tcb_status::TcbStatus,
+};
+use ed25519_dalek::SigningKey;
+
+fn summary() -> AttestationSummary {
+    AttestationSummary {
+        measurements: FullMeasurements {
+            rtmrs: Measurements {
+                mrtd: [1; 48],
+                rtmr0: [2; 48],
+                rtmr1: [3; 48],
+                rtmr2: [4; 48],
+            },
+            key_provider_event_digest: [5; 48],
+            app_compose_hash_payload: [6; 32],
+        },
+        ppid: HexBytes::from([7; 16]),
+        fmspc: Fmspc::from([0xb0, 0xc0, 0x6f, 0, 0, 0]),
+        platform_tcb_status: TcbStatus::SWHardeningNeeded,
+        qe_tcb_status: Some(TcbStatus::UpToDate),
+        mr_config_id: [8; 48],
+        mr_owner: [9; 48],
+        report_data: ReportData::new([10; 64]),
+        advisory_ids: vec!["INTEL-SA-00615".to_string()],
+        verified_at: 1_763_626_832,
+        tcb_info_issued_at: 1_763_600_000,
+        tcb_info_next_update: 1_766_192_000,
+    }
+}
+
+fn coordinator_key() -> SigningKey {
+    SigningKey::from_bytes(&[42; 32])
+}
+
+#[test]
+fn test_signed_summary_verifies_with_signer_key() {
+    let summary = summary();
+
+    let signed = summary.sign(&coordinator_key());
+
+    let verified = signed.verify(&coordinator_key().verifying_key()).unwrap();
+    assert_eq!(verified.signing_payload(), summary.signing_payload());
+}
+
+#[test]
+fn test_signed_summary_rejects_other_key() {
+    let signed = summary().sign(&coordinator_key());
+
+    assert_matches!(
+        signed.verify(&SigningKey::from_bytes(&[7; 32]).verifying_key()),
+        Err(_)
+    );
+}
+
+#[test]
+fn test_signed_summary_rejects_tampered_fields() {
+    let signed = summary().sign(&coordinator_key());
+    let tampered: [fn(&mut AttestationSummary); 4] = [
+        |summary| summary.platform_tcb_status = TcbStatus::UpToDate,
+        |summary| summary.qe_tcb_status = None,
+        |summary| summary.advisory_ids.clear(),
+        |summary| summary.verified_at += 1,
+    ];
+
+    for tamper in tampered {
+        let mut signed = signed.clone();
+        tamper(&mut signed.summary);
+
+        assert_matches!(signed.verify(&coordinator_key().verifying_key()), Err(_));
+    }
+}
+
+#[test]
+fn test_signing_payload_separates_advisory_ids() {
+    let mut joined = summary();
+    joined.advisory_ids = vec!["INTEL-SA-00615INTEL-SA-00828".to_string()];
+    let mut split = summary();
+    split.advisory_ids = vec!["INTEL-SA-00615".to_string(), "INTEL-SA-00828".to_string()];
+
+    assert_ne!(joined.signing_payload(), split.signing_payload());
+}
@@ -0,0 +1,37 @@
+use attestation::svn::TeeTcbSvn;
+use std::cmp::Ordering;
+
+fn svn(first_components: &[u8]) -> TeeTcbSvn {
+    let mut components = [0; 16];
+    components[..first_components.len()].copy_from_slice(first_components);
+    TeeTcbSvn::from_report_bytes(components)
+}
+
+#[test]
+fn test_svn_comparison_is_componentwise() {
+    assert_eq!(
+        svn(&[5, 0, 3]).partial_cmp(&svn(&[5, 0, 3])),
+        Some(Ordering::Equal)
+    );
+    assert!(svn(&[5, 0, 3]) > svn(&[5, 0, 2]));
+    assert!(svn(&[5, 0, 3]) >= svn(&[4, 0, 3]));
+    assert!(svn(&[4, 0, 2]) < svn(&[5, 0, 3]));
+}
+
+#[test]
+fn test_svn_comparison_is_not_lexicographic() {
+    // Lexicographically greater, but the third component is below the level.
+    let higher_first = svn(&[6, 0, 1]);
+    let level = svn(&[5, 0, 3]);
+
+    assert_eq!(higher_first.partial_cmp(&level), None);
+    assert_eq!(level.partial_cmp(&higher_first), None);
+}
+
+#[test]
+fn test_tee_tcb_svn_without_tdx_module_clears_module_components() {
+    let versioned = svn(&[5, 1, 3]);
+
+    assert_eq!(versioned.tdx_module_major_version(), 1);
+    assert_eq!(versioned.without_tdx_module(), svn(&[0, 0, 3]));
+}
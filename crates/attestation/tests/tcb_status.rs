@@ -0,0 +1,616 @@
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{QuoteVerifyOptions, VerificationError, verify_qe_tcb, verify_tcb_status},
+    collateral::Collateral,
+    quote::{ParsedQuote, qe_isv_svn},
+    svn::{PckSvn, TeeTcbSvn},
+    tcb_status::{
+        SvnComparison, TcbEvaluationError, TcbPolicy, TcbStatus, UnknownTcbStatus,
+        advisories_by_level, advisory_delta, compare_tdx_module_tcb_levels,
+        converge_tdx_module_status, evaluate_qe_tcb, evaluate_tdx_module_identity,
+        evaluate_tdx_module_tcb, explain_status, pck_svn,
+    },
+};
+use dcap_qvl::{
+    quote::{Report, TDReport10},
+    verify::VerifiedReport,
+};
+use serde_json::json;
+use test_utils::attestation::{collateral, quote};
+
+fn tcb_info() -> String {
+    Collateral::try_from_json(collateral())
+        .unwrap()
+        .tcb_info
+        .clone()
+}
+
+/// A TDX module TCB SVN with the given first three components, the rest zero.
+fn tee_tcb_svn(components: [u8; 3]) -> TeeTcbSvn {
+    let mut tee_tcb_svn = [0; 16];
+    tee_tcb_svn[..3].copy_from_slice(&components);
+    TeeTcbSvn::from_report_bytes(tee_tcb_svn)
+}
+
+fn advisories(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| String::from(*id)).collect()
+}
+
+fn verified_report(status: &str, advisory_ids: &[&str]) -> VerifiedReport {
+    VerifiedReport {
+        status: String::from(status),
+        advisory_ids: advisories(advisory_ids),
+        report: Report::TD10(TDReport10 {
+            tee_tcb_svn: [0; 16],
+            mr_seam: [0; 48],
+            mr_signer_seam: [0; 48],
+            seam_attributes: [0; 8],
+            td_attributes: [0; 8],
+            xfam: [0; 8],
+            mr_td: [0; 48],
+            mr_config_id: [0; 48],
+            mr_owner: [0; 48],
+            mr_owner_config: [0; 48],
+            rt_mr0: [0; 48],
+            rt_mr1: [0; 48],
+            rt_mr2: [0; 48],
+            rt_mr3: [0; 48],
+            report_data: [0; 64],
+        }),
+        ppid: Vec::new(),
+    }
+}
+
+#[test]
+fn test_tcb_status_parses_intel_strings() {
+    for status in TcbStatus::ALL {
+        assert_eq!(status.as_str().parse(), Ok(status));
+    }
+    assert_eq!(
+        "ConfigurationAndSWHardeningNeeded".parse(),
+        Ok(TcbStatus::ConfigurationAndSWHardeningNeeded)
+    );
+    assert_eq!(
+        "uptodate".parse::<TcbStatus>(),
+        Err(UnknownTcbStatus(String::from("uptodate")))
+    );
+}
+
+#[test]
+fn test_verify_tcb_status_default_only_allows_up_to_date() {
+    let options = QuoteVerifyOptions::default();
+
+    assert_matches!(
+        verify_tcb_status(&verified_report("UpToDate", &[]), &options.tcb_policy),
+        Ok(TcbStatus::UpToDate)
+    );
+    assert_matches!(
+        verify_tcb_status(
+            &verified_report("ConfigurationAndSWHardeningNeeded", &["INTEL-SA-00837"]),
+            &options.tcb_policy
+        ),
+        Err(VerificationError::TcbStatusNotAllowed(status)) if status == "ConfigurationAndSWHardeningNeeded"
+    );
+}
+
+#[test]
+fn test_verify_tcb_status_accepts_selected_status_with_advisories() {
+    let allowed = TcbPolicy::allowing([
+        TcbStatus::UpToDate,
+        TcbStatus::ConfigurationAndSWHardeningNeeded,
+    ]);
+    let report = verified_report(
+        "ConfigurationAndSWHardeningNeeded",
+        &["INTEL-SA-00615", "INTEL-SA-00837"],
+    );
+
+    assert_matches!(
+        verify_tcb_status(&report, &allowed),
+        Ok(TcbStatus::ConfigurationAndSWHardeningNeeded)
+    );
+    assert_matches!(
+        verify_tcb_status(
+            &verified_report("SWHardeningNeeded", &["INTEL-SA-00615"]),
+            &allowed
+        ),
+        Err(VerificationError::TcbStatusNotAllowed(_))
+    );
+}
+
+#[test]
+fn test_verify_tcb_status_rejects_advisories_for_up_to_date() {
+    assert_matches!(
+        verify_tcb_status(
+            &verified_report("UpToDate", &["INTEL-SA-00615"]),
+            &TcbPolicy::default()
+        ),
+        Err(VerificationError::NonEmptyAdvisoryIds(_))
+    );
+}
+
+#[test]
+fn test_default_tcb_policy_only_accepts_up_to_date_without_advisories() {
+    let policy = TcbPolicy::default();
+
+    assert!(policy.is_acceptable(TcbStatus::UpToDate, &[]));
+    assert!(!policy.is_acceptable(TcbStatus::UpToDate, &advisories(&["INTEL-SA-00615"])));
+    assert!(!policy.is_acceptable(TcbStatus::SWHardeningNeeded, &[]));
+}
+
+#[test]
+fn test_tcb_policy_accepts_any_advisory_without_rules() {
+    let policy = TcbPolicy::allowing([TcbStatus::SWHardeningNeeded]);
+
+    assert!(policy.is_acceptable(
+        TcbStatus::SWHardeningNeeded,
+        &advisories(&["INTEL-SA-00615", "INTEL-SA-00837"])
+    ));
+    assert!(!policy.is_acceptable(TcbStatus::OutOfDate, &[]));
+}
+
+#[test]
+fn test_tcb_policy_advisory_allowlist() {
+    let policy = TcbPolicy::allowing(TcbStatus::ALL).allow_advisory("INTEL-SA-00615");
+
+    assert!(policy.is_acceptable(TcbStatus::OutOfDate, &advisories(&["INTEL-SA-00615"])));
+    assert!(!policy.is_acceptable(
+        TcbStatus::OutOfDate,
+        &advisories(&["INTEL-SA-00615", "INTEL-SA-00837"])
+    ));
+    assert!(policy.is_acceptable(TcbStatus::OutOfDate, &[]));
+}
+
+#[test]
+fn test_tcb_policy_advisory_denylist_overrides_allowlist() {
+    let policy = TcbPolicy::allowing([TcbStatus::SWHardeningNeeded])
+        .allow_advisory("INTEL-SA-00615")
+        .deny_advisory("INTEL-SA-00615")
+        .deny_advisory("INTEL-SA-00837");
+
+    assert!(!policy.allows_advisory("INTEL-SA-00615"));
+    assert!(!policy.is_acceptable(
+        TcbStatus::SWHardeningNeeded,
+        &advisories(&["INTEL-SA-00837"])
+    ));
+}
+
+#[test]
+fn test_tcb_policy_allow_status_is_idempotent() {
+    let policy = TcbPolicy::default()
+        .allow_status(TcbStatus::OutOfDate)
+        .allow_status(TcbStatus::OutOfDate);
+
+    assert_eq!(
+        policy.allowed_statuses,
+        [TcbStatus::UpToDate, TcbStatus::OutOfDate]
+    );
+}
+
+#[test]
+fn test_verify_tcb_status_rejects_denied_advisory() {
+    let policy =
+        TcbPolicy::allowing([TcbStatus::SWHardeningNeeded]).deny_advisory("INTEL-SA-00837");
+
+    assert_matches!(
+        verify_tcb_status(
+            &verified_report("SWHardeningNeeded", &["INTEL-SA-00615", "INTEL-SA-00837"]),
+            &policy
+        ),
+        Err(VerificationError::NonEmptyAdvisoryIds(advisories)) if advisories.contains("INTEL-SA-00837")
+    );
+    assert_matches!(
+        verify_tcb_status(
+            &verified_report("SWHardeningNeeded", &["INTEL-SA-00615"]),
+            &policy
+        ),
+        Ok(TcbStatus::SWHardeningNeeded)
+    );
+}
+
+#[test]
+fn test_verify_tcb_status_rejects_unknown_status() {
+    assert_matches!(
+        verify_tcb_status(
+            &verified_report("Unknown", &[]),
+            &TcbPolicy::allowing(TcbStatus::ALL)
+        ),
+        Err(VerificationError::TcbStatusNotAllowed(_))
+    );
+}
+
+/// The SGX SVNs of the platform of the test quote.
+fn platform_svn() -> PckSvn {
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+    pck_svn(parsed.pck_cert_chain().unwrap()).unwrap()
+}
+
+/// A TD report whose TDX module TCB SVN has the given first three components, the rest zero.
+fn td_report(components: [u8; 3]) -> Report {
+    with_td10(verified_report("UpToDate", &[]).report, |td10| {
+        td10.tee_tcb_svn = *tee_tcb_svn(components).components();
+    })
+}
+
+fn with_td10(mut report: Report, edit: impl FnOnce(&mut TDReport10)) -> Report {
+    let Report::TD10(td10) = &mut report else {
+        unreachable!()
+    };
+    edit(td10);
+    report
+}
+
+#[test]
+fn test_evaluate_tdx_module_tcb_of_test_quote() {
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+    let report = &parsed.quote().report;
+
+    assert_eq!(
+        evaluate_tdx_module_identity(report, &tcb_info()),
+        Ok(Some(TcbStatus::UpToDate))
+    );
+    assert_eq!(
+        evaluate_tdx_module_tcb(report, &platform_svn(), &tcb_info()),
+        Ok(TcbStatus::UpToDate)
+    );
+}
+
+#[test]
+fn test_evaluate_tdx_module_tcb_picks_highest_matching_level() {
+    let tcb_info = tcb_info();
+
+    assert_eq!(
+        evaluate_tdx_module_tcb(&td_report([5, 0, 3]), &platform_svn(), &tcb_info),
+        Ok(TcbStatus::UpToDate)
+    );
+    assert_eq!(
+        evaluate_tdx_module_tcb(&td_report([5, 0, 2]), &platform_svn(), &tcb_info),
+        Ok(TcbStatus::OutOfDate)
+    );
+}
+
+#[test]
+fn test_evaluate_tdx_module_tcb_rejects_svn_below_tcb_info_threshold() {
+    assert_eq!(
+        evaluate_tdx_module_tcb(&td_report([5, 0, 1]), &platform_svn(), &tcb_info()),
+        Err(TcbEvaluationError::NoMatchingTcbLevel)
+    );
+}
+
+#[test]
+fn test_evaluate_tdx_module_tcb_matches_sgx_and_tdx_components_together() {
+    let tcb_info = tcb_info();
+    let report = td_report([5, 0, 3]);
+
+    // The TDX components are up to date, but the CPU SVN is only at the out of date levels.
+    let mut outdated_cpu = platform_svn();
+    outdated_cpu.cpu_svn[0] = 2;
+    assert_eq!(
+        evaluate_tdx_module_tcb(&report, &outdated_cpu, &tcb_info),
+        Ok(TcbStatus::OutOfDate)
+    );
+
+    // The PCE SVN is only at the last level.
+    let outdated_pce = PckSvn {
+        pce_svn: 5,
+        ..platform_svn()
+    };
+    let levels =
+        compare_tdx_module_tcb_levels(&outdated_pce, &tee_tcb_svn([5, 0, 3]), &tcb_info).unwrap();
+    assert_eq!(levels.len(), 3);
+    assert!(levels[2].passed);
+    assert_eq!(
+        evaluate_tdx_module_tcb(&report, &outdated_pce, &tcb_info),
+        Ok(TcbStatus::OutOfDate)
+    );
+
+    let below_every_level = PckSvn {
+        pce_svn: 4,
+        ..platform_svn()
+    };
+    assert_eq!(
+        evaluate_tdx_module_tcb(&report, &below_every_level, &tcb_info),
+        Err(TcbEvaluationError::NoMatchingTcbLevel)
+    );
+}
+
+#[test]
+fn test_evaluate_tdx_module_tcb_evaluates_versioned_module_identity() {
+    let tcb_info = tcb_info();
+    let evaluate =
+        |components| evaluate_tdx_module_tcb(&td_report(components), &platform_svn(), &tcb_info);
+
+    // A non-zero major version means the first two components identify the TDX module, which
+    // is evaluated against the `TDX_01` identity rather than the TCB level.
+    assert_eq!(evaluate([6, 1, 3]), Ok(TcbStatus::UpToDate));
+    assert_eq!(evaluate([4, 1, 3]), Ok(TcbStatus::OutOfDate));
+    assert_eq!(evaluate([6, 1, 2]), Ok(TcbStatus::OutOfDate));
+    assert_eq!(
+        evaluate([1, 1, 3]),
+        Err(TcbEvaluationError::NoMatchingTcbLevel)
+    );
+    assert_eq!(
+        evaluate([6, 2, 3]),
+        Err(TcbEvaluationError::TdxModuleMismatch(String::from(
+            "TDX_02"
+        )))
+    );
+}
+
+#[test]
+fn test_evaluate_tdx_module_identity_rejects_other_signer_or_attributes() {
+    let tcb_info = tcb_info();
+
+    let other_signer = with_td10(td_report([6, 1, 3]), |td10| td10.mr_signer_seam[0] = 1);
+    assert_eq!(
+        evaluate_tdx_module_identity(&other_signer, &tcb_info),
+        Err(TcbEvaluationError::TdxModuleMismatch(String::from(
+            "TDX_01"
+        )))
+    );
+
+    let other_attributes = with_td10(td_report([5, 0, 3]), |td10| td10.seam_attributes[7] = 1);
+    assert_eq!(
+        evaluate_tdx_module_identity(&other_attributes, &tcb_info),
+        Err(TcbEvaluationError::TdxModuleMismatch(String::from(
+            "tdxModule"
+        )))
+    );
+    assert_eq!(
+        evaluate_tdx_module_identity(&td_report([5, 0, 3]), &tcb_info),
+        Ok(None)
+    );
+}
+
+#[test]
+fn test_converge_tdx_module_status() {
+    use TcbStatus::*;
+
+    for (level_status, module_status, expected) in [
+        (UpToDate, UpToDate, UpToDate),
+        (SWHardeningNeeded, UpToDate, SWHardeningNeeded),
+        (UpToDate, OutOfDate, OutOfDate),
+        (SWHardeningNeeded, OutOfDate, OutOfDate),
+        (ConfigurationNeeded, OutOfDate, OutOfDateConfigurationNeeded),
+        (
+            ConfigurationAndSWHardeningNeeded,
+            OutOfDate,
+            OutOfDateConfigurationNeeded,
+        ),
+        (OutOfDate, OutOfDate, OutOfDate),
+        (Revoked, UpToDate, Revoked),
+        (UpToDate, Revoked, Revoked),
+    ] {
+        assert_eq!(
+            converge_tdx_module_status(level_status, module_status),
+            expected,
+            "{level_status} with a {module_status} module"
+        );
+    }
+}
+
+#[test]
+fn test_evaluate_tdx_module_tcb_rejects_invalid_tcb_info() {
+    assert_matches!(
+        evaluate_tdx_module_tcb(&td_report([5, 0, 3]), &platform_svn(), "{}"),
+        Err(TcbEvaluationError::InvalidTcbInfo(_))
+    );
+}
+
+#[test]
+fn test_pck_svn_rejects_invalid_chain() {
+    assert_matches!(
+        pck_svn(b"not a certificate"),
+        Err(TcbEvaluationError::InvalidPckCertificate(_))
+    );
+}
+
+#[test]
+fn test_compare_tdx_module_tcb_levels_shows_failing_component() {
+    let levels =
+        compare_tdx_module_tcb_levels(&platform_svn(), &tee_tcb_svn([5, 0, 2]), &tcb_info())
+            .unwrap();
+
+    assert_eq!(levels.len(), 2);
+    assert_eq!(levels[0].status, TcbStatus::UpToDate);
+    assert!(!levels[0].passed);
+    assert!(levels[0].pce_svn.passed);
+    assert!(
+        levels[0]
+            .sgx_components
+            .iter()
+            .all(|component| component.passed)
+    );
+    let failed: Vec<_> = levels[0]
+        .components
+        .iter()
+        .filter(|component| !component.passed)
+        .collect();
+    assert_eq!(
+        failed,
+        [&SvnComparison {
+            index: 2,
+            reported: 2,
+            threshold: 3,
+            passed: false,
+        }]
+    );
+    assert_eq!(levels[1].status, TcbStatus::OutOfDate);
+    assert!(levels[1].passed);
+}
+
+#[test]
+fn test_compare_tdx_module_tcb_levels_skips_module_components_for_versioned_module() {
+    let levels =
+        compare_tdx_module_tcb_levels(&platform_svn(), &tee_tcb_svn([0, 1, 3]), &tcb_info())
+            .unwrap();
+
+    assert_eq!(levels.len(), 1);
+    assert_eq!(levels[0].sgx_components.len(), 16);
+    assert_eq!(levels[0].components.len(), 14);
+    assert_eq!(levels[0].components[0].index, 2);
+}
+
+#[test]
+fn test_explain_status_matches_evaluation() {
+    let mut report = verified_report("OutOfDate", &[]);
+    report.report = td_report([5, 0, 2]);
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let explanation = explain_status(&report, &platform_svn(), &collateral).unwrap();
+
+    assert_eq!(explanation.platform_status, TcbStatus::OutOfDate);
+    assert_eq!(explanation.tdx_module_status, Some(TcbStatus::OutOfDate));
+    assert_eq!(
+        explanation.tdx_module_levels,
+        compare_tdx_module_tcb_levels(
+            &platform_svn(),
+            &tee_tcb_svn([5, 0, 2]),
+            &collateral.tcb_info
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_explain_status_lowers_status_of_outdated_module() {
+    let mut report = verified_report("UpToDate", &[]);
+    report.report = td_report([4, 1, 3]);
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let explanation = explain_status(&report, &platform_svn(), &collateral).unwrap();
+
+    assert_eq!(explanation.tdx_module_levels[0].status, TcbStatus::UpToDate);
+    assert!(explanation.tdx_module_levels[0].passed);
+    assert_eq!(explanation.tdx_module_status, Some(TcbStatus::OutOfDate));
+}
+
+#[test]
+fn test_explain_status_without_matching_level() {
+    let report = verified_report("UpToDate", &[]);
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let explanation = explain_status(&report, &platform_svn(), &collateral).unwrap();
+
+    assert_eq!(explanation.tdx_module_status, None);
+    assert!(
+        explanation
+            .tdx_module_levels
+            .iter()
+            .all(|level| !level.passed)
+    );
+}
+
+fn qe_identity_with_levels(levels: &[(u64, &str)]) -> String {
+    let tcb_levels: Vec<_> = levels
+        .iter()
+        .map(|(isvsvn, status)| json!({ "tcb": { "isvsvn": isvsvn }, "tcbStatus": status }))
+        .collect();
+    json!({ "id": "TD_QE", "tcbLevels": tcb_levels }).to_string()
+}
+
+#[test]
+fn test_evaluate_qe_tcb_of_test_quote_is_up_to_date() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+
+    assert_eq!(
+        evaluate_qe_tcb(qe_isv_svn(&parsed), &collateral.qe_identity),
+        Ok(TcbStatus::UpToDate)
+    );
+}
+
+#[test]
+fn test_evaluate_qe_tcb_reports_out_of_date_qe_independently_of_platform() {
+    // The platform of the test quote is up to date, see
+    // `test_evaluate_tdx_module_tcb_picks_highest_matching_level`, but its QE (ISV SVN 6) is
+    // below the latest QE TCB level.
+    let parsed = ParsedQuote::parse(&quote()).unwrap();
+    let qe_identity = qe_identity_with_levels(&[(8, "UpToDate"), (4, "OutOfDate")]);
+
+    assert_eq!(
+        evaluate_qe_tcb(qe_isv_svn(&parsed), &qe_identity),
+        Ok(TcbStatus::OutOfDate)
+    );
+    assert_eq!(
+        verify_qe_tcb(&quote(), &qe_identity, &TcbPolicy::default()),
+        Err(VerificationError::TcbStatusNotAllowed(String::from(
+            "QE OutOfDate"
+        )))
+    );
+}
+
+#[test]
+fn test_evaluate_qe_tcb_rejects_svn_below_every_level() {
+    assert_eq!(
+        evaluate_qe_tcb(3, &qe_identity_with_levels(&[(4, "UpToDate")])),
+        Err(TcbEvaluationError::NoMatchingTcbLevel)
+    );
+}
+
+#[test]
+fn test_advisories_by_level_of_test_collateral() {
+    let levels = advisories_by_level(&tcb_info()).unwrap();
+
+    let statuses: Vec<_> = levels.iter().map(|(status, _)| *status).collect();
+    assert_eq!(
+        statuses,
+        [
+            TcbStatus::UpToDate,
+            TcbStatus::OutOfDate,
+            TcbStatus::OutOfDate
+        ]
+    );
+    assert!(levels[0].1.is_empty());
+    assert_eq!(levels[1].1.len(), 5);
+    assert_eq!(levels[2].1.len(), 19);
+    // A lower level is affected by the advisories of the levels above it.
+    assert!(
+        levels[1]
+            .1
+            .iter()
+            .all(|advisory| levels[2].1.contains(advisory))
+    );
+    assert!(levels[2].1.contains(&String::from("INTEL-SA-00837")));
+
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    assert_eq!(collateral.advisories_by_level().unwrap(), levels);
+}
+
+#[test]
+fn test_advisory_delta_lists_advisories_only_at_lower_status() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let levels = collateral.advisories_by_level().unwrap();
+
+    let delta = advisory_delta(TcbStatus::UpToDate, TcbStatus::OutOfDate, &collateral).unwrap();
+
+    // The lowest OutOfDate level is affected by every advisory of the one above it.
+    let mut expected = levels[2].1.clone();
+    expected.sort();
+    assert_eq!(delta, expected);
+    assert!(delta.contains(&String::from("INTEL-SA-00837")));
+    assert_eq!(
+        advisory_delta(TcbStatus::OutOfDate, TcbStatus::UpToDate, &collateral),
+        Ok(Vec::new())
+    );
+    assert_eq!(
+        advisory_delta(TcbStatus::OutOfDate, TcbStatus::OutOfDate, &collateral),
+        Ok(Vec::new())
+    );
+    assert_eq!(
+        advisory_delta(TcbStatus::UpToDate, TcbStatus::Revoked, &collateral),
+        Ok(Vec::new())
+    );
+}
+
+#[test]
+fn test_advisories_by_level_rejects_malformed_advisories() {
+    let tcb_info = json!({
+        "tcbLevels": [{ "tcbStatus": "OutOfDate", "advisoryIDs": [1] }]
+    })
+    .to_string();
+
+    assert_matches!(
+        advisories_by_level(&tcb_info),
+        Err(TcbEvaluationError::InvalidTcbInfo(_))
+    );
+}
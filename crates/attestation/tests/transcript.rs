@@ -0,0 +1,107 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{
+        DstackAttestation, QuoteVerifyOptions, RecordedResult, Transcript, VerificationError,
+    },
+    collateral::Collateral,
+    report_data::ReportData,
+    tcb_info::TcbInfo,
+    tcb_status::{TcbPolicy, TcbStatus},
+};
+use test_utils::attestation::{TEST_TCB_INFO_STRING, collateral, quote};
+
+/// A timestamp at which the test quote and collateral are valid.
+const VALID_TIMESTAMP_SECONDS: u64 = 1763626832;
+
+fn attestation() -> DstackAttestation {
+    let tcb_info: TcbInfo = serde_json::from_str(TEST_TCB_INFO_STRING).unwrap();
+    DstackAttestation::new(
+        quote(),
+        Collateral::try_from_json(collateral()).unwrap(),
+        tcb_info,
+    )
+}
+
+/// Records a verification that is rejected, as the report data of the test quote is not
+/// all zeros.
+fn rejected_transcript() -> (VerificationError, Transcript) {
+    let (result, transcript) = Transcript::record(
+        attestation(),
+        ReportData::new([0; 64]),
+        VALID_TIMESTAMP_SECONDS,
+        &[],
+        &[],
+        &QuoteVerifyOptions::default(),
+    );
+    (result.unwrap_err(), transcript)
+}
+
+#[test]
+fn test_transcript_records_result() {
+    let (error, transcript) = rejected_transcript();
+
+    assert_eq!(transcript.now, VALID_TIMESTAMP_SECONDS);
+    assert_eq!(transcript.options, QuoteVerifyOptions::default());
+    assert_eq!(
+        transcript.result,
+        RecordedResult::Rejected(error.to_string())
+    );
+}
+
+#[test]
+fn test_transcript_replays_after_json_roundtrip() {
+    let (error, transcript) = rejected_transcript();
+
+    let json = serde_json::to_string(&transcript).unwrap();
+    let replayed: Transcript = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(replayed.replay().unwrap_err(), error);
+}
+
+#[test]
+fn test_transcript_replay_rejects_altered_input() {
+    let (_, mut transcript) = rejected_transcript();
+    // The collateral has expired by then, which fails verification differently.
+    transcript.now = VALID_TIMESTAMP_SECONDS + 365 * 24 * 60 * 60;
+
+    assert_matches!(
+        transcript.replay(),
+        Err(VerificationError::TranscriptMismatch { recorded, replayed })
+            if recorded.starts_with("rejected") && replayed.starts_with("rejected")
+                && recorded != replayed
+    );
+}
+
+#[test]
+fn test_transcript_replay_rejects_altered_result() {
+    let (_, mut transcript) = rejected_transcript();
+    transcript.result = RecordedResult::Rejected("another error".to_string());
+
+    assert_matches!(
+        transcript.replay(),
+        Err(VerificationError::TranscriptMismatch { recorded, .. })
+            if recorded == "rejected with `another error`"
+    );
+}
+
+#[test]
+fn test_quote_verify_options_json_roundtrip() {
+    let options = QuoteVerifyOptions::builder()
+        .allowed_skew_secs(300)
+        .tcb_policy(TcbPolicy::allowing([TcbStatus::SWHardeningNeeded]))
+        .expected_mr_config_id([1; 48])
+        .report_data_domain(b"domain".to_vec())
+        .min_seam_svn(0x0103)
+        .build();
+
+    let json = serde_json::to_value(&options).unwrap();
+
+    assert_eq!(json["expected_mr_config_id"], hex::encode([1; 48]));
+    assert_eq!(json["report_data_domain"], hex::encode(b"domain"));
+    assert_eq!(
+        serde_json::from_value::<QuoteVerifyOptions>(json).unwrap(),
+        options
+    );
+}
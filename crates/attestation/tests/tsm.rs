@@ -0,0 +1,61 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::quote::{QuoteError, parse_tsm_report};
+use test_utils::attestation::quote;
+
+/// Wraps `quote` in a GHCI quote buffer with the given status and `out_len`, padded like the
+/// fixed size buffer the kernel allocates.
+fn ghci_quote_buffer(quote: &[u8], status: u64, out_len: u32) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&1u64.to_le_bytes());
+    buffer.extend_from_slice(&status.to_le_bytes());
+    buffer.extend_from_slice(&64u32.to_le_bytes());
+    buffer.extend_from_slice(&out_len.to_le_bytes());
+    buffer.extend_from_slice(quote);
+    buffer.resize(buffer.len() + 512, 0);
+    buffer
+}
+
+#[test]
+fn test_parse_tsm_report_accepts_raw_quote() {
+    let quote: Vec<u8> = quote().into();
+
+    assert_eq!(parse_tsm_report(&quote), Ok(quote));
+}
+
+#[test]
+fn test_parse_tsm_report_unwraps_ghci_quote_buffer() {
+    let quote: Vec<u8> = quote().into();
+    let out_len = u32::try_from(quote.len()).unwrap();
+
+    let blob = ghci_quote_buffer(&quote, 0, out_len);
+
+    assert_eq!(parse_tsm_report(&blob), Ok(quote));
+}
+
+#[test]
+fn test_parse_tsm_report_rejects_failed_ghci_status() {
+    let quote: Vec<u8> = quote().into();
+    let out_len = u32::try_from(quote.len()).unwrap();
+
+    for status in [u64::MAX, 0x8000_0000_0000_0000] {
+        let blob = ghci_quote_buffer(&quote, status, out_len);
+        assert_matches!(parse_tsm_report(&blob), Err(QuoteError::TsmReport(_)));
+    }
+}
+
+#[test]
+fn test_parse_tsm_report_rejects_out_len_past_buffer() {
+    let quote: Vec<u8> = quote().into();
+
+    let blob = ghci_quote_buffer(&quote, 0, u32::MAX);
+
+    assert_matches!(parse_tsm_report(&blob), Err(QuoteError::TsmReport(message)) if message.contains("exceeds"));
+}
+
+#[test]
+fn test_parse_tsm_report_rejects_unknown_layout() {
+    assert_matches!(parse_tsm_report(&[]), Err(QuoteError::TsmReport(_)));
+    assert_matches!(parse_tsm_report(&[7; 1024]), Err(QuoteError::TsmReport(_)));
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "std")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    attestation::{VerificationError, verify_hex, verify_with_guidance},
+    collateral::Collateral,
+    measurements::{FullMeasurements, FullMeasurementsHex, Measurements},
+    tcb_info::TcbInfo,
+    tcb_status::TcbStatus,
+};
+use test_utils::attestation::{TEST_TCB_INFO_STRING, collateral, quote};
+
+/// A timestamp at which the test quote and collateral are valid.
+const VALID_TIMESTAMP_SECONDS: u64 = 1763626832;
+
+fn quote_hex() -> String {
+    hex::encode(quote().as_slice())
+}
+
+fn collateral_json() -> String {
+    collateral().to_string()
+}
+
+fn expected() -> FullMeasurementsHex {
+    let tcb_info: TcbInfo = serde_json::from_str(TEST_TCB_INFO_STRING).unwrap();
+    FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: *tcb_info.mrtd,
+            rtmr0: *tcb_info.rtmr0,
+            rtmr1: *tcb_info.rtmr1,
+            rtmr2: *tcb_info.rtmr2,
+        },
+        key_provider_event_digest: [5; 48],
+        app_compose_hash_payload: [6; 32],
+    }
+    .into()
+}
+
+#[test]
+fn test_verify_hex_accepts_test_quote() {
+    let summary = verify_hex(
+        &quote_hex(),
+        &collateral_json(),
+        VALID_TIMESTAMP_SECONDS,
+        &expected(),
+    )
+    .unwrap();
+
+    assert_eq!(summary.measurements, FullMeasurements::from(expected()));
+    assert_eq!(summary.platform_tcb_status, TcbStatus::UpToDate);
+    assert_eq!(summary.verified_at, VALID_TIMESTAMP_SECONDS);
+    assert_eq!(summary.fmspc.to_hex(), "b0c06f000000");
+}
+
+#[test]
+fn test_verify_hex_reports_tcb_info_dates() {
+    let summary = verify_hex(
+        &quote_hex(),
+        &collateral_json(),
+        VALID_TIMESTAMP_SECONDS,
+        &expected(),
+    )
+    .unwrap();
+
+    // The `issueDate` and `nextUpdate` of the test TCB info, 2025-11-18T19:38:14Z and
+    // 2025-12-18T19:38:14Z.
+    assert_eq!(summary.tcb_info_issued_at, 1763494694);
+    assert_eq!(summary.tcb_info_next_update, 1766086694);
+}
+
+#[test]
+fn test_verify_hex_rejects_mismatching_register() {
+    let mut expected = FullMeasurements::from(expected());
+    expected.rtmrs.rtmr1[0] ^= 0xff;
+
+    let result = verify_hex(
+        &quote_hex(),
+        &collateral_json(),
+        VALID_TIMESTAMP_SECONDS,
+        &expected.into(),
+    );
+
+    assert_matches!(
+        result,
+        Err(VerificationError::WrongHash { name: "rtmr1", .. })
+    );
+}
+
+#[test]
+fn test_verify_hex_rejects_malformed_inputs() {
+    assert_matches!(
+        verify_hex(
+            "not hex",
+            &collateral_json(),
+            VALID_TIMESTAMP_SECONDS,
+            &expected()
+        ),
+        Err(VerificationError::InvalidInput(_))
+    );
+    assert_matches!(
+        verify_hex(&quote_hex(), "{}", VALID_TIMESTAMP_SECONDS, &expected()),
+        Err(VerificationError::InvalidInput(_))
+    );
+}
+
+#[test]
+fn test_verify_with_guidance_accepts_test_quote() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let result = verify_with_guidance(&quote(), &collateral, VALID_TIMESTAMP_SECONDS, &expected());
+
+    assert_matches!(result, Ok(_));
+}
+
+#[test]
+fn test_verify_with_guidance_names_each_mismatching_register() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+    let mut expected = FullMeasurements::from(expected());
+    let found_rtmr2 = hex::encode(expected.rtmrs.rtmr2);
+    expected.rtmrs.rtmr1[0] ^= 0xff;
+    expected.rtmrs.rtmr2 = [7; 48];
+
+    let error = verify_with_guidance(
+        &quote(),
+        &collateral,
+        VALID_TIMESTAMP_SECONDS,
+        &expected.into(),
+    )
+    .unwrap_err();
+
+    assert_matches!(
+        error.error,
+        VerificationError::WrongHash { name: "rtmr1", .. }
+    );
+    let steps = &error.remediation;
+    assert_eq!(steps.len(), 4);
+    assert!(steps[0].starts_with("Confirm which image"));
+    assert!(steps[1].starts_with("Check rtmr1: expected "));
+    assert!(steps[2].starts_with(&format!(
+        "Check rtmr2: expected {}, found {found_rtmr2}.",
+        hex::encode([7; 48])
+    )));
+    assert!(steps[3].contains("allowlist"));
+    assert!(error.checklist().starts_with("1. Confirm which image"));
+    assert_eq!(error.checklist().lines().count(), 4);
+}
+
+#[test]
+fn test_verify_with_guidance_guides_expired_collateral() {
+    let collateral = Collateral::try_from_json(collateral()).unwrap();
+
+    let error = verify_with_guidance(&quote(), &collateral, u64::MAX, &expected()).unwrap_err();
+
+    assert_matches!(error.error, VerificationError::Collateral(_));
+    assert!(error.remediation[0].contains("clock"));
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "semver")]
+
+use assert_matches::assert_matches;
+use attestation::{
+    measurements::{FullMeasurements, Measurements, MeasurementsError, VersionedAllowlist},
+    quote::ParsedQuote,
+    tcb_info::TcbInfo,
+};
+use dcap_qvl::verify::VerifiedReport;
+use semver::{Version, VersionReq};
+use test_utils::attestation::{TEST_TCB_INFO_STRING, quote};
+
+fn test_report() -> VerifiedReport {
+    VerifiedReport {
+        status: String::from("UpToDate"),
+        advisory_ids: Vec::new(),
+        report: ParsedQuote::parse(&quote()).unwrap().quote().report.clone(),
+        ppid: Vec::new(),
+    }
+}
+
+/// The measurements of the test quote.
+fn test_quote_measurements() -> FullMeasurements {
+    let tcb_info: TcbInfo = serde_json::from_str(TEST_TCB_INFO_STRING).unwrap();
+    FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: *tcb_info.mrtd,
+            rtmr0: *tcb_info.rtmr0,
+            rtmr1: *tcb_info.rtmr1,
+            rtmr2: *tcb_info.rtmr2,
+        },
+        key_provider_event_digest: [0; 48],
+        app_compose_hash_payload: *tcb_info.compose_hash,
+    }
+}
+
+fn other_measurements() -> FullMeasurements {
+    FullMeasurements {
+        rtmrs: Measurements {
+            mrtd: [1; 48],
+            rtmr0: [2; 48],
+            rtmr1: [3; 48],
+            rtmr2: [4; 48],
+        },
+        ..test_quote_measurements()
+    }
+}
+
+/// The test quote is release 1.3.0, between releases of other measurements.
+fn allowlist() -> VersionedAllowlist {
+    VersionedAllowlist::from_registry([
+        (other_measurements(), "1.2.0"),
+        (test_quote_measurements(), "1.3.0"),
+        (other_measurements(), "2.0.0"),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn test_accept_range_returns_version_of_report() {
+    let req = VersionReq::parse(">=1.2, <2.0").unwrap();
+
+    assert_eq!(
+        allowlist().accept_range(&test_report(), &req),
+        Ok(Version::new(1, 3, 0))
+    );
+}
+
+#[test]
+fn test_accept_range_returns_highest_satisfying_version() {
+    let mut allowlist = allowlist();
+    allowlist.insert(Version::new(1, 3, 1), test_quote_measurements());
+    allowlist.insert(Version::new(3, 0, 0), test_quote_measurements());
+
+    assert_eq!(
+        allowlist.accept_range(&test_report(), &VersionReq::parse("^1.3").unwrap()),
+        Ok(Version::new(1, 3, 1))
+    );
+}
+
+#[test]
+fn test_accept_range_rejects_version_outside_requirement() {
+    let req = VersionReq::parse(">=2.0").unwrap();
+
+    assert_eq!(
+        allowlist().accept_range(&test_report(), &req),
+        Err(MeasurementsError::VersionNotAccepted {
+            version: String::from("1.3.0"),
+            requirement: req.to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_accept_range_rejects_unknown_measurements() {
+    let allowlist = VersionedAllowlist::new([(Version::new(1, 0, 0), other_measurements())]);
+
+    assert_eq!(
+        allowlist.accept_range(&test_report(), &VersionReq::STAR),
+        Err(MeasurementsError::NotInAllowlist)
+    );
+}
+
+#[test]
+fn test_from_registry_rejects_invalid_version() {
+    assert_matches!(
+        VersionedAllowlist::from_registry([(other_measurements(), "1.2")]),
+        Err(MeasurementsError::InvalidVersion(_))
+    );
+}
+
+#[test]
+fn test_insert_replaces_measurements_of_same_version() {
+    let mut allowlist = allowlist();
+
+    let replaced = allowlist.insert(Version::new(1, 3, 0), other_measurements());
+
+    assert_eq!(replaced, Some(test_quote_measurements()));
+    assert_eq!(allowlist.len(), 3);
+    assert_eq!(
+        allowlist
+            .versions_of(&other_measurements().rtmrs)
+            .map(Version::to_string)
+            .collect::<Vec<_>>(),
+        ["1.2.0", "1.3.0", "2.0.0"]
+    );
+}
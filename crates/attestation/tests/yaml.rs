@@ -0,0 +1,120 @@
+#![cfg(feature = "yaml")]
+
+use assert_matches::assert_matches;
+use attestation::measurements::{FullMeasurements, MeasurementsError, from_yaml_str};
+
+fn register(byte: u8) -> String {
+    hex::encode([byte; 48])
+}
+
+fn digest(byte: u8) -> String {
+    hex::encode([byte; 32])
+}
+
+#[test]
+fn test_from_yaml_str_reads_plain_document() {
+    let yaml = format!(
+        "rtmrs:\n  mrtd: {}\n  rtmr0: {}\n  rtmr1: {}\n  rtmr2: {}\n\
+         key_provider_event_digest: {}\napp_compose_hash_payload: {}\n",
+        register(1),
+        register(2),
+        register(3),
+        register(4),
+        register(5),
+        digest(6),
+    );
+
+    let measurements = FullMeasurements::from(from_yaml_str(&yaml).unwrap());
+
+    assert_eq!(measurements.rtmrs.mrtd, [1; 48]);
+    assert_eq!(measurements.rtmrs.rtmr0, [2; 48]);
+    assert_eq!(measurements.rtmrs.rtmr1, [3; 48]);
+    assert_eq!(measurements.rtmrs.rtmr2, [4; 48]);
+    assert_eq!(measurements.key_provider_event_digest, [5; 48]);
+    assert_eq!(measurements.app_compose_hash_payload, [6; 32]);
+}
+
+#[test]
+fn test_from_yaml_str_resolves_aliases() {
+    let yaml = format!(
+        "shared:\n  firmware: &firmware {}\n  key_provider: &key_provider {}\n\
+         rtmrs:\n  mrtd: *firmware\n  rtmr0: *firmware\n  rtmr1: {}\n  rtmr2: {}\n\
+         key_provider_event_digest: *key_provider\napp_compose_hash_payload: {}\n",
+        register(1),
+        register(5),
+        register(3),
+        register(4),
+        digest(6),
+    );
+
+    let measurements = FullMeasurements::from(from_yaml_str(&yaml).unwrap());
+
+    assert_eq!(measurements.rtmrs.mrtd, [1; 48]);
+    assert_eq!(measurements.rtmrs.rtmr0, [1; 48]);
+    assert_eq!(measurements.key_provider_event_digest, [5; 48]);
+}
+
+#[test]
+fn test_from_yaml_str_resolves_merge_keys() {
+    let yaml = format!(
+        "staging: &staging\n  mrtd: {}\n  rtmr0: {}\n  rtmr1: {}\n  rtmr2: {}\n\
+         rtmrs:\n  <<: *staging\n  rtmr2: {}\n\
+         key_provider_event_digest: {}\napp_compose_hash_payload: {}\n",
+        register(1),
+        register(2),
+        register(3),
+        register(4),
+        register(9),
+        register(5),
+        digest(6),
+    );
+
+    let measurements = FullMeasurements::from(from_yaml_str(&yaml).unwrap());
+
+    assert_eq!(measurements.rtmrs.mrtd, [1; 48]);
+    assert_eq!(measurements.rtmrs.rtmr1, [3; 48]);
+    // Keys of the mapping take precedence over the merged ones.
+    assert_eq!(measurements.rtmrs.rtmr2, [9; 48]);
+}
+
+#[test]
+fn test_from_yaml_str_rejects_missing_field() {
+    let yaml = format!(
+        "rtmrs:\n  mrtd: {r}\n  rtmr0: {r}\n  rtmr1: {r}\n  rtmr2: {r}\n\
+         key_provider_event_digest: {r}\n",
+        r = register(1),
+    );
+
+    assert_matches!(
+        from_yaml_str(&yaml),
+        Err(MeasurementsError::InvalidYaml(message)) if message.contains("app_compose_hash_payload")
+    );
+}
+
+#[test]
+fn test_from_yaml_str_rejects_wrong_length_register() {
+    let yaml = format!(
+        "rtmrs:\n  mrtd: {r}\n  rtmr0: {}\n  rtmr1: {r}\n  rtmr2: {r}\n\
+         key_provider_event_digest: {r}\napp_compose_hash_payload: {}\n",
+        digest(2),
+        digest(6),
+        r = register(1),
+    );
+
+    assert_matches!(from_yaml_str(&yaml), Err(MeasurementsError::InvalidYaml(_)));
+}
+
+#[test]
+fn test_from_yaml_str_rejects_merge_of_scalar() {
+    let yaml = format!("scalar: &scalar {}\nrtmrs:\n  <<: *scalar\n", register(1));
+
+    assert_matches!(from_yaml_str(&yaml), Err(MeasurementsError::InvalidYaml(_)));
+}
+
+#[test]
+fn test_from_yaml_str_rejects_invalid_yaml() {
+    assert_matches!(
+        from_yaml_str("rtmrs: [unclosed"),
+        Err(MeasurementsError::InvalidYaml(_))
+    );
+}
@@ -1,7 +1,7 @@
 use crate::sandbox::{
     common::{gen_accounts, init_env, submit_tee_attestations, SandboxTestSetup},
     utils::{
-        consts::{ALL_SIGNATURE_SCHEMES, PARTICIPANT_LEN},
+        consts::{ALL_SIGNATURE_SCHEMES, MAX_GAS_FOR_SUBMIT_DSTACK_ATTESTATION, PARTICIPANT_LEN},
         interface::IntoInterfaceType,
         mpc_contract::{
             assert_running_return_participants, assert_running_return_threshold,
@@ -275,6 +275,33 @@ async fn test_submit_participant_info_test_method_available_in_integration_tests
     Ok(())
 }
 
+/// **Gas of attestation verification** - Tests that submitting a dstack attestation stays within
+/// [`MAX_GAS_FOR_SUBMIT_DSTACK_ATTESTATION`], so that checks added to quote verification do not
+/// silently raise the cost of every submission.
+#[tokio::test]
+#[ignore]
+async fn test_submit_participant_info_with_dstack_attestation_gas() -> Result<()> {
+    let (contract, accounts, attestation, tls_key) = setup_tee_test().await?;
+    setup_approved_mpc_hash(&contract, &accounts).await?;
+
+    let execution = accounts[0]
+        .call(contract.id(), "submit_participant_info")
+        .args_json((&attestation, &tls_key))
+        .max_gas()
+        .transact()
+        .await?;
+
+    assert!(execution.is_success());
+    let gas_burnt = execution.total_gas_burnt;
+    assert!(
+        gas_burnt.as_tgas() <= MAX_GAS_FOR_SUBMIT_DSTACK_ATTESTATION.as_tgas(),
+        "Gas usage for submitting a dstack attestation ({} TGas) should be <= {} TGas",
+        gas_burnt.as_tgas(),
+        MAX_GAS_FOR_SUBMIT_DSTACK_ATTESTATION.as_tgas()
+    );
+    Ok(())
+}
+
 // / **Mock attestation bypass** - Tests that participant info submission succeeds with mock attestation.
 // / Different from the dstack attestation tests above, this uses a mock attestation which bypasses complex TEE verification.
 /// This demonstrates that the submission mechanism itself works when attestation verification passes.
@@ -39,6 +39,11 @@ pub const GAS_FOR_VOTE_BEFORE_THRESHOLD: Gas = Gas::from_tgas(5);
 /// This vote is more expensive because it deploys the new contract code and executes
 /// the migration function.
 pub const MAX_GAS_FOR_THRESHOLD_VOTE: Gas = Gas::from_tgas(147);
+/// Maximum gas expected for submitting a dstack attestation, which is dominated by DCAP
+/// verification of the quote. The contract verifies with the default options, which skip the
+/// extended collateral checks of the attestation crate; enabling them costs about five more
+/// ECDSA verifications and should show up here.
+pub const MAX_GAS_FOR_SUBMIT_DSTACK_ATTESTATION: Gas = Gas::from_tgas(150);
 
 /* --- Deposit constants --- */
 /// This is the current deposit required for a contract deploy. This is subject to change but make
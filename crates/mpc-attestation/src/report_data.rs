@@ -123,13 +123,7 @@ impl ReportDataV1 {
     /// Parses V1 report data from bytes. Returns the hash of public keys.
     /// Note: This only extracts the hash, not the original public keys.
     pub fn from_bytes(bytes: &[u8; REPORT_DATA_SIZE]) -> [u8; Self::PUBLIC_KEYS_HASH_SIZE] {
-        // Extract hash using V1 format
-        let mut hash = [0u8; Self::PUBLIC_KEYS_HASH_SIZE];
-        hash.copy_from_slice(
-            &bytes
-                [Self::PUBLIC_KEYS_OFFSET..Self::PUBLIC_KEYS_OFFSET + Self::PUBLIC_KEYS_HASH_SIZE],
-        );
-        hash
+        ::attestation::report_data::ReportData::new(*bytes).public_key_hash()
     }
 }
 
@@ -0,0 +1,51 @@
+#!/usr/bin/env bash
+# Generates a throwaway root CA, an intermediate CA issued by it, and root CA CRLs with and
+# without the intermediate revoked. Used to test root CA CRL checks without Intel material.
+set -euo pipefail
+cd "$(dirname "$0")"
+
+work=$(mktemp -d)
+trap 'rm -rf "$work"' EXIT
+
+cat > "$work/ca.cnf" <<CNF
+[ca]
+default_ca = root
+[root]
+database = $work/index.txt
+crlnumber = $work/crlnumber
+default_md = sha256
+default_crl_days = 3650
+[v3_ca]
+basicConstraints = critical, CA:true
+keyUsage = critical, keyCertSign, cRLSign
+subjectKeyIdentifier = hash
+authorityKeyIdentifier = keyid
+CNF
+touch "$work/index.txt"
+echo 01 > "$work/crlnumber"
+
+openssl ecparam -name prime256v1 -genkey -noout -out "$work/root.key"
+openssl req -x509 -new -key "$work/root.key" -subj "/CN=Test Root CA/O=Test" \
+    -days 3650 -sha256 -out "$work/root.pem"
+
+openssl ecparam -name prime256v1 -genkey -noout -out "$work/intermediate.key"
+openssl req -new -key "$work/intermediate.key" -subj "/CN=Test Intermediate CA/O=Test" \
+    -out "$work/intermediate.csr"
+openssl x509 -req -in "$work/intermediate.csr" -CA "$work/root.pem" -CAkey "$work/root.key" \
+    -set_serial 0x1234 -days 3650 -sha256 -extfile "$work/ca.cnf" -extensions v3_ca \
+    -out "$work/intermediate.pem"
+
+cat "$work/intermediate.pem" "$work/root.pem" > chain.pem
+openssl x509 -in "$work/intermediate.pem" -outform DER -out "$work/intermediate.der"
+openssl x509 -in "$work/root.pem" -outform DER -out "$work/root.der"
+cat "$work/intermediate.der" "$work/root.der" > chain.der
+
+openssl ca -config "$work/ca.cnf" -gencrl -keyfile "$work/root.key" -cert "$work/root.pem" \
+    -out "$work/empty.crl.pem"
+openssl crl -in "$work/empty.crl.pem" -outform DER -out root_ca_empty.crl.der
+
+openssl ca -config "$work/ca.cnf" -revoke "$work/intermediate.pem" -keyfile "$work/root.key" \
+    -cert "$work/root.pem"
+openssl ca -config "$work/ca.cnf" -gencrl -keyfile "$work/root.key" -cert "$work/root.pem" \
+    -out "$work/revoked.crl.pem"
+openssl crl -in "$work/revoked.crl.pem" -outform DER -out root_ca_revoked_intermediate.crl.der
@@ -8,6 +8,7 @@ use serde_json::Value;
 use sha2::{Digest, Sha256};
 
 pub const TEST_TCB_INFO_STRING: &str = include_str!("../assets/tcb_info.json");
+pub const TEST_PUBLIC_DATA_STRING: &str = include_str!("../assets/public_data.json");
 pub const TEST_APP_COMPOSE_STRING: &str = include_str!("../assets/app_compose.json");
 pub const TEST_APP_COMPOSE_WITH_SERVICES_STRING: &str =
     include_str!("../assets/app_compose_with_services.json");
@@ -18,6 +19,16 @@ pub const TEST_MPC_IMAGE_DIGEST_HEX: &str = include_str!("../assets/mpc_image_di
 pub const TEST_LAUNCHER_IMAGE_COMPOSE_STRING: &str =
     include_str!("../assets/launcher_image_compose.yaml");
 
+/// PEM chain of a test intermediate CA with serial number `0x1234`, followed by its root CA.
+pub const TEST_CRL_CHAIN_PEM: &[u8] = include_bytes!("../assets/crl/chain.pem");
+/// [`TEST_CRL_CHAIN_PEM`] as concatenated DER certificates.
+pub const TEST_CRL_CHAIN_DER: &[u8] = include_bytes!("../assets/crl/chain.der");
+/// DER encoded CRL of the test root CA, revoking nothing.
+pub const TEST_ROOT_CA_EMPTY_CRL_DER: &[u8] = include_bytes!("../assets/crl/root_ca_empty.crl.der");
+/// DER encoded CRL of the test root CA, revoking the test intermediate CA.
+pub const TEST_ROOT_CA_REVOKED_INTERMEDIATE_CRL_DER: &[u8] =
+    include_bytes!("../assets/crl/root_ca_revoked_intermediate.crl.der");
+
 pub fn launcher_compose_digest() -> LauncherDockerComposeHash {
     let digest: [u8; 32] = Sha256::digest(TEST_LAUNCHER_IMAGE_COMPOSE_STRING).into();
     LauncherDockerComposeHash::from(digest)